@@ -0,0 +1,54 @@
+//! Call Rust from JavaScript and JavaScript from Rust with typed arguments and results
+
+use dioxus::prelude::*;
+use dioxus_desktop::use_bridge_handler;
+use serde::{Deserialize, Serialize};
+
+fn main() {
+    dioxus_desktop::launch(app);
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+fn app(cx: Scope) -> Element {
+    let window = dioxus_desktop::use_window(cx);
+    let distance = use_state(cx, || None);
+
+    // JavaScript calls this with `await window.dioxus.invoke("add", [1, 2])`
+    use_bridge_handler(cx, "add", |(a, b): (i32, i32)| async move {
+        a.checked_add(b).ok_or("The sum overflowed")
+    });
+
+    // Register a JavaScript function that Rust can call
+    use_effect(cx, (), |_| async move {
+        _ = eval(
+            r#"
+            window.dioxus.expose("distance", ({ x, y }) => Math.sqrt(x * x + y * y));
+            window.dioxus.invoke("add", [1, 2]).then((sum) => console.log("1 + 2 =", sum));
+            window.dioxus.invoke("add", [2147483647, 1]).catch((error) => console.error(error));
+            "#,
+        );
+    });
+
+    cx.render(rsx! {
+        button {
+            onclick: move |_| {
+                to_owned![window, distance];
+                async move {
+                    let result = window.call_js::<_, f64>("distance", Point { x: 3.0, y: 4.0 }).await;
+                    distance.set(Some(result.map_err(|err| err.to_string())));
+                }
+            },
+            "Measure"
+        }
+        match distance.get() {
+            Some(Ok(distance)) => rsx! { p { "The distance is {distance}" } },
+            Some(Err(err)) => rsx! { p { "Error: {err}" } },
+            None => rsx! { p { "Click the button to call JavaScript" } },
+        }
+    })
+}
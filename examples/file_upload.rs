@@ -1,6 +1,7 @@
 #![allow(non_snake_case)]
 use dioxus::html::HasFileData;
 use dioxus::prelude::*;
+use dioxus_desktop::{use_window, FileDialog};
 use tokio::time::sleep;
 
 fn main() {
@@ -10,6 +11,7 @@ fn main() {
 fn App(cx: Scope) -> Element {
     let enable_directory_upload = use_state(cx, || false);
     let files_uploaded: &UseRef<Vec<String>> = use_ref(cx, Vec::new);
+    let window = use_window(cx);
 
     cx.render(rsx! {
         label {
@@ -41,6 +43,22 @@ fn App(cx: Scope) -> Element {
                 }
             },
         }
+        button {
+            onclick: move |_| {
+                to_owned![files_uploaded, window];
+                async move {
+                    let dialog = FileDialog::new()
+                        .with_title("Upload files")
+                        .with_accept(".txt,.rs")
+                        .with_multiple(true);
+                    // The native dialog returns the same file engine as the input above
+                    if let Some(file_engine) = window.open_file_dialog(dialog).await {
+                        files_uploaded.write().extend(file_engine.files());
+                    }
+                }
+            },
+            "Open a native file dialog"
+        }
         div {
             width: "100px",
             height: "100px",
@@ -0,0 +1,75 @@
+//! Native menus that follow the state of the app
+//!
+//! The menu bar and the context menu are declared every render, so their items enable, disable and check themselves as the state changes.
+
+use dioxus::prelude::*;
+use dioxus_desktop::{use_context_menu, use_menu_bar, Menu, MenuBar, MenuItem, NativeMenuItem};
+
+fn main() {
+    dioxus_desktop::launch(app);
+}
+
+fn app(cx: Scope) -> Element {
+    let count = use_state(cx, || 0);
+    let locked = use_state(cx, || false);
+
+    use_menu_bar(
+        cx,
+        MenuBar::new()
+            .menu(
+                Menu::new("Counter")
+                    .item(
+                        MenuItem::new("Increment")
+                            .accelerator("CmdOrCtrl+Up")
+                            .enabled(!locked)
+                            .on_click({
+                                to_owned![count];
+                                move || count.modify(|count| count + 1)
+                            }),
+                    )
+                    .item(
+                        MenuItem::new("Reset")
+                            .enabled(!locked && **count != 0)
+                            .on_click({
+                                to_owned![count];
+                                move || count.set(0)
+                            }),
+                    )
+                    .separator()
+                    .item(MenuItem::checkbox("Locked", **locked).on_click({
+                        to_owned![locked];
+                        move || locked.modify(|locked| !locked)
+                    }))
+                    .separator()
+                    .native(NativeMenuItem::Quit),
+            )
+            .menu(
+                Menu::new("Edit")
+                    .native(NativeMenuItem::Cut)
+                    .native(NativeMenuItem::Copy)
+                    .native(NativeMenuItem::Paste)
+                    .native(NativeMenuItem::SelectAll),
+            ),
+    );
+
+    let context_menu = use_context_menu(
+        cx,
+        Menu::new("").item(
+            MenuItem::new(format!("Decrement from {count}"))
+                .enabled(!locked)
+                .on_click({
+                    to_owned![count];
+                    move || count.modify(|count| count - 1)
+                }),
+        ),
+    );
+
+    cx.render(rsx! {
+        div {
+            prevent_default: "oncontextmenu",
+            oncontextmenu: move |_| context_menu.show(),
+            h1 { "Count: {count}" }
+            p { "Use the menu bar or right click to change the count" }
+        }
+    })
+}
@@ -1,10 +1,25 @@
 use dioxus::prelude::*;
+use dioxus_desktop::{use_window_list, use_window_message_handler};
+use std::{cell::Cell, rc::Rc};
 
 fn main() {
     dioxus_desktop::launch(app);
 }
 
+/// Tells every window that the shared counter changed
+#[derive(Clone)]
+struct CounterChanged;
+
 fn app(cx: Scope) -> Element {
+    let windows = use_window_list(cx);
+
+    // Every window opened from here gets the same counter
+    cx.use_hook(|| {
+        let counter = Rc::new(Cell::new(0));
+        dioxus_desktop::window().share_context(counter.clone());
+        cx.provide_context(counter)
+    });
+
     cx.render(rsx! {
         div {
             button {
@@ -14,6 +29,8 @@ fn app(cx: Scope) -> Element {
                 },
                 "New Window"
             }
+            p { "{windows.len()} windows are open" }
+            counter {}
         }
     })
 }
@@ -21,5 +38,22 @@ fn app(cx: Scope) -> Element {
 fn popup(cx: Scope) -> Element {
     cx.render(rsx! {
         div { "This is a popup!" }
+        counter {}
+    })
+}
+
+fn counter(cx: Scope) -> Element {
+    let counter = use_context::<Rc<Cell<i32>>>(cx)?;
+    let update = cx.schedule_update();
+    use_window_message_handler(cx, move |_: CounterChanged, _from| update());
+
+    cx.render(rsx! {
+        button {
+            onclick: move |_| {
+                counter.set(counter.get() + 1);
+                dioxus_desktop::window().broadcast_message(CounterChanged);
+            },
+            "The counter is {counter.get()}"
+        }
     })
 }
@@ -0,0 +1,29 @@
+//! Reopen the window where it was closed and remember a setting between launches
+
+use dioxus::prelude::*;
+use dioxus_desktop::{use_persisted, Config};
+
+fn main() {
+    dioxus_desktop::launch_cfg(
+        app,
+        Config::new()
+            .with_data_directory(std::env::temp_dir().join("dioxus-persisted-settings"))
+            .with_window_state_persistence("main"),
+    );
+}
+
+fn app(cx: Scope) -> Element {
+    let dark = use_persisted::<bool>(cx, "dark-mode");
+    let background = if *dark.read() { "black" } else { "white" };
+    let color = if *dark.read() { "white" } else { "black" };
+
+    cx.render(rsx! {
+        div {
+            background: "{background}",
+            color: "{color}",
+            height: "100vh",
+            button { onclick: move |_| dark.with_mut(|dark| *dark = !*dark), "Toggle dark mode" }
+            p { "Move or resize the window and restart the app, it opens in the same place" }
+        }
+    })
+}
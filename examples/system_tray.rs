@@ -0,0 +1,55 @@
+//! Keep the app running in the system tray when its window is closed
+//!
+//! Double click the tray icon or use its menu to bring the window back.
+
+use dioxus::prelude::*;
+use dioxus_desktop::{
+    use_tray_event_handler, use_tray_menu, use_window, Config, Menu, MenuItem, NativeMenuItem,
+    SystemTray, TrayClick, WindowCloseBehaviour,
+};
+
+fn main() {
+    dioxus_desktop::launch_cfg(
+        app,
+        Config::new()
+            .with_system_tray(SystemTray::new().with_tooltip("Dioxus tray"))
+            .with_close_behaviour(WindowCloseBehaviour::LastWindowHides),
+    );
+}
+
+fn app(cx: Scope) -> Element {
+    let window = use_window(cx);
+    let clicks = use_state(cx, || 0);
+
+    use_tray_event_handler(cx, {
+        to_owned![window];
+        move |event| {
+            if event.click == TrayClick::Double {
+                window.set_visible(true);
+                window.set_focus();
+            }
+        }
+    });
+
+    use_tray_menu(
+        cx,
+        Menu::new("")
+            .item(MenuItem::new("Show window").on_click({
+                to_owned![window];
+                move || window.set_visible(true)
+            }))
+            .item(MenuItem::new(format!("Clicked {clicks} times")).on_click({
+                to_owned![clicks, window];
+                move || {
+                    clicks.modify(|clicks| clicks + 1);
+                    window.set_tray_tooltip(format!("Clicked {} times", clicks.current() + 1));
+                }
+            }))
+            .separator()
+            .native(NativeMenuItem::Quit),
+    );
+
+    cx.render(rsx! {
+        h1 { "Close this window, the app keeps running in the tray" }
+    })
+}
@@ -8,6 +8,8 @@ mod bundle;
 pub use bundle::*;
 mod cargo;
 pub use cargo::*;
+mod updater;
+pub use updater::*;
 
 #[doc(hidden)]
 pub mod __private {
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// The release manifest that `dx bundle --updater` writes next to the signed bundles.
+///
+/// The desktop updater downloads this file to find out if there is a newer version of the app.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateManifest {
+    /// The version of the release
+    pub version: String,
+    /// Release notes to show to the user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// The date the release was published in RFC 3339 format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pub_date: Option<String>,
+    /// The bundle for each platform, keyed by [`update_target`]
+    pub platforms: HashMap<String, UpdatePlatform>,
+}
+
+/// The bundle of a release for one platform
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdatePlatform {
+    /// The URL of the bundle. Relative URLs are resolved against the URL of the manifest
+    pub url: String,
+    /// The hex encoded SHA-256 hash of the bundle, created with [`update_bundle_hash`]
+    pub sha256: String,
+    /// The base64 encoded ed25519 signature of the [`update_signature_message`] for this platform
+    pub signature: String,
+}
+
+/// The key of a platform in [`UpdateManifest::platforms`], like `linux-x86_64` or `macos-aarch64`
+pub fn update_target(os: &str, arch: &str) -> String {
+    format!("{os}-{arch}")
+}
+
+/// The hex encoded SHA-256 hash of a bundle
+pub fn update_bundle_hash(bundle: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bundle))
+}
+
+/// The message that is signed for each platform in the manifest.
+///
+/// It covers the version and platform as well as the bundle, so a signed bundle can't be offered as another version or
+/// for another platform, and an old manifest can't be replayed to downgrade the app.
+pub fn update_signature_message(version: &str, target: &str, sha256: &str) -> Vec<u8> {
+    format!("dioxus-update\nversion:{version}\ntarget:{target}\nsha256:{sha256}").into_bytes()
+}
@@ -13,6 +13,12 @@ use crate::{build_desktop, cfg::ConfigOptsBundle};
 pub struct Bundle {
     #[clap(long)]
     pub package: Option<Vec<String>>,
+    /// Sign the bundle and write a `latest.json` update manifest for the desktop updater
+    #[clap(long)]
+    pub updater: bool,
+    /// The file the key used to sign updates is stored in. A new key is generated if it doesn't exist
+    #[clap(long, default_value = "updater.key")]
+    pub updater_key: PathBuf,
     #[clap(flatten)]
     pub build: ConfigOptsBundle,
 }
@@ -149,6 +155,8 @@ impl Bundle {
             }
         }
 
+        let out_dir = crate_config.out_dir.clone();
+        let version = package.version().to_string();
         let mut settings = SettingsBuilder::new()
             .project_out_directory(crate_config.out_dir)
             .package_settings(PackageSettings {
@@ -180,13 +188,25 @@ impl Bundle {
         #[cfg(target_os = "macos")]
         std::env::set_var("CI", "true");
 
-        tauri_bundler::bundle::bundle_project(settings.unwrap()).unwrap_or_else(|err|{
+        let bundles = tauri_bundler::bundle::bundle_project(settings.unwrap()).unwrap_or_else(|err|{
             #[cfg(target_os = "macos")]
             panic!("Failed to bundle project: {:#?}\nMake sure you have automation enabled in your terminal (https://github.com/tauri-apps/tauri/issues/3055#issuecomment-1624389208) and full disk access enabled for your terminal (https://github.com/tauri-apps/tauri/issues/3055#issuecomment-1624389208)", err);
             #[cfg(not(target_os = "macos"))]
             panic!("Failed to bundle project: {:#?}", err);
         });
 
+        if self.updater {
+            let key = crate::updater::load_or_create_key(&self.updater_key)?;
+            let manifest = crate::updater::write_update_manifest(
+                &bundles,
+                &out_dir,
+                &version,
+                self.build.target.as_deref(),
+                &key,
+            )?;
+            log::info!("📝 Wrote the update manifest to {}", manifest.display());
+        }
+
         Ok(())
     }
 }
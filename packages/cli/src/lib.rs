@@ -8,6 +8,7 @@ mod assets;
 pub mod builder;
 pub mod server;
 pub mod tools;
+pub mod updater;
 
 pub use builder::*;
 
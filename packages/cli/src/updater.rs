@@ -0,0 +1,266 @@
+//! Sign bundles and write the release manifest that the desktop updater reads
+
+use crate::{Error, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use dioxus_cli_config::{
+    update_bundle_hash, update_signature_message, update_target, UpdateManifest, UpdatePlatform,
+};
+use ed25519_dalek::{Signer, SigningKey};
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+use tauri_bundler::{Bundle, PackageType};
+
+/// The name of the manifest that is written to the out directory
+pub const MANIFEST_NAME: &str = "latest.json";
+
+/// Load the signing key from `path`, or generate a new one there if it doesn't exist.
+///
+/// The key file holds the base64 encoded secret key. Keep it out of version control: anyone with the key can publish
+/// updates for the app. New key files can only be read by their owner.
+pub fn load_or_create_key(path: &Path) -> Result<SigningKey> {
+    if path.exists() {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(path)?.permissions().mode();
+            if mode & 0o077 != 0 {
+                log::warn!(
+                    "The updater key {} can be read by other users. Restrict it with `chmod 600 {}`",
+                    path.display(),
+                    path.display()
+                );
+            }
+        }
+
+        let contents = std::fs::read_to_string(path)?;
+        let bytes = STANDARD.decode(contents.trim()).map_err(|err| {
+            Error::Unique(format!("Invalid updater key {}: {err}", path.display()))
+        })?;
+        let bytes: [u8; 32] = bytes.try_into().map_err(|_| {
+            Error::Unique(format!(
+                "Invalid updater key {}: expected 32 bytes",
+                path.display()
+            ))
+        })?;
+        return Ok(SigningKey::from_bytes(&bytes));
+    }
+
+    let key = SigningKey::from_bytes(&rand::random::<[u8; 32]>());
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+        .open(path)?
+        .write_all(STANDARD.encode(key.to_bytes()).as_bytes())?;
+    log::info!("🔑 Generated a new updater key at {}", path.display());
+    log::info!(
+        "Pass this public key to the desktop updater: {}",
+        public_key(&key)
+    );
+    Ok(key)
+}
+
+/// The base64 encoded public key that verifies signatures made with `key`
+pub fn public_key(key: &SigningKey) -> String {
+    STANDARD.encode(key.verifying_key().to_bytes())
+}
+
+/// Sign the bundle the updater can install on this platform and add it to the manifest in `out_dir`.
+///
+/// The signature covers the version, the platform and the hash of the bundle. If the manifest already exists and is for
+/// the same version, the bundle is added to it so the manifest can be built up from bundles made on several platforms.
+pub fn write_update_manifest(
+    bundles: &[Bundle],
+    out_dir: &Path,
+    version: &str,
+    target_triple: Option<&str>,
+    key: &SigningKey,
+) -> Result<PathBuf> {
+    let target = target_triple
+        .map(target_from_triple)
+        .unwrap_or_else(|| update_target(std::env::consts::OS, std::env::consts::ARCH));
+
+    let bundle = updater_bundle(bundles)?;
+    let sha256 = update_bundle_hash(&std::fs::read(&bundle)?);
+    let signature = key.sign(&update_signature_message(version, &target, &sha256));
+    let signature = STANDARD.encode(signature.to_bytes());
+
+    let manifest_path = out_dir.join(MANIFEST_NAME);
+    let mut manifest = std::fs::read(&manifest_path)
+        .ok()
+        .and_then(|contents| serde_json::from_slice::<UpdateManifest>(&contents).ok())
+        .filter(|manifest| manifest.version == version)
+        .unwrap_or_else(|| UpdateManifest {
+            version: version.to_string(),
+            notes: None,
+            pub_date: None,
+            platforms: Default::default(),
+        });
+    manifest.pub_date = Some(chrono::Utc::now().to_rfc3339());
+    manifest.platforms.insert(
+        target,
+        UpdatePlatform {
+            url: relative_url(&bundle, out_dir),
+            sha256,
+            signature,
+        },
+    );
+
+    std::fs::write(
+        &manifest_path,
+        serde_json::to_vec_pretty(&manifest).map_err(|err| Error::Unique(err.to_string()))?,
+    )?;
+    Ok(manifest_path)
+}
+
+/// Find the bundle the updater can install: the AppImage on Linux, the msi on Windows and an archive of the app
+/// bundle on macOS
+fn updater_bundle(bundles: &[Bundle]) -> Result<PathBuf> {
+    let find = |package_type: PackageType, extension: &str| {
+        bundles
+            .iter()
+            .filter(|bundle| bundle.package_type == package_type)
+            .flat_map(|bundle| bundle.bundle_paths.iter())
+            .find(|path| path.extension().map_or(false, |ext| ext == extension))
+            .cloned()
+    };
+
+    if let Some(app_image) = find(PackageType::AppImage, "AppImage") {
+        return Ok(app_image);
+    }
+    if let Some(msi) = find(PackageType::WindowsMsi, "msi") {
+        return Ok(msi);
+    }
+    if let Some(app) = find(PackageType::MacOsBundle, "app") {
+        return archive_app(&app);
+    }
+
+    Err(Error::Unique(
+        "No bundle the updater can install was created. Bundle an AppImage on Linux, an msi on Windows or a macOS app bundle on macOS".to_string(),
+    ))
+}
+
+/// Compress a macOS app bundle into a `.app.tar.gz` next to it
+fn archive_app(app: &Path) -> Result<PathBuf> {
+    let name = app
+        .file_name()
+        .ok_or_else(|| Error::Unique(format!("Invalid app bundle {}", app.display())))?;
+    let mut archive_name = name.to_os_string();
+    archive_name.push(".tar.gz");
+    let archive_path = app.with_file_name(archive_name);
+
+    let encoder =
+        flate2::write::GzEncoder::new(File::create(&archive_path)?, flate2::Compression::default());
+    let mut archive = tar::Builder::new(encoder);
+    archive.append_dir_all(name, app)?;
+    archive.into_inner()?.finish()?;
+
+    Ok(archive_path)
+}
+
+/// Get the platform key of a target triple like `aarch64-apple-darwin`
+fn target_from_triple(triple: &str) -> String {
+    let arch = match triple.split('-').next().unwrap_or_default() {
+        "i586" | "i686" => "x86",
+        arch => arch,
+    };
+    let os = if triple.contains("windows") {
+        "windows"
+    } else if triple.contains("darwin") {
+        "macos"
+    } else if triple.contains("linux") {
+        "linux"
+    } else {
+        triple.split('-').nth(2).unwrap_or_default()
+    };
+    update_target(os, arch)
+}
+
+/// The URL of the bundle relative to the manifest, so the out directory can be uploaded anywhere
+fn relative_url(bundle: &Path, out_dir: &Path) -> String {
+    let relative = bundle.strip_prefix(out_dir).unwrap_or(bundle);
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ed25519_dalek::{Verifier, VerifyingKey};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_target_from_triple() {
+        assert_eq!(
+            target_from_triple("x86_64-unknown-linux-gnu"),
+            "linux-x86_64"
+        );
+        assert_eq!(target_from_triple("aarch64-apple-darwin"), "macos-aarch64");
+        assert_eq!(target_from_triple("i686-pc-windows-msvc"), "windows-x86");
+        assert_eq!(
+            target_from_triple("x86_64-unknown-freebsd"),
+            "freebsd-x86_64"
+        );
+    }
+
+    #[test]
+    fn test_relative_url() {
+        let out_dir = Path::new("dist").join("bundle");
+        assert_eq!(
+            relative_url(&out_dir.join("appimage").join("app.AppImage"), &out_dir),
+            "appimage/app.AppImage"
+        );
+        assert_eq!(relative_url(Path::new("app.msi"), &out_dir), "app.msi");
+    }
+
+    #[test]
+    fn test_load_or_create_key() -> anyhow::Result<()> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join("keys").join("updater.key");
+
+        let created = load_or_create_key(&path)?;
+        assert!(path.exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(
+                std::fs::metadata(&path)?.permissions().mode() & 0o777,
+                0o600
+            );
+        }
+        let loaded = load_or_create_key(&path)?;
+        assert_eq!(created.to_bytes(), loaded.to_bytes());
+
+        // The public key verifies signatures made with the key
+        let public_key: [u8; 32] = STANDARD.decode(public_key(&loaded))?.try_into().unwrap();
+        let signature = loaded.sign(b"bundle");
+        let verifying_key = VerifyingKey::from_bytes(&public_key)?;
+        assert!(verifying_key.verify(b"bundle", &signature).is_ok());
+        assert!(verifying_key.verify(b"tampered", &signature).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_key() -> anyhow::Result<()> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join("updater.key");
+
+        std::fs::write(&path, "not base64")?;
+        assert!(load_or_create_key(&path).is_err());
+        std::fs::write(&path, STANDARD.encode([0; 16]))?;
+        assert!(load_or_create_key(&path).is_err());
+
+        Ok(())
+    }
+}
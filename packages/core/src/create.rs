@@ -14,7 +14,7 @@ use std::iter::Peekable;
 use TemplateNode::*;
 
 #[cfg(debug_assertions)]
-fn sort_bfs(paths: &[&'static [u8]]) -> Vec<(usize, &'static [u8])> {
+pub(crate) fn sort_bfs(paths: &[&'static [u8]]) -> Vec<(usize, &'static [u8])> {
     let mut with_indecies = paths.iter().copied().enumerate().collect::<Vec<_>>();
     with_indecies.sort_unstable_by(|(_, a), (_, b)| {
         let mut a = a.iter();
@@ -16,6 +16,7 @@ mod lazynodes;
 mod mutations;
 mod nodes;
 mod properties;
+mod replay;
 mod runtime;
 mod scheduler;
 mod scope_arena;
@@ -0,0 +1,272 @@
+use crate::innerlude::{AttributeType, BorrowedAttributeValue, VComponent, VPlaceholder, VText};
+use crate::mutations::Mutation::*;
+use crate::nodes::{DynamicNode, TemplateNode, VNode};
+use crate::virtual_dom::VirtualDom;
+use crate::{AttributeValue, ElementId, Mutations, RenderReturn, ScopeId};
+use rustc_hash::FxHashSet;
+
+impl VirtualDom {
+    /// Write the mutations that create the current state of the VirtualDom from scratch.
+    ///
+    /// Unlike [`VirtualDom::rebuild`], this doesn't run any components or allocate new ids. The mutations reuse the ids of the existing nodes, so events from a renderer that applied them can still be handled. This is useful for renderers that lost their state and need to recreate the whole tree, like a liveview client that reconnects.
+    ///
+    /// The VirtualDom must already be built.
+    ///
+    /// ```rust
+    /// # use dioxus::prelude::*;
+    /// fn app(cx: Scope) -> Element {
+    ///     render! { div { "Hello, world!" } }
+    /// }
+    ///
+    /// let mut dom = VirtualDom::new(app);
+    /// let _ = dom.rebuild();
+    ///
+    /// let mutations = dom.replay();
+    /// assert!(!mutations.edits.is_empty());
+    /// ```
+    pub fn replay(&self) -> Mutations<'_> {
+        let mut replay = Replay {
+            dom: self,
+            seen_templates: Default::default(),
+            mutations: Mutations::default(),
+        };
+
+        match self.base_scope().root_node() {
+            RenderReturn::Ready(node) => {
+                let m = replay.vnode(node);
+                replay.mutations.edits.push(AppendChildren {
+                    id: ElementId(0),
+                    m,
+                });
+            }
+            RenderReturn::Aborted(placeholder) => replay.aborted(placeholder),
+        }
+
+        // Templates without instances in the tree right now can still be created by a later diff, so the renderer needs them too
+        for template in self
+            .templates
+            .values()
+            .flat_map(|templates| templates.values())
+        {
+            if !template.is_completely_dynamic() && replay.seen_templates.insert(template.name) {
+                replay.mutations.templates.push(*template);
+            }
+        }
+
+        replay.mutations
+    }
+
+    /// Write the mutations that create the current state of a single scope and all of its children from scratch.
+    ///
+    /// Like [`VirtualDom::replay`], the mutations reuse the ids of the existing nodes. The root nodes of the scope are appended to the root element ([`ElementId(0)`](ElementId)), so renderers can move them to where the scope should be mounted. This is useful for renderers that need to recreate part of the tree, like a web renderer that repairs a scope that was hydrated with the wrong HTML.
+    ///
+    /// Only the templates used by the scope are included. If the scope doesn't exist, no mutations are written.
+    ///
+    /// ```rust
+    /// # use dioxus::prelude::*;
+    /// fn app(cx: Scope) -> Element {
+    ///     render! { div { "Hello, world!" } }
+    /// }
+    ///
+    /// let mut dom = VirtualDom::new(app);
+    /// let _ = dom.rebuild();
+    ///
+    /// let mutations = dom.replay_scope(ScopeId::ROOT);
+    /// assert!(!mutations.edits.is_empty());
+    /// ```
+    pub fn replay_scope(&self, scope: ScopeId) -> Mutations<'_> {
+        let mut replay = Replay {
+            dom: self,
+            seen_templates: Default::default(),
+            mutations: Mutations::default(),
+        };
+
+        let m = replay.scope(scope);
+        if m > 0 {
+            replay.mutations.edits.push(AppendChildren {
+                id: ElementId(0),
+                m,
+            });
+        }
+
+        replay.mutations
+    }
+}
+
+struct Replay<'a> {
+    dom: &'a VirtualDom,
+    seen_templates: FxHashSet<&'static str>,
+    mutations: Mutations<'a>,
+}
+
+impl<'a> Replay<'a> {
+    fn scope(&mut self, scope: ScopeId) -> usize {
+        let scope = match self.dom.get_scope(scope) {
+            Some(scope) => scope,
+            None => return 0,
+        };
+        match scope.root_node() {
+            RenderReturn::Ready(node) => self.vnode(node),
+            RenderReturn::Aborted(placeholder) => {
+                self.aborted(placeholder);
+                1
+            }
+        }
+    }
+
+    fn aborted(&mut self, placeholder: &VPlaceholder) {
+        if let Some(id) = placeholder.id.get() {
+            self.mutations.edits.push(CreatePlaceholder { id });
+        }
+    }
+
+    /// Write the mutations for a node in the same order [`VirtualDom::rebuild`] creates them
+    fn vnode(&mut self, node: &'a VNode<'a>) -> usize {
+        let template = node.template.get();
+        if !template.is_completely_dynamic() && self.seen_templates.insert(template.name) {
+            self.mutations.templates.push(template);
+        }
+
+        #[cfg(not(debug_assertions))]
+        let (attrs, nodes): (Vec<_>, Vec<_>) = (
+            template.attr_paths.iter().copied().enumerate().collect(),
+            template.node_paths.iter().copied().enumerate().collect(),
+        );
+        #[cfg(debug_assertions)]
+        let (attrs, nodes) = (
+            crate::create::sort_bfs(template.attr_paths),
+            crate::create::sort_bfs(template.node_paths),
+        );
+
+        let root_ids = node.root_ids.borrow();
+        let mut created = 0;
+        for (root_idx, root) in template.roots.iter().enumerate() {
+            created += match root {
+                TemplateNode::DynamicText { id } | TemplateNode::Dynamic { id } => {
+                    self.dynamic_root(&node.dynamic_nodes[*id])
+                }
+                TemplateNode::Element { .. } | TemplateNode::Text { .. } => {
+                    let root_id = root_ids.get(root_idx).copied().unwrap_or_default();
+                    self.mutations.edits.push(LoadTemplate {
+                        name: template.name,
+                        index: root_idx,
+                        id: root_id,
+                    });
+
+                    // Write the attributes below this root
+                    let mut last_path = None;
+                    for (attr_idx, path) in attrs
+                        .iter()
+                        .filter(|(_, path)| path.first() == Some(&(root_idx as u8)))
+                    {
+                        let attribute = &node.dynamic_attrs[*attr_idx];
+                        let id = attribute.mounted_element.get();
+                        if path.len() > 1 && last_path != Some(*path) {
+                            self.mutations.edits.push(AssignId {
+                                path: &path[1..],
+                                id,
+                            });
+                        }
+                        last_path = Some(*path);
+                        match &attribute.ty {
+                            AttributeType::Single(attribute) => self.attribute(attribute, id),
+                            AttributeType::Many(attributes) => {
+                                for attribute in *attributes {
+                                    self.attribute(attribute, id);
+                                }
+                            }
+                        }
+                    }
+
+                    // Load the dynamic nodes below this root in reverse so the paths stay valid
+                    for (node_idx, path) in nodes
+                        .iter()
+                        .rev()
+                        .filter(|(_, path)| path.len() > 1 && path[0] == root_idx as u8)
+                    {
+                        let m = self.dynamic_node(&node.dynamic_nodes[*node_idx], &path[1..]);
+                        if m > 0 {
+                            self.mutations.edits.push(ReplacePlaceholder {
+                                m,
+                                path: &path[1..],
+                            });
+                        }
+                    }
+
+                    1
+                }
+            };
+        }
+        created
+    }
+
+    fn attribute(&mut self, attribute: &'a crate::Attribute<'a>, id: ElementId) {
+        match &attribute.value {
+            AttributeValue::Listener(_) => self.mutations.edits.push(NewEventListener {
+                // all listeners start with "on"
+                name: &attribute.name[2..],
+                id,
+            }),
+            value => {
+                let value: BorrowedAttributeValue<'a> = value.into();
+                self.mutations.edits.push(SetAttribute {
+                    name: attribute.name,
+                    value,
+                    ns: attribute.namespace,
+                    id,
+                })
+            }
+        }
+    }
+
+    fn dynamic_root(&mut self, node: &'a DynamicNode<'a>) -> usize {
+        match node {
+            DynamicNode::Text(VText { id, value }) => {
+                if let Some(id) = id.get() {
+                    self.mutations
+                        .edits
+                        .push(CreateTextNode { value: *value, id });
+                }
+                1
+            }
+            DynamicNode::Placeholder(VPlaceholder { id, .. }) => {
+                if let Some(id) = id.get() {
+                    self.mutations.edits.push(CreatePlaceholder { id });
+                }
+                1
+            }
+            DynamicNode::Component(component) => self.component(component),
+            DynamicNode::Fragment(children) => children.iter().map(|node| self.vnode(node)).sum(),
+        }
+    }
+
+    fn dynamic_node(&mut self, node: &'a DynamicNode<'a>, path: &'static [u8]) -> usize {
+        match node {
+            DynamicNode::Text(VText { id, value }) => {
+                if let Some(id) = id.get() {
+                    self.mutations.edits.push(HydrateText {
+                        path,
+                        value: *value,
+                        id,
+                    });
+                }
+                0
+            }
+            DynamicNode::Placeholder(VPlaceholder { id, .. }) => {
+                if let Some(id) = id.get() {
+                    self.mutations.edits.push(AssignId { path, id });
+                }
+                0
+            }
+            DynamicNode::Component(component) => self.component(component),
+            DynamicNode::Fragment(children) => children.iter().map(|node| self.vnode(node)).sum(),
+        }
+    }
+
+    fn component(&mut self, component: &VComponent) -> usize {
+        match component.scope.get() {
+            Some(scope) => self.scope(scope),
+            None => 0,
+        }
+    }
+}
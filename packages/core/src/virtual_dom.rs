@@ -304,6 +304,19 @@ impl VirtualDom {
         self.get_scope(ScopeId::ROOT).unwrap()
     }
 
+    /// Estimate the number of bytes the nodes of the VirtualDom use
+    ///
+    /// This counts the memory of the arenas every component renders into. Memory owned by hooks and contexts is not included.
+    pub fn allocated_bytes(&self) -> usize {
+        self.scopes
+            .iter()
+            .map(|(_, scope)| {
+                scope.node_arena_1.bump().allocated_bytes()
+                    + scope.node_arena_2.bump().allocated_bytes()
+            })
+            .sum()
+    }
+
     /// Build the virtualdom with a global context inserted into the base scope
     ///
     /// This is useful for what is essentially dependency injection when building the app
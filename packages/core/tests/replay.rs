@@ -0,0 +1,123 @@
+use dioxus::prelude::*;
+
+/// Replaying a VirtualDom should produce the same mutations as the rebuild that created it
+#[test]
+fn replay_matches_rebuild() {
+    fn app(cx: Scope) -> Element {
+        let count = 3;
+        let show = true;
+        cx.render(rsx! {
+            div { class: "root-{count}", onclick: |_| {},
+                "Count: {count}"
+                (0..count).map(|i| rsx! { Child { key: "{i}", id: i } }),
+                if show { rsx! { span { "shown" } } }
+                None::<Element>
+            }
+            "{count}"
+            Child { id: 10 }
+        })
+    }
+
+    #[component]
+    fn Child(cx: Scope, id: usize) -> Element {
+        cx.render(rsx! {
+            p { id: "{id}", "child {id}" }
+            button { onmouseenter: |_| {}, "hover" }
+        })
+    }
+
+    let mut dom = VirtualDom::new(app);
+    let (rebuild_edits, rebuild_templates) = {
+        let rebuild = dom.rebuild();
+        (
+            format!("{:?}", rebuild.edits),
+            format!("{:?}", rebuild.templates),
+        )
+    };
+
+    let replay = dom.replay();
+    assert_eq!(format!("{:?}", replay.edits), rebuild_edits);
+    assert_eq!(format!("{:?}", replay.templates), rebuild_templates);
+}
+
+/// Replaying should reflect the current state of the VirtualDom, not the first render
+#[test]
+fn replay_after_update() {
+    fn app(cx: Scope) -> Element {
+        let count = cx.generation();
+        cx.render(rsx! { div { "{count}" } })
+    }
+
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+    dom.mark_dirty(ScopeId::ROOT);
+    _ = dom.render_immediate();
+
+    let replay = dom.replay();
+    assert!(replay.edits.iter().any(|edit| matches!(
+        edit,
+        dioxus_core::Mutation::HydrateText { value, .. } if *value == "1"
+    )));
+}
+
+/// Replaying a scope should only create the nodes of that scope and its children
+#[test]
+fn replay_scope_creates_subtree() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            div { "parent" }
+            Child { id: 1 }
+        })
+    }
+
+    #[component]
+    fn Child(cx: Scope, id: usize) -> Element {
+        cx.render(rsx! {
+            p { id: "{id}", "child" }
+            Grandchild {}
+        })
+    }
+
+    fn Grandchild(cx: Scope) -> Element {
+        cx.render(rsx! { span { "grandchild" } })
+    }
+
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+
+    let child = ScopeId(1);
+    assert_eq!(dom.get_scope(child).unwrap().name(), "Child");
+
+    let replay = dom.replay_scope(child);
+    let loaded: Vec<_> = replay
+        .edits
+        .iter()
+        .filter_map(|edit| match edit {
+            dioxus_core::Mutation::LoadTemplate { name, id, .. } => Some((*name, *id)),
+            _ => None,
+        })
+        .collect();
+
+    // the child root and the grandchild root are created, but not the parent root
+    assert_eq!(loaded.len(), 2);
+    assert!(matches!(
+        replay.edits.last(),
+        Some(dioxus_core::Mutation::AppendChildren { id, m: 2 }) if *id == dioxus_core::ElementId(0)
+    ));
+    assert!(replay
+        .edits
+        .iter()
+        .any(|edit| matches!(edit, dioxus_core::Mutation::SetAttribute { name: "id", .. })));
+
+    // the ids are reused from the rebuild
+    let replay_all = dom.replay();
+    for (name, id) in loaded {
+        assert!(replay_all.edits.iter().any(|edit| matches!(
+            edit,
+            dioxus_core::Mutation::LoadTemplate { name: other, id: other_id, .. }
+                if *other == name && *other_id == id
+        )));
+    }
+
+    assert!(dom.replay_scope(ScopeId(100)).edits.is_empty());
+}
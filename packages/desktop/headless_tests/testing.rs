@@ -0,0 +1,42 @@
+use dioxus::prelude::*;
+use dioxus_desktop::testing::{launch_test, TestFailure};
+
+fn main() -> Result<(), TestFailure> {
+    launch_test(app, |window| async move {
+        let button = window.find_by_role("button").await?;
+        button.click().await?;
+        button.click().await?;
+        button.assert_text("Clicked 2 times").await?;
+
+        let input = window.find("#name").await?;
+        input.type_text("Dioxus").await?;
+        window.find_by_text("Hello, Dioxus!").await?;
+
+        let items = window.query_all("li").await?;
+        TestFailure::ensure(items.len() == 3, "the list should have three items")?;
+        items[1].assert_text("item 1").await?;
+
+        match window.find_by_text("not in the page").await {
+            Err(TestFailure::NotFound { .. }) => Ok(()),
+            _ => Err(TestFailure::Assertion(
+                "missing text should not be found".to_string(),
+            )),
+        }
+    })
+}
+
+fn app(cx: Scope) -> Element {
+    let mut clicks = use_state(cx, || 0);
+    let name = use_state(cx, String::new);
+
+    render! {
+        button { onclick: move |_| clicks += 1, "Clicked {clicks} times" }
+        input { id: "name", value: "{name}", oninput: move |event| name.set(event.value()) }
+        if !name.is_empty() {
+            rsx! { p { "Hello, {name}!" } }
+        }
+        ul {
+            (0..3).map(|i| rsx! { li { key: "{i}", "item {i}" } })
+        }
+    }
+}
@@ -1,10 +1,14 @@
 use crate::{
+    bridge::{BridgeInvoke, BridgeReturn},
     config::{Config, WindowCloseBehaviour},
     desktop_context::WindowEventHandlers,
     element::DesktopElement,
     file_upload::FileDialogRequest,
     ipc::IpcMessage,
     ipc::{EventData, UserWindowEvent},
+    menu::MenuHandlers,
+    multiwindow::{WindowContexts, WindowList, WindowMessages},
+    persistence::Storage,
     query::QueryResult,
     shortcut::{GlobalHotKeyEvent, ShortcutRegistry},
     webview::WebviewInstance,
@@ -54,6 +58,17 @@ pub struct SharedContext {
     pub(crate) event_handlers: WindowEventHandlers,
     pub(crate) pending_webviews: RefCell<Vec<WebviewInstance>>,
     pub(crate) shortcut_manager: ShortcutRegistry,
+    pub(crate) menu_handlers: MenuHandlers,
+    pub(crate) window_contexts: WindowContexts,
+    pub(crate) window_messages: WindowMessages,
+    pub(crate) window_list: WindowList,
+    /// Where persisted state is stored, if the app has a data directory
+    pub(crate) storage: Option<Storage>,
+    /// The icon of the app in the system tray, if the app has one
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub(crate) tray: RefCell<Option<tray_icon::TrayIcon>>,
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub(crate) tray_handlers: crate::tray::TrayHandlers,
     pub(crate) global_hotkey_channel: Receiver<GlobalHotKeyEvent>,
     pub(crate) proxy: EventLoopProxy<UserWindowEvent>,
     pub(crate) target: EventLoopWindowTarget<UserWindowEvent>,
@@ -62,6 +77,9 @@ pub struct SharedContext {
 impl<P: 'static> App<P> {
     pub fn new(cfg: Config, props: P, root: Component<P>) -> (EventLoop<UserWindowEvent>, Self) {
         let event_loop = EventLoopBuilder::<UserWindowEvent>::with_user_event().build();
+        let storage = Storage::new(cfg.data_dir.clone())
+            .map_err(|err| tracing::warn!("Persisted state is disabled: {err}"))
+            .ok();
 
         let app = Self {
             root,
@@ -75,6 +93,15 @@ impl<P: 'static> App<P> {
                 event_handlers: WindowEventHandlers::default(),
                 pending_webviews: Default::default(),
                 shortcut_manager: ShortcutRegistry::new(),
+                menu_handlers: MenuHandlers::default(),
+                window_contexts: WindowContexts::default(),
+                window_messages: WindowMessages::default(),
+                window_list: WindowList::default(),
+                storage,
+                #[cfg(not(any(target_os = "ios", target_os = "android")))]
+                tray: Default::default(),
+                #[cfg(not(any(target_os = "ios", target_os = "android")))]
+                tray_handlers: Default::default(),
                 global_hotkey_channel: GlobalHotKeyEvent::receiver().clone(),
                 proxy: event_loop.create_proxy(),
                 target: event_loop.clone(),
@@ -93,6 +120,12 @@ impl<P: 'static> App<P> {
         #[cfg(all(feature = "hot-reload", debug_assertions))]
         app.connect_hotreload();
 
+        #[cfg(not(any(target_os = "ios", target_os = "android")))]
+        {
+            app.connect_menu_events();
+            crate::tray::connect_tray_events(app.shared.proxy.clone());
+        }
+
         (event_loop, app)
     }
 
@@ -121,10 +154,33 @@ impl<P: 'static> App<P> {
         });
     }
 
+    /// Forward clicks on native menus to the event loop. Menu events come from another thread on some platforms
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub fn connect_menu_events(&self) {
+        let proxy = std::sync::Mutex::new(self.shared.proxy.clone());
+        muda::MenuEvent::set_event_handler(Some(move |event: muda::MenuEvent| {
+            let event = EventData::MenuEvent(event.id.0);
+            _ = proxy
+                .lock()
+                .unwrap()
+                .send_event(UserWindowEvent(event, unsafe { WindowId::dummy() }));
+        }));
+    }
+
+    pub fn handle_menu_event(&mut self, id: String) {
+        self.shared.menu_handlers.call(&id);
+    }
+
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub fn handle_tray_event(&mut self, event: crate::tray::TrayEvent) {
+        self.shared.tray_handlers.call(event);
+    }
+
     pub fn handle_new_window(&mut self) {
         for handler in self.shared.pending_webviews.borrow_mut().drain(..) {
             let id = handler.desktop_context.window.id();
             self.webviews.insert(id, handler);
+            self.shared.window_list.opened(id);
             _ = self
                 .shared
                 .proxy
@@ -135,9 +191,13 @@ impl<P: 'static> App<P> {
     pub fn handle_close_requested(&mut self, id: WindowId) {
         use WindowCloseBehaviour::*;
 
+        if let Some(webview) = self.webviews.get(&id) {
+            webview.save_window_state();
+        }
+
         match self.window_behavior {
             LastWindowExitsApp => {
-                self.webviews.remove(&id);
+                self.remove_webview(id);
                 if self.webviews.is_empty() {
                     self.control_flow = ControlFlow::Exit
                 }
@@ -151,13 +211,13 @@ impl<P: 'static> App<P> {
             }
 
             CloseWindow => {
-                self.webviews.remove(&id);
+                self.remove_webview(id);
             }
         }
     }
 
     pub fn window_destroyed(&mut self, id: WindowId) {
-        self.webviews.remove(&id);
+        self.remove_webview(id);
 
         if matches!(
             self.window_behavior,
@@ -170,7 +230,14 @@ impl<P: 'static> App<P> {
 
     pub fn handle_start_cause_init(&mut self) {
         let props = self.props.take().unwrap();
-        let cfg = self.cfg.take().unwrap();
+        #[allow(unused_mut)]
+        let mut cfg = self.cfg.take().unwrap();
+
+        // The tray icon has to exist before the first render so components can add a menu to it
+        #[cfg(not(any(target_os = "ios", target_os = "android")))]
+        if let Some(tray) = cfg.system_tray.take() {
+            *self.shared.tray.borrow_mut() = tray.build();
+        }
 
         self.is_visible_before_start = cfg.window.window.visible;
 
@@ -182,6 +249,7 @@ impl<P: 'static> App<P> {
 
         let id = webview.desktop_context.window.id();
         self.webviews.insert(id, webview);
+        self.shared.window_list.opened(id);
 
         _ = self
             .shared
@@ -189,6 +257,23 @@ impl<P: 'static> App<P> {
             .send_event(UserWindowEvent(EventData::Poll, id));
     }
 
+    /// Save the state of the windows that are still open when the app exits
+    pub fn handle_loop_destroyed(&mut self) {
+        for webview in self.webviews.values() {
+            webview.save_window_state();
+        }
+    }
+
+    /// Drop a window and tell the components that watch the window list
+    fn remove_webview(&mut self, id: WindowId) {
+        self.webviews.remove(&id);
+        self.shared.window_list.closed(id);
+    }
+
+    pub fn handle_window_messages(&mut self) {
+        self.shared.window_messages.deliver();
+    }
+
     pub fn handle_browser_open(&mut self, msg: IpcMessage) {
         if let Some(temp) = msg.params().as_object() {
             if temp.contains_key("href") {
@@ -206,10 +291,17 @@ impl<P: 'static> App<P> {
         view.desktop_context
             .window
             .set_visible(self.is_visible_before_start);
+
+        if let Some(on_initialize) = view.on_initialize.take() {
+            on_initialize(&view.dom);
+        }
     }
 
     pub fn handle_close_msg(&mut self, id: WindowId) {
-        self.webviews.remove(&id);
+        if let Some(webview) = self.webviews.get(&id) {
+            webview.save_window_state();
+        }
+        self.remove_webview(id);
 
         if self.webviews.is_empty() {
             self.control_flow = ControlFlow::Exit
@@ -228,6 +320,41 @@ impl<P: 'static> App<P> {
         view.desktop_context.query.send(result);
     }
 
+    pub fn handle_bridge_invoke_msg(&mut self, msg: IpcMessage, id: WindowId) {
+        let Ok(invoke) = serde_json::from_value::<BridgeInvoke>(msg.params())
+            .map_err(|err| tracing::error!("Error parsing bridge_invoke: {:?}", err))
+        else {
+            return;
+        };
+
+        let Some(view) = self.webviews.get(&id) else {
+            return;
+        };
+
+        // Run the handler as a task of the window so async handlers can await other work
+        let desktop = view.desktop_context.clone();
+        let task = desktop.bridge.invoke(invoke, desktop.clone());
+        view.dom.base_scope().spawn_forever(task);
+    }
+
+    pub fn handle_bridge_return_msg(&mut self, msg: IpcMessage, id: WindowId) {
+        let Ok(result) = serde_json::from_value::<BridgeReturn>(msg.params()) else {
+            return;
+        };
+
+        let Some(view) = self.webviews.get(&id) else {
+            return;
+        };
+
+        view.desktop_context.bridge.resolve(result);
+    }
+
+    pub fn handle_bridge_reset_msg(&mut self, id: WindowId) {
+        if let Some(view) = self.webviews.get(&id) {
+            view.desktop_context.bridge.reset();
+        }
+    }
+
     pub fn handle_user_event_msg(&mut self, msg: IpcMessage, id: WindowId) {
         let parsed_params = serde_json::from_value(msg.params())
             .map_err(|err| tracing::error!("Error parsing user_event: {:?}", err));
@@ -0,0 +1,279 @@
+//! A typed bridge between Rust and the JavaScript running in the webview
+//!
+//! JavaScript calls Rust handlers with `window.dioxus.invoke(name, args)` and Rust calls functions that JavaScript
+//! registered with `window.dioxus.expose(name, function)`. Both directions go through the ipc messages in [`crate::ipc`].
+
+use futures_channel::oneshot;
+use futures_util::future::LocalBoxFuture;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use slab::Slab;
+use std::{cell::RefCell, collections::HashMap, fmt::Display, future::Future, rc::Rc};
+use thiserror::Error;
+
+use crate::DesktopContext;
+
+/// The JavaScript side of the bridge. This runs before the page loads in every webview
+pub(crate) const BRIDGE_JS: &str = r#"
+window.dioxus = window.dioxus || {};
+(function (dioxus) {
+  let nextCall = 0;
+  const pending = new Map();
+  const exposed = new Map();
+  const post = (method, params) => window.ipc.postMessage(JSON.stringify({ method, params }));
+
+  // This page can't answer the calls Rust made to the page that was loaded before it
+  post("bridge_reset", {});
+
+  // Call a Rust handler and get a promise that resolves with its result
+  dioxus.invoke = (name, args) => new Promise((resolve, reject) => {
+    const id = nextCall++;
+    pending.set(id, { resolve, reject });
+    post("bridge_invoke", { id, name, args: args === undefined ? null : args });
+  });
+
+  // Let Rust call a function. The function may return a promise
+  dioxus.expose = (name, f) => {
+    exposed.set(name, f);
+  };
+
+  dioxus.unexpose = (name) => {
+    exposed.delete(name);
+  };
+
+  dioxus.__bridgeResolve = (id, result) => {
+    const call = pending.get(id);
+    if (call) {
+      pending.delete(id);
+      if ("Ok" in result) {
+        call.resolve(result.Ok);
+      } else {
+        call.reject(new Error(result.Err));
+      }
+    }
+  };
+
+  dioxus.__bridgeCall = async (id, name, args) => {
+    let result;
+    try {
+      const f = exposed.get(name);
+      if (!f) {
+        throw new Error(`No function named "${name}" is exposed to Rust`);
+      }
+      const value = await f(args);
+      result = { Ok: value === undefined ? null : value };
+    } catch (error) {
+      result = { Err: error instanceof Error ? error.message : String(error) };
+    }
+    post("bridge_return", { id, result });
+  };
+})(window.dioxus);
+"#;
+
+/// An error from a call across the bridge
+#[derive(Error, Debug)]
+pub enum BridgeError {
+    /// The arguments could not be converted to JSON
+    #[error("Error serializing the arguments: {0}")]
+    Serialize(serde_json::Error),
+    /// The result could not be converted from JSON to the expected type
+    #[error("Error deserializing the result: {0}")]
+    Deserialize(serde_json::Error),
+    /// The call could not be sent to the webview
+    #[error("Error sending the call to the webview: {0}")]
+    Send(String),
+    /// The JavaScript function threw an error or rejected
+    #[error("JavaScript error: {0}")]
+    Js(String),
+    /// The window closed before the call returned
+    #[error("The window closed before the call returned")]
+    Closed,
+    /// The webview reloaded or navigated to another page before the call returned
+    #[error("The page was reloaded before the call returned")]
+    Reloaded,
+}
+
+type BridgeHandler = Rc<dyn Fn(Value) -> LocalBoxFuture<'static, Result<Value, String>>>;
+
+/// A call from JavaScript to a Rust handler
+#[derive(Deserialize, Debug)]
+pub(crate) struct BridgeInvoke {
+    id: usize,
+    name: String,
+    #[serde(default)]
+    args: Value,
+}
+
+/// The result of a call from Rust to a JavaScript function
+#[derive(Deserialize, Debug)]
+pub(crate) struct BridgeReturn {
+    id: usize,
+    result: Result<Value, String>,
+}
+
+/// The handlers and pending calls of the bridge in one window
+#[derive(Default)]
+pub(crate) struct Bridge {
+    handlers: RefCell<HashMap<String, BridgeHandler>>,
+    pending: RefCell<Slab<oneshot::Sender<Result<Value, BridgeError>>>>,
+}
+
+impl Bridge {
+    /// Resolve a call to JavaScript with the result the webview sent back
+    pub(crate) fn resolve(&self, message: BridgeReturn) {
+        let sender = self.pending.borrow_mut().try_remove(message.id);
+        if let Some(sender) = sender {
+            _ = sender.send(message.result.map_err(BridgeError::Js));
+        }
+    }
+
+    /// Fail every pending call to JavaScript. The webview loaded a new page, so the calls will never return
+    pub(crate) fn reset(&self) {
+        let pending = std::mem::take(&mut *self.pending.borrow_mut());
+        for (_, sender) in pending {
+            _ = sender.send(Err(BridgeError::Reloaded));
+        }
+    }
+
+    /// Run the handler for a call from JavaScript. The future resolves the promise in the webview when it finishes
+    pub(crate) fn invoke(
+        &self,
+        message: BridgeInvoke,
+        desktop: DesktopContext,
+    ) -> impl Future<Output = ()> + 'static {
+        let BridgeInvoke { id, name, args } = message;
+        let handler = self.handlers.borrow().get(&name).cloned();
+
+        async move {
+            let result = match handler {
+                Some(handler) => handler(args).await,
+                None => Err(format!("No Rust handler named \"{name}\" is registered")),
+            };
+
+            let result = serde_json::to_string(&result).unwrap_or_else(|err| {
+                serde_json::to_string(&Err::<Value, _>(err.to_string())).unwrap()
+            });
+            if let Err(err) = desktop
+                .webview
+                .evaluate_script(&format!("window.dioxus.__bridgeResolve({id}, {result});"))
+            {
+                tracing::warn!("Failed to return the result of {name} to the webview: {err}");
+            }
+        }
+    }
+}
+
+impl crate::DesktopService {
+    /// Register a Rust handler that the JavaScript in this window can call with `window.dioxus.invoke(name, args)`.
+    ///
+    /// The arguments are deserialized from the value JavaScript passes and the promise in JavaScript resolves with
+    /// the serialized result of the handler, or rejects with the error message if the handler fails. Registering a
+    /// handler with the same name replaces the old one.
+    ///
+    /// ```rust, ignore
+    /// window().register_bridge_handler("add", |(a, b): (i32, i32)| async move { Ok::<_, String>(a + b) });
+    /// ```
+    ///
+    /// ```js
+    /// const sum = await window.dioxus.invoke("add", [1, 2]);
+    /// ```
+    ///
+    /// See [`crate::use_bridge_handler`] for a hook that removes the handler when the component is dropped.
+    pub fn register_bridge_handler<Args, R, E, F, Fut>(&self, name: impl Into<String>, handler: F)
+    where
+        Args: DeserializeOwned + 'static,
+        R: Serialize + 'static,
+        E: Display + 'static,
+        F: Fn(Args) -> Fut + 'static,
+        Fut: Future<Output = Result<R, E>> + 'static,
+    {
+        let name = name.into();
+        let handler_name = name.clone();
+        let handler: BridgeHandler = Rc::new(move |args: Value| -> LocalBoxFuture<'static, _> {
+            let args = serde_json::from_value::<Args>(args);
+            let handler_name = handler_name.clone();
+            let future = args.map(&handler);
+            Box::pin(async move {
+                let value = future
+                    .map_err(|err| format!("Invalid arguments for {handler_name}: {err}"))?
+                    .await
+                    .map_err(|err| err.to_string())?;
+                serde_json::to_value(value).map_err(|err| err.to_string())
+            })
+        });
+        self.bridge.handlers.borrow_mut().insert(name, handler);
+    }
+
+    /// Remove a Rust handler registered with [`Self::register_bridge_handler`]
+    pub fn remove_bridge_handler(&self, name: &str) {
+        self.bridge.handlers.borrow_mut().remove(name);
+    }
+
+    /// Call a JavaScript function that was registered with `window.dioxus.expose(name, function)`.
+    ///
+    /// The arguments are serialized and passed to the function as one value. If the function returns a promise, the
+    /// call resolves when the promise does. Errors the function throws are returned as [`BridgeError::Js`].
+    ///
+    /// ```rust, ignore
+    /// let title: String = window().call_js("setTitle", "New title").await?;
+    /// ```
+    pub async fn call_js<Args: Serialize, R: DeserializeOwned>(
+        &self,
+        name: &str,
+        args: Args,
+    ) -> Result<R, BridgeError> {
+        let args = serde_json::to_string(&args).map_err(BridgeError::Serialize)?;
+        let name_json = serde_json::to_string(name).map_err(BridgeError::Serialize)?;
+
+        let (tx, rx) = oneshot::channel();
+        let id = self.bridge.pending.borrow_mut().insert(tx);
+
+        if let Err(err) = self.webview.evaluate_script(&format!(
+            "window.dioxus.__bridgeCall({id}, {name_json}, {args});"
+        )) {
+            self.bridge.pending.borrow_mut().try_remove(id);
+            return Err(BridgeError::Send(err.to_string()));
+        }
+
+        let value = rx.await.map_err(|_| BridgeError::Closed)??;
+        serde_json::from_value(value).map_err(BridgeError::Deserialize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(bridge: &Bridge) -> oneshot::Receiver<Result<Value, BridgeError>> {
+        let (tx, rx) = oneshot::channel();
+        bridge.pending.borrow_mut().insert(tx);
+        rx
+    }
+
+    #[test]
+    fn reloads_fail_pending_calls() {
+        let bridge = Bridge::default();
+        let mut returned = call(&bridge);
+        let mut reloaded = call(&bridge);
+        bridge.resolve(BridgeReturn {
+            id: 0,
+            result: Ok(Value::from(1)),
+        });
+        bridge.reset();
+
+        assert!(matches!(returned.try_recv(), Ok(Some(Ok(value))) if value == 1));
+        assert!(matches!(
+            reloaded.try_recv(),
+            Ok(Some(Err(BridgeError::Reloaded)))
+        ));
+        assert!(bridge.pending.borrow().is_empty());
+
+        // The ids of the failed calls are reused for new calls
+        let mut next = call(&bridge);
+        bridge.resolve(BridgeReturn {
+            id: 0,
+            result: Err("failed".to_string()),
+        });
+        assert!(matches!(next.try_recv(), Ok(Some(Err(BridgeError::Js(err)))) if err == "failed"));
+    }
+}
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 use std::path::PathBuf;
 
-use dioxus_core::prelude::Component;
+use dioxus_core::{prelude::Component, VirtualDom};
 use tao::window::{Icon, WindowBuilder, WindowId};
 use wry::{
     http::{Request as HttpRequest, Response as HttpResponse},
@@ -34,6 +34,11 @@ pub struct Config {
     pub(crate) background_color: Option<(u8, u8, u8, u8)>,
     pub(crate) last_window_close_behaviour: WindowCloseBehaviour,
     pub(crate) enable_default_menu_bar: bool,
+    pub(crate) window_state_key: Option<String>,
+    /// Called with the virtual dom of the window once the page has loaded. This is how [`crate::testing`] starts tests
+    pub(crate) on_initialize: Option<Box<dyn FnOnce(&VirtualDom)>>,
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub(crate) system_tray: Option<crate::SystemTray>,
 }
 
 type DropHandler = Box<dyn Fn(WindowId, FileDropEvent) -> bool>;
@@ -69,6 +74,10 @@ impl Config {
             background_color: None,
             last_window_close_behaviour: WindowCloseBehaviour::LastWindowExitsApp,
             enable_default_menu_bar: true,
+            window_state_key: None,
+            on_initialize: None,
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            system_tray: None,
         }
     }
 
@@ -94,6 +103,15 @@ impl Config {
         self
     }
 
+    /// Add an icon for the app to the system tray.
+    ///
+    /// Add a menu to the tray icon with [`crate::use_tray_menu`] and handle clicks with [`crate::use_tray_event_handler`]. The tray icon is only created for the first window of the app.
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub fn with_system_tray(mut self, tray: crate::SystemTray) -> Self {
+        self.system_tray = Some(tray);
+        self
+    }
+
     /// set the directory from which assets will be searched in release mode
     pub fn with_resource_directory(mut self, path: impl Into<PathBuf>) -> Self {
         self.resource_dir = Some(path.into());
@@ -108,6 +126,17 @@ impl Config {
         self
     }
 
+    /// Save the size, position, maximized and fullscreen state and zoom level of the window when it closes and restore
+    /// them the next time a window with the same key opens.
+    ///
+    /// The state is stored in the data directory set with [`Self::with_data_directory`], or in a directory named after the
+    /// executable in the local data directory of the platform if no data directory is set. Use a different key for each kind
+    /// of window in the app.
+    pub fn with_window_state_persistence(mut self, key: impl Into<String>) -> Self {
+        self.window_state_key = Some(key.into());
+        self
+    }
+
     /// Set whether or not the right-click context menu should be disabled.
     pub fn with_disable_context_menu(mut self, disable: bool) -> Self {
         self.disable_context_menu = disable;
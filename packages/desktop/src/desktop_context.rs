@@ -1,6 +1,7 @@
 use crate::{
     app::SharedContext,
     assets::AssetHandlerRegistry,
+    bridge::Bridge,
     edits::EditQueue,
     ipc::{EventData, UserWindowEvent},
     query::QueryEngine,
@@ -15,7 +16,13 @@ use dioxus_core::{
 use dioxus_interpreter_js::binary_protocol::Channel;
 use rustc_hash::FxHashMap;
 use slab::Slab;
-use std::{cell::RefCell, fmt::Debug, rc::Rc, rc::Weak, sync::atomic::AtomicU16};
+use std::{
+    cell::{Cell, RefCell},
+    fmt::Debug,
+    rc::Rc,
+    rc::Weak,
+    sync::atomic::AtomicU16,
+};
 use tao::{
     event::Event,
     event_loop::EventLoopWindowTarget,
@@ -66,6 +73,12 @@ pub struct DesktopService {
     pub(crate) max_template_count: AtomicU16,
     pub(crate) channel: RefCell<Channel>,
     pub(crate) asset_handlers: AssetHandlerRegistry,
+    pub(crate) bridge: Bridge,
+    pub(crate) zoom_level: Cell<f64>,
+
+    /// The default menu bar of the window, until a component replaces it with [`crate::use_menu_bar`]
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub(crate) default_menu_bar: RefCell<Option<muda::Menu>>,
 
     #[cfg(target_os = "ios")]
     pub(crate) views: Rc<RefCell<Vec<*mut objc::runtime::Object>>>,
@@ -95,6 +108,10 @@ impl DesktopService {
             edit_queue,
             asset_handlers,
             query: Default::default(),
+            bridge: Default::default(),
+            zoom_level: Cell::new(1.0),
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            default_menu_bar: Default::default(),
             templates: Default::default(),
             max_template_count: Default::default(),
             channel: Default::default(),
@@ -196,6 +213,7 @@ impl DesktopService {
     /// Set the zoom level of the webview
     pub fn set_zoom_level(&self, level: f64) {
         self.webview.zoom(level);
+        self.zoom_level.set(level);
     }
 
     /// opens DevTool window
@@ -1,7 +1,13 @@
 #![allow(unused)]
 
+use crate::DesktopService;
+use dioxus_html::{native_bind::NativeFileEngine, FileEngine};
 use serde::Deserialize;
-use std::{path::PathBuf, str::FromStr};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct FileDialogRequest {
@@ -124,3 +130,308 @@ impl FromStr for Filters {
         }
     }
 }
+
+/// The options of a native file dialog opened with [`DesktopService::open_file_dialog`], [`DesktopService::save_file_dialog`] or [`DesktopService::pick_folder`]
+///
+/// ```rust, ignore
+/// let dialog = FileDialog::new()
+///     .with_title("Open an image")
+///     .with_filter("Images", &["png", "jpg"])
+///     .with_multiple(true);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FileDialog {
+    title: Option<String>,
+    filters: Vec<(String, Vec<String>)>,
+    directory: Option<PathBuf>,
+    file_name: Option<String>,
+    multiple: bool,
+}
+
+impl FileDialog {
+    /// Create a dialog with the default options of the platform
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the title of the dialog
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Only show files with one of the extensions. The extensions don't include the dot. Adding several filters lets the user choose between them
+    pub fn with_filter(mut self, name: impl Into<String>, extensions: &[&str]) -> Self {
+        self.filters.push((
+            name.into(),
+            extensions.iter().map(|ext| ext.to_string()).collect(),
+        ));
+        self
+    }
+
+    /// Only show files that match the `accept` attribute of an `<input type="file">`, like `image/*,.pdf`
+    pub fn with_accept(self, accept: &str) -> Self {
+        let extensions: Vec<_> = accept
+            .split(',')
+            .filter_map(|s| Filters::from_str(s.trim()).ok())
+            .flat_map(|filter| {
+                filter
+                    .as_extensions()
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        if extensions.is_empty() {
+            return self;
+        }
+        let extensions: Vec<_> = extensions.iter().map(String::as_str).collect();
+        self.with_filter(accept, &extensions)
+    }
+
+    /// Set the directory the dialog opens in
+    pub fn with_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// Set the file name the dialog suggests. This is mostly useful for save dialogs
+    pub fn with_file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    /// Let the user select more than one file or folder
+    pub fn with_multiple(mut self, multiple: bool) -> Self {
+        self.multiple = multiple;
+        self
+    }
+}
+
+#[cfg(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+impl FileDialog {
+    fn build(&self, window: &tao::window::Window) -> rfd::AsyncFileDialog {
+        let mut dialog = rfd::AsyncFileDialog::new().set_parent(window);
+        if let Some(title) = &self.title {
+            dialog = dialog.set_title(title);
+        }
+        for (name, extensions) in &self.filters {
+            dialog = dialog.add_filter(name, extensions.as_slice());
+        }
+        if let Some(directory) = &self.directory {
+            dialog = dialog.set_directory(directory);
+        }
+        if let Some(file_name) = &self.file_name {
+            dialog = dialog.set_file_name(file_name);
+        }
+        dialog
+    }
+
+    async fn pick_files(&self, window: &tao::window::Window) -> Vec<PathBuf> {
+        let dialog = self.build(window);
+        let files = if self.multiple {
+            dialog.pick_files().await.unwrap_or_default()
+        } else {
+            dialog.pick_file().await.into_iter().collect()
+        };
+        files.iter().map(|file| file.path().to_path_buf()).collect()
+    }
+
+    async fn pick_folders(&self, window: &tao::window::Window) -> Vec<PathBuf> {
+        let dialog = self.build(window);
+        let folders = if self.multiple {
+            dialog.pick_folders().await.unwrap_or_default()
+        } else {
+            dialog.pick_folder().await.into_iter().collect()
+        };
+        folders
+            .iter()
+            .map(|folder| folder.path().to_path_buf())
+            .collect()
+    }
+
+    async fn save_file(&self, window: &tao::window::Window) -> Option<PathBuf> {
+        let file = self.build(window).save_file().await?;
+        Some(file.path().to_path_buf())
+    }
+}
+
+// Mobile platforms don't have native file dialogs, so every dialog is cancelled
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+impl FileDialog {
+    async fn pick_files(&self, _: &tao::window::Window) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    async fn pick_folders(&self, _: &tao::window::Window) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    async fn save_file(&self, _: &tao::window::Window) -> Option<PathBuf> {
+        None
+    }
+}
+
+/// Add every file in a folder and its subfolders to `files`.
+///
+/// Symlinks to folders are skipped so a link to a parent folder can't make this recurse forever. Symlinks to files are kept.
+fn collect_files(folder: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(folder) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_files(&path, files);
+        } else if file_type.is_file() || path.is_file() {
+            files.push(path);
+        }
+    }
+}
+
+impl DesktopService {
+    /// Open a native dialog to pick files. Returns `None` if the user cancels the dialog.
+    ///
+    /// The files are returned as the same [`FileEngine`] that `<input type="file">` events have, so code that reads
+    /// uploaded files works with both.
+    ///
+    /// ```rust, ignore
+    /// cx.spawn(async move {
+    ///     if let Some(files) = window().open_file_dialog(FileDialog::new().with_multiple(true)).await {
+    ///         for file in files.files() {
+    ///             let contents = files.read_file_to_string(&file).await;
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    pub async fn open_file_dialog(&self, dialog: FileDialog) -> Option<Arc<dyn FileEngine>> {
+        let files = dialog.pick_files(&self.window).await;
+        if files.is_empty() {
+            return None;
+        }
+        Some(Arc::new(NativeFileEngine::new(files)))
+    }
+
+    /// Open a native dialog to pick folders. Returns `None` if the user cancels the dialog.
+    ///
+    /// Like an `<input type="file" webkitdirectory>` on the web, the returned [`FileEngine`] has every file in the picked
+    /// folders and their subfolders.
+    pub async fn pick_folder(&self, dialog: FileDialog) -> Option<Arc<dyn FileEngine>> {
+        let folders = dialog.pick_folders(&self.window).await;
+        if folders.is_empty() {
+            return None;
+        }
+        let mut files = Vec::new();
+        for folder in &folders {
+            collect_files(folder, &mut files);
+        }
+        Some(Arc::new(NativeFileEngine::new(files)))
+    }
+
+    /// Open a native dialog to choose where to save a file. Returns the chosen path or `None` if the user cancels the
+    /// dialog. The file is not created.
+    pub async fn save_file_dialog(&self, dialog: FileDialog) -> Option<PathBuf> {
+        dialog.save_file(&self.window).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filters(dialog: &FileDialog) -> Vec<(&str, Vec<&str>)> {
+        dialog
+            .filters
+            .iter()
+            .map(|(name, extensions)| {
+                (
+                    name.as_str(),
+                    extensions.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn accept_maps_to_extension_filters() {
+        let dialog = FileDialog::new().with_accept("image/*, .pdf");
+        assert_eq!(
+            filters(&dialog),
+            [(
+                "image/*, .pdf",
+                vec!["png", "jpg", "jpeg", "gif", "webp", "pdf"]
+            )]
+        );
+
+        // Mime types without known extensions don't restrict the dialog
+        let dialog = FileDialog::new().with_accept("application/json");
+        assert!(dialog.filters.is_empty());
+    }
+
+    #[test]
+    fn picked_folders_include_nested_files() {
+        let dir = std::env::temp_dir().join(format!("dioxus-file-dialog-{}", std::process::id()));
+        _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("nested/empty")).unwrap();
+        std::fs::write(dir.join("a.txt"), "a").unwrap();
+        std::fs::write(dir.join("nested/b.txt"), "b").unwrap();
+
+        let mut files = Vec::new();
+        collect_files(&dir, &mut files);
+        files.sort();
+        assert_eq!(files, [dir.join("a.txt"), dir.join("nested/b.txt")]);
+
+        let mut files = Vec::new();
+        collect_files(&dir.join("missing"), &mut files);
+        assert!(files.is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_folders_are_not_followed() {
+        let dir = std::env::temp_dir().join(format!("dioxus-file-links-{}", std::process::id()));
+        _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("a.txt"), "a").unwrap();
+        std::fs::write(dir.join("nested/b.txt"), "b").unwrap();
+        // A link back to the picked folder would recurse forever if it was followed
+        std::os::unix::fs::symlink(&dir, dir.join("nested/loop")).unwrap();
+        std::os::unix::fs::symlink(dir.join("a.txt"), dir.join("link.txt")).unwrap();
+        std::os::unix::fs::symlink(dir.join("missing.txt"), dir.join("broken.txt")).unwrap();
+
+        let mut files = Vec::new();
+        collect_files(&dir, &mut files);
+        files.sort();
+        assert_eq!(
+            files,
+            [
+                dir.join("a.txt"),
+                dir.join("link.txt"),
+                dir.join("nested/b.txt")
+            ]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,9 +1,14 @@
 use crate::{
-    assets::*, ipc::UserWindowEvent, shortcut::IntoAccelerator, window, DesktopContext,
-    ShortcutHandle, ShortcutRegistryError, WryEventHandler,
+    assets::*, ipc::UserWindowEvent, multiwindow::WindowListSubscription,
+    shortcut::IntoAccelerator, window, ContextMenuHandle, DesktopContext, Menu, MenuBar,
+    ShortcutHandle, ShortcutRegistryError, UsePersisted, WindowMessageHandler, WryEventHandler,
 };
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+use crate::{TrayEvent, TrayEventHandler};
 use dioxus_core::ScopeState;
-use tao::{event::Event, event_loop::EventLoopWindowTarget};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{cell::RefCell, fmt::Display, future::Future, rc::Rc};
+use tao::{event::Event, event_loop::EventLoopWindowTarget, window::WindowId};
 use wry::RequestAsyncResponder;
 
 /// Get an imperative handle to the current window
@@ -13,6 +18,42 @@ pub fn use_window(cx: &ScopeState) -> &DesktopContext {
         .unwrap()
 }
 
+/// Get the ids of the open windows in the order they were opened. The component re-renders when a window opens or closes.
+pub fn use_window_list(cx: &ScopeState) -> Vec<WindowId> {
+    cx.use_hook(|| {
+        let list = window().shared.window_list.clone();
+        let id = list.subscribe(cx.schedule_update());
+        WindowListSubscription { list, id }
+    })
+    .list
+    .windows()
+}
+
+/// Handle messages of type `T` that windows send to this window with [`crate::DesktopService::send_message`] or
+/// [`crate::DesktopService::broadcast_message`]. The handler gets the message and the id of the window that sent it.
+///
+/// The handler is removed when the component is dropped.
+///
+/// ```rust, ignore
+/// #[derive(Clone)]
+/// struct Refresh;
+///
+/// use_window_message_handler(cx, move |_: Refresh, _from| todos.needs_update());
+/// ```
+pub fn use_window_message_handler<T: Clone + 'static>(
+    cx: &ScopeState,
+    handler: impl FnMut(T, WindowId) + 'static,
+) -> &WindowMessageHandler {
+    cx.use_hook(move || {
+        let desktop = window();
+        WindowMessageHandler::new(
+            desktop.shared.window_messages.clone(),
+            desktop.window.id(),
+            handler,
+        )
+    })
+}
+
 /// Get a closure that executes any JavaScript in the WebView context.
 pub fn use_wry_event_handler(
     cx: &ScopeState,
@@ -58,6 +99,68 @@ pub fn use_asset_handler(
     }
 }
 
+/// Register a Rust handler that the JavaScript in this window can call with `window.dioxus.invoke(name, args)`.
+///
+/// The handler is removed when the component is dropped. See [`crate::DesktopService::register_bridge_handler`] for more details.
+pub fn use_bridge_handler<Args, R, E, F, Fut>(cx: &ScopeState, name: &str, handler: F)
+where
+    Args: DeserializeOwned + 'static,
+    R: Serialize + 'static,
+    E: Display + 'static,
+    F: Fn(Args) -> Fut + 'static,
+    Fut: Future<Output = Result<R, E>> + 'static,
+{
+    let mut handler = Some(handler);
+    let registered = cx.use_hook(|| {
+        let desktop = window();
+        // The registered handler calls the handler from the latest render
+        let current = Rc::new(RefCell::new(handler.take().unwrap()));
+        desktop.register_bridge_handler(name, {
+            let current = current.clone();
+            move |args: Args| (current.borrow())(args)
+        });
+
+        Handler {
+            desktop,
+            name: name.to_string(),
+            current,
+        }
+    });
+    if let Some(handler) = handler {
+        *registered.current.borrow_mut() = handler;
+    }
+
+    struct Handler<F> {
+        desktop: DesktopContext,
+        name: String,
+        current: Rc<RefCell<F>>,
+    }
+    impl<F> Drop for Handler<F> {
+        fn drop(&mut self) {
+            self.desktop.remove_bridge_handler(&self.name);
+        }
+    }
+}
+
+/// Get a setting that is saved to the data directory of the app, or the default value if it was never saved.
+///
+/// Every change made with [`crate::UsePersisted::set`] or [`crate::UsePersisted::with_mut`] is written to disk, so the setting
+/// keeps its value when the app restarts. Components that use the same key don't share the value while the app is running.
+///
+/// ```rust, ignore
+/// let theme = use_persisted::<String>(cx, "theme");
+///
+/// cx.render(rsx! {
+///     button { onclick: move |_| theme.set("dark".to_string()), "Current theme: {theme.read()}" }
+/// })
+/// ```
+pub fn use_persisted<'a, T: Serialize + DeserializeOwned + Default + 'static>(
+    cx: &'a ScopeState,
+    key: &str,
+) -> &'a UsePersisted<T> {
+    cx.use_hook(|| UsePersisted::new(window(), key, cx.schedule_update()))
+}
+
 /// Get a closure that executes any JavaScript in the WebView context.
 pub fn use_global_shortcut(
     cx: &ScopeState,
@@ -75,3 +178,102 @@ pub fn use_global_shortcut(
         })
     })
 }
+
+/// Set the menu bar of the current window from the state of the component.
+///
+/// Call this every render. The native menu bar is updated to match the latest menu bar and clicks call the closures from the latest render. The menu bar is removed when the component is dropped.
+///
+/// On macOS the menu bar is shared by every window of the app.
+#[allow(unused)]
+pub fn use_menu_bar(cx: &ScopeState, menu_bar: MenuBar) {
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    cx.use_hook(|| crate::menu::NativeMenu::new(window(), crate::menu::MenuKind::Bar))
+        .update(menu_bar.into_entries());
+}
+
+/// Create a context menu from the state of the component.
+///
+/// Call this every render and open the menu from an `oncontextmenu` handler:
+///
+/// ```rust, ignore
+/// let menu = use_context_menu(cx, Menu::new("").item(MenuItem::new("Delete").on_click(move || delete())));
+///
+/// cx.render(rsx! {
+///     div {
+///         prevent_default: "oncontextmenu",
+///         oncontextmenu: move |_| menu.show(),
+///         "Right click me"
+///     }
+/// })
+/// ```
+#[allow(unused)]
+pub fn use_context_menu(cx: &ScopeState, menu: Menu) -> &ContextMenuHandle {
+    let handle = cx.use_hook(|| ContextMenuHandle {
+        #[cfg(not(any(target_os = "ios", target_os = "android")))]
+        menu: std::rc::Rc::new(std::cell::RefCell::new(crate::menu::NativeMenu::new(
+            window(),
+            crate::menu::MenuKind::Context,
+        ))),
+    });
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    handle.menu.borrow_mut().update(menu.into_entries());
+    handle
+}
+
+/// Set the menu of the tray icon from the state of the component. The tray icon is added with [`crate::Config::with_system_tray`].
+///
+/// Call this every render. The native menu is updated to match the latest menu and clicks call the closures from the latest render. The menu is removed when the component is dropped.
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub fn use_tray_menu(cx: &ScopeState, menu: Menu) {
+    cx.use_hook(|| crate::menu::NativeMenu::new(window(), crate::menu::MenuKind::Tray))
+        .update(menu.into_entries());
+}
+
+/// Handle clicks on the tray icon. The tray icon is added with [`crate::Config::with_system_tray`].
+///
+/// The handler is removed when the component is dropped.
+///
+/// ```rust, ignore
+/// use_tray_event_handler(cx, move |event| {
+///     if event.click == TrayClick::Double {
+///         window().set_visible(true);
+///     }
+/// });
+/// ```
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub fn use_tray_event_handler(
+    cx: &ScopeState,
+    handler: impl FnMut(TrayEvent) + 'static,
+) -> &TrayEventHandler {
+    cx.use_hook(move || TrayEventHandler::new(window().shared.tray_handlers.clone(), handler))
+}
+
+/// Check for and download updates of the app in the background. The component re-renders when the status of the update changes.
+///
+/// ```rust, ignore
+/// let updater = use_updater(cx, || {
+///     Updater::new(MANIFEST_URL, PUBLIC_KEY, env!("CARGO_PKG_VERSION")).expect("invalid updater config")
+/// });
+///
+/// cx.render(match updater.status() {
+///     UpdateStatus::Available(update) => rsx! {
+///         button { onclick: move |_| updater.download(), "Download version {update.version}" }
+///     },
+///     UpdateStatus::Downloading(progress) => rsx! { "Downloaded {progress.downloaded} bytes" },
+///     UpdateStatus::Ready(_) => rsx! {
+///         button { onclick: move |_| _ = updater.install(), "Restart to update" }
+///     },
+///     _ => rsx! { button { onclick: move |_| updater.check(), "Check for updates" } },
+/// })
+/// ```
+#[cfg(feature = "updater")]
+pub fn use_updater(
+    cx: &ScopeState,
+    updater: impl FnOnce() -> crate::updater::Updater,
+) -> &crate::updater::UseUpdater {
+    cx.use_hook(|| {
+        let (handle, task) = crate::updater::UseUpdater::new(updater(), cx.schedule_update());
+        cx.push_future(task);
+        handle
+    })
+}
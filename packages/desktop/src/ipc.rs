@@ -23,6 +23,16 @@ pub enum EventData {
 
     /// Close a given window (could be any window!)
     CloseWindow,
+
+    /// A window sent a message to other windows
+    WindowMessage,
+
+    /// A native menu item was clicked, with the id of the item
+    MenuEvent(String),
+
+    /// The tray icon was clicked
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    TrayEvent(crate::tray::TrayEvent),
 }
 
 /// A message struct that manages the communication between the webview and the eventloop code
@@ -42,6 +52,9 @@ pub enum IpcMethod<'a> {
     Query,
     BrowserOpen,
     Initialize,
+    BridgeInvoke,
+    BridgeReturn,
+    BridgeReset,
     Other(&'a str),
 }
 
@@ -54,6 +67,9 @@ impl IpcMessage {
             "query" => IpcMethod::Query,
             "browser_open" => IpcMethod::BrowserOpen,
             "initialize" => IpcMethod::Initialize,
+            "bridge_invoke" => IpcMethod::BridgeInvoke,
+            "bridge_return" => IpcMethod::BridgeReturn,
+            "bridge_reset" => IpcMethod::BridgeReset,
             _ => IpcMethod::Other(&self.method),
         }
     }
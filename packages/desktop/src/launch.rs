@@ -98,35 +98,47 @@ pub fn launch_with_props_blocking<P: 'static>(root: Component<P>, props: P, cfg:
     let (event_loop, mut app) = App::new(cfg, props, root);
 
     event_loop.run(move |window_event, _, control_flow| {
-        app.tick(&window_event);
-
-        match window_event {
-            Event::NewEvents(StartCause::Init) => app.handle_start_cause_init(),
-            Event::WindowEvent {
-                event, window_id, ..
-            } => match event {
-                WindowEvent::CloseRequested => app.handle_close_requested(window_id),
-                WindowEvent::Destroyed { .. } => app.window_destroyed(window_id),
-                _ => {}
-            },
-            Event::UserEvent(UserWindowEvent(event, id)) => match event {
-                EventData::Poll => app.poll_vdom(id),
-                EventData::NewWindow => app.handle_new_window(),
-                EventData::CloseWindow => app.handle_close_msg(id),
-                #[cfg(feature = "hot-reload")]
-                EventData::HotReloadEvent(msg) => app.handle_hot_reload_msg(msg),
-                EventData::Ipc(msg) => match msg.method() {
-                    IpcMethod::FileDialog => app.handle_file_dialog_msg(msg, id),
-                    IpcMethod::UserEvent => app.handle_user_event_msg(msg, id),
-                    IpcMethod::Query => app.handle_query_msg(msg, id),
-                    IpcMethod::BrowserOpen => app.handle_browser_open(msg),
-                    IpcMethod::Initialize => app.handle_initialize_msg(id),
-                    IpcMethod::Other(_) => {}
-                },
-            },
-            _ => {}
-        }
-
+        handle_event(&mut app, window_event);
         *control_flow = app.control_flow;
     })
 }
+
+/// Handle an event from the event loop. The control flow the event loop should use next is in [`App::control_flow`]
+pub(crate) fn handle_event<P: 'static>(app: &mut App<P>, window_event: Event<'_, UserWindowEvent>) {
+    app.tick(&window_event);
+
+    match window_event {
+        Event::NewEvents(StartCause::Init) => app.handle_start_cause_init(),
+        Event::LoopDestroyed => app.handle_loop_destroyed(),
+        Event::WindowEvent {
+            event, window_id, ..
+        } => match event {
+            WindowEvent::CloseRequested => app.handle_close_requested(window_id),
+            WindowEvent::Destroyed { .. } => app.window_destroyed(window_id),
+            _ => {}
+        },
+        Event::UserEvent(UserWindowEvent(event, id)) => match event {
+            EventData::Poll => app.poll_vdom(id),
+            EventData::NewWindow => app.handle_new_window(),
+            EventData::CloseWindow => app.handle_close_msg(id),
+            EventData::WindowMessage => app.handle_window_messages(),
+            EventData::MenuEvent(menu_id) => app.handle_menu_event(menu_id),
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            EventData::TrayEvent(event) => app.handle_tray_event(event),
+            #[cfg(feature = "hot-reload")]
+            EventData::HotReloadEvent(msg) => app.handle_hot_reload_msg(msg),
+            EventData::Ipc(msg) => match msg.method() {
+                IpcMethod::FileDialog => app.handle_file_dialog_msg(msg, id),
+                IpcMethod::UserEvent => app.handle_user_event_msg(msg, id),
+                IpcMethod::Query => app.handle_query_msg(msg, id),
+                IpcMethod::BrowserOpen => app.handle_browser_open(msg),
+                IpcMethod::Initialize => app.handle_initialize_msg(id),
+                IpcMethod::BridgeInvoke => app.handle_bridge_invoke_msg(msg, id),
+                IpcMethod::BridgeReturn => app.handle_bridge_return_msg(msg, id),
+                IpcMethod::BridgeReset => app.handle_bridge_reset_msg(id),
+                IpcMethod::Other(_) => {}
+            },
+        },
+        _ => {}
+    }
+}
@@ -5,6 +5,7 @@
 
 mod app;
 mod assets;
+mod bridge;
 mod config;
 mod desktop_context;
 mod edits;
@@ -15,10 +16,17 @@ mod events;
 mod file_upload;
 mod hooks;
 mod ipc;
+mod menu;
 mod menubar;
+mod multiwindow;
+mod persistence;
 mod protocol;
 mod query;
 mod shortcut;
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+mod tray;
+#[cfg(feature = "updater")]
+pub mod updater;
 mod waker;
 mod webview;
 
@@ -33,19 +41,39 @@ mod mobile_shortcut;
 pub use launch::*;
 mod launch;
 
+// Integration tests for components in a real webview
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub mod testing;
+
 // Reexport tao and wry, might want to re-export other important things
 pub use tao;
 pub use tao::dpi::{LogicalPosition, LogicalSize};
 pub use tao::event::WindowEvent;
 pub use tao::window::WindowBuilder;
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub use tray_icon;
 pub use wry;
 
 // Public exports
 pub use assets::AssetRequest;
+pub use bridge::BridgeError;
 pub use config::{Config, WindowCloseBehaviour};
 pub use desktop_context::{
     window, DesktopContext, DesktopService, WryEventHandler, WryEventHandlerId,
 };
-pub use hooks::{use_asset_handler, use_global_shortcut, use_window, use_wry_event_handler};
+pub use file_upload::FileDialog;
+#[cfg(feature = "updater")]
+pub use hooks::use_updater;
+pub use hooks::{
+    use_asset_handler, use_bridge_handler, use_context_menu, use_global_shortcut, use_menu_bar,
+    use_persisted, use_window, use_window_list, use_window_message_handler, use_wry_event_handler,
+};
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub use hooks::{use_tray_event_handler, use_tray_menu};
+pub use menu::{ContextMenuHandle, Menu, MenuBar, MenuItem, NativeMenuItem};
+pub use multiwindow::WindowMessageHandler;
+pub use persistence::UsePersisted;
 pub use shortcut::{ShortcutHandle, ShortcutId, ShortcutRegistryError};
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub use tray::{SystemTray, TrayClick, TrayEvent, TrayEventHandler};
 pub use wry::RequestAsyncResponder;
@@ -0,0 +1,596 @@
+//! Native menu bars and context menus declared from the state of a component
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+/// A menu bar declared from the state of a component. Set it on the current window with [`crate::use_menu_bar`].
+///
+/// ```rust, ignore
+/// let saved = use_state(cx, || true);
+///
+/// use_menu_bar(
+///     cx,
+///     MenuBar::new().menu(
+///         Menu::new("File")
+///             .item(
+///                 MenuItem::new("Save")
+///                     .accelerator("CmdOrCtrl+S")
+///                     .enabled(!saved)
+///                     .on_click(move || saved.set(true)),
+///             )
+///             .separator()
+///             .native(NativeMenuItem::Quit),
+///     ),
+/// );
+/// ```
+#[derive(Default)]
+pub struct MenuBar {
+    menus: Vec<Menu>,
+}
+
+impl MenuBar {
+    /// Create an empty menu bar
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a menu to the end of the menu bar
+    pub fn menu(mut self, menu: Menu) -> Self {
+        self.menus.push(menu);
+        self
+    }
+}
+
+/// A menu in a [`MenuBar`], a submenu, or a context menu created with [`crate::use_context_menu`]
+pub struct Menu {
+    text: String,
+    enabled: bool,
+    entries: Vec<MenuEntry>,
+}
+
+impl Menu {
+    /// Create an empty menu with a title. The title is not shown for context menus
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            enabled: true,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Set whether the menu can be opened. Menus are enabled by default
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Add an item to the end of the menu
+    pub fn item(mut self, item: MenuItem) -> Self {
+        self.entries.push(MenuEntry::Item(item));
+        self
+    }
+
+    /// Add a submenu to the end of the menu
+    pub fn submenu(mut self, menu: Menu) -> Self {
+        self.entries.push(MenuEntry::Submenu(menu));
+        self
+    }
+
+    /// Add a separator to the end of the menu
+    pub fn separator(self) -> Self {
+        self.native(NativeMenuItem::Separator)
+    }
+
+    /// Add an item with a behavior provided by the operating system to the end of the menu
+    pub fn native(mut self, item: NativeMenuItem) -> Self {
+        self.entries.push(MenuEntry::Native(item));
+        self
+    }
+}
+
+/// A clickable item in a [`Menu`]
+pub struct MenuItem {
+    text: String,
+    enabled: bool,
+    checked: Option<bool>,
+    accelerator: Option<String>,
+    on_click: Option<Box<dyn FnMut()>>,
+}
+
+impl MenuItem {
+    /// Create a menu item with a label
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            enabled: true,
+            checked: None,
+            accelerator: None,
+            on_click: None,
+        }
+    }
+
+    /// Create a menu item with a check mark.
+    ///
+    /// The check mark always shows `checked`. To toggle it, change the state `checked` is read from in [`MenuItem::on_click`].
+    pub fn checkbox(text: impl Into<String>, checked: bool) -> Self {
+        Self {
+            checked: Some(checked),
+            ..Self::new(text)
+        }
+    }
+
+    /// Set whether the item can be clicked. Items are enabled by default
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Set the keyboard shortcut of the item, like `"CmdOrCtrl+S"` or `"Alt+F4"`
+    pub fn accelerator(mut self, accelerator: impl Into<String>) -> Self {
+        self.accelerator = Some(accelerator.into());
+        self
+    }
+
+    /// Call a closure when the item is clicked or its accelerator is pressed
+    pub fn on_click(mut self, on_click: impl FnMut() + 'static) -> Self {
+        self.on_click = Some(Box::new(on_click));
+        self
+    }
+}
+
+/// A menu item with a behavior provided by the operating system.
+///
+/// Some of these are only supported on some platforms. Unsupported items are not shown. If you replace the default menu bar on macOS, add the edit items to keep the copy and paste shortcuts working in the webview.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NativeMenuItem {
+    /// A line between two groups of items
+    Separator,
+    /// Undo the last edit
+    Undo,
+    /// Redo the last undone edit
+    Redo,
+    /// Cut the selected text
+    Cut,
+    /// Copy the selected text
+    Copy,
+    /// Paste the clipboard
+    Paste,
+    /// Select all text
+    SelectAll,
+    /// Minimize the window
+    Minimize,
+    /// Maximize the window
+    Maximize,
+    /// Toggle fullscreen
+    Fullscreen,
+    /// Hide the app
+    Hide,
+    /// Close the window
+    CloseWindow,
+    /// Quit the app
+    Quit,
+}
+
+pub(crate) enum MenuEntry {
+    Item(MenuItem),
+    Submenu(Menu),
+    Native(NativeMenuItem),
+}
+
+/// The closures of the menu items in every window, by menu item id
+#[derive(Clone, Default)]
+pub(crate) struct MenuHandlers {
+    handlers: Rc<RefCell<HashMap<String, MenuHandler>>>,
+}
+
+#[derive(Clone)]
+struct MenuHandler {
+    on_click: Option<Rc<RefCell<Box<dyn FnMut()>>>>,
+    /// Reset the native state of the item after a click. Checkboxes toggle themselves, but their state comes from the component
+    after_click: Option<Rc<dyn Fn()>>,
+}
+
+impl MenuHandlers {
+    fn insert(&self, id: String, handler: MenuHandler) {
+        self.handlers.borrow_mut().insert(id, handler);
+    }
+
+    fn remove(&self, id: &str) {
+        self.handlers.borrow_mut().remove(id);
+    }
+
+    /// Call the closure of the menu item with this id
+    pub(crate) fn call(&self, id: &str) {
+        // Don't hold the borrow while the closure runs, it may create or drop menus
+        let handler = self.handlers.borrow().get(id).cloned();
+        if let Some(handler) = handler {
+            if let Some(on_click) = handler.on_click {
+                (on_click.borrow_mut())();
+            }
+            if let Some(after_click) = handler.after_click {
+                after_click();
+            }
+        }
+    }
+}
+
+/// A context menu created with [`crate::use_context_menu`]
+#[derive(Clone)]
+pub struct ContextMenuHandle {
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub(crate) menu: Rc<RefCell<native::NativeMenu>>,
+}
+
+impl ContextMenuHandle {
+    /// Open the context menu at the mouse cursor. Call this from an `oncontextmenu` handler.
+    ///
+    /// Add `prevent_default: "oncontextmenu"` to the element so the webview doesn't open its own context menu as well.
+    pub fn show(&self) {
+        #[cfg(not(any(target_os = "ios", target_os = "android")))]
+        {
+            // The menu may be updated while it is open, so don't keep it borrowed
+            let shown = self.menu.borrow().context_menu();
+            if let Some((menu, desktop)) = shown {
+                native::show_context_menu(&menu, &desktop.window);
+            }
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub(crate) mod native {
+    use super::*;
+    use crate::DesktopContext;
+    use muda::{
+        accelerator::Accelerator, CheckMenuItem, ContextMenu, IsMenuItem, PredefinedMenuItem,
+        Submenu,
+    };
+    use std::{
+        str::FromStr,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    static NEXT_MENU: AtomicUsize = AtomicUsize::new(0);
+
+    /// Where a declared menu is shown
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    pub(crate) enum MenuKind {
+        Bar,
+        Context,
+        Tray,
+    }
+
+    /// The parts of a menu that can't be changed on the native menu. The native menu is rebuilt when they change
+    #[derive(PartialEq)]
+    enum Shape {
+        Item {
+            accelerator: Option<String>,
+            checkbox: bool,
+        },
+        Submenu {
+            len: usize,
+        },
+        Native(NativeMenuItem),
+    }
+
+    enum Handle {
+        Item(muda::MenuItem),
+        Check(CheckMenuItem),
+        Submenu(Submenu),
+        Native,
+    }
+
+    enum Root {
+        Bar(muda::Menu),
+        Context(Submenu),
+        /// The tray icon owns the menu
+        Tray,
+    }
+
+    /// A native menu that follows the menu a component declares
+    pub(crate) struct NativeMenu {
+        kind: MenuKind,
+        prefix: String,
+        desktop: DesktopContext,
+        root: Option<Root>,
+        shape: Vec<Shape>,
+        /// The native items, in the same order as the shape
+        handles: Vec<Handle>,
+        /// The ids of the items with handlers
+        ids: Vec<String>,
+    }
+
+    impl NativeMenu {
+        pub(crate) fn new(desktop: DesktopContext, kind: MenuKind) -> Self {
+            Self {
+                kind,
+                prefix: format!("dioxus-menu-{}", NEXT_MENU.fetch_add(1, Ordering::Relaxed)),
+                desktop,
+                root: None,
+                shape: Vec::new(),
+                handles: Vec::new(),
+                ids: Vec::new(),
+            }
+        }
+
+        /// Update the native menu to match the declared entries
+        pub(crate) fn update(&mut self, entries: Vec<MenuEntry>) {
+            let mut shape = Vec::new();
+            shape_of(&entries, &mut shape);
+            if self.root.is_none() || shape != self.shape {
+                self.rebuild(&entries);
+                self.shape = shape;
+            } else {
+                let mut index = 0;
+                self.refresh(&entries, &mut index);
+            }
+            self.register_handlers(entries);
+        }
+
+        /// The native context menu and the window to show it in
+        pub(crate) fn context_menu(&self) -> Option<(Submenu, DesktopContext)> {
+            match &self.root {
+                Some(Root::Context(menu)) => Some((menu.clone(), self.desktop.clone())),
+                _ => None,
+            }
+        }
+
+        fn id(&self, index: usize) -> String {
+            format!("{}-{}", self.prefix, index)
+        }
+
+        fn rebuild(&mut self, entries: &[MenuEntry]) {
+            self.remove_root();
+            let mut handles = Vec::new();
+            match self.kind {
+                MenuKind::Bar => {
+                    // Replace the default menu bar instead of showing both
+                    if let Some(default) = self.desktop.default_menu_bar.take() {
+                        crate::menubar::remove_menu_bar(&default, &self.desktop.window);
+                    }
+                    let menu = muda::Menu::new();
+                    self.build(entries, &mut handles, &|item| _ = menu.append(item));
+                    crate::menubar::init_menu_bar(&menu, &self.desktop.window);
+                    self.root = Some(Root::Bar(menu));
+                }
+                MenuKind::Context => {
+                    let menu = Submenu::new("", true);
+                    self.build(entries, &mut handles, &|item| _ = menu.append(item));
+                    self.root = Some(Root::Context(menu));
+                }
+                MenuKind::Tray => {
+                    let menu = muda::Menu::new();
+                    self.build(entries, &mut handles, &|item| _ = menu.append(item));
+                    if let Some(tray) = &*self.desktop.shared.tray.borrow() {
+                        tray.set_menu(Some(Box::new(menu)));
+                    }
+                    self.root = Some(Root::Tray);
+                }
+            }
+            self.handles = handles;
+        }
+
+        fn build(
+            &self,
+            entries: &[MenuEntry],
+            handles: &mut Vec<Handle>,
+            append: &dyn Fn(&dyn IsMenuItem),
+        ) {
+            for entry in entries {
+                let id = self.id(handles.len());
+                match entry {
+                    MenuEntry::Item(item) => {
+                        let accelerator = item.accelerator.as_deref().and_then(parse_accelerator);
+                        match item.checked {
+                            Some(checked) => {
+                                let native = CheckMenuItem::with_id(
+                                    id,
+                                    &item.text,
+                                    item.enabled,
+                                    checked,
+                                    accelerator,
+                                );
+                                append(&native);
+                                handles.push(Handle::Check(native));
+                            }
+                            None => {
+                                let native = muda::MenuItem::with_id(
+                                    id,
+                                    &item.text,
+                                    item.enabled,
+                                    accelerator,
+                                );
+                                append(&native);
+                                handles.push(Handle::Item(native));
+                            }
+                        }
+                    }
+                    MenuEntry::Submenu(menu) => {
+                        let native = Submenu::with_id(id, &menu.text, menu.enabled);
+                        append(&native);
+                        handles.push(Handle::Submenu(native.clone()));
+                        self.build(&menu.entries, handles, &|item| _ = native.append(item));
+                    }
+                    MenuEntry::Native(item) => {
+                        append(&native_item(*item));
+                        handles.push(Handle::Native);
+                    }
+                }
+            }
+        }
+
+        /// Update the text and state of the native items in place
+        fn refresh(&self, entries: &[MenuEntry], index: &mut usize) {
+            for entry in entries {
+                let handle = &self.handles[*index];
+                *index += 1;
+                match (entry, handle) {
+                    (MenuEntry::Item(item), Handle::Item(native)) => {
+                        native.set_text(&item.text);
+                        native.set_enabled(item.enabled);
+                    }
+                    (MenuEntry::Item(item), Handle::Check(native)) => {
+                        native.set_text(&item.text);
+                        native.set_enabled(item.enabled);
+                        native.set_checked(item.checked.unwrap_or_default());
+                    }
+                    (MenuEntry::Submenu(menu), Handle::Submenu(native)) => {
+                        native.set_text(&menu.text);
+                        native.set_enabled(menu.enabled);
+                        self.refresh(&menu.entries, index);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        /// Replace the closures of the items with the closures from the latest render
+        fn register_handlers(&mut self, entries: Vec<MenuEntry>) {
+            let handlers = &self.desktop.shared.menu_handlers;
+            for id in self.ids.drain(..) {
+                handlers.remove(&id);
+            }
+
+            let mut index = 0;
+            let mut stack = vec![entries.into_iter()];
+            while let Some(entries) = stack.last_mut() {
+                let Some(entry) = entries.next() else {
+                    stack.pop();
+                    continue;
+                };
+                let id = self.id(index);
+                let handle = &self.handles[index];
+                index += 1;
+                match entry {
+                    MenuEntry::Item(item) => {
+                        let after_click = match (handle, item.checked) {
+                            (Handle::Check(native), Some(checked)) => {
+                                let native = native.clone();
+                                Some(Rc::new(move || native.set_checked(checked)) as Rc<dyn Fn()>)
+                            }
+                            _ => None,
+                        };
+                        if item.on_click.is_some() || after_click.is_some() {
+                            handlers.insert(
+                                id.clone(),
+                                MenuHandler {
+                                    on_click: item.on_click.map(|f| Rc::new(RefCell::new(f))),
+                                    after_click,
+                                },
+                            );
+                            self.ids.push(id);
+                        }
+                    }
+                    MenuEntry::Submenu(menu) => stack.push(menu.entries.into_iter()),
+                    MenuEntry::Native(_) => {}
+                }
+            }
+        }
+
+        fn remove_root(&mut self) {
+            match self.root.take() {
+                Some(Root::Bar(menu)) => {
+                    crate::menubar::remove_menu_bar(&menu, &self.desktop.window);
+                }
+                Some(Root::Tray) => {
+                    if let Some(tray) = &*self.desktop.shared.tray.borrow() {
+                        tray.set_menu(None);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    impl Drop for NativeMenu {
+        fn drop(&mut self) {
+            for id in &self.ids {
+                self.desktop.shared.menu_handlers.remove(id);
+            }
+            self.remove_root();
+        }
+    }
+
+    fn shape_of(entries: &[MenuEntry], shape: &mut Vec<Shape>) {
+        for entry in entries {
+            match entry {
+                MenuEntry::Item(item) => shape.push(Shape::Item {
+                    accelerator: item.accelerator.clone(),
+                    checkbox: item.checked.is_some(),
+                }),
+                MenuEntry::Submenu(menu) => {
+                    shape.push(Shape::Submenu {
+                        len: menu.entries.len(),
+                    });
+                    shape_of(&menu.entries, shape);
+                }
+                MenuEntry::Native(item) => shape.push(Shape::Native(*item)),
+            }
+        }
+    }
+
+    fn parse_accelerator(accelerator: &str) -> Option<Accelerator> {
+        Accelerator::from_str(accelerator)
+            .map_err(|err| tracing::warn!("Invalid menu accelerator {accelerator:?}: {err}"))
+            .ok()
+    }
+
+    fn native_item(item: NativeMenuItem) -> PredefinedMenuItem {
+        match item {
+            NativeMenuItem::Separator => PredefinedMenuItem::separator(),
+            NativeMenuItem::Undo => PredefinedMenuItem::undo(None),
+            NativeMenuItem::Redo => PredefinedMenuItem::redo(None),
+            NativeMenuItem::Cut => PredefinedMenuItem::cut(None),
+            NativeMenuItem::Copy => PredefinedMenuItem::copy(None),
+            NativeMenuItem::Paste => PredefinedMenuItem::paste(None),
+            NativeMenuItem::SelectAll => PredefinedMenuItem::select_all(None),
+            NativeMenuItem::Minimize => PredefinedMenuItem::minimize(None),
+            NativeMenuItem::Maximize => PredefinedMenuItem::maximize(None),
+            NativeMenuItem::Fullscreen => PredefinedMenuItem::fullscreen(None),
+            NativeMenuItem::Hide => PredefinedMenuItem::hide(None),
+            NativeMenuItem::CloseWindow => PredefinedMenuItem::close_window(None),
+            NativeMenuItem::Quit => PredefinedMenuItem::quit(None),
+        }
+    }
+
+    #[allow(unused)]
+    pub(crate) fn show_context_menu(menu: &Submenu, window: &tao::window::Window) {
+        #[cfg(target_os = "windows")]
+        {
+            use tao::platform::windows::WindowExtWindows;
+            // Clicks on context menus are only reported for windows with the menu subclass
+            menu.attach_menu_subclass_for_hwnd(window.hwnd());
+            menu.show_context_menu_for_hwnd(window.hwnd(), None);
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            use gtk::prelude::Cast;
+            use tao::platform::unix::WindowExtUnix;
+            menu.show_context_menu_for_gtk_window(window.gtk_window().upcast_ref(), None);
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            use tao::platform::macos::WindowExtMacOS;
+            menu.show_context_menu_for_nsview(window.ns_view() as _, None);
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub(crate) use native::{MenuKind, NativeMenu};
+
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+impl MenuBar {
+    pub(crate) fn into_entries(self) -> Vec<MenuEntry> {
+        self.menus.into_iter().map(MenuEntry::Submenu).collect()
+    }
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+impl Menu {
+    pub(crate) fn into_entries(self) -> Vec<MenuEntry> {
+        self.entries
+    }
+}
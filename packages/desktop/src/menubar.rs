@@ -1,11 +1,57 @@
 use tao::window::Window;
 
+/// Show the default menu bar in a window if it is enabled. Returns the menu bar so it can be removed when a component sets its own
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
 #[allow(unused)]
-pub fn build_menu(window: &Window, default_menu_bar: bool) {
+pub fn build_menu(window: &Window, default_menu_bar: bool) -> Option<muda::Menu> {
     if default_menu_bar {
-        #[cfg(not(any(target_os = "ios", target_os = "android")))]
-        impl_::build_menu_bar(impl_::build_default_menu_bar(), window)
+        let menu = impl_::build_default_menu_bar();
+        impl_::build_menu_bar(&menu, window);
+        Some(menu)
+    } else {
+        None
+    }
+}
+
+/// Show a menu bar in a window, replacing the menu bar the window had before
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+#[allow(unused)]
+pub(crate) fn init_menu_bar(menu: &muda::Menu, window: &Window) {
+    #[cfg(target_os = "windows")]
+    {
+        use tao::platform::windows::WindowExtWindows;
+        _ = menu.init_for_hwnd(window.hwnd());
     }
+
+    #[cfg(target_os = "linux")]
+    {
+        use tao::platform::unix::WindowExtUnix;
+        _ = menu.init_for_gtk_window(window.gtk_window(), window.default_vbox());
+    }
+
+    // macOS only has one menu bar for the whole app
+    #[cfg(target_os = "macos")]
+    menu.init_for_nsapp();
+}
+
+/// Remove a menu bar that was added with [`init_menu_bar`] from a window
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+#[allow(unused)]
+pub(crate) fn remove_menu_bar(menu: &muda::Menu, window: &Window) {
+    #[cfg(target_os = "windows")]
+    {
+        use tao::platform::windows::WindowExtWindows;
+        _ = menu.remove_for_hwnd(window.hwnd());
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use tao::platform::unix::WindowExtUnix;
+        _ = menu.remove_for_gtk_window(window.gtk_window());
+    }
+
+    #[cfg(target_os = "macos")]
+    menu.remove_for_nsapp();
 }
 
 #[cfg(not(any(target_os = "ios", target_os = "android")))]
@@ -14,7 +60,7 @@ mod impl_ {
     use muda::{Menu, PredefinedMenuItem, Submenu};
 
     #[allow(unused)]
-    pub fn build_menu_bar(menu: Menu, window: &Window) {
+    pub fn build_menu_bar(menu: &Menu, window: &Window) {
         #[cfg(target_os = "windows")]
         use tao::platform::windows::WindowExtWindows;
 
@@ -0,0 +1,423 @@
+//! State and messages shared between the windows of the app
+//!
+//! Every window runs its own [`dioxus_core::VirtualDom`], so windows can't share hooks or signals directly. Instead
+//! windows can share contexts with the windows they open, send each other messages and watch the list of open windows.
+
+use crate::{
+    ipc::{EventData, UserWindowEvent},
+    DesktopService,
+};
+use dioxus_core::VirtualDom;
+use slab::Slab;
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    rc::Rc,
+    sync::Arc,
+};
+use tao::window::WindowId;
+
+type ContextProvider = Rc<dyn Fn(&VirtualDom)>;
+
+/// The contexts that are provided to every new window
+#[derive(Default)]
+pub(crate) struct WindowContexts {
+    providers: RefCell<Vec<ContextProvider>>,
+}
+
+impl WindowContexts {
+    /// Provide a context to every window that is created after this call
+    pub(crate) fn share<T: Clone + 'static>(&self, context: T) {
+        self.providers
+            .borrow_mut()
+            .push(Rc::new(move |dom: &VirtualDom| {
+                dom.base_scope().provide_context(context.clone());
+            }));
+    }
+
+    /// Provide the shared contexts to the root of a new window
+    pub(crate) fn provide(&self, dom: &VirtualDom) {
+        for provider in self.providers.borrow().iter() {
+            provider(dom);
+        }
+    }
+}
+
+type MessageCallback<W> = Rc<RefCell<dyn FnMut(&dyn Any, W)>>;
+
+struct MessageHandler<W> {
+    window: W,
+    callback: MessageCallback<W>,
+}
+
+struct QueuedMessage<W> {
+    from: W,
+    /// The window the message is for or `None` if it is for every window
+    to: Option<W>,
+    message: Rc<dyn Any>,
+}
+
+/// The messages windows sent each other and the closures that handle them
+pub(crate) struct WindowMessages<W = WindowId> {
+    handlers: Rc<RefCell<Slab<MessageHandler<W>>>>,
+    queue: Rc<RefCell<VecDeque<QueuedMessage<W>>>>,
+}
+
+impl<W> Clone for WindowMessages<W> {
+    fn clone(&self) -> Self {
+        Self {
+            handlers: self.handlers.clone(),
+            queue: self.queue.clone(),
+        }
+    }
+}
+
+impl<W> Default for WindowMessages<W> {
+    fn default() -> Self {
+        Self {
+            handlers: Default::default(),
+            queue: Default::default(),
+        }
+    }
+}
+
+impl<W: Copy + PartialEq + 'static> WindowMessages<W> {
+    pub(crate) fn add<T: Clone + 'static>(
+        &self,
+        window: W,
+        mut handler: impl FnMut(T, W) + 'static,
+    ) -> usize {
+        // Windows can receive messages of many types, so handlers ignore messages of other types
+        let callback = move |message: &dyn Any, from: W| {
+            if let Some(message) = message.downcast_ref::<T>() {
+                handler(message.clone(), from);
+            }
+        };
+        self.handlers.borrow_mut().insert(MessageHandler {
+            window,
+            callback: Rc::new(RefCell::new(callback)),
+        })
+    }
+
+    pub(crate) fn remove(&self, id: usize) {
+        self.handlers.borrow_mut().try_remove(id);
+    }
+
+    /// Queue a message for the window `to`, or for every window if it is `None`
+    pub(crate) fn queue(&self, from: W, to: Option<W>, message: Rc<dyn Any>) {
+        self.queue
+            .borrow_mut()
+            .push_back(QueuedMessage { from, to, message });
+    }
+
+    /// Call the handlers of every message that was sent since the last time this was called
+    pub(crate) fn deliver(&self) {
+        loop {
+            // Handlers may send more messages, so the queue can't be borrowed while they run
+            let Some(QueuedMessage { from, to, message }) = self.queue.borrow_mut().pop_front()
+            else {
+                break;
+            };
+
+            let callbacks: Vec<_> = self
+                .handlers
+                .borrow()
+                .iter()
+                .filter(|(_, handler)| to.is_none() || to == Some(handler.window))
+                .map(|(_, handler)| handler.callback.clone())
+                .collect();
+            for callback in callbacks {
+                (callback.borrow_mut())(&*message, from);
+            }
+        }
+    }
+}
+
+/// A handler for messages from other windows created with [`crate::use_window_message_handler`]. The handler is removed when this is dropped.
+pub struct WindowMessageHandler {
+    messages: WindowMessages,
+    /// The id of the handler, until it is removed. The id may be reused by another handler after that
+    id: Cell<Option<usize>>,
+}
+
+impl WindowMessageHandler {
+    pub(crate) fn new<T: Clone + 'static>(
+        messages: WindowMessages,
+        window: WindowId,
+        handler: impl FnMut(T, WindowId) + 'static,
+    ) -> Self {
+        let id = messages.add(window, handler);
+        Self {
+            messages,
+            id: Cell::new(Some(id)),
+        }
+    }
+
+    /// Remove the handler
+    pub fn remove(&self) {
+        if let Some(id) = self.id.take() {
+            self.messages.remove(id);
+        }
+    }
+}
+
+impl Drop for WindowMessageHandler {
+    fn drop(&mut self) {
+        self.remove();
+    }
+}
+
+type WindowListSubscriber = Arc<dyn Fn() + Send + Sync>;
+
+/// The ids of the open windows in the order they were opened and the components that watch them
+pub(crate) struct WindowList<W = WindowId> {
+    windows: Rc<RefCell<Vec<W>>>,
+    subscribers: Rc<RefCell<Slab<WindowListSubscriber>>>,
+}
+
+impl<W> Clone for WindowList<W> {
+    fn clone(&self) -> Self {
+        Self {
+            windows: self.windows.clone(),
+            subscribers: self.subscribers.clone(),
+        }
+    }
+}
+
+impl<W> Default for WindowList<W> {
+    fn default() -> Self {
+        Self {
+            windows: Default::default(),
+            subscribers: Default::default(),
+        }
+    }
+}
+
+impl<W: Copy + PartialEq> WindowList<W> {
+    pub(crate) fn windows(&self) -> Vec<W> {
+        self.windows.borrow().clone()
+    }
+
+    pub(crate) fn opened(&self, window: W) {
+        self.windows.borrow_mut().push(window);
+        self.notify();
+    }
+
+    pub(crate) fn closed(&self, window: W) {
+        let mut windows = self.windows.borrow_mut();
+        let len = windows.len();
+        windows.retain(|id| *id != window);
+        let changed = windows.len() != len;
+        drop(windows);
+
+        if changed {
+            self.notify();
+        }
+    }
+
+    pub(crate) fn subscribe(&self, update: WindowListSubscriber) -> usize {
+        self.subscribers.borrow_mut().insert(update)
+    }
+
+    pub(crate) fn unsubscribe(&self, id: usize) {
+        self.subscribers.borrow_mut().try_remove(id);
+    }
+
+    fn notify(&self) {
+        for (_, update) in self.subscribers.borrow().iter() {
+            update();
+        }
+    }
+}
+
+/// Re-renders the component that created it when windows open or close until it is dropped
+pub(crate) struct WindowListSubscription<W: Copy + PartialEq = WindowId> {
+    pub(crate) list: WindowList<W>,
+    pub(crate) id: usize,
+}
+
+impl<W: Copy + PartialEq> Drop for WindowListSubscription<W> {
+    fn drop(&mut self) {
+        self.list.unsubscribe(self.id);
+    }
+}
+
+impl DesktopService {
+    /// Share a context with every window that is opened after this call.
+    ///
+    /// The context is provided to the root of each new window, so components in those windows can get it with
+    /// `use_context`. Every window gets a clone of the value, so use a type that shares its state when cloned, like
+    /// `Rc<RefCell<T>>`. Changes to the state don't re-render other windows; send them a message with
+    /// [`Self::send_message`] or [`Self::broadcast_message`] when they need to update.
+    ///
+    /// ```rust, ignore
+    /// let todos = cx.use_hook(|| {
+    ///     let todos = Rc::new(RefCell::new(Vec::new()));
+    ///     window().share_context(todos.clone());
+    ///     todos
+    /// });
+    /// ```
+    pub fn share_context<T: Clone + 'static>(&self, context: T) {
+        self.shared.window_contexts.share(context);
+    }
+
+    /// Send a message to the window with the given id.
+    ///
+    /// The message is delivered to the handlers for messages of type `T` in that window after the current event is
+    /// handled. Handlers are added with [`crate::use_window_message_handler`]. Messages to windows that are closed are
+    /// dropped.
+    pub fn send_message<T: Clone + 'static>(&self, window: WindowId, message: T) {
+        self.queue_message(Some(window), Rc::new(message));
+    }
+
+    /// Send a message to every open window, including this one.
+    pub fn broadcast_message<T: Clone + 'static>(&self, message: T) {
+        self.queue_message(None, Rc::new(message));
+    }
+
+    fn queue_message(&self, to: Option<WindowId>, message: Rc<dyn Any>) {
+        self.shared
+            .window_messages
+            .queue(self.window.id(), to, message);
+        _ = self
+            .shared
+            .proxy
+            .send_event(UserWindowEvent(EventData::WindowMessage, self.window.id()));
+    }
+
+    /// Get the ids of the open windows in the order they were opened. Windows that are hidden are still open.
+    ///
+    /// See [`crate::use_window_list`] for a hook that re-renders when windows open or close.
+    pub fn windows(&self) -> Vec<WindowId> {
+        self.shared.window_list.windows()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dioxus_core::{Element, Scope};
+
+    #[derive(Clone)]
+    struct Ping(&'static str);
+
+    #[test]
+    fn shared_contexts_are_provided_to_new_windows() {
+        fn app(cx: Scope) -> Element {
+            let windows = cx.consume_context::<Rc<Cell<usize>>>().unwrap();
+            windows.set(windows.get() + 1);
+            None
+        }
+
+        let contexts = WindowContexts::default();
+        let windows = Rc::new(Cell::new(0));
+        contexts.share(windows.clone());
+        for _ in 0..2 {
+            let mut dom = VirtualDom::new(app);
+            contexts.provide(&dom);
+            _ = dom.rebuild();
+        }
+        assert_eq!(windows.get(), 2);
+    }
+
+    #[test]
+    fn messages_are_routed_by_window_and_type() {
+        let messages = WindowMessages::<u32>::default();
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let record = |window: u32| {
+            let received = received.clone();
+            move |Ping(message): Ping, from: u32| {
+                received.borrow_mut().push((window, message, from))
+            }
+        };
+        messages.add(1, record(1));
+        let second = messages.add(2, record(2));
+        messages.add(2, {
+            let received = received.clone();
+            move |number: usize, from| {
+                received
+                    .borrow_mut()
+                    .push((2, "number", from + number as u32))
+            }
+        });
+
+        messages.queue(1, Some(2), Rc::new(Ping("to 2")));
+        messages.queue(2, None, Rc::new(Ping("to all")));
+        messages.queue(1, Some(2), Rc::new(10usize));
+        // Messages to closed windows are dropped
+        messages.queue(1, Some(3), Rc::new(Ping("to 3")));
+        assert!(received.borrow().is_empty());
+
+        messages.deliver();
+        assert_eq!(
+            *received.borrow(),
+            [
+                (2, "to 2", 1),
+                (1, "to all", 2),
+                (2, "to all", 2),
+                (2, "number", 11)
+            ]
+        );
+
+        received.borrow_mut().clear();
+        messages.remove(second);
+        messages.queue(1, None, Rc::new(Ping("again")));
+        messages.deliver();
+        assert_eq!(*received.borrow(), [(1, "again", 1)]);
+    }
+
+    #[test]
+    fn handlers_can_send_messages() {
+        let messages = WindowMessages::<u32>::default();
+        let received = Rc::new(RefCell::new(Vec::new()));
+        messages.add(1, {
+            let messages = messages.clone();
+            move |Ping(message): Ping, _| {
+                if message == "ping" {
+                    messages.queue(1, Some(2), Rc::new(Ping("pong")));
+                }
+            }
+        });
+        messages.add(2, {
+            let received = received.clone();
+            move |Ping(message): Ping, from| received.borrow_mut().push((message, from))
+        });
+
+        messages.queue(2, Some(1), Rc::new(Ping("ping")));
+        messages.deliver();
+        assert_eq!(*received.borrow(), [("pong", 1)]);
+    }
+
+    #[test]
+    fn window_list_notifies_subscribers() {
+        let list = WindowList::<u32>::default();
+        let updates = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let subscription = WindowListSubscription {
+            list: list.clone(),
+            id: list.subscribe({
+                let updates = updates.clone();
+                Arc::new(move || {
+                    updates.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                })
+            }),
+        };
+        let updates = move || updates.load(std::sync::atomic::Ordering::SeqCst);
+
+        list.opened(1);
+        list.opened(2);
+        list.opened(3);
+        list.closed(2);
+        assert_eq!(list.windows(), [1, 3]);
+        assert_eq!(updates(), 4);
+
+        // Closing a window that is not open does not re-render
+        list.closed(2);
+        assert_eq!(updates(), 4);
+
+        drop(subscription);
+        list.opened(4);
+        assert_eq!(list.windows(), [1, 3, 4]);
+        assert_eq!(updates(), 4);
+    }
+}
@@ -0,0 +1,309 @@
+//! Save window geometry and small app settings to the data directory of the app
+
+use crate::{DesktopContext, DesktopService};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    cell::{Ref, RefCell},
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+};
+use tao::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    event_loop::EventLoopWindowTarget,
+    window::{Fullscreen, Window, WindowBuilder},
+};
+
+/// The error returned when there is no directory to store persisted state in
+#[derive(Debug, thiserror::Error)]
+#[error("no data directory was set with `Config::with_data_directory` and the platform has no local data directory")]
+pub(crate) struct NoDataDirectory;
+
+/// The directory persisted state is stored in
+#[derive(Clone)]
+pub(crate) struct Storage {
+    dir: PathBuf,
+}
+
+impl Storage {
+    /// Store files in the data directory of the app, or in a directory named after the executable in the local data directory of the platform
+    pub(crate) fn new(data_dir: Option<PathBuf>) -> Result<Self, NoDataDirectory> {
+        let dir = match data_dir {
+            Some(dir) => dir,
+            None => {
+                let app = std::env::current_exe()
+                    .ok()
+                    .and_then(|exe| exe.file_stem().map(|stem| stem.to_owned()))
+                    .ok_or(NoDataDirectory)?;
+                dirs_next::data_local_dir()
+                    .ok_or(NoDataDirectory)?
+                    .join(app)
+            }
+        };
+        Ok(Self { dir })
+    }
+
+    /// The path of the file for a key. Characters that can't be in a file name are replaced with `_`
+    pub(crate) fn path(&self, kind: &str, key: &str) -> PathBuf {
+        let key: String = key
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+                _ => '_',
+            })
+            .collect();
+        self.dir.join(format!("{kind}-{key}.json"))
+    }
+}
+
+fn load<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let contents = std::fs::read(path).ok()?;
+    serde_json::from_slice(&contents)
+        .map_err(|err| tracing::warn!("Failed to parse {}: {err}", path.display()))
+        .ok()
+}
+
+fn save<T: Serialize>(path: &Path, value: &T) {
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| Ok(serde_json::to_vec_pretty(value)?))
+        .and_then(|contents| std::fs::write(path, contents));
+    if let Err(err) = result {
+        tracing::warn!("Failed to save {}: {err}", path.display());
+    }
+}
+
+/// The geometry of a window that is saved when it closes and restored when it opens
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct WindowState {
+    position: (i32, i32),
+    size: (u32, u32),
+    maximized: bool,
+    fullscreen: bool,
+    zoom: f64,
+}
+
+/// Saves and restores the state of a window that was created with [`crate::Config::with_window_state_persistence`]
+pub(crate) struct PersistedWindow {
+    path: PathBuf,
+}
+
+impl PersistedWindow {
+    pub(crate) fn new(storage: &Storage, key: &str) -> Self {
+        Self {
+            path: storage.path("window", key),
+        }
+    }
+
+    /// Apply the saved state to the window before it is built. Returns the saved zoom level
+    pub(crate) fn restore<T>(
+        &self,
+        window: WindowBuilder,
+        target: &EventLoopWindowTarget<T>,
+    ) -> (WindowBuilder, Option<f64>) {
+        let Some(state) = load::<WindowState>(&self.path) else {
+            return (window, None);
+        };
+
+        let mut window = window
+            .with_inner_size(PhysicalSize::new(state.size.0, state.size.1))
+            .with_maximized(state.maximized);
+
+        // Don't put the window on a monitor that was disconnected since it was saved
+        let (x, y) = state.position;
+        let on_screen = target.available_monitors().any(|monitor| {
+            let PhysicalPosition { x: left, y: top } = monitor.position();
+            let PhysicalSize { width, height } = monitor.size();
+            x >= left && y >= top && x < left + width as i32 && y < top + height as i32
+        });
+        if on_screen {
+            window = window.with_position(PhysicalPosition::new(x, y));
+        }
+
+        if state.fullscreen {
+            window = window.with_fullscreen(Some(Fullscreen::Borderless(None)));
+        }
+
+        (window, Some(state.zoom))
+    }
+
+    /// Save the current state of the window
+    pub(crate) fn save(&self, desktop: &DesktopService) {
+        let window: &Window = &desktop.window;
+        let position = window.outer_position().unwrap_or_default();
+        let size = window.inner_size();
+        self.save_state(WindowState {
+            position: (position.x, position.y),
+            size: (size.width, size.height),
+            maximized: window.is_maximized(),
+            fullscreen: window.fullscreen().is_some(),
+            zoom: desktop.zoom_level.get(),
+        });
+    }
+
+    fn save_state(&self, state: WindowState) {
+        // A maximized or fullscreen window would reopen with the size of the screen when it is restored, so keep the last normal geometry
+        let state = match load::<WindowState>(&self.path) {
+            Some(previous) if state.maximized || state.fullscreen => WindowState {
+                position: previous.position,
+                size: previous.size,
+                ..state
+            },
+            _ => state,
+        };
+
+        save(&self.path, &state);
+    }
+}
+
+impl DesktopService {
+    /// Load a setting that was saved with [`Self::save_persisted`]. Returns `None` if the setting was never saved or can't be read.
+    ///
+    /// Settings are stored as JSON files in the data directory of the app set with [`crate::Config::with_data_directory`], or in a
+    /// directory named after the executable in the local data directory of the platform if the app has no data directory.
+    pub fn load_persisted<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        load(&self.shared.storage.as_ref()?.path("setting", key))
+    }
+
+    /// Save a setting to the data directory of the app so it can be loaded the next time the app starts
+    pub fn save_persisted<T: Serialize>(&self, key: &str, value: &T) {
+        match &self.shared.storage {
+            Some(storage) => save(&storage.path("setting", key), value),
+            None => tracing::warn!("Failed to save the setting {key}: {NoDataDirectory}"),
+        }
+    }
+}
+
+/// A setting that is saved to the data directory of the app. Created with [`crate::use_persisted`].
+pub struct UsePersisted<T> {
+    desktop: DesktopContext,
+    key: String,
+    value: Rc<RefCell<T>>,
+    update: Arc<dyn Fn()>,
+}
+
+impl<T: Serialize + 'static> UsePersisted<T> {
+    pub(crate) fn new(desktop: DesktopContext, key: &str, update: Arc<dyn Fn()>) -> Self
+    where
+        T: DeserializeOwned + Default,
+    {
+        let value = desktop.load_persisted(key).unwrap_or_default();
+        Self {
+            desktop,
+            key: key.to_string(),
+            value: Rc::new(RefCell::new(value)),
+            update,
+        }
+    }
+
+    /// Read the current value
+    pub fn read(&self) -> Ref<'_, T> {
+        self.value.borrow()
+    }
+
+    /// Set the value, save it and re-render the component
+    pub fn set(&self, value: T) {
+        self.with_mut(|current| *current = value);
+    }
+
+    /// Modify the value in place, save it and re-render the component
+    pub fn with_mut(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.value.borrow_mut());
+        self.desktop
+            .save_persisted(&self.key, &*self.value.borrow());
+        (self.update)();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A data directory that is removed when the test ends
+    struct TestDir(PathBuf);
+
+    impl TestDir {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("dioxus-desktop-{name}-{}", std::process::id()));
+            _ = std::fs::remove_dir_all(&dir);
+            Self(dir)
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn settings_round_trip() {
+        let dir = TestDir::new("settings");
+        // The data directory is created when the first value is saved
+        let storage = Storage::new(Some(dir.0.join("data"))).unwrap();
+        let path = storage.path("setting", "theme/dark mode");
+        assert_eq!(
+            path,
+            dir.0.join("data").join("setting-theme_dark_mode.json")
+        );
+        assert_eq!(load::<Vec<String>>(&path), None);
+
+        let value = vec!["dark".to_string(), "compact".to_string()];
+        save(&path, &value);
+        assert_eq!(load::<Vec<String>>(&path), Some(value));
+
+        // Values that don't match the type are ignored
+        assert_eq!(load::<u32>(&path), None);
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(load::<Vec<String>>(&path), None);
+    }
+
+    #[test]
+    fn window_state_round_trips() {
+        let dir = TestDir::new("window");
+        let storage = Storage::new(Some(dir.0.clone())).unwrap();
+        let window = PersistedWindow::new(&storage, "main");
+        assert_eq!(window.path, dir.0.join("window-main.json"));
+
+        let normal = WindowState {
+            position: (-20, 40),
+            size: (800, 600),
+            maximized: false,
+            fullscreen: false,
+            zoom: 1.5,
+        };
+        window.save_state(normal.clone());
+        assert_eq!(load::<WindowState>(&window.path), Some(normal));
+
+        // Maximizing keeps the geometry the window is restored to
+        window.save_state(WindowState {
+            position: (0, 0),
+            size: (1920, 1080),
+            maximized: true,
+            fullscreen: false,
+            zoom: 2.0,
+        });
+        let restored = PersistedWindow::new(&storage, "main");
+        assert_eq!(
+            load::<WindowState>(&restored.path),
+            Some(WindowState {
+                position: (-20, 40),
+                size: (800, 600),
+                maximized: true,
+                fullscreen: false,
+                zoom: 2.0,
+            })
+        );
+    }
+
+    #[test]
+    fn storage_defaults_to_the_platform_data_directory() {
+        let storage = Storage::new(None);
+        match dirs_next::data_local_dir() {
+            Some(data) => assert!(storage.unwrap().dir.starts_with(data)),
+            None => assert!(storage.is_err()),
+        }
+    }
+}
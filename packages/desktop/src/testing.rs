@@ -0,0 +1,565 @@
+//! Run integration tests against a component in a real webview
+//!
+//! Tests launch a component in a hidden window, find elements in the page, interact with them through real DOM events
+//! and check the result. Because the webview needs the main thread, test files use their own `main` function instead
+//! of the default test harness:
+//!
+//! ```toml
+//! [[test]]
+//! name = "counter"
+//! path = "tests/counter.rs"
+//! harness = false
+//! ```
+//!
+//! ```rust, no_run
+//! use dioxus::prelude::*;
+//! use dioxus_desktop::testing::{launch_test, TestFailure};
+//!
+//! fn app(cx: Scope) -> Element {
+//!     let mut count = use_state(cx, || 0);
+//!     cx.render(rsx! {
+//!         button { onclick: move |_| count += 1, "Count: {count}" }
+//!     })
+//! }
+//!
+//! fn main() -> Result<(), TestFailure> {
+//!     launch_test(app, |window| async move {
+//!         let button = window.find_by_role("button").await?;
+//!         button.click().await?;
+//!         button.assert_text("Count: 1").await
+//!     })
+//! }
+//! ```
+//!
+//! On Linux the tests need a display server. On CI they can run under Xvfb with `xvfb-run cargo test`.
+
+use crate::{
+    app::App,
+    eval::DesktopEvaluator,
+    ipc::{EventData, UserWindowEvent},
+    Config, DesktopContext,
+};
+use dioxus_core::{Component, VirtualDom};
+use dioxus_html::prelude::Evaluator;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tao::{
+    event_loop::ControlFlow,
+    platform::run_return::EventLoopExtRunReturn,
+    window::{WindowBuilder, WindowId},
+};
+use thiserror::Error;
+
+/// How long a test can run before it is stopped
+const TEST_TIMEOUT: Duration = Duration::from_secs(100);
+
+/// How long the page has to go without changes before it counts as rendered
+const IDLE_TIME: Duration = Duration::from_millis(50);
+
+/// Helpers for finding elements and sending events that are added to the page before each script the test runs
+const TEST_JS: &str = r#"
+if (!window.__dioxusTest) {
+  const elements = [];
+  const implicitRoles = {
+    A: "link", ARTICLE: "article", BUTTON: "button", DIALOG: "dialog", FORM: "form", H1: "heading", H2: "heading",
+    H3: "heading", H4: "heading", H5: "heading", H6: "heading", IMG: "img", LI: "listitem", MAIN: "main",
+    NAV: "navigation", OL: "list", OPTION: "option", SELECT: "combobox", TABLE: "table", TEXTAREA: "textbox", UL: "list",
+  };
+  const inputRoles = {
+    button: "button", checkbox: "checkbox", number: "spinbutton", radio: "radio", range: "slider", reset: "button",
+    search: "searchbox", submit: "button",
+  };
+  const roleOf = (el) => {
+    if (el.hasAttribute("role")) return el.getAttribute("role");
+    if (el.tagName === "INPUT") return inputRoles[el.type] || "textbox";
+    if (el.tagName === "A" && !el.hasAttribute("href")) return null;
+    return implicitRoles[el.tagName] || null;
+  };
+  const textOf = (el) => el.textContent.trim();
+  const matches = (query) => {
+    if (query.kind === "selector") return Array.from(document.querySelectorAll(query.value));
+    const all = Array.from(document.body.querySelectorAll("*"));
+    if (query.kind === "role") return all.filter((el) => roleOf(el) === query.value);
+    // Only the innermost elements with the text match, not every parent of them
+    return all.filter((el) => textOf(el) === query.value && !Array.from(el.children).some((child) => textOf(child) === query.value));
+  };
+  const register = (el) => {
+    let id = elements.indexOf(el);
+    if (id === -1) {
+      id = elements.length;
+      elements.push(el);
+    }
+    return id;
+  };
+  const element = (id) => {
+    const el = elements[id];
+    if (!el || !el.isConnected) throw new Error("The element was removed from the page");
+    return el;
+  };
+  window.__dioxusTest = {
+    async find(query, timeout) {
+      const end = Date.now() + timeout;
+      while (true) {
+        const found = matches(query);
+        if (found.length > 0 || Date.now() >= end) return found.map(register);
+        await new Promise((resolve) => setTimeout(resolve, 10));
+      }
+    },
+    info(id) {
+      const el = element(id);
+      return {
+        tag: el.tagName.toLowerCase(),
+        text: el.textContent,
+        html: el.outerHTML,
+        value: "value" in el ? String(el.value) : null,
+        attributes: Object.fromEntries(Array.from(el.attributes).map((attr) => [attr.name, attr.value])),
+      };
+    },
+    click(id) {
+      const el = element(id);
+      const init = { view: window, bubbles: true, cancelable: true, button: 0 };
+      el.dispatchEvent(new MouseEvent("mousedown", { ...init, buttons: 1 }));
+      el.dispatchEvent(new MouseEvent("mouseup", init));
+      el.dispatchEvent(new MouseEvent("click", init));
+    },
+    type(id, text) {
+      const el = element(id);
+      el.focus();
+      for (const key of text) {
+        const init = { key, bubbles: true, cancelable: true };
+        el.dispatchEvent(new KeyboardEvent("keydown", init));
+        el.dispatchEvent(new KeyboardEvent("keypress", init));
+        el.value += key;
+        el.dispatchEvent(new InputEvent("input", { data: key, inputType: "insertText", bubbles: true }));
+        el.dispatchEvent(new KeyboardEvent("keyup", init));
+      }
+      el.dispatchEvent(new Event("change", { bubbles: true }));
+    },
+    idle(quiet, timeout) {
+      return new Promise((resolve) => {
+        const start = Date.now();
+        let timer;
+        const observer = new MutationObserver(() => wait());
+        const done = (idle) => {
+          observer.disconnect();
+          clearTimeout(timer);
+          resolve(idle);
+        };
+        const wait = () => {
+          clearTimeout(timer);
+          if (Date.now() - start >= timeout) {
+            done(false);
+          } else {
+            timer = setTimeout(() => done(true), quiet);
+          }
+        };
+        observer.observe(document.documentElement, { subtree: true, childList: true, attributes: true, characterData: true });
+        wait();
+      });
+    },
+  };
+}
+"#;
+
+/// Why a test failed
+#[non_exhaustive]
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum TestFailure {
+    /// No element matched a query before the timeout
+    #[error("No element matches {query}")]
+    NotFound {
+        /// The query that was searched for
+        query: String,
+    },
+    /// The page was still changing when the timeout ran out
+    #[error("Timed out waiting for {waiting_for}")]
+    Timeout {
+        /// What the test was waiting for
+        waiting_for: String,
+    },
+    /// A value in the page didn't match what the test expected
+    #[error("Expected {what} to be {expected:?}, but it was {actual:?}")]
+    Mismatch {
+        /// What was checked
+        what: String,
+        /// The value the test expected
+        expected: String,
+        /// The value in the page
+        actual: String,
+    },
+    /// A script in the webview threw an error
+    #[error("JavaScript error while {context}: {message}")]
+    Js {
+        /// What the test was doing
+        context: String,
+        /// The error the script threw
+        message: String,
+    },
+    /// A custom assertion failed
+    #[error("{0}")]
+    Assertion(String),
+}
+
+impl TestFailure {
+    /// Fail the test with a custom message unless the condition is true
+    pub fn ensure(condition: bool, message: impl Into<String>) -> Result<(), TestFailure> {
+        if condition {
+            Ok(())
+        } else {
+            Err(TestFailure::Assertion(message.into()))
+        }
+    }
+}
+
+/// Launch a component in a hidden window and run a test against it. Returns when the test finishes, fails or times out.
+///
+/// This must be called on the main thread. The event loop can't be started again after it returns, so each test
+/// file can only launch one test.
+pub fn launch_test<F, Fut>(root: Component, test: F) -> Result<(), TestFailure>
+where
+    F: FnOnce(TestWindow) -> Fut + 'static,
+    Fut: Future<Output = Result<(), TestFailure>> + 'static,
+{
+    launch_test_with_config(
+        root,
+        Config::new().with_window(WindowBuilder::new().with_visible(false)),
+        test,
+    )
+}
+
+/// Launch a component in a window with a custom configuration and run a test against it. See [`launch_test`] for more details.
+pub fn launch_test_with_config<F, Fut>(
+    root: Component,
+    mut cfg: Config,
+    test: F,
+) -> Result<(), TestFailure>
+where
+    F: FnOnce(TestWindow) -> Fut + 'static,
+    Fut: Future<Output = Result<(), TestFailure>> + 'static,
+{
+    let result: Arc<Mutex<Option<Result<(), TestFailure>>>> = Default::default();
+
+    // Start the test once the page has loaded so scripts the test runs aren't lost
+    cfg.on_initialize = Some(Box::new({
+        let result = result.clone();
+        move |dom: &VirtualDom| {
+            let desktop = dom
+                .base_scope()
+                .consume_context::<DesktopContext>()
+                .unwrap();
+            let window = TestWindow::new(desktop);
+            dom.base_scope().spawn_forever(async move {
+                // Let the first render reach the page before the test starts
+                let test_result = match window.wait_for_idle().await {
+                    Ok(()) => test(window).await,
+                    Err(err) => Err(err),
+                };
+                result.lock().unwrap().get_or_insert(test_result);
+            });
+        }
+    }));
+
+    let run = move || {
+        let (mut event_loop, mut app) = App::new(cfg, (), root);
+
+        // Stop the test if it doesn't finish in time
+        let proxy = event_loop.create_proxy();
+        std::thread::spawn({
+            let result = result.clone();
+            move || {
+                std::thread::sleep(TEST_TIMEOUT);
+                result
+                    .lock()
+                    .unwrap()
+                    .get_or_insert(Err(TestFailure::Timeout {
+                        waiting_for: "the test to finish".to_string(),
+                    }));
+                // Wake up the event loop so it sees the result
+                _ = proxy.send_event(UserWindowEvent(EventData::Poll, unsafe {
+                    WindowId::dummy()
+                }));
+            }
+        });
+
+        event_loop.run_return(|window_event, _, control_flow| {
+            crate::launch::handle_event(&mut app, window_event);
+            *control_flow = match result.lock().unwrap().is_some() {
+                true => ControlFlow::Exit,
+                false => app.control_flow,
+            };
+        });
+
+        let result = result.lock().unwrap().take();
+        result.unwrap_or_else(|| {
+            Err(TestFailure::Assertion(
+                "the app exited before the test finished".to_string(),
+            ))
+        })
+    };
+
+    #[cfg(feature = "tokio")]
+    let run = move || {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(tokio::task::unconstrained(async move { run() }))
+    };
+
+    run()
+}
+
+/// A query for elements in the page
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(tag = "kind", content = "value", rename_all = "lowercase")]
+enum Query {
+    Selector(String),
+    Text(String),
+    Role(String),
+}
+
+impl std::fmt::Display for Query {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Query::Selector(selector) => write!(f, "the selector {selector:?}"),
+            Query::Text(text) => write!(f, "the text {text:?}"),
+            Query::Role(role) => write!(f, "the role {role:?}"),
+        }
+    }
+}
+
+/// The window a test runs in
+#[derive(Clone)]
+pub struct TestWindow {
+    desktop: DesktopContext,
+    timeout: Duration,
+}
+
+impl TestWindow {
+    fn new(desktop: DesktopContext) -> Self {
+        Self {
+            desktop,
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    /// The desktop context of the window
+    pub fn desktop(&self) -> &DesktopContext {
+        &self.desktop
+    }
+
+    /// Set how long queries wait for elements to appear and how long [`Self::wait_for_idle`] waits for the page to stop changing. The default is 5 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Run JavaScript in the page and return the value it returns
+    pub async fn eval<T: DeserializeOwned>(&self, js: &str) -> Result<T, TestFailure> {
+        self.run("running a script", js).await
+    }
+
+    /// Run a script with the test helpers. Errors the script throws are returned instead of stopping the query
+    async fn run<T: DeserializeOwned>(&self, context: &str, js: &str) -> Result<T, TestFailure> {
+        let js_error = |message: String| TestFailure::Js {
+            context: context.to_string(),
+            message,
+        };
+        let evaluator = DesktopEvaluator::new(
+            self.desktop.clone(),
+            format!(
+                "{TEST_JS}
+                try {{
+                    const value = await (async () => {{ {js} }})();
+                    return {{ Ok: value === undefined ? null : value }};
+                }} catch (error) {{
+                    return {{ Err: error instanceof Error ? error.message : String(error) }};
+                }}"
+            ),
+        );
+        let result = evaluator
+            .join()
+            .await
+            .map_err(|err| js_error(format!("{err:?}")))?;
+        let value = serde_json::from_value::<Result<Value, String>>(result)
+            .map_err(|err| js_error(err.to_string()))?
+            .map_err(js_error)?;
+        serde_json::from_value(value).map_err(|err| js_error(err.to_string()))
+    }
+
+    /// Wait until the page stops changing. Events the test sends are handled and rendered before the page is idle
+    pub async fn wait_for_idle(&self) -> Result<(), TestFailure> {
+        let idle: bool = self
+            .run(
+                "waiting for the page to render",
+                &format!(
+                    "return await window.__dioxusTest.idle({}, {});",
+                    IDLE_TIME.as_millis(),
+                    self.timeout.as_millis()
+                ),
+            )
+            .await?;
+        if idle {
+            Ok(())
+        } else {
+            Err(TestFailure::Timeout {
+                waiting_for: "the page to stop changing".to_string(),
+            })
+        }
+    }
+
+    async fn find_matching(
+        &self,
+        query: Query,
+        timeout: Duration,
+    ) -> Result<Vec<TestElement>, TestFailure> {
+        let ids: Vec<usize> = self
+            .run(
+                &format!("finding elements with {query}"),
+                &format!(
+                    "return await window.__dioxusTest.find({}, {});",
+                    serde_json::to_string(&query).unwrap(),
+                    timeout.as_millis()
+                ),
+            )
+            .await?;
+        Ok(ids
+            .into_iter()
+            .map(|id| TestElement {
+                window: self.clone(),
+                id,
+                query: query.to_string(),
+            })
+            .collect())
+    }
+
+    async fn find_first(&self, query: Query) -> Result<TestElement, TestFailure> {
+        self.find_matching(query.clone(), self.timeout)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(TestFailure::NotFound {
+                query: query.to_string(),
+            })
+    }
+
+    /// Wait for an element that matches a CSS selector
+    pub async fn find(&self, selector: &str) -> Result<TestElement, TestFailure> {
+        self.find_first(Query::Selector(selector.to_string())).await
+    }
+
+    /// Wait for an element whose text is exactly `text`, ignoring whitespace at the start and end
+    pub async fn find_by_text(&self, text: &str) -> Result<TestElement, TestFailure> {
+        self.find_first(Query::Text(text.to_string())).await
+    }
+
+    /// Wait for an element with an ARIA role. Elements have the role from their `role` attribute or the implicit role of their tag, like `button` for `<button>` and `heading` for `<h1>`
+    pub async fn find_by_role(&self, role: &str) -> Result<TestElement, TestFailure> {
+        self.find_first(Query::Role(role.to_string())).await
+    }
+
+    /// Get every element that matches a CSS selector right now without waiting
+    pub async fn query_all(&self, selector: &str) -> Result<Vec<TestElement>, TestFailure> {
+        self.find_matching(Query::Selector(selector.to_string()), Duration::ZERO)
+            .await
+    }
+}
+
+#[derive(Deserialize)]
+struct ElementInfo {
+    tag: String,
+    text: String,
+    html: String,
+    value: Option<String>,
+    attributes: HashMap<String, String>,
+}
+
+/// An element in the page of a test that was found with a query on [`TestWindow`]
+#[derive(Clone)]
+pub struct TestElement {
+    window: TestWindow,
+    id: usize,
+    query: String,
+}
+
+impl TestElement {
+    async fn info(&self) -> Result<ElementInfo, TestFailure> {
+        self.window
+            .run(
+                &format!("reading the element with {}", self.query),
+                &format!("return window.__dioxusTest.info({});", self.id),
+            )
+            .await
+    }
+
+    /// The name of the tag of the element in lowercase
+    pub async fn tag(&self) -> Result<String, TestFailure> {
+        Ok(self.info().await?.tag)
+    }
+
+    /// The text content of the element and its children
+    pub async fn text(&self) -> Result<String, TestFailure> {
+        Ok(self.info().await?.text)
+    }
+
+    /// The outer HTML of the element
+    pub async fn html(&self) -> Result<String, TestFailure> {
+        Ok(self.info().await?.html)
+    }
+
+    /// The current value of an input, textarea or select element
+    pub async fn value(&self) -> Result<Option<String>, TestFailure> {
+        Ok(self.info().await?.value)
+    }
+
+    /// The value of an attribute of the element
+    pub async fn attribute(&self, name: &str) -> Result<Option<String>, TestFailure> {
+        Ok(self.info().await?.attributes.remove(name))
+    }
+
+    /// Fail the test unless the text of the element is `expected`, ignoring whitespace at the start and end
+    pub async fn assert_text(&self, expected: &str) -> Result<(), TestFailure> {
+        let actual = self.text().await?;
+        if actual.trim() == expected.trim() {
+            Ok(())
+        } else {
+            Err(TestFailure::Mismatch {
+                what: format!("the text of the element with {}", self.query),
+                expected: expected.to_string(),
+                actual,
+            })
+        }
+    }
+
+    /// Click the element with the left mouse button and wait for the app to render the result
+    pub async fn click(&self) -> Result<(), TestFailure> {
+        self.window
+            .run::<Value>(
+                &format!("clicking the element with {}", self.query),
+                &format!("window.__dioxusTest.click({});", self.id),
+            )
+            .await?;
+        self.window.wait_for_idle().await
+    }
+
+    /// Focus the element and type text into it one key at a time, then wait for the app to render the result
+    pub async fn type_text(&self, text: &str) -> Result<(), TestFailure> {
+        self.window
+            .run::<Value>(
+                &format!("typing into the element with {}", self.query),
+                &format!(
+                    "window.__dioxusTest.type({}, {});",
+                    self.id,
+                    serde_json::to_string(text).unwrap()
+                ),
+            )
+            .await?;
+        self.window.wait_for_idle().await
+    }
+}
@@ -0,0 +1,242 @@
+//! An icon for the app in the system tray
+
+use crate::{
+    ipc::{EventData, UserWindowEvent},
+    DesktopService,
+};
+use slab::Slab;
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+use tao::{dpi::PhysicalPosition, event_loop::EventLoopProxy, window::WindowId};
+use tray_icon::{ClickType, Icon, TrayIcon, TrayIconBuilder, TrayIconEvent};
+
+/// The configuration of the system tray icon of the app. Add it to the app with [`crate::Config::with_system_tray`].
+///
+/// The tray icon stays when every window is closed, so it works well with [`crate::WindowCloseBehaviour::LastWindowHides`]:
+///
+/// ```rust, ignore
+/// dioxus_desktop::launch_cfg(
+///     app,
+///     Config::new()
+///         .with_system_tray(SystemTray::new().with_tooltip("My App"))
+///         .with_close_behaviour(WindowCloseBehaviour::LastWindowHides),
+/// );
+/// ```
+#[derive(Default)]
+pub struct SystemTray {
+    icon: Option<Icon>,
+    tooltip: Option<String>,
+}
+
+impl SystemTray {
+    /// Create a tray icon with the default Dioxus icon
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the icon of the tray icon
+    pub fn with_icon(mut self, icon: Icon) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Set the text that is shown when the mouse hovers over the tray icon. Tooltips are not supported on Linux.
+    pub fn with_tooltip(mut self, tooltip: impl Into<String>) -> Self {
+        self.tooltip = Some(tooltip.into());
+        self
+    }
+
+    /// Create the tray icon
+    pub(crate) fn build(self) -> Option<TrayIcon> {
+        let icon = self.icon.unwrap_or_else(|| {
+            Icon::from_rgba(
+                include_bytes!("./assets/default_icon.bin").to_vec(),
+                460,
+                460,
+            )
+            .expect("image parse failed")
+        });
+
+        let mut builder = TrayIconBuilder::new().with_icon(icon);
+        if let Some(tooltip) = self.tooltip {
+            builder = builder.with_tooltip(tooltip);
+        }
+        builder
+            .build()
+            .map_err(|err| tracing::error!("Failed to create the tray icon: {err}"))
+            .ok()
+    }
+}
+
+/// How the tray icon was clicked
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayClick {
+    /// The tray icon was clicked with the left mouse button
+    Left,
+    /// The tray icon was clicked with the right mouse button. This opens the tray menu if there is one
+    Right,
+    /// The tray icon was double clicked with the left mouse button
+    Double,
+}
+
+/// A click on the tray icon
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrayEvent {
+    /// How the tray icon was clicked
+    pub click: TrayClick,
+    /// The position of the mouse on the screen
+    pub position: PhysicalPosition<f64>,
+}
+
+impl From<TrayIconEvent> for TrayEvent {
+    fn from(event: TrayIconEvent) -> Self {
+        Self {
+            click: match event.click_type {
+                ClickType::Left => TrayClick::Left,
+                ClickType::Right => TrayClick::Right,
+                ClickType::Double => TrayClick::Double,
+            },
+            position: PhysicalPosition::new(event.x, event.y),
+        }
+    }
+}
+
+/// Forward clicks on the tray icon to the event loop. Tray events come from another thread on some platforms
+pub(crate) fn connect_tray_events(proxy: EventLoopProxy<UserWindowEvent>) {
+    let proxy = std::sync::Mutex::new(proxy);
+    TrayIconEvent::set_event_handler(Some(move |event: TrayIconEvent| {
+        let event = EventData::TrayEvent(event.into());
+        _ = proxy
+            .lock()
+            .unwrap()
+            .send_event(UserWindowEvent(event, unsafe { WindowId::dummy() }));
+    }));
+}
+
+/// The closures that handle clicks on the tray icon in every window
+#[derive(Clone, Default)]
+pub(crate) struct TrayHandlers {
+    handlers: Rc<RefCell<Slab<Rc<RefCell<Box<dyn FnMut(TrayEvent)>>>>>>,
+}
+
+impl TrayHandlers {
+    pub(crate) fn add(&self, handler: impl FnMut(TrayEvent) + 'static) -> usize {
+        self.handlers
+            .borrow_mut()
+            .insert(Rc::new(RefCell::new(Box::new(handler))))
+    }
+
+    pub(crate) fn remove(&self, id: usize) {
+        self.handlers.borrow_mut().try_remove(id);
+    }
+
+    pub(crate) fn call(&self, event: TrayEvent) {
+        // Handlers may add or remove other handlers while they run
+        let handlers: Vec<_> = self
+            .handlers
+            .borrow()
+            .iter()
+            .map(|(_, handler)| handler.clone())
+            .collect();
+        for handler in handlers {
+            (handler.borrow_mut())(event);
+        }
+    }
+}
+
+/// A handler for clicks on the tray icon created with [`crate::use_tray_event_handler`]. The handler is removed when this is dropped.
+pub struct TrayEventHandler {
+    handlers: TrayHandlers,
+    /// The id of the handler, until it is removed. The id may be reused by another handler after that
+    id: Cell<Option<usize>>,
+}
+
+impl TrayEventHandler {
+    pub(crate) fn new(handlers: TrayHandlers, handler: impl FnMut(TrayEvent) + 'static) -> Self {
+        let id = handlers.add(handler);
+        Self {
+            handlers,
+            id: Cell::new(Some(id)),
+        }
+    }
+
+    /// Remove the handler
+    pub fn remove(&self) {
+        if let Some(id) = self.id.take() {
+            self.handlers.remove(id);
+        }
+    }
+}
+
+impl Drop for TrayEventHandler {
+    fn drop(&mut self) {
+        self.remove();
+    }
+}
+
+impl DesktopService {
+    /// Change the icon in the system tray. This does nothing if the app has no tray icon
+    pub fn set_tray_icon(&self, icon: Icon) {
+        if let Some(tray) = &*self.shared.tray.borrow() {
+            if let Err(err) = tray.set_icon(Some(icon)) {
+                tracing::warn!("Failed to set the tray icon: {err}");
+            }
+        }
+    }
+
+    /// Change the text that is shown when the mouse hovers over the tray icon. This does nothing if the app has no tray icon
+    pub fn set_tray_tooltip(&self, tooltip: impl AsRef<str>) {
+        if let Some(tray) = &*self.shared.tray.borrow() {
+            if let Err(err) = tray.set_tooltip(Some(tooltip)) {
+                tracing::warn!("Failed to set the tray tooltip: {err}");
+            }
+        }
+    }
+
+    /// Show or hide the tray icon. This does nothing if the app has no tray icon
+    pub fn set_tray_visible(&self, visible: bool) {
+        if let Some(tray) = &*self.shared.tray.borrow() {
+            if let Err(err) = tray.set_visible(visible) {
+                tracing::warn!("Failed to show or hide the tray icon: {err}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn click() -> TrayEvent {
+        TrayEvent {
+            click: TrayClick::Left,
+            position: PhysicalPosition::new(0.0, 0.0),
+        }
+    }
+
+    #[test]
+    fn removed_handlers_do_not_remove_reused_ids() {
+        let handlers = TrayHandlers::default();
+        let calls = Rc::new(Cell::new(0));
+
+        let first = TrayEventHandler::new(handlers.clone(), |_| {});
+        first.remove();
+
+        // The second handler reuses the id of the first one
+        let second = TrayEventHandler::new(handlers.clone(), {
+            let calls = calls.clone();
+            move |_| calls.set(calls.get() + 1)
+        });
+        drop(first);
+        handlers.call(click());
+        assert_eq!(calls.get(), 1);
+
+        second.remove();
+        drop(second);
+        handlers.call(click());
+        assert_eq!(calls.get(), 1);
+    }
+}
@@ -0,0 +1,730 @@
+//! Update a bundled app to the latest release
+//!
+//! `dx bundle --updater` signs the bundles of the app and writes a release manifest next to them. Upload both, then point
+//! an [`Updater`] at the manifest. The updater verifies the signed version, platform and bundle hash in the manifest with
+//! the public key of the app, checks for a newer version, downloads the bundle for the current platform, checks its
+//! hash and installs it.
+
+use base64::Engine;
+use dioxus_cli_config::{
+    update_bundle_hash, update_signature_message, update_target, UpdateManifest,
+};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures_util::StreamExt;
+use reqwest::Url;
+use std::{
+    cell::RefCell,
+    convert::TryFrom,
+    future::Future,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+};
+use thiserror::Error;
+
+/// An error while checking for, downloading or installing an update
+#[derive(Error, Debug)]
+pub enum UpdateError {
+    /// A request to the update server failed
+    #[error("Error downloading the update: {0}")]
+    Http(#[from] reqwest::Error),
+    /// A file could not be read or written
+    #[error("I/O error while updating: {0}")]
+    Io(#[from] std::io::Error),
+    /// The release manifest is not valid JSON or is missing fields
+    #[error("Invalid release manifest: {0}")]
+    Manifest(#[from] serde_json::Error),
+    /// A version in the manifest or the current version is not a valid semantic version
+    #[error("Invalid version: {0}")]
+    Version(#[from] semver::Error),
+    /// The location of the manifest or of a bundle is not a valid URL or path
+    #[error("Invalid update location: {0}")]
+    Location(String),
+    /// The public key of the updater is not a base64 encoded ed25519 public key
+    #[error("Invalid public key")]
+    PublicKey,
+    /// The signature of the release in the manifest is not valid, or the downloaded bundle doesn't match the signed hash
+    #[error("The signature of the update is not valid")]
+    Signature,
+    /// The release has no bundle for this platform
+    #[error("The release has no bundle for {0}")]
+    UnsupportedPlatform(String),
+    /// The downloaded update could not be installed
+    #[error("Error installing the update: {0}")]
+    Install(String),
+}
+
+/// Where the manifest or a bundle is downloaded from
+#[derive(Clone, Debug)]
+enum Location {
+    Url(Url),
+    Path(PathBuf),
+}
+
+impl Location {
+    fn parse(location: &str) -> Self {
+        match Url::parse(location) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => Location::Url(url),
+            Ok(url) if url.scheme() == "file" => url
+                .to_file_path()
+                .map(Location::Path)
+                .unwrap_or_else(|_| Location::Path(PathBuf::from(location))),
+            // Anything else is a path, including Windows paths like `C:\updates\latest.json`
+            _ => Location::Path(PathBuf::from(location)),
+        }
+    }
+
+    /// Resolve a location in the manifest against the location of the manifest
+    fn join(&self, location: &str) -> Result<Self, UpdateError> {
+        if Url::parse(location).is_ok() {
+            return Ok(Location::parse(location));
+        }
+        match self {
+            Location::Url(base) => base
+                .join(location)
+                .map(Location::Url)
+                .map_err(|err| UpdateError::Location(err.to_string())),
+            Location::Path(base) => Ok(Location::Path(
+                base.parent().unwrap_or(Path::new("")).join(location),
+            )),
+        }
+    }
+
+    fn file_name(&self) -> Option<String> {
+        match self {
+            Location::Url(url) => url
+                .path_segments()
+                .and_then(|mut segments| segments.next_back())
+                .filter(|name| !name.is_empty())
+                .map(str::to_string),
+            Location::Path(path) => path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string()),
+        }
+    }
+}
+
+/// Checks a release manifest for new versions of the app.
+///
+/// ```rust, ignore
+/// let updater = Updater::new(
+///     "https://example.com/releases/latest.json",
+///     include_str!("../updater.pub"),
+///     env!("CARGO_PKG_VERSION"),
+/// )?;
+///
+/// if let Some(update) = updater.check().await? {
+///     let staged = update.download(|progress| println!("{progress:?}")).await?;
+///     staged.install()?;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Updater {
+    manifest: Location,
+    public_key: VerifyingKey,
+    current_version: semver::Version,
+    target: String,
+    staging_dir: PathBuf,
+}
+
+impl Updater {
+    /// Create an updater.
+    ///
+    /// - `manifest` is the URL of the manifest `dx bundle --updater` created. A local path can be used for testing.
+    /// - `public_key` is the base64 encoded public key `dx bundle --updater` printed when it created the signing key.
+    /// - `current_version` is the version of the running app, usually `env!("CARGO_PKG_VERSION")`.
+    pub fn new(
+        manifest: &str,
+        public_key: &str,
+        current_version: &str,
+    ) -> Result<Self, UpdateError> {
+        let public_key = base64::engine::general_purpose::STANDARD
+            .decode(public_key.trim())
+            .ok()
+            .and_then(|key| <[u8; 32]>::try_from(key).ok())
+            .and_then(|key| VerifyingKey::from_bytes(&key).ok())
+            .ok_or(UpdateError::PublicKey)?;
+
+        Ok(Self {
+            manifest: Location::parse(manifest),
+            public_key,
+            current_version: semver::Version::parse(current_version)?,
+            target: update_target(std::env::consts::OS, std::env::consts::ARCH),
+            staging_dir: std::env::temp_dir().join("dioxus-updates"),
+        })
+    }
+
+    /// Use the bundle for a different platform than the current one. The default is the current OS and architecture, like `linux-x86_64`
+    pub fn with_target(mut self, target: impl Into<String>) -> Self {
+        self.target = target.into();
+        self
+    }
+
+    /// Set the directory updates are downloaded to before they are installed. The default is a directory in the temporary directory of the system
+    pub fn with_staging_directory(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.staging_dir = dir.into();
+        self
+    }
+
+    /// Download the manifest and return the update if it has a newer version than the running app
+    pub async fn check(&self) -> Result<Option<Update>, UpdateError> {
+        let manifest: UpdateManifest = serde_json::from_slice(&read(&self.manifest).await?)?;
+
+        let platform = manifest
+            .platforms
+            .get(&self.target)
+            .ok_or_else(|| UpdateError::UnsupportedPlatform(self.target.clone()))?;
+
+        // Only trust the version after the signature is verified so an edited or replayed manifest can't downgrade the app
+        let signature = base64::engine::general_purpose::STANDARD
+            .decode(platform.signature.trim())
+            .ok()
+            .and_then(|signature| Signature::from_slice(&signature).ok())
+            .ok_or(UpdateError::Signature)?;
+        let message = update_signature_message(&manifest.version, &self.target, &platform.sha256);
+        self.public_key
+            .verify(&message, &signature)
+            .map_err(|_| UpdateError::Signature)?;
+
+        let version = semver::Version::parse(&manifest.version)?;
+        if version <= self.current_version {
+            return Ok(None);
+        }
+
+        Ok(Some(Update {
+            version: manifest.version.clone(),
+            notes: manifest.notes.clone(),
+            pub_date: manifest.pub_date.clone(),
+            location: self.manifest.join(&platform.url)?,
+            sha256: platform.sha256.clone(),
+            staging_dir: self.staging_dir.clone(),
+        }))
+    }
+}
+
+async fn read(location: &Location) -> Result<Vec<u8>, UpdateError> {
+    match location {
+        Location::Url(url) => Ok(reqwest::get(url.clone())
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?
+            .to_vec()),
+        Location::Path(path) => Ok(tokio::fs::read(path).await?),
+    }
+}
+
+/// How much of an update was downloaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpdateProgress {
+    /// The number of bytes that were downloaded
+    pub downloaded: u64,
+    /// The size of the update in bytes if the server sent it
+    pub total: Option<u64>,
+}
+
+/// A newer version of the app that can be downloaded
+#[derive(Clone, Debug)]
+pub struct Update {
+    /// The version of the update
+    pub version: String,
+    /// The release notes from the manifest
+    pub notes: Option<String>,
+    /// The date the update was published
+    pub pub_date: Option<String>,
+    location: Location,
+    /// The signed hash of the bundle
+    sha256: String,
+    staging_dir: PathBuf,
+}
+
+impl Update {
+    /// Download the update and check it against the signed hash. `on_progress` is called every time part of the update is downloaded
+    pub async fn download(
+        &self,
+        mut on_progress: impl FnMut(UpdateProgress),
+    ) -> Result<StagedUpdate, UpdateError> {
+        let contents = match &self.location {
+            Location::Url(url) => {
+                let mut response = reqwest::get(url.clone()).await?.error_for_status()?;
+                let total = response.content_length();
+                let mut contents = Vec::with_capacity(total.unwrap_or_default() as usize);
+                on_progress(UpdateProgress {
+                    downloaded: 0,
+                    total,
+                });
+                while let Some(chunk) = response.chunk().await? {
+                    contents.extend_from_slice(&chunk);
+                    on_progress(UpdateProgress {
+                        downloaded: contents.len() as u64,
+                        total,
+                    });
+                }
+                contents
+            }
+            Location::Path(_) => {
+                let contents = read(&self.location).await?;
+                let total = contents.len() as u64;
+                on_progress(UpdateProgress {
+                    downloaded: total,
+                    total: Some(total),
+                });
+                contents
+            }
+        };
+
+        if !update_bundle_hash(&contents).eq_ignore_ascii_case(self.sha256.trim()) {
+            return Err(UpdateError::Signature);
+        }
+
+        // Only verified updates are written to disk
+        let file_name = self
+            .location
+            .file_name()
+            .ok_or_else(|| UpdateError::Location("the bundle has no file name".to_string()))?;
+        let dir = self.staging_dir.join(&self.version);
+        tokio::fs::create_dir_all(&dir).await?;
+        let path = dir.join(file_name);
+        tokio::fs::write(&path, contents).await?;
+
+        Ok(StagedUpdate {
+            version: self.version.clone(),
+            path,
+        })
+    }
+}
+
+/// An update that was downloaded and verified and is ready to install
+#[derive(Clone, Debug)]
+pub struct StagedUpdate {
+    /// The version of the update
+    pub version: String,
+    /// The path of the downloaded bundle
+    pub path: PathBuf,
+}
+
+impl StagedUpdate {
+    /// Replace the running app with the update and restart it. If the update is installed, this exits the current process.
+    ///
+    /// - On Windows the update must be an `.msi` or `.exe` installer, which is started before the app exits.
+    /// - On macOS the update must be a `.app.tar.gz` archive of the app bundle.
+    /// - On Linux the app must be running from an AppImage and the update must be the new AppImage.
+    pub fn install(&self) -> Result<(), UpdateError> {
+        install(&self.path)?;
+        std::process::exit(0);
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn install(path: &Path) -> Result<(), UpdateError> {
+    use std::process::Command;
+
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    match extension {
+        "msi" => Command::new("msiexec.exe")
+            .arg("/i")
+            .arg(path)
+            .arg("/passive")
+            .spawn()?,
+        "exe" => Command::new(path).spawn()?,
+        _ => {
+            return Err(UpdateError::Install(format!(
+                "{} is not an installer",
+                path.display()
+            )))
+        }
+    };
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn install(path: &Path) -> Result<(), UpdateError> {
+    use std::process::Command;
+
+    // The executable is at App.app/Contents/MacOS/app
+    let exe = std::env::current_exe()?;
+    let bundle = exe
+        .ancestors()
+        .nth(3)
+        .filter(|bundle| bundle.extension().map_or(false, |ext| ext == "app"))
+        .ok_or_else(|| UpdateError::Install("the app is not running from a bundle".to_string()))?
+        .to_path_buf();
+
+    let extracted = path.with_extension("extracted");
+    _ = std::fs::remove_dir_all(&extracted);
+    std::fs::create_dir_all(&extracted)?;
+    let status = Command::new("tar")
+        .arg("-xzf")
+        .arg(path)
+        .arg("-C")
+        .arg(&extracted)
+        .status()?;
+    if !status.success() {
+        return Err(UpdateError::Install(format!(
+            "failed to extract {}",
+            path.display()
+        )));
+    }
+
+    let new_bundle = std::fs::read_dir(&extracted)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| path.extension().map_or(false, |ext| ext == "app"))
+        .ok_or_else(|| UpdateError::Install("the update has no app bundle".to_string()))?;
+
+    // Keep the old bundle until the new one is in place so a failed update doesn't remove the app
+    let backup = bundle.with_extension("app.old");
+    _ = std::fs::remove_dir_all(&backup);
+    std::fs::rename(&bundle, &backup)?;
+    if let Err(err) = std::fs::rename(&new_bundle, &bundle) {
+        std::fs::rename(&backup, &bundle)?;
+        return Err(err.into());
+    }
+    _ = std::fs::remove_dir_all(&backup);
+
+    Command::new("open").arg("-n").arg(&bundle).spawn()?;
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn install(path: &Path) -> Result<(), UpdateError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let app_image = std::env::var_os("APPIMAGE")
+        .map(PathBuf::from)
+        .ok_or_else(|| {
+            UpdateError::Install("updates on Linux are only supported for AppImages".to_string())
+        })?;
+
+    // Copy next to the AppImage first so the rename that replaces it is atomic
+    let temporary = app_image.with_extension("update");
+    std::fs::copy(path, &temporary)?;
+    std::fs::set_permissions(&temporary, std::fs::Permissions::from_mode(0o755))?;
+    std::fs::rename(&temporary, &app_image)?;
+
+    std::process::Command::new(&app_image).spawn()?;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn install(_: &Path) -> Result<(), UpdateError> {
+    Err(UpdateError::UnsupportedPlatform(
+        std::env::consts::OS.to_string(),
+    ))
+}
+
+/// The state of the update of the app in [`crate::use_updater`]
+#[derive(Clone, Debug)]
+pub enum UpdateStatus {
+    /// The updater hasn't checked for updates yet
+    Idle,
+    /// The updater is downloading the manifest
+    Checking,
+    /// The app is the latest version
+    UpToDate,
+    /// A newer version is available and can be downloaded with [`UseUpdater::download`]
+    Available(Box<Update>),
+    /// The update is downloading
+    Downloading(UpdateProgress),
+    /// The update was downloaded and verified and can be installed with [`UseUpdater::install`]
+    Ready(StagedUpdate),
+    /// Checking for, downloading or installing the update failed
+    Failed(Rc<UpdateError>),
+}
+
+enum UpdaterCommand {
+    Check,
+    Download,
+}
+
+/// A handle to the updater created with [`crate::use_updater`]
+pub struct UseUpdater {
+    status: Rc<RefCell<UpdateStatus>>,
+    commands: UnboundedSender<UpdaterCommand>,
+}
+
+impl UseUpdater {
+    /// Create the handle and the task that checks for and downloads updates in the background
+    pub(crate) fn new(
+        updater: Updater,
+        update: Arc<dyn Fn()>,
+    ) -> (Self, impl Future<Output = ()> + 'static) {
+        let status = Rc::new(RefCell::new(UpdateStatus::Idle));
+        let (commands, rx) = unbounded();
+        let task = run_updater(updater, status.clone(), update, rx);
+        (Self { status, commands }, task)
+    }
+
+    /// The current state of the update
+    pub fn status(&self) -> UpdateStatus {
+        self.status.borrow().clone()
+    }
+
+    /// Check the manifest for a newer version in the background
+    pub fn check(&self) {
+        _ = self.commands.unbounded_send(UpdaterCommand::Check);
+    }
+
+    /// Download the available update in the background. This does nothing unless the status is [`UpdateStatus::Available`]
+    pub fn download(&self) {
+        _ = self.commands.unbounded_send(UpdaterCommand::Download);
+    }
+
+    /// Install the downloaded update and restart the app. This does nothing unless the status is [`UpdateStatus::Ready`]
+    pub fn install(&self) -> Result<(), UpdateError> {
+        let status = self.status();
+        match status {
+            UpdateStatus::Ready(staged) => staged.install(),
+            _ => Ok(()),
+        }
+    }
+}
+
+async fn run_updater(
+    updater: Updater,
+    status: Rc<RefCell<UpdateStatus>>,
+    update: Arc<dyn Fn()>,
+    mut commands: UnboundedReceiver<UpdaterCommand>,
+) {
+    let set_status = |new: UpdateStatus| {
+        *status.borrow_mut() = new;
+        update();
+    };
+
+    while let Some(command) = commands.next().await {
+        let current = status.borrow().clone();
+        match (command, current) {
+            (UpdaterCommand::Check, UpdateStatus::Checking | UpdateStatus::Downloading(_)) => {}
+            (UpdaterCommand::Check, _) => {
+                set_status(UpdateStatus::Checking);
+                set_status(match updater.check().await {
+                    Ok(Some(available)) => UpdateStatus::Available(Box::new(available)),
+                    Ok(None) => UpdateStatus::UpToDate,
+                    Err(err) => UpdateStatus::Failed(Rc::new(err)),
+                });
+            }
+            (UpdaterCommand::Download, UpdateStatus::Available(available)) => {
+                let result = available
+                    .download(|progress| set_status(UpdateStatus::Downloading(progress)))
+                    .await;
+                set_status(match result {
+                    Ok(staged) => UpdateStatus::Ready(staged),
+                    Err(err) => UpdateStatus::Failed(Rc::new(err)),
+                });
+            }
+            (UpdaterCommand::Download, _) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dioxus_cli_config::UpdatePlatform;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const BUNDLE: &[u8] = b"the new version of the app";
+
+    /// A release with a signed bundle for linux in a temporary directory that is removed when the test ends
+    struct Release {
+        dir: PathBuf,
+        key: SigningKey,
+    }
+
+    impl Release {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("dioxus-updater-{name}-{}", std::process::id()));
+            _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(dir.join("bundles")).unwrap();
+
+            // A fixed key so the test doesn't depend on a random number generator
+            let release = Self {
+                dir,
+                key: SigningKey::from_bytes(&[7; 32]),
+            };
+            std::fs::write(release.dir.join("bundles/app.AppImage"), BUNDLE).unwrap();
+            release.write_manifest(release.signed_manifest("1.2.0", "linux-x86_64", &release.key));
+            release
+        }
+
+        /// A manifest for `version` with the bundle for `target` signed with `key`
+        fn signed_manifest(&self, version: &str, target: &str, key: &SigningKey) -> UpdateManifest {
+            let sha256 = update_bundle_hash(BUNDLE);
+            let signature = key.sign(&update_signature_message(version, target, &sha256));
+            UpdateManifest {
+                version: version.to_string(),
+                notes: Some("Bug fixes".to_string()),
+                pub_date: None,
+                platforms: std::iter::once((
+                    target.to_string(),
+                    UpdatePlatform {
+                        url: "bundles/app.AppImage".to_string(),
+                        sha256,
+                        signature: base64::engine::general_purpose::STANDARD
+                            .encode(signature.to_bytes()),
+                    },
+                ))
+                .collect(),
+            }
+        }
+
+        fn write_manifest(&self, manifest: UpdateManifest) {
+            std::fs::write(
+                self.dir.join("latest.json"),
+                serde_json::to_vec(&manifest).unwrap(),
+            )
+            .unwrap();
+        }
+
+        fn updater(&self, version: &str) -> Updater {
+            let public_key = base64::engine::general_purpose::STANDARD
+                .encode(self.key.verifying_key().to_bytes());
+            Updater::new(
+                self.dir.join("latest.json").to_str().unwrap(),
+                &public_key,
+                version,
+            )
+            .unwrap()
+            .with_target("linux-x86_64")
+            .with_staging_directory(self.dir.join("staging"))
+        }
+    }
+
+    impl Drop for Release {
+        fn drop(&mut self) {
+            _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    #[tokio::test]
+    async fn finds_newer_versions_for_the_platform() {
+        let release = Release::new("check");
+        let update = release.updater("1.0.0").check().await.unwrap().unwrap();
+        assert_eq!(update.version, "1.2.0");
+        assert_eq!(update.notes.as_deref(), Some("Bug fixes"));
+        assert!(
+            matches!(&update.location, Location::Path(path) if *path == release.dir.join("bundles/app.AppImage"))
+        );
+
+        assert!(release.updater("1.2.0").check().await.unwrap().is_none());
+        assert!(matches!(
+            release.updater("1.0.0").with_target("windows-x86_64").check().await,
+            Err(UpdateError::UnsupportedPlatform(target)) if target == "windows-x86_64"
+        ));
+
+        std::fs::write(release.dir.join("latest.json"), "{\"version\": \"1.2.0\"}").unwrap();
+        assert!(matches!(
+            release.updater("1.0.0").check().await,
+            Err(UpdateError::Manifest(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn verifies_the_signature_of_the_manifest() {
+        let release = Release::new("manifest");
+
+        // The version is signed, so it can't be edited to make an old release look new
+        let mut manifest = release.signed_manifest("1.2.0", "linux-x86_64", &release.key);
+        manifest.version = "9.0.0".to_string();
+        release.write_manifest(manifest);
+        assert!(matches!(
+            release.updater("1.0.0").check().await,
+            Err(UpdateError::Signature)
+        ));
+
+        // An old release is verified and then ignored instead of being installed over a newer version
+        release.write_manifest(release.signed_manifest("0.9.0", "linux-x86_64", &release.key));
+        assert!(release.updater("1.0.0").check().await.unwrap().is_none());
+
+        // A bundle that was signed for another platform is rejected
+        let mut manifest = release.signed_manifest("1.2.0", "windows-x86_64", &release.key);
+        let platform = manifest.platforms.remove("windows-x86_64").unwrap();
+        manifest
+            .platforms
+            .insert("linux-x86_64".to_string(), platform);
+        release.write_manifest(manifest);
+        assert!(matches!(
+            release.updater("1.0.0").check().await,
+            Err(UpdateError::Signature)
+        ));
+
+        // So is a release signed with another key
+        let other_key = SigningKey::from_bytes(&[8; 32]);
+        release.write_manifest(release.signed_manifest("1.2.0", "linux-x86_64", &other_key));
+        assert!(matches!(
+            release.updater("1.0.0").check().await,
+            Err(UpdateError::Signature)
+        ));
+    }
+
+    #[tokio::test]
+    async fn verifies_the_hash_of_downloads() {
+        let release = Release::new("download");
+        let update = release.updater("1.0.0").check().await.unwrap().unwrap();
+
+        let mut progress = Vec::new();
+        let staged = update
+            .download(|update| progress.push(update))
+            .await
+            .unwrap();
+        assert_eq!(staged.version, "1.2.0");
+        assert_eq!(staged.path, release.dir.join("staging/1.2.0/app.AppImage"));
+        assert_eq!(std::fs::read(&staged.path).unwrap(), BUNDLE);
+        let size = BUNDLE.len() as u64;
+        assert_eq!(
+            progress,
+            [UpdateProgress {
+                downloaded: size,
+                total: Some(size)
+            }]
+        );
+
+        // A bundle that was changed after it was signed is rejected and never staged
+        std::fs::remove_dir_all(release.dir.join("staging")).unwrap();
+        std::fs::write(release.dir.join("bundles/app.AppImage"), b"malware").unwrap();
+        assert!(matches!(
+            update.download(|_| {}).await,
+            Err(UpdateError::Signature)
+        ));
+        assert!(!release.dir.join("staging").exists());
+    }
+
+    #[test]
+    fn rejects_invalid_public_keys() {
+        for key in ["", "not base64", "AAAA"] {
+            assert!(matches!(
+                Updater::new("latest.json", key, "1.0.0"),
+                Err(UpdateError::PublicKey)
+            ));
+        }
+    }
+
+    #[test]
+    fn resolves_bundle_locations() {
+        let manifest = Location::parse("https://example.com/releases/latest.json");
+        assert!(matches!(
+            manifest.join("app.AppImage").unwrap(),
+            Location::Url(url) if url.as_str() == "https://example.com/releases/app.AppImage"
+        ));
+        assert!(matches!(
+            manifest.join("https://cdn.example.com/app.msi").unwrap(),
+            Location::Url(url) if url.as_str() == "https://cdn.example.com/app.msi"
+        ));
+        assert_eq!(
+            manifest
+                .join("app.AppImage")
+                .unwrap()
+                .file_name()
+                .as_deref(),
+            Some("app.AppImage")
+        );
+
+        let manifest = Location::parse("updates/latest.json");
+        assert!(matches!(
+            manifest.join("app.AppImage").unwrap(),
+            Location::Path(path) if path == Path::new("updates/app.AppImage")
+        ));
+    }
+}
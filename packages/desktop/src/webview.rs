@@ -4,6 +4,7 @@ use crate::{
     edits::EditQueue,
     eval::DesktopEvalProvider,
     ipc::{EventData, UserWindowEvent},
+    persistence::PersistedWindow,
     protocol::{self},
     waker::tao_waker,
     Config, DesktopContext, DesktopService,
@@ -19,6 +20,11 @@ pub struct WebviewInstance {
     pub desktop_context: DesktopContext,
     pub waker: Waker,
 
+    /// Saves the size and position of the window if it was created with [`Config::with_window_state_persistence`]
+    persisted_window: Option<PersistedWindow>,
+
+    pub(crate) on_initialize: Option<Box<dyn FnOnce(&VirtualDom)>>,
+
     // Wry assumes the webcontext is alive for the lifetime of the webview.
     // We need to keep the webcontext alive, otherwise the webview will crash
     _web_context: WebContext,
@@ -26,11 +32,20 @@ pub struct WebviewInstance {
 
 impl WebviewInstance {
     pub fn new(mut cfg: Config, dom: VirtualDom, shared: Rc<SharedContext>) -> WebviewInstance {
-        let window = cfg.window.clone().build(&shared.target).unwrap();
+        let persisted_window = cfg
+            .window_state_key
+            .as_deref()
+            .zip(shared.storage.as_ref())
+            .map(|(key, storage)| PersistedWindow::new(storage, key));
+        let (window, zoom) = match &persisted_window {
+            Some(persisted) => persisted.restore(cfg.window.clone(), &shared.target),
+            None => (cfg.window.clone(), None),
+        };
+        let window = window.build(&shared.target).unwrap();
 
-        // TODO: allow users to specify their own menubars, again :/
+        // Components can replace the default menu bar with `use_menu_bar`
         #[cfg(not(any(target_os = "android", target_os = "ios")))]
-        crate::menubar::build_menu(&window, cfg.enable_default_menu_bar);
+        let default_menu_bar = crate::menubar::build_menu(&window, cfg.enable_default_menu_bar);
 
         // We assume that if the icon is None in cfg, then the user just didnt set it
         if cfg.window.window.window_icon.is_none() {
@@ -93,6 +108,7 @@ impl WebviewInstance {
             .with_url("dioxus://index.html/")
             .unwrap()
             .with_ipc_handler(ipc_handler)
+            .with_initialization_script(crate::bridge::BRIDGE_JS)
             .with_asynchronous_custom_protocol(String::from("dioxus"), request_handler)
             .with_web_context(&mut web_context);
 
@@ -144,6 +160,13 @@ impl WebviewInstance {
             asset_handlers,
         ));
 
+        if let Some(zoom) = zoom {
+            desktop_context.set_zoom_level(zoom);
+        }
+
+        #[cfg(not(any(target_os = "android", target_os = "ios")))]
+        desktop_context.default_menu_bar.replace(default_menu_bar);
+
         // Provide the desktop context to the virtualdom
         dom.base_scope().provide_context(desktop_context.clone());
 
@@ -154,14 +177,26 @@ impl WebviewInstance {
             Rc::new(DesktopEvalProvider::new(desktop_context.clone()));
         dom.base_scope().provide_context(provider);
 
+        // Give the new window the contexts other windows shared with it
+        shared.window_contexts.provide(&dom);
+
         WebviewInstance {
             waker: tao_waker(shared.proxy.clone(), desktop_context.window.id()),
             desktop_context,
             dom,
+            persisted_window,
+            on_initialize: cfg.on_initialize.take(),
             _web_context: web_context,
         }
     }
 
+    /// Save the state of the window if it was created with [`Config::with_window_state_persistence`]
+    pub fn save_window_state(&self) {
+        if let Some(persisted) = &self.persisted_window {
+            persisted.save(&self.desktop_context);
+        }
+    }
+
     pub fn poll_vdom(&mut self) {
         let mut cx = std::task::Context::from_waker(&self.waker);
 
@@ -55,6 +55,30 @@ fn tui_update(c: &mut Criterion) {
             );
         }
     }
+
+    {
+        let mut group = c.benchmark_group("Update large list");
+
+        for len in [100, 1_000, 10_000usize] {
+            let parameter_string = len.to_string();
+            group.bench_with_input(
+                BenchmarkId::new("length", parameter_string),
+                &len,
+                |b, len| {
+                    b.iter(|| {
+                        dioxus_tui::launch_cfg_with_props(
+                            list_app,
+                            ListProps {
+                                len: *len,
+                                update_count: 20,
+                            },
+                            Config::default().with_headless(),
+                        )
+                    })
+                },
+            );
+        }
+    }
 }
 
 #[derive(Props, PartialEq)]
@@ -163,3 +187,71 @@ fn app(cx: Scope<GridProps>) -> Element {
         }
     })
 }
+
+#[derive(Props, PartialEq)]
+struct ListItemProps {
+    index: usize,
+    count: usize,
+}
+#[allow(non_snake_case)]
+fn ListItem(cx: Scope<ListItemProps>) -> Element {
+    let index = cx.props.index;
+    let count = cx.props.count;
+
+    render! {
+        div{
+            width: "100%",
+            "item {index} updated {count} times"
+        }
+    }
+}
+
+#[derive(Props, PartialEq)]
+struct ListProps {
+    len: usize,
+    update_count: usize,
+}
+/// A long list where one item changes every render. Only the changed item and its ancestors should be laid out again.
+#[allow(non_snake_case)]
+fn List(cx: Scope<ListProps>) -> Element {
+    let len = cx.props.len;
+    let count = use_state(cx, || 0);
+
+    let ctx: TuiContext = cx.consume_context().unwrap();
+    if *count.get() >= cx.props.update_count {
+        ctx.quit();
+    } else {
+        count.with_mut(|i| *i += 1);
+    }
+    let updated = *count.get() % len;
+
+    render! {
+        div{
+            width: "100%",
+            flex_direction: "column",
+            (0..len).map(|i| {
+                let count = if i == updated { *count.get() } else { 0 };
+                rsx! {
+                    ListItem{
+                        index: i,
+                        count: count,
+                        key: "{i}",
+                    }
+                }
+            })
+        }
+    }
+}
+
+fn list_app(cx: Scope<ListProps>) -> Element {
+    cx.render(rsx! {
+        div{
+            width: "100%",
+            height: "100%",
+            List{
+                len: cx.props.len,
+                update_count: cx.props.update_count,
+            }
+        }
+    })
+}
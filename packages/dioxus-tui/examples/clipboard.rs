@@ -0,0 +1,34 @@
+use dioxus::prelude::*;
+use dioxus_tui::Config;
+
+fn main() {
+    dioxus_tui::launch_cfg(app, Config::new().with_copy_on_select());
+}
+
+fn app(cx: Scope) -> Element {
+    let copied = use_state(cx, String::new);
+    let pasted = use_state(cx, String::new);
+
+    cx.render(rsx! {
+        div {
+            width: "100%",
+            height: "100%",
+            flex_direction: "column",
+
+            div {
+                flex_direction: "column",
+                oncopy: move |data| copied.set(data.text().unwrap_or_default()),
+                "Drag the mouse over this text to select it."
+                "It is copied to the clipboard when you let go."
+            }
+            "Copied: {copied}"
+
+            "Paste into the input"
+            input {
+                width: "50%",
+                onpaste: move |data| pasted.set(data.text().unwrap_or_default()),
+            }
+            "Pasted: {pasted}"
+        }
+    })
+}
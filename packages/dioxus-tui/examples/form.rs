@@ -0,0 +1,69 @@
+use dioxus::prelude::*;
+
+fn main() {
+    dioxus_tui::launch(app);
+}
+
+fn app(cx: Scope) -> Element {
+    let fruit = use_state(cx, || "apple".to_string());
+    let notes = use_state(cx, String::new);
+    let progress = notes.len().min(100);
+
+    cx.render(rsx! {
+        div {
+            width: "100%",
+            height: "100%",
+            flex_direction: "column",
+
+            "Pick a fruit with the arrow keys or the mouse"
+            select {
+                value: "{fruit}",
+                onchange: move |data| fruit.set(data.value()),
+                option { value: "apple", "Apple" }
+                option { value: "banana", "Banana" }
+                optgroup {
+                    option { value: "cherry", "Cherry" }
+                    option { value: "durian", disabled: true, "Durian" }
+                }
+            }
+
+            "Write some notes"
+            textarea {
+                width: "50%",
+                height: "6px",
+                oninput: move |data| notes.set(data.value()),
+            }
+
+            "Characters written"
+            progress {
+                width: "50%",
+                value: "{progress}",
+                max: "100",
+            }
+            meter {
+                width: "50%",
+                value: "{progress}",
+                max: "100",
+                low: "30",
+                high: "70",
+                optimum: "50",
+            }
+
+            table {
+                width: "50%",
+                border_width: "1px",
+                tr {
+                    th { "Fruit" }
+                    th { "Characters" }
+                }
+                tr {
+                    td { "{fruit}" }
+                    td { "{notes.len()}" }
+                }
+                tr {
+                    td { colspan: "2", "A cell spanning both columns" }
+                }
+            }
+        }
+    })
+}
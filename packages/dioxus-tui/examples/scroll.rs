@@ -0,0 +1,50 @@
+use dioxus::prelude::*;
+
+fn main() {
+    dioxus_tui::launch(app);
+}
+
+fn app(cx: Scope) -> Element {
+    let scroll_events = use_state(cx, || 0);
+    let first_item = use_ref(cx, || None);
+
+    cx.render(rsx! {
+        div {
+            width: "100%",
+            height: "100%",
+            flex_direction: "column",
+
+            "Scroll the list with the mouse wheel, or focus it with tab and use the arrow and page keys"
+            "Scroll events: {scroll_events}"
+
+            div {
+                width: "50%",
+                height: "50%",
+                flex_direction: "column",
+                border_width: "1px",
+                overflow_y: "scroll",
+                onscroll: move |_| scroll_events.modify(|count| count + 1),
+
+                for i in 0..100 {
+                    div {
+                        onmounted: move |cx| {
+                            if i == 0 {
+                                first_item.set(Some(cx.inner().clone()));
+                            }
+                        },
+                        "item {i}"
+                    }
+                }
+            }
+
+            button {
+                onclick: move |_| {
+                    if let Some(item) = first_item.read().as_ref() {
+                        item.scroll_to(ScrollBehavior::Instant);
+                    }
+                },
+                "Scroll to top"
+            }
+        }
+    })
+}
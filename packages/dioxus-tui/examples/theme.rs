@@ -0,0 +1,32 @@
+use dioxus::prelude::*;
+use dioxus_tui::{Config, Theme};
+
+fn main() {
+    // pick the light or dark theme from the terminal and override the accent color
+    let theme = Theme::detect().with_variable("--accent", "orchid");
+    dioxus_tui::launch_cfg(app, Config::new().with_theme(theme));
+}
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx! {
+        div {
+            width: "100%",
+            height: "100%",
+            flex_direction: "column",
+            background_color: "var(--bg)",
+            color: "var(--fg)",
+
+            div {
+                border_width: "1px",
+                border_color: "var(--border)",
+                "Colors come from the theme"
+            }
+            div { color: "var(--accent)", "accent" }
+            div { color: "var(--success)", "success" }
+            div { color: "var(--warning)", "warning" }
+            div { color: "var(--error)", "error" }
+            div { color: "var(--muted)", "muted" }
+            div { color: "var(--missing, cyan)", "a missing variable uses the fallback" }
+        }
+    })
+}
@@ -6,7 +6,7 @@ use std::{
 use dioxus_core::{ElementId, Mutations, VirtualDom};
 use dioxus_html::{
     geometry::euclid::{Point2D, Rect, Size2D},
-    MountedData, MountedError, RenderedElementBacking,
+    MountedData, MountedError, RenderedElementBacking, ScrollBehavior,
 };
 
 use dioxus_native_core::NodeId;
@@ -82,6 +82,21 @@ impl RenderedElementBacking for TuiElement {
         })
     }
 
+    fn scroll_to(
+        &self,
+        _behavior: ScrollBehavior,
+    ) -> std::pin::Pin<Box<dyn futures::Future<Output = dioxus_html::MountedResult<()>>>> {
+        // the terminal can't animate scrolling, so smooth scrolling is instant
+        let scrolled = self.query.scroll_into_view(self.id);
+        Box::pin(async move {
+            if scrolled {
+                Ok(())
+            } else {
+                Err(MountedError::OperationFailed(Box::new(TuiElementNotFound)))
+            }
+        })
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -18,8 +18,12 @@ impl HtmlEventConverter for SerializedHtmlEventConverter {
         panic!("animation events not supported")
     }
 
-    fn convert_clipboard_data(&self, _: &PlatformEventData) -> ClipboardData {
-        panic!("clipboard events not supported")
+    fn convert_clipboard_data(&self, event: &PlatformEventData) -> ClipboardData {
+        if let plasmo::EventData::Clipboard(event) = downcast(event) {
+            ClipboardData::new(event)
+        } else {
+            panic!("event should be of type Clipboard")
+        }
     }
 
     fn convert_composition_data(&self, _: &PlatformEventData) -> CompositionData {
@@ -78,12 +82,20 @@ impl HtmlEventConverter for SerializedHtmlEventConverter {
         panic!("pointer events not supported")
     }
 
-    fn convert_scroll_data(&self, _: &PlatformEventData) -> ScrollData {
-        panic!("scroll events not supported")
+    fn convert_scroll_data(&self, event: &PlatformEventData) -> ScrollData {
+        if let plasmo::EventData::Scroll(event) = downcast(event) {
+            ScrollData::new(event)
+        } else {
+            panic!("event should be of type Scroll")
+        }
     }
 
-    fn convert_selection_data(&self, _: &PlatformEventData) -> SelectionData {
-        panic!("selection events not supported")
+    fn convert_selection_data(&self, event: &PlatformEventData) -> SelectionData {
+        if let plasmo::EventData::Selection(event) = downcast(event) {
+            SelectionData::new(event)
+        } else {
+            panic!("event should be of type Selection")
+        }
     }
 
     fn convert_toggle_data(&self, _: &PlatformEventData) -> ToggleData {
@@ -9,7 +9,7 @@ use std::{
     any::Any,
     ops::Deref,
     rc::Rc,
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
 };
 
 use dioxus_core::{Component, ElementId, VirtualDom};
@@ -18,8 +18,13 @@ use dioxus_native_core::dioxus::{DioxusState, NodeImmutableDioxusExt};
 use dioxus_native_core::prelude::*;
 
 use element::{create_mounted_events, find_mount_events};
-pub use plasmo::{query::Query, Config, RenderingMode, Size, TuiContext};
-use plasmo::{render, Driver};
+use futures::channel::mpsc::UnboundedSender;
+pub use plasmo::{
+    query::Query, Clipboard, Color, Config, Modifier, Osc52Clipboard, RenderingMode, Size,
+    Snapshot, SnapshotCell, Theme, TuiContext, SNAPSHOT_TIMEOUT, UPDATE_SNAPSHOTS_ENV,
+};
+use plasmo::{render, Driver, InputEvent};
+use taffy::Taffy;
 
 pub fn launch(app: Component<()>) {
     launch_cfg(app, Config::default())
@@ -33,56 +38,94 @@ pub fn launch_cfg_with_props<Props: 'static>(app: Component<Props>, props: Props
     dioxus_html::set_event_converter(Box::new(events::SerializedHtmlEventConverter));
 
     render(cfg, |rdom, taffy, event_tx| {
-        let dioxus_state = {
-            let mut rdom = rdom.write().unwrap();
-            DioxusState::create(&mut rdom)
-        };
-        let dioxus_state = Rc::new(RwLock::new(dioxus_state));
-        let mut vdom = VirtualDom::new_with_props(app, props)
-            .with_root_context(TuiContext::new(event_tx))
-            .with_root_context(Query::new(rdom.clone(), taffy.clone()))
-            .with_root_context(DioxusElementToNodeId {
-                mapping: dioxus_state.clone(),
-            });
-        let muts = vdom.rebuild();
+        create_renderer(app, props, rdom, taffy, event_tx)
+    })
+    .unwrap();
+}
 
-        let mut queued_events = Vec::new();
+/// Render a component into a virtual terminal of a fixed size and return the last frame drawn before the component
+/// quits with [`TuiContext::quit`]. Events can be sent to the component with [`TuiContext::inject_event`].
+///
+/// Panics if the component doesn't quit within [`SNAPSHOT_TIMEOUT`].
+///
+/// ```rust, ignore
+/// let snapshot = dioxus_tui::render_snapshot(app, 20, 5);
+/// snapshot.assert_snapshot("tests/snapshots/app.txt");
+/// ```
+pub fn render_snapshot(app: Component<()>, width: u16, height: u16) -> Snapshot {
+    render_snapshot_with_props(app, (), width, height)
+}
 
-        {
-            let mut rdom = rdom.write().unwrap();
-            let mut dioxus_state = dioxus_state.write().unwrap();
+/// Like [`render_snapshot`], but renders a component that takes props
+pub fn render_snapshot_with_props<Props: 'static>(
+    app: Component<Props>,
+    props: Props,
+    width: u16,
+    height: u16,
+) -> Snapshot {
+    dioxus_html::set_event_converter(Box::new(events::SerializedHtmlEventConverter));
 
-            // Find any mount events
-            let mounted = find_mount_events(&muts);
+    plasmo::render_snapshot(Config::new(), width, height, |rdom, taffy, event_tx| {
+        create_renderer(app, props, rdom, taffy, event_tx)
+    })
+    .unwrap()
+}
 
-            dioxus_state.apply_mutations(&mut rdom, muts);
+fn create_renderer<Props: 'static>(
+    app: Component<Props>,
+    props: Props,
+    rdom: &Arc<RwLock<RealDom>>,
+    taffy: &Arc<Mutex<Taffy>>,
+    event_tx: UnboundedSender<InputEvent>,
+) -> DioxusRenderer {
+    let dioxus_state = {
+        let mut rdom = rdom.write().unwrap();
+        DioxusState::create(&mut rdom)
+    };
+    let dioxus_state = Rc::new(RwLock::new(dioxus_state));
+    let mut vdom = VirtualDom::new_with_props(app, props)
+        .with_root_context(TuiContext::new(event_tx))
+        .with_root_context(Query::new(rdom.clone(), taffy.clone()))
+        .with_root_context(DioxusElementToNodeId {
+            mapping: dioxus_state.clone(),
+        });
+    let muts = vdom.rebuild();
 
-            // Send the mount events
-            create_mounted_events(
-                &vdom,
-                &mut queued_events,
-                mounted
-                    .iter()
-                    .map(|id| (*dbg!(id), dioxus_state.element_to_node_id(*id))),
-            );
-        }
+    let mut queued_events = Vec::new();
 
-        DioxusRenderer {
-            vdom,
-            dioxus_state,
-            queued_events,
-            #[cfg(all(feature = "hot-reload", debug_assertions))]
-            hot_reload_rx: {
-                let (hot_reload_tx, hot_reload_rx) =
-                    tokio::sync::mpsc::unbounded_channel::<dioxus_hot_reload::HotReloadMsg>();
-                dioxus_hot_reload::connect(move |msg| {
-                    let _ = hot_reload_tx.send(msg);
-                });
-                hot_reload_rx
-            },
-        }
-    })
-    .unwrap();
+    {
+        let mut rdom = rdom.write().unwrap();
+        let mut dioxus_state = dioxus_state.write().unwrap();
+
+        // Find any mount events
+        let mounted = find_mount_events(&muts);
+
+        dioxus_state.apply_mutations(&mut rdom, muts);
+
+        // Send the mount events
+        create_mounted_events(
+            &vdom,
+            &mut queued_events,
+            mounted
+                .iter()
+                .map(|id| (*id, dioxus_state.element_to_node_id(*id))),
+        );
+    }
+
+    DioxusRenderer {
+        vdom,
+        dioxus_state,
+        queued_events,
+        #[cfg(all(feature = "hot-reload", debug_assertions))]
+        hot_reload_rx: {
+            let (hot_reload_tx, hot_reload_rx) =
+                tokio::sync::mpsc::unbounded_channel::<dioxus_hot_reload::HotReloadMsg>();
+            dioxus_hot_reload::connect(move |msg| {
+                let _ = hot_reload_tx.send(msg);
+            });
+            hot_reload_rx
+        },
+    }
 }
 
 struct DioxusRenderer {
@@ -0,0 +1,130 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use dioxus::prelude::*;
+use dioxus_tui::TuiContext;
+
+fn key(code: KeyCode) -> Event {
+    Event::Key(KeyEvent {
+        code,
+        modifiers: KeyModifiers::NONE,
+        kind: crossterm::event::KeyEventKind::Press,
+        state: crossterm::event::KeyEventState::NONE,
+    })
+}
+
+fn mouse(kind: MouseEventKind, column: u16, row: u16) -> Event {
+    Event::Mouse(MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+/// The wheel scrolls the element under the last position of the mouse, so the mouse moves over the element first
+fn wheel_down(column: u16, row: u16) -> [Event; 2] {
+    [
+        mouse(MouseEventKind::Moved, column, row),
+        mouse(MouseEventKind::ScrollDown, column, row),
+    ]
+}
+
+#[derive(Props, PartialEq)]
+struct ListProps {
+    overflow: &'static str,
+    events: Vec<Event>,
+}
+
+/// A list of ten items in a container three rows high. The app quits after the container scrolls, or right away if
+/// there are no events
+fn list(cx: Scope<ListProps>) -> Element {
+    let tui_ctx: TuiContext = cx.consume_context().unwrap();
+    if cx.generation() == 0 {
+        for event in &cx.props.events {
+            tui_ctx.inject_event(event.clone());
+        }
+    }
+    if cx.props.events.is_empty() {
+        tui_ctx.quit();
+    }
+    cx.render(rsx! {
+        div {
+            width: "100%",
+            height: "100%",
+            flex_direction: "column",
+            overflow_y: cx.props.overflow,
+            onscroll: move |_| tui_ctx.quit(),
+            for i in 0..10 {
+                div { "item {i}" }
+            }
+        }
+    })
+}
+
+fn render_list(overflow: &'static str, events: Vec<Event>) -> dioxus_tui::Snapshot {
+    dioxus_tui::render_snapshot_with_props(list, ListProps { overflow, events }, 10, 3)
+}
+
+#[test]
+fn scrollbar_shows_the_visible_part() {
+    let snapshot = render_list("scroll", Vec::new());
+    assert_eq!(snapshot.lines(), ["item 0   █", "item 1   │", "item 2   │"]);
+
+    // content that fits doesn't need a scrollbar with overflow: auto
+    let snapshot = dioxus_tui::render_snapshot(app, 10, 3);
+    assert_eq!(snapshot.lines(), ["item 0", "", ""]);
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        tui_ctx.quit();
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                flex_direction: "column",
+                overflow_y: "auto",
+                div { "item 0" }
+            }
+        })
+    }
+}
+
+#[test]
+fn wheel_scrolls_the_container() {
+    // one line down moves the thumb less than a cell
+    let snapshot = render_list("scroll", wheel_down(0, 0).to_vec());
+    assert_eq!(snapshot.lines(), ["item 1   █", "item 2   │", "item 3   │"]);
+}
+
+#[test]
+fn keys_scroll_the_focused_container() {
+    let snapshot = render_list("auto", vec![key(KeyCode::Tab), key(KeyCode::End)]);
+    assert_eq!(snapshot.lines(), ["item 7   │", "item 8   │", "item 9   █"]);
+}
+
+#[test]
+fn hidden_overflow_does_not_scroll() {
+    // the frame drawn after the wheel event still shows the first items
+    let snapshot = dioxus_tui::render_snapshot(app, 10, 3);
+    assert_eq!(snapshot.lines(), ["item 0", "item 1", "item 2"]);
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        if cx.generation() == 0 {
+            for event in wheel_down(0, 0) {
+                tui_ctx.inject_event(event);
+            }
+        }
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                flex_direction: "column",
+                overflow_y: "hidden",
+                onwheel: move |_| tui_ctx.quit(),
+                for i in 0..10 {
+                    div { "item {i}" }
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,261 @@
+use std::sync::{Arc, Mutex};
+
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use dioxus::prelude::*;
+use dioxus_tui::{Clipboard, Config, Modifier, TuiContext};
+
+fn key(code: KeyCode) -> Event {
+    Event::Key(KeyEvent {
+        code,
+        modifiers: KeyModifiers::NONE,
+        kind: crossterm::event::KeyEventKind::Press,
+        state: crossterm::event::KeyEventState::NONE,
+    })
+}
+
+fn mouse(kind: MouseEventKind, column: u16, row: u16) -> Event {
+    Event::Mouse(MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+/// Press the primary button at one cell, drag to another and release it there
+fn drag(from: (u16, u16), to: (u16, u16)) -> [Event; 3] {
+    [
+        mouse(MouseEventKind::Down(MouseButton::Left), from.0, from.1),
+        mouse(MouseEventKind::Drag(MouseButton::Left), to.0, to.1),
+        mouse(MouseEventKind::Up(MouseButton::Left), to.0, to.1),
+    ]
+}
+
+/// Send events to the app the first time it renders
+fn inject(cx: &ScopeState, events: impl IntoIterator<Item = Event>) {
+    if cx.generation() == 0 {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        for event in events {
+            tui_ctx.inject_event(event);
+        }
+    }
+}
+
+#[test]
+fn drag_selects_across_text_nodes() {
+    let snapshot = dioxus_tui::render_snapshot(app, 30, 4);
+    // copy on select is off by default, so only the select event is sent
+    assert_eq!(&snapshot.lines()[2..], [r#"select: "line\nsecond""#, ""]);
+    // the selected cells are drawn reversed
+    let reversed = |x, y| {
+        snapshot
+            .cell(x, y)
+            .unwrap()
+            .modifier
+            .contains(Modifier::REVERSED)
+    };
+    assert!(!reversed(5, 0));
+    assert!(reversed(6, 0));
+    assert!(reversed(9, 0));
+    assert!(reversed(5, 1));
+    assert!(!reversed(6, 1));
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        let events = use_ref(cx, Vec::new);
+        // from the middle of the first line to the middle of the second line
+        inject(cx, drag((6, 0), (5, 1)));
+        cx.render(rsx! {
+            div {
+                flex_direction: "column",
+                oncopy: move |evt| events.write().push(format!("copy: {:?}", evt.text().unwrap_or_default())),
+                div {
+                    flex_direction: "column",
+                    onselect: move |evt| {
+                        events.write().push(format!("select: {:?}", evt.text().unwrap_or_default()));
+                        tui_ctx.quit();
+                    },
+                    div { "first line" }
+                    div { "second line" }
+                }
+                for event in events.read().iter() {
+                    div { "{event}" }
+                }
+            }
+        })
+    }
+}
+
+/// A clipboard that remembers the text copied to it
+#[derive(Clone, Default)]
+struct RecordingClipboard(Arc<Mutex<Vec<String>>>);
+
+impl Clipboard for RecordingClipboard {
+    fn set_text(&self, text: &str) {
+        self.0.lock().unwrap().push(text.to_string());
+    }
+}
+
+#[test]
+fn drag_copies_to_the_clipboard() {
+    let clipboard = RecordingClipboard::default();
+    dioxus_tui::launch_cfg(
+        app,
+        Config::new()
+            .with_headless()
+            .with_clipboard(clipboard.clone())
+            .with_copy_on_select(),
+    );
+    assert_eq!(*clipboard.0.lock().unwrap(), ["hello"]);
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        // clicking without dragging does not copy anything
+        let [click_down, _, click_up] = drag((2, 0), (2, 0));
+        inject(
+            cx,
+            [click_down, click_up]
+                .into_iter()
+                .chain(drag((0, 0), (4, 0))),
+        );
+        cx.render(rsx! {
+            div {
+                oncopy: move |_| tui_ctx.quit(),
+                "hello world"
+            }
+        })
+    }
+}
+
+#[test]
+fn drag_does_not_copy_unless_copy_on_select_is_set() {
+    let clipboard = RecordingClipboard::default();
+    dioxus_tui::launch_cfg(
+        app,
+        Config::new()
+            .with_headless()
+            .with_clipboard(clipboard.clone()),
+    );
+    assert!(clipboard.0.lock().unwrap().is_empty());
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        inject(cx, drag((0, 0), (4, 0)));
+        cx.render(rsx! {
+            div {
+                onselect: move |_| tui_ctx.quit(),
+                "hello world"
+            }
+        })
+    }
+}
+
+#[test]
+fn paste_into_a_text_input_drops_line_breaks() {
+    let snapshot = dioxus_tui::render_snapshot(app, 20, 4);
+    assert!(snapshot.lines()[1].starts_with("│onetwo "));
+    assert_eq!(snapshot.lines()[3], r#"input: "onetwo""#);
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        let value = use_state(cx, String::new);
+        inject(
+            cx,
+            [key(KeyCode::Tab), Event::Paste("one\ntwo".to_string())],
+        );
+        cx.render(rsx! {
+            div {
+                flex_direction: "column",
+                input {
+                    r#type: "text",
+                    // the input is empty when it is created
+                    oninput: move |evt| {
+                        if !evt.value().is_empty() {
+                            value.set(evt.value());
+                            tui_ctx.quit();
+                        }
+                    },
+                }
+                div { "input: {value:?}" }
+            }
+        })
+    }
+}
+
+#[test]
+fn paste_into_a_textarea_keeps_line_breaks() {
+    let snapshot = dioxus_tui::render_snapshot(app, 20, 5);
+    assert_eq!(
+        snapshot.lines(),
+        [
+            "┌──────┐",
+            "│one   │",
+            "│two   │",
+            "└──────┘",
+            r#"input: "one\ntwo""#
+        ]
+    );
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        let value = use_state(cx, String::new);
+        // terminals send line breaks as carriage returns
+        inject(
+            cx,
+            [key(KeyCode::Tab), Event::Paste("one\r\ntwo".to_string())],
+        );
+        cx.render(rsx! {
+            div {
+                flex_direction: "column",
+                textarea {
+                    width: "8px",
+                    cols: "6",
+                    rows: "2",
+                    oninput: move |evt| {
+                        value.set(evt.value());
+                        tui_ctx.quit();
+                    },
+                }
+                div { "input: {value:?}" }
+            }
+        })
+    }
+}
+
+#[test]
+fn paste_into_a_number_input_only_accepts_numbers() {
+    let snapshot = dioxus_tui::render_snapshot(app, 20, 4);
+    // pasting text that is not a number does nothing
+    assert_eq!(snapshot.lines()[3], r#"input: "42""#);
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        let value = use_state(cx, String::new);
+        inject(
+            cx,
+            [
+                key(KeyCode::Tab),
+                Event::Paste("abc".to_string()),
+                Event::Paste("42".to_string()),
+            ],
+        );
+        cx.render(rsx! {
+            div {
+                flex_direction: "column",
+                input {
+                    r#type: "number",
+                    // the input is empty when it is created
+                    oninput: move |evt| {
+                        if !evt.value().is_empty() {
+                            value.set(evt.value());
+                            tui_ctx.quit();
+                        }
+                    },
+                }
+                div { "input: {value:?}" }
+            }
+        })
+    }
+}
@@ -0,0 +1,113 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use dioxus::prelude::*;
+use dioxus_tui::{Color, TuiContext};
+
+fn key(code: KeyCode) -> Event {
+    Event::Key(KeyEvent {
+        code,
+        modifiers: KeyModifiers::NONE,
+        kind: crossterm::event::KeyEventKind::Press,
+        state: crossterm::event::KeyEventState::NONE,
+    })
+}
+
+#[test]
+fn text_snapshot() {
+    let snapshot = dioxus_tui::render_snapshot(app, 12, 3);
+    snapshot.assert_snapshot(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/snapshots/hello_world.txt"
+    ));
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        // the first frame is drawn before the quit event is handled
+        tui_ctx.quit();
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                flex_direction: "column",
+                "hello world"
+                "goodbye"
+            }
+        })
+    }
+}
+
+#[test]
+fn styled_snapshot() {
+    let snapshot = dioxus_tui::render_snapshot(app, 4, 2);
+    assert_eq!(snapshot.lines(), ["hi", ""]);
+    assert_eq!(snapshot.cell(0, 0).unwrap().fg, Color::Rgb(255, 0, 0));
+    assert_eq!(snapshot.cell(0, 1).unwrap().fg, Color::Reset);
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        tui_ctx.quit();
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                color: "rgb(255, 0, 0)",
+                "hi"
+            }
+        })
+    }
+}
+
+#[test]
+fn snapshot_after_events() {
+    let snapshot = dioxus_tui::render_snapshot(app, 10, 1);
+    assert_eq!(snapshot.lines(), ["count: 1"]);
+
+    fn app(cx: Scope) -> Element {
+        let count = use_state(cx, || 0);
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        if *count.get() == 0 {
+            // focus the element and press a key
+            tui_ctx.inject_event(key(KeyCode::Tab));
+            tui_ctx.inject_event(key(KeyCode::Char('a')));
+        }
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                onkeydown: move |_| {
+                    count.set(1);
+                    tui_ctx.quit();
+                },
+                "count: {count}"
+            }
+        })
+    }
+}
+
+#[test]
+fn outline_and_shadow_snapshot() {
+    let snapshot = dioxus_tui::render_snapshot(app, 8, 4);
+    // the outline is drawn around the element and the shadow is one cell to the right and below it
+    assert_eq!(snapshot.lines(), ["┌──┐", "│hi│", "└──┘", ""]);
+    assert_eq!(snapshot.cell(1, 1).unwrap().bg, Color::Reset);
+    assert_eq!(snapshot.cell(3, 2).unwrap().bg, Color::Rgb(0, 0, 255));
+    assert_eq!(snapshot.cell(1, 2).unwrap().bg, Color::Reset);
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        tui_ctx.quit();
+        cx.render(rsx! {
+            div {
+                width: "100%",
+                height: "100%",
+                padding: "1px",
+                div {
+                    width: "2px",
+                    height: "1px",
+                    outline: "1px solid",
+                    box_shadow: "1px 1px rgb(0, 0, 255)",
+                    "hi"
+                }
+            }
+        })
+    }
+}
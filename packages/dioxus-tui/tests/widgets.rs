@@ -0,0 +1,222 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use dioxus::prelude::*;
+use dioxus_tui::{Color, TuiContext};
+
+fn key(code: KeyCode) -> Event {
+    Event::Key(KeyEvent {
+        code,
+        modifiers: KeyModifiers::NONE,
+        kind: crossterm::event::KeyEventKind::Press,
+        state: crossterm::event::KeyEventState::NONE,
+    })
+}
+
+/// Send events to the app the first time it renders
+fn inject(cx: &ScopeState, events: impl IntoIterator<Item = Event>) {
+    if cx.generation() == 0 {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        for event in events {
+            tui_ctx.inject_event(event);
+        }
+    }
+}
+
+#[test]
+fn select_skips_disabled_options() {
+    let snapshot = dioxus_tui::render_snapshot(app, 10, 3);
+    assert_eq!(snapshot.lines(), ["Cherry ▾", "input: c", "change: c"]);
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        let events = use_ref(cx, Vec::new);
+        // focus the select and move to the next option
+        inject(cx, [key(KeyCode::Tab), key(KeyCode::Down)]);
+        cx.render(rsx! {
+            div {
+                flex_direction: "column",
+                // only the first listener on an element is called, so the change event is handled after it bubbles
+                onchange: move |evt| {
+                    events.write().push(format!("change: {}", evt.value()));
+                    tui_ctx.quit();
+                },
+                select {
+                    oninput: move |evt| events.write().push(format!("input: {}", evt.value())),
+                    option { value: "a", "Apple" }
+                    option { value: "b", disabled: true, "Banana" }
+                    option { value: "c", "Cherry" }
+                }
+                for event in events.read().iter() {
+                    div { "{event}" }
+                }
+            }
+        })
+    }
+}
+
+#[test]
+fn select_opens_and_chooses_with_the_keyboard() {
+    let snapshot = dioxus_tui::render_snapshot(app, 10, 4);
+    // the list closes after an option is chosen
+    assert_eq!(snapshot.lines(), ["Banana ▾", "", "", ""]);
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        // open the list, jump to the last option, move up one and choose it
+        inject(
+            cx,
+            [
+                key(KeyCode::Tab),
+                key(KeyCode::Enter),
+                key(KeyCode::End),
+                key(KeyCode::Up),
+                key(KeyCode::Enter),
+            ],
+        );
+        cx.render(rsx! {
+            select {
+                onchange: move |_| tui_ctx.quit(),
+                option { value: "a", "Apple" }
+                option { value: "b", "Banana" }
+                option { value: "c", "Cherry" }
+            }
+        })
+    }
+}
+
+#[test]
+fn select_finds_options_by_their_first_letter() {
+    let snapshot = dioxus_tui::render_snapshot(app, 10, 1);
+    assert_eq!(snapshot.lines(), ["Cherry ▾"]);
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        inject(cx, [key(KeyCode::Tab), key(KeyCode::Char('c'))]);
+        cx.render(rsx! {
+            select {
+                onchange: move |_| tui_ctx.quit(),
+                option { value: "a", "Apple" }
+                option { value: "b", "Banana" }
+                option { value: "c", "Cherry" }
+            }
+        })
+    }
+}
+
+#[test]
+fn textarea_soft_wraps_long_lines() {
+    let snapshot = dioxus_tui::render_snapshot(app, 8, 4);
+    assert_eq!(
+        snapshot.lines(),
+        ["┌──────┐", "│hello │", "│world │", "└──────┘"]
+    );
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        tui_ctx.quit();
+        cx.render(rsx! {
+            textarea { cols: "6", rows: "2", value: "hello world" }
+        })
+    }
+}
+
+#[test]
+fn textarea_scrolls_to_the_cursor() {
+    let snapshot = dioxus_tui::render_snapshot(app, 8, 4);
+    assert_eq!(
+        snapshot.lines(),
+        ["┌──────┐", "│three │", "│!four │", "└──────┘"]
+    );
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        // move the cursor from the first line to the last line and type there
+        inject(
+            cx,
+            [
+                key(KeyCode::Tab),
+                key(KeyCode::Down),
+                key(KeyCode::Down),
+                key(KeyCode::Down),
+                key(KeyCode::Char('!')),
+            ],
+        );
+        cx.render(rsx! {
+            textarea {
+                cols: "6",
+                rows: "2",
+                value: "one\ntwo\nthree\nfour",
+                oninput: move |_| tui_ctx.quit(),
+            }
+        })
+    }
+}
+
+#[test]
+fn textarea_fires_input_and_change_events() {
+    let snapshot = dioxus_tui::render_snapshot(app, 12, 7);
+    assert_eq!(
+        &snapshot.lines()[4..],
+        ["input: h", "input: hi", "change: hi"]
+    );
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        let events = use_ref(cx, Vec::new);
+        // type two characters and move the focus to the next element
+        inject(
+            cx,
+            [
+                key(KeyCode::Tab),
+                key(KeyCode::Char('h')),
+                key(KeyCode::Char('i')),
+                key(KeyCode::Tab),
+            ],
+        );
+        cx.render(rsx! {
+            div {
+                flex_direction: "column",
+                onchange: move |evt| {
+                    events.write().push(format!("change: {}", evt.value()));
+                    tui_ctx.quit();
+                },
+                textarea {
+                    cols: "6",
+                    rows: "2",
+                    oninput: move |evt| events.write().push(format!("input: {}", evt.value())),
+                }
+                div { tabindex: "0" }
+                for event in events.read().iter() {
+                    div { "{event}" }
+                }
+            }
+        })
+    }
+}
+
+#[test]
+fn progress_fills_the_value() {
+    let snapshot = dioxus_tui::render_snapshot(app, 10, 2);
+    let green = Color::Rgb(0, 128, 0);
+    let filled = |y| {
+        (0..10)
+            .take_while(|x| snapshot.cell(*x, y).unwrap().bg == green)
+            .count()
+    };
+    // the default width is ten cells
+    assert_eq!(filled(0), 3);
+    assert_ne!(snapshot.cell(3, 0).unwrap().bg, Color::Reset);
+    assert_eq!(filled(1), 0);
+
+    fn app(cx: Scope) -> Element {
+        let tui_ctx: TuiContext = cx.consume_context().unwrap();
+        tui_ctx.quit();
+        cx.render(rsx! {
+            div {
+                flex_direction: "column",
+                progress { value: "30", max: "100" }
+                // without a value the progress bar is indeterminate and drawn empty
+                progress {}
+            }
+        })
+    }
+}
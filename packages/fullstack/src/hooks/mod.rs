@@ -1,2 +1,4 @@
 pub mod server_cached;
 pub mod server_future;
+pub mod server_future_keyed;
+pub mod session;
@@ -110,11 +110,11 @@ where
 }
 
 pub struct UseServerFuture<T> {
-    update: Arc<dyn Fn()>,
-    needs_regen: Cell<bool>,
-    task: Cell<Option<TaskId>>,
-    dependencies: Vec<Box<dyn Any>>,
-    value: Rc<RefCell<Option<Box<T>>>>,
+    pub(crate) update: Arc<dyn Fn()>,
+    pub(crate) needs_regen: Cell<bool>,
+    pub(crate) task: Cell<Option<TaskId>>,
+    pub(crate) dependencies: Vec<Box<dyn Any>>,
+    pub(crate) value: Rc<RefCell<Option<Box<T>>>>,
 }
 
 impl<T> UseServerFuture<T> {
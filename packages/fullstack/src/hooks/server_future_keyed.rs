@@ -0,0 +1,181 @@
+use dioxus::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+use std::cell::Cell;
+use std::fmt::Debug;
+use std::future::Future;
+
+use super::server_future::UseServerFuture;
+
+/// A future that resolves to a value and is shared by key.
+///
+/// This works like [`super::server_future::use_server_future`], but every component that uses the same key on a page shares one value:
+/// - On the server, only the first component that uses a key runs its future during a render. Other components with the same key wait for that value instead of running their own future.
+/// - The value is serialized into the page once and every component with the key hydrates from it on the client.
+/// - If [`crate::prelude::ServeConfigBuilder::cache_server_futures`] is enabled, the value is also reused by later requests until it expires or is invalidated.
+///
+/// Every component that uses a key must resolve to the same type.
+///
+/// # Example
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_fullstack::prelude::*;
+///
+/// fn app(cx: Scope) -> Element {
+///     let count = use_server_future_keyed(cx, "visitor-count", (), |_| async { 1234 })?;
+///
+///     render! {
+///         "Visitors: {count.value():?}"
+///     }
+/// }
+/// ```
+#[must_use = "Consider using `cx.spawn` to run a future without reading its value"]
+pub fn use_server_future_keyed<T, F, D>(
+    cx: &ScopeState,
+    key: impl Into<String>,
+    dependencies: D,
+    future: impl FnOnce(D::Out) -> F,
+) -> Option<&UseServerFuture<T>>
+where
+    T: 'static + Serialize + DeserializeOwned + Debug,
+    F: Future<Output = T> + 'static,
+    D: UseFutureDep,
+{
+    let state = cx.use_hook(move || UseServerFuture {
+        update: cx.schedule_update(),
+        needs_regen: Cell::new(true),
+        value: Default::default(),
+        task: Cell::new(None),
+        dependencies: Vec::new(),
+    });
+
+    let first_run = { state.value.borrow().as_ref().is_none() && state.task.get().is_none() };
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        if first_run {
+            let key = key.into();
+            match crate::html_storage::deserialize::get_keyed_server_data::<T>(&key) {
+                Some(data) => {
+                    tracing::trace!("Loaded {data:?} for key {key:?} from server");
+                    *state.value.borrow_mut() = Some(Box::new(data));
+                    state.needs_regen.set(false);
+                    // Track the dependencies so they don't immediately restart the future
+                    dependencies.apply(&mut state.dependencies);
+                    return Some(state);
+                }
+                None => {
+                    tracing::trace!("Failed to load {key:?} from server... running future");
+                }
+            };
+        }
+    }
+
+    if dependencies.clone().apply(&mut state.dependencies) || state.needs_regen.get() {
+        // We don't need regen anymore
+        state.needs_regen.set(false);
+
+        // Clone in our cells
+        let value = state.value.clone();
+        let schedule_update = state.update.clone();
+
+        // Cancel the current future
+        if let Some(current) = state.task.take() {
+            cx.remove_future(current);
+        }
+
+        #[cfg(feature = "ssr")]
+        {
+            if first_run {
+                use crate::server_future_cache::*;
+
+                let key = key.into();
+                let context = crate::prelude::server_context();
+                let task = match claim_keyed(&context, &key) {
+                    KeyedClaim::Ready(bytes) => match deserialize_shared::<T>(&key, &bytes) {
+                        Some(data) => {
+                            tracing::trace!("Reusing resolved value for key {key:?}");
+                            *value.borrow_mut() = Some(Box::new(data));
+                            return Some(state);
+                        }
+                        None => {
+                            let fut = future(dependencies.out());
+                            cx.push_future(async move {
+                                *value.borrow_mut() = Some(Box::new(fut.await));
+                                schedule_update();
+                            })
+                        }
+                    },
+                    KeyedClaim::Waiting => {
+                        let fut = future(dependencies.out());
+                        let wait = WaitForKeyed {
+                            entries: context.keyed_entries.clone(),
+                            key: key.clone(),
+                        };
+                        cx.push_future(async move {
+                            let shared = wait
+                                .await
+                                .and_then(|bytes| deserialize_shared::<T>(&key, &bytes));
+                            let data = match shared {
+                                Some(data) => data,
+                                None => fut.await,
+                            };
+                            *value.borrow_mut() = Some(Box::new(data));
+                            schedule_update();
+                        })
+                    }
+                    KeyedClaim::Owner(owner) => {
+                        let fut = future(dependencies.out());
+                        // If this future is cancelled, dropping the owner releases the key for any waiting components
+                        cx.push_future(async move {
+                            let data = fut.await;
+                            let mut serialized = Vec::new();
+                            match ciborium::into_writer(&data, &mut serialized) {
+                                Ok(()) => owner.resolve(serialized),
+                                Err(err) => {
+                                    tracing::error!(
+                                        "Failed to serialize value for key {key:?}: {err}"
+                                    )
+                                }
+                            }
+                            *value.borrow_mut() = Some(Box::new(data));
+                            schedule_update();
+                        })
+                    }
+                };
+                state.task.set(Some(task));
+                tracing::trace!("Suspending first run of use_server_future_keyed");
+                cx.suspend();
+                return None;
+            }
+        }
+
+        // Create the new future
+        let fut = future(dependencies.out());
+
+        state.task.set(Some(cx.push_future(async move {
+            let data = fut.await;
+            *value.borrow_mut() = Some(Box::new(data));
+
+            schedule_update();
+        })));
+    }
+
+    if first_run {
+        None
+    } else {
+        Some(state)
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn deserialize_shared<T: DeserializeOwned>(key: &str, bytes: &[u8]) -> Option<T> {
+    match ciborium::from_reader(std::io::Cursor::new(bytes)) {
+        Ok(data) => Some(data),
+        Err(err) => {
+            tracing::error!(
+                "Failed to deserialize the shared value for key {key:?}. Make sure every use of the key resolves to the same type: {err}"
+            );
+            None
+        }
+    }
+}
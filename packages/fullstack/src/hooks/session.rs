@@ -0,0 +1,41 @@
+use dioxus::prelude::ScopeState;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Read a value from the session of the current request while rendering.
+///
+/// On the server, the value is read from the [`crate::prelude::Session`] loaded for the request and serialized into the HTML. On the client, the value is read from the serialized data, so the first render on the client matches the server render.
+///
+/// The session is only loaded during server side rendering if a [`crate::prelude::SessionConfig`] is inserted into the server context. Pages that read the session should not be cached with incremental rendering.
+///
+/// # Example
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_fullstack::prelude::*;
+///
+/// fn app(cx: Scope) -> Element {
+///     let user = use_session_value::<String>(cx, "user");
+///
+///     render! {
+///         "Logged in as: {user:?}"
+///     }
+/// }
+/// ```
+pub fn use_session_value<'a, T: 'static + Serialize + DeserializeOwned>(
+    cx: &'a ScopeState,
+    key: &str,
+) -> &'a Option<T> {
+    cx.use_hook(|| {
+        crate::hooks::server_cached::server_cached(|| {
+            #[cfg(feature = "ssr")]
+            {
+                crate::prelude::server_context()
+                    .get::<crate::prelude::Session>()
+                    .and_then(|session| session.get(key))
+            }
+            #[cfg(not(feature = "ssr"))]
+            {
+                None
+            }
+        })
+    })
+}
@@ -58,6 +58,10 @@ pub(crate) fn take_server_data<T: DeserializeOwned>() -> Option<T> {
     SERVER_DATA.as_ref()?.take()
 }
 
+pub(crate) fn get_keyed_server_data<T: DeserializeOwned>(key: &str) -> Option<T> {
+    SERVER_DATA.as_ref()?.get_keyed(key)
+}
+
 #[cfg(not(feature = "ssr"))]
 /// Get the props from the document. This is only available in the browser.
 ///
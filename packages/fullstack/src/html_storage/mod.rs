@@ -1,6 +1,6 @@
 #![allow(unused)]
 
-use std::{io::Cursor, sync::atomic::AtomicUsize};
+use std::{collections::HashMap, io::Cursor, sync::atomic::AtomicUsize};
 
 use serde::{de::DeserializeOwned, Serialize};
 
@@ -11,6 +11,9 @@ pub(crate) mod serialize;
 #[derive(serde::Serialize, serde::Deserialize, Default)]
 pub(crate) struct HTMLData {
     pub data: Vec<Vec<u8>>,
+    /// Data that is shared by key instead of by the order it was pushed in
+    #[serde(default)]
+    pub keyed: HashMap<String, Vec<u8>>,
 }
 
 impl HTMLData {
@@ -20,9 +23,15 @@ impl HTMLData {
         self.data.push(serialized);
     }
 
+    /// Insert already serialized data under a key. Only the first value for each key is kept.
+    pub(crate) fn insert_keyed(&mut self, key: String, serialized: Vec<u8>) {
+        self.keyed.entry(key).or_insert(serialized);
+    }
+
     pub(crate) fn cursor(self) -> HTMLDataCursor {
         HTMLDataCursor {
             data: self.data,
+            keyed: self.keyed,
             index: AtomicUsize::new(0),
         }
     }
@@ -30,10 +39,23 @@ impl HTMLData {
 
 pub(crate) struct HTMLDataCursor {
     data: Vec<Vec<u8>>,
+    keyed: HashMap<String, Vec<u8>>,
     index: AtomicUsize,
 }
 
 impl HTMLDataCursor {
+    /// Get the data for a key. Unlike [`HTMLDataCursor::take`], keyed data can be read any number of times.
+    pub fn get_keyed<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let bytes = self.keyed.get(key)?;
+        match ciborium::from_reader(Cursor::new(bytes)) {
+            Ok(x) => Some(x),
+            Err(e) => {
+                tracing::error!("Error deserializing keyed data {key:?}: {:?}", e);
+                None
+            }
+        }
+    }
+
     pub fn take<T: DeserializeOwned>(&self) -> Option<T> {
         let current = self.index.load(std::sync::atomic::Ordering::SeqCst);
         if current >= self.data.len() {
@@ -0,0 +1,502 @@
+//! Partial hydration for mostly static pages.
+//!
+//! Wrap the interactive parts of a page in an [`Island`]. The server renders the rest of the page as static HTML and only serializes the props of each island. On the client, an [`IslandRegistry`] boots a separate small [`VirtualDom`] for each island instead of hydrating the whole page.
+//!
+//! Islands are rendered in their own [`VirtualDom`], so they don't share context with the page around them. On the server, islands can use the server context of the request and suspense inside an island is awaited before the page is sent.
+//!
+//! The hydration ids inside an island are numbered from zero like a separate page. The wrapper element of each island separates its ids from the ids of the page or island around it, and islands nested inside another island are keyed under the key of that island.
+#![allow(non_snake_case)]
+use dioxus::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// The props for an [`Island`]
+#[derive(Props)]
+pub struct IslandProps<P: 'static> {
+    /// The name the component is registered under in the [`IslandRegistry`] on the client
+    name: &'static str,
+    /// The component to render inside the island
+    component: Component<P>,
+    /// The props of the component. These are serialized into the page so the client can render the same island
+    props: P,
+}
+
+impl<P: PartialEq> PartialEq for IslandProps<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.props == other.props
+    }
+}
+
+/// An interactive part of a mostly static page.
+///
+/// On the server, the component is rendered in its own [`VirtualDom`] and its props are serialized into the page. On the client, the island is hydrated by [`IslandRegistry::launch`] if a component is registered under the same name.
+///
+/// # Example
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_fullstack::prelude::*;
+///
+/// fn app(cx: Scope) -> Element {
+///     render! {
+///         h1 { "A static page" }
+///         Island {
+///             name: "counter",
+///             component: Counter,
+///             props: CounterProps { start: 10 },
+///         }
+///     }
+/// }
+///
+/// #[derive(Props, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+/// struct CounterProps {
+///     start: i32,
+/// }
+///
+/// fn Counter(cx: Scope<CounterProps>) -> Element {
+///     let mut count = use_state(cx, || cx.props.start);
+///     render! {
+///         button { onclick: move |_| count += 1, "{count}" }
+///     }
+/// }
+/// ```
+pub fn Island<'a, P>(cx: Scope<'a, IslandProps<P>>) -> Element<'a>
+where
+    P: for<'b> Properties<'b> + Clone + PartialEq + Serialize + DeserializeOwned + 'static,
+{
+    #[cfg(feature = "ssr")]
+    {
+        let (id, html) = cx.use_hook(|| render_island(cx, next_island_key(cx), cx.props));
+        let Some(html) = html.borrow().clone() else {
+            // The island is still waiting for suspense
+            cx.suspend();
+            return None;
+        };
+
+        render! {
+            div {
+                id: "{id}",
+                "data-dioxus-island": cx.props.name,
+                dangerous_inner_html: "{html}",
+            }
+        }
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        // Outside of the server, the component is rendered directly inside the same wrapper
+        let IslandProps {
+            name,
+            component,
+            props,
+        } = cx.props;
+        let id = cx.use_hook(|| next_island_key(cx));
+
+        render! {
+            div {
+                id: "{id}",
+                "data-dioxus-island": *name,
+                cx.component(
+                    IslandRoot,
+                    IslandRootProps {
+                        island: id.clone(),
+                        name,
+                        component: *component,
+                        props: props.clone(),
+                    },
+                    "IslandRoot",
+                )
+            }
+        }
+    }
+}
+
+/// The prefix of the keys of islands on the page
+const ISLAND_PREFIX: &str = "dioxus-island";
+
+/// The number of islands rendered so far in a VirtualDom, and the key the islands are numbered under
+#[derive(Clone)]
+struct IslandCount {
+    prefix: std::rc::Rc<str>,
+    next: std::rc::Rc<std::cell::Cell<usize>>,
+}
+
+impl IslandCount {
+    fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.into(),
+            next: Default::default(),
+        }
+    }
+}
+
+/// The key of the next island rendered in this VirtualDom. Islands are numbered in the order they are first rendered, which is the same on the
+/// server and the client.
+fn next_island_key(cx: &ScopeState) -> String {
+    let count = cx
+        .consume_context::<IslandCount>()
+        .unwrap_or_else(|| cx.provide_root_context(IslandCount::new(ISLAND_PREFIX)));
+    let id = count.next.get();
+    count.next.set(id + 1);
+    format!("{}-{id}", count.prefix)
+}
+
+/// The props of the root component of an island's VirtualDom
+#[derive(Props)]
+struct IslandRootProps<P: 'static> {
+    island: String,
+    name: &'static str,
+    component: Component<P>,
+    props: P,
+}
+
+/// The root of the VirtualDom an island is rendered in. Islands nested inside it are keyed under the key of this island.
+fn IslandRoot<P>(cx: Scope<IslandRootProps<P>>) -> Element
+where
+    P: for<'a> Properties<'a> + Clone + 'static,
+{
+    cx.use_hook(|| cx.provide_context(IslandCount::new(&cx.props.island)));
+    let IslandRootProps {
+        name,
+        component,
+        props,
+        ..
+    } = cx.props;
+    render! { cx.component(*component, props.clone(), name) }
+}
+
+/// Render the component of an island in its own VirtualDom with the server context of the request.
+///
+/// Returns the id of the island and its HTML. The HTML is `None` until the suspense inside the island is resolved.
+#[cfg(feature = "ssr")]
+fn render_island<P>(
+    cx: &ScopeState,
+    key: String,
+    props: &IslandProps<P>,
+) -> (String, std::rc::Rc<std::cell::RefCell<Option<String>>>)
+where
+    P: for<'a> Properties<'a> + Clone + Serialize + 'static,
+{
+    use futures_util::FutureExt;
+
+    let context = crate::prelude::server_context();
+
+    let mut serialized = Vec::new();
+    match ciborium::into_writer(&props.props, &mut serialized) {
+        Ok(()) => match context.html_data_mut() {
+            Ok(mut html_data) => html_data.insert_keyed(key.clone(), serialized),
+            Err(err) => tracing::error!("Failed to store the props of island {key}: {err}"),
+        },
+        Err(err) => tracing::error!("Failed to serialize the props of island {key}: {err}"),
+    }
+
+    let mut vdom = VirtualDom::new_with_props(
+        IslandRoot,
+        IslandRootProps {
+            island: key.clone(),
+            name: props.name,
+            component: props.component,
+            props: props.props.clone(),
+        },
+    );
+    dioxus_ssr::eval::init_eval(vdom.base_scope());
+    let html = std::rc::Rc::new(std::cell::RefCell::new(None));
+
+    // Most islands don't suspend, so try to render them right away
+    let (ready, _) = crate::server_context::with_server_context(Box::new(context.clone()), || {
+        let _ = vdom.rebuild();
+        vdom.wait_for_suspense().now_or_never().is_some()
+    });
+    if ready {
+        *html.borrow_mut() = Some(pre_render_island(&vdom));
+    } else {
+        let update = cx.schedule_update();
+        let island_html = html.clone();
+        cx.push_future(crate::prelude::ProvideServerContext::new(
+            async move {
+                vdom.wait_for_suspense().await;
+                *island_html.borrow_mut() = Some(pre_render_island(&vdom));
+                update();
+            },
+            context,
+        ));
+    }
+
+    (key, html)
+}
+
+#[cfg(feature = "ssr")]
+fn pre_render_island(vdom: &VirtualDom) -> String {
+    let mut renderer = dioxus_ssr::Renderer::new();
+    renderer.pre_render = true;
+    renderer.render(vdom)
+}
+
+/// The key of an island at the top level of the page
+#[cfg(any(test, all(feature = "web", target_arch = "wasm32")))]
+fn island_key(id: usize) -> String {
+    format!("{ISLAND_PREFIX}-{id}")
+}
+
+/// The components that can hydrate islands on the client.
+///
+/// # Example
+/// ```rust, ignore
+/// IslandRegistry::new()
+///     .island("counter", Counter)
+///     .launch();
+/// ```
+#[cfg(feature = "web")]
+#[derive(Default)]
+pub struct IslandRegistry {
+    islands: std::collections::HashMap<&'static str, Box<dyn Fn(String)>>,
+}
+
+#[cfg(feature = "web")]
+impl IslandRegistry {
+    /// Create a new empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a component to hydrate every island with the same name
+    pub fn island<P>(mut self, name: &'static str, component: Component<P>) -> Self
+    where
+        P: for<'a> Properties<'a> + Clone + DeserializeOwned + 'static,
+    {
+        self.islands.insert(
+            name,
+            Box::new(move |key| {
+                match crate::html_storage::deserialize::get_keyed_server_data::<P>(&key) {
+                    Some(props) => dioxus_web::launch_with_props(
+                        IslandRoot,
+                        IslandRootProps {
+                            island: key.clone(),
+                            name,
+                            component,
+                            props,
+                        },
+                        dioxus_web::Config::new().hydrate(true).rootname(key),
+                    ),
+                    None => tracing::error!("Failed to load the props of island {key}"),
+                }
+            }),
+        );
+        self
+    }
+
+    /// Hydrate every island on the page that has a registered component. The rest of the page is left as static HTML.
+    pub fn launch(self) {
+        #[cfg(target_arch = "wasm32")]
+        {
+            let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+                tracing::error!("Failed to get the document");
+                return;
+            };
+
+            // Islands are numbered in the order the server rendered them. Nested islands are hydrated by the island around them
+            let mut id = 0;
+            while let Some(element) = document.get_element_by_id(&island_key(id)) {
+                id += 1;
+                let Some(name) = element.get_attribute("data-dioxus-island") else {
+                    continue;
+                };
+                match self.islands.get(name.as_str()) {
+                    Some(launch) => launch(element.id()),
+                    None => tracing::trace!("No component registered for island {name}"),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+    use crate::html_storage::{
+        deserialize::serde_from_bytes, serialize::serde_to_writable, HTMLData,
+    };
+    use crate::prelude::{DioxusServerContext, ProvideServerContext};
+
+    #[derive(Props, PartialEq, Clone, Serialize, serde::Deserialize)]
+    struct CounterProps {
+        start: i32,
+    }
+
+    fn Counter(cx: Scope<CounterProps>) -> Element {
+        render! {
+            button { onclick: |_| {}, "{cx.props.start}" }
+        }
+    }
+
+    #[derive(Clone)]
+    struct User(&'static str);
+
+    fn Greeting(cx: Scope) -> Element {
+        let user = crate::prelude::server_context()
+            .get::<User>()
+            .map_or("nobody", |user| user.0);
+        render! {
+            p { "hello {user}" }
+            div { Loaded {} }
+        }
+    }
+
+    fn Loaded(cx: Scope) -> Element {
+        let loaded = crate::prelude::use_server_future(cx, (), |_| async {
+            tokio::task::yield_now().await;
+            "loaded".to_string()
+        })?;
+        let loaded = loaded.value();
+        render! { "{loaded}" }
+    }
+
+    fn app(cx: Scope) -> Element {
+        render! {
+            h1 { "A static page" }
+            main {
+                Island { name: "counter", component: Counter, props: CounterProps { start: 10 } }
+                Island { name: "greeting", component: Greeting, props: () }
+            }
+        }
+    }
+
+    /// Render the app like the fullstack renderer and return the page with the data sent to the client
+    fn render_page(app: Component, context: DioxusServerContext) -> (String, HTMLData) {
+        let html = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(ProvideServerContext::new(
+                async {
+                    let mut vdom = VirtualDom::new(app);
+                    let _ = vdom.rebuild();
+                    vdom.wait_for_suspense().await;
+                    dioxus_ssr::pre_render(&vdom)
+                },
+                context.clone(),
+            ));
+
+        let mut serialized = Vec::new();
+        serde_to_writable(&*context.html_data().unwrap(), &mut serialized).unwrap();
+        (html, serde_from_bytes(&serialized).unwrap())
+    }
+
+    #[test]
+    fn islands_hydrate_from_the_server_html() {
+        let (page, data) = render_page(app, DioxusServerContext::default());
+        let key = (0..2)
+            .map(island_key)
+            .find(|key| page.contains(&format!(r#"id="{key}" data-dioxus-island="counter""#)))
+            .unwrap();
+
+        // The client loads the props from the page and renders the same HTML the island was hydrated with
+        let props: CounterProps = data.cursor().get_keyed(&key).unwrap();
+        assert_eq!(props.start, 10);
+        let mut client = VirtualDom::new_with_props(Counter, props);
+        let _ = client.rebuild();
+        let client_html = dioxus_ssr::pre_render(&client);
+        assert!(client_html.contains(">10<"));
+        assert!(page.contains(&client_html));
+    }
+
+    #[test]
+    fn islands_use_the_server_context() {
+        let mut context = DioxusServerContext::default();
+        context.insert(User("ferris")).unwrap();
+        let (page, _) = render_page(app, context);
+
+        assert!(page.contains(r#"data-dioxus-island="greeting""#));
+        assert!(page.contains("hello ferris"));
+        assert!(page.contains("loaded"));
+    }
+
+    fn Outer(cx: Scope) -> Element {
+        let label = "outer";
+        render! {
+            span { class: "{label}", "{label}" }
+            Island { name: "counter", component: Counter, props: CounterProps { start: 2 } }
+            b { class: "{label}", "{label}" }
+        }
+    }
+
+    fn nested_app(cx: Scope) -> Element {
+        let title = "nested";
+        render! {
+            h1 { class: "{title}", "{title}" }
+            Island { name: "outer", component: Outer, props: () }
+            p { class: "{title}", "{title}" }
+            Island { name: "counter", component: Counter, props: CounterProps { start: 1 } }
+        }
+    }
+
+    /// Collect the hydration ids in the page, grouped by the key of the island they are in. Nodes outside of any island are grouped under an empty key
+    fn hydration_ids(page: &str) -> std::collections::BTreeMap<String, Vec<usize>> {
+        let attribute = |tag: &str, name: &str| {
+            let start = tag.find(&format!(r#" {name}=""#))? + name.len() + 3;
+            let len = tag[start..].find('"')?;
+            Some(tag[start..start + len].to_string())
+        };
+        let mut ids = std::collections::BTreeMap::<String, Vec<usize>>::new();
+        // The island each open element's children are in
+        let mut stack = vec![String::new()];
+        let mut rest = page;
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start..];
+            let namespace = stack.last().unwrap().clone();
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                let end = comment.find("-->").unwrap();
+                if let Some(id) = comment[..end].strip_prefix("node-id") {
+                    ids.entry(namespace).or_default().push(id.parse().unwrap());
+                }
+                rest = &comment[end + 3..];
+            } else if rest.starts_with("</") {
+                stack.pop();
+                rest = &rest[rest.find('>').unwrap() + 1..];
+            } else {
+                let end = rest.find('>').unwrap();
+                let tag = &rest[..end];
+                if let Some(hydration) = attribute(tag, "data-node-hydration") {
+                    let id = hydration.split(',').next().unwrap().parse().unwrap();
+                    ids.entry(namespace.clone()).or_default().push(id);
+                }
+                match attribute(tag, "data-dioxus-island") {
+                    Some(_) => stack.push(attribute(tag, "id").unwrap()),
+                    None => stack.push(namespace),
+                }
+                rest = &rest[end + 1..];
+            }
+        }
+        ids
+    }
+
+    #[test]
+    fn nested_islands_number_hydration_ids_separately() {
+        let (page, data) = render_page(nested_app, DioxusServerContext::default());
+
+        // Nested islands are keyed under the island around them, like the client registry launches them
+        let data = data.cursor();
+        let start = |key: &str| data.get_keyed::<CounterProps>(key).map(|props| props.start);
+        assert_eq!(start("dioxus-island-0-0"), Some(2));
+        assert_eq!(start("dioxus-island-1"), Some(1));
+        assert!(data.get_keyed::<()>("dioxus-island-0").is_some());
+
+        // The ids of the page and of every island start at zero and don't skip any node, so they can't collide
+        let ids = hydration_ids(&page);
+        assert_eq!(
+            ids.keys().collect::<Vec<_>>(),
+            [
+                "",
+                "dioxus-island-0",
+                "dioxus-island-0-0",
+                "dioxus-island-1"
+            ]
+        );
+        for (namespace, ids) in ids {
+            let mut sorted = ids.clone();
+            sorted.sort_unstable();
+            assert_eq!(
+                sorted,
+                (0..ids.len()).collect::<Vec<_>>(),
+                "{namespace}: {page}"
+            );
+        }
+    }
+}
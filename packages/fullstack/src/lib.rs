@@ -18,6 +18,7 @@ mod collect_assets;
 mod hooks;
 #[cfg(all(debug_assertions, feature = "hot-reload", feature = "ssr"))]
 mod hot_reload;
+pub mod islands;
 pub mod launch;
 #[cfg(feature = "ssr")]
 mod layer;
@@ -28,6 +29,10 @@ mod serve_config;
 #[cfg(feature = "ssr")]
 mod server_context;
 mod server_fn;
+#[cfg(feature = "ssr")]
+mod server_future_cache;
+#[cfg(feature = "ssr")]
+pub mod session;
 
 /// A prelude of commonly used items in dioxus-fullstack.
 pub mod prelude {
@@ -40,6 +45,9 @@ pub mod prelude {
     use crate::hooks;
     #[cfg(not(feature = "ssr"))]
     pub use crate::html_storage::deserialize::get_root_props_from_document;
+    #[cfg(feature = "web")]
+    pub use crate::islands::IslandRegistry;
+    pub use crate::islands::{Island, IslandProps};
     pub use crate::launch::LaunchBuilder;
     #[cfg(feature = "ssr")]
     pub use crate::layer::{Layer, Service};
@@ -60,12 +68,22 @@ pub mod prelude {
     pub use crate::server_fn::DioxusServerFn;
     #[cfg(feature = "ssr")]
     pub use crate::server_fn::{ServerFnMiddleware, ServerFnTraitObj, ServerFunction};
+    #[cfg(feature = "ssr")]
+    pub use crate::server_future_cache::ServerFutureCache;
+    #[cfg(feature = "ssr")]
+    pub use crate::session::{
+        CookieStore, MemoryStore, SameSite, Session, SessionConfig, SessionData, SessionError,
+        SessionStore,
+    };
     pub use dioxus_server_macro::*;
     #[cfg(feature = "ssr")]
     pub use dioxus_ssr::incremental::IncrementalRendererConfig;
     pub use server_fn::{self, ServerFn as _, ServerFnError};
 
-    pub use hooks::{server_cached::server_cached, server_future::use_server_future};
+    pub use hooks::{
+        server_cached::server_cached, server_future::use_server_future,
+        server_future_keyed::use_server_future_keyed, session::use_session_value,
+    };
 }
 
 // Warn users about overlapping features
@@ -101,31 +101,24 @@ impl SsrRendererPool {
                 let (tx, rx) = tokio::sync::oneshot::channel();
 
                 let server_context = server_context.clone();
+                // Revalidated routes are stored for every visitor, so they are rendered without the request of this visitor
+                let revalidate_context = revalidate_server_context(cfg, &server_context);
+                let revalidate_wrapper = FullstackRenderer {
+                    cfg: cfg.clone(),
+                    server_context: revalidate_context.clone(),
+                };
                 spawn_blocking(move || {
                     tokio::runtime::Runtime::new()
                         .expect("couldn't spawn runtime")
                         .block_on(async move {
                             let mut to = WriteBuffer { buffer: Vec::new() };
-                            match renderer
+                            let freshness = match renderer
                                 .render(
-                                    route,
+                                    route.clone(),
                                     component,
-                                    props,
+                                    props.clone(),
                                     &mut *to,
-                                    |vdom| {
-                                        Box::pin(async move {
-                                            // before polling the future, we need to set the context
-                                            let prev_context = SERVER_CONTEXT
-                                                .with(|ctx| ctx.replace(Box::new(server_context)));
-                                            // poll the future, which may call server_context()
-                                            tracing::info!("Rebuilding vdom");
-                                            let _ = vdom.rebuild();
-                                            vdom.wait_for_suspense().await;
-                                            tracing::info!("Suspense resolved");
-                                            // after polling the future, we need to restore the context
-                                            SERVER_CONTEXT.with(|ctx| ctx.replace(prev_context));
-                                        })
-                                    },
+                                    incremental_rebuild(server_context.clone()),
                                     &wrapper,
                                 )
                                 .await
@@ -143,9 +136,27 @@ impl SsrRendererPool {
                                             let _ = tx.send(Err(err));
                                         }
                                     }
+                                    freshness
                                 }
                                 Err(err) => {
                                     let _ = tx.send(Err(err));
+                                    return;
+                                }
+                            };
+
+                            // The stale page was already sent, render a fresh version for the next request
+                            if freshness.is_stale() {
+                                if let Err(err) = renderer
+                                    .revalidate(
+                                        route,
+                                        component,
+                                        props,
+                                        incremental_rebuild(revalidate_context),
+                                        &revalidate_wrapper,
+                                    )
+                                    .await
+                                {
+                                    tracing::error!("Failed to revalidate route: {}", err);
                                 }
                             }
                         })
@@ -202,6 +213,18 @@ impl SSRState {
         async move {
             let ServeConfig { app, props, .. } = cfg;
 
+            // Share keyed server futures with other requests
+            if let Some(cache) = &cfg.server_future_cache {
+                let _ = server_context.clone().insert(cache.clone());
+            }
+
+            // Load the session before rendering so components can read it synchronously
+            if server_context.get::<SessionConfig>().is_some() {
+                if let Err(err) = Session::load(server_context).await {
+                    tracing::error!("Failed to load session: {}", err);
+                }
+            }
+
             let (freshness, html) = self
                 .renderers
                 .render_to(cfg, route, *app, props.clone(), server_context)
@@ -324,6 +347,44 @@ impl RenderResponse {
     }
 }
 
+/// Rebuild a VirtualDom for the incremental renderer with the server context of the request
+fn incremental_rebuild(
+    server_context: DioxusServerContext,
+) -> impl FnOnce(&mut VirtualDom) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + '_>> {
+    move |vdom: &mut VirtualDom| {
+        Box::pin(async move {
+            // before polling the future, we need to set the context
+            let prev_context = SERVER_CONTEXT.with(|ctx| ctx.replace(Box::new(server_context)));
+            // poll the future, which may call server_context()
+            tracing::info!("Rebuilding vdom");
+            let _ = vdom.rebuild();
+            vdom.wait_for_suspense().await;
+            tracing::info!("Suspense resolved");
+            // after polling the future, we need to restore the context
+            SERVER_CONTEXT.with(|ctx| ctx.replace(prev_context));
+        })
+    }
+}
+
+/// Create a server context to revalidate a route with. It keeps the method and uri of the request that found the stale route, but not its
+/// headers or session, so nothing specific to that visitor ends up in the cache
+fn revalidate_server_context<P: Clone>(
+    cfg: &ServeConfig<P>,
+    request: &DioxusServerContext,
+) -> DioxusServerContext {
+    let mut parts = http::Request::new(()).into_parts().0;
+    if let Ok(request) = request.request_parts() {
+        parts.method = request.method.clone();
+        parts.uri = request.uri.clone();
+        parts.version = request.version;
+    }
+    let mut server_context = DioxusServerContext::new(Arc::new(RwLock::new(parts)));
+    if let Some(cache) = &cfg.server_future_cache {
+        let _ = server_context.insert(cache.clone());
+    }
+    server_context
+}
+
 fn pre_renderer() -> Renderer {
     let mut renderer = Renderer::default();
     renderer.pre_render = true;
@@ -19,6 +19,15 @@ pub struct ServeConfigBuilder<P: Clone> {
     pub(crate) assets_path: Option<&'static str>,
     pub(crate) incremental:
         Option<std::sync::Arc<dioxus_ssr::incremental::IncrementalRendererConfig>>,
+    pub(crate) server_future_cache: Option<CacheServerFutures>,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum CacheServerFutures {
+    /// Expire values when the incremental renderer invalidates routes
+    WithIncremental,
+    /// Expire values after a fixed time
+    For(std::time::Duration),
 }
 
 /// A template for incremental rendering that does nothing.
@@ -63,6 +72,7 @@ impl<P: Clone> ServeConfigBuilder<P> {
             index_path: None,
             assets_path: None,
             incremental: None,
+            server_future_cache: None,
         }
     }
 
@@ -72,6 +82,20 @@ impl<P: Clone> ServeConfigBuilder<P> {
         self
     }
 
+    /// Share the values of keyed server futures between requests.
+    ///
+    /// Values live for the invalidation duration of [`ServeConfigBuilder::incremental`]. Invalidating a route on the incremental renderer does not remove values from this cache; invalidate them with [`ServeConfig::server_future_cache`] instead. If incremental rendering is not enabled or has no invalidation duration, values are kept until they are invalidated.
+    pub fn cache_server_futures(mut self) -> Self {
+        self.server_future_cache = Some(CacheServerFutures::WithIncremental);
+        self
+    }
+
+    /// Share the values of keyed server futures between requests for a fixed amount of time
+    pub fn cache_server_futures_for(mut self, ttl: std::time::Duration) -> Self {
+        self.server_future_cache = Some(CacheServerFutures::For(ttl));
+        self
+    }
+
     /// Set the path of the index.html file to be served. (defaults to {assets_path}/index.html)
     pub fn index_path(mut self, index_path: &'static str) -> Self {
         self.index_path = Some(index_path);
@@ -103,12 +127,24 @@ impl<P: Clone> ServeConfigBuilder<P> {
 
         let index = load_index_html(index_path, root_id);
 
+        let server_future_cache = self.server_future_cache.map(|cache| {
+            let ttl = match cache {
+                CacheServerFutures::WithIncremental => self
+                    .incremental
+                    .as_ref()
+                    .and_then(|incremental| incremental.invalidation_duration()),
+                CacheServerFutures::For(ttl) => Some(ttl),
+            };
+            crate::server_future_cache::ServerFutureCache::new(ttl)
+        });
+
         ServeConfig {
             app: self.app,
             props: self.props,
             index,
             assets_path,
             incremental: self.incremental,
+            server_future_cache,
         }
     }
 }
@@ -153,6 +189,14 @@ pub struct ServeConfig<P: Clone> {
     pub(crate) assets_path: &'static str,
     pub(crate) incremental:
         Option<std::sync::Arc<dioxus_ssr::incremental::IncrementalRendererConfig>>,
+    pub(crate) server_future_cache: Option<crate::server_future_cache::ServerFutureCache>,
+}
+
+impl<P: Clone> ServeConfig<P> {
+    /// Get the cache shared by keyed server futures, if [`ServeConfigBuilder::cache_server_futures`] is enabled. Use this to invalidate cached values when the underlying data changes.
+    pub fn server_future_cache(&self) -> Option<&crate::server_future_cache::ServerFutureCache> {
+        self.server_future_cache.as_ref()
+    }
 }
 
 impl<P: Clone> From<ServeConfigBuilder<P>> for ServeConfig<P> {
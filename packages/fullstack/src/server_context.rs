@@ -1,4 +1,5 @@
 use crate::html_storage::HTMLData;
+use crate::server_future_cache::KeyedEntries;
 pub use server_fn_impl::*;
 use std::sync::Arc;
 use std::sync::RwLock;
@@ -12,9 +13,10 @@ pub struct DioxusServerContext {
     shared_context: std::sync::Arc<
         std::sync::RwLock<anymap::Map<dyn anymap::any::Any + Send + Sync + 'static>>,
     >,
-    response_parts: std::sync::Arc<std::sync::RwLock<http::response::Parts>>,
+    pub(crate) response_parts: std::sync::Arc<std::sync::RwLock<http::response::Parts>>,
     pub(crate) parts: Arc<RwLock<http::request::Parts>>,
     html_data: Arc<RwLock<HTMLData>>,
+    pub(crate) keyed_entries: KeyedEntries,
 }
 
 #[allow(clippy::derivable_impls)]
@@ -27,6 +29,7 @@ impl Default for DioxusServerContext {
             )),
             parts: std::sync::Arc::new(RwLock::new(http::request::Request::new(()).into_parts().0)),
             html_data: Arc::new(RwLock::new(HTMLData::default())),
+            keyed_entries: Default::default(),
         }
     }
 }
@@ -49,6 +52,7 @@ mod server_fn_impl {
                     http::response::Response::new(()).into_parts().0,
                 )),
                 html_data: Arc::new(RwLock::new(HTMLData::default())),
+                keyed_entries: Default::default(),
             }
         }
 
@@ -119,6 +123,11 @@ mod server_fn_impl {
         pub(crate) fn html_data(&self) -> LockResult<RwLockReadGuard<'_, HTMLData>> {
             self.html_data.read()
         }
+
+        /// Get the html data store mutably
+        pub(crate) fn html_data_mut(&self) -> LockResult<RwLockWriteGuard<'_, HTMLData>> {
+            self.html_data.write()
+        }
     }
 }
 
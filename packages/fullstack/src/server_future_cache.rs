@@ -0,0 +1,296 @@
+//! Deduplication and caching for keyed server futures.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, RwLock};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+use crate::server_context::DioxusServerContext;
+
+/// The state of a keyed server future within a single request
+pub(crate) enum KeyedEntry {
+    /// The future is running. The wakers are woken when it resolves
+    Pending(Vec<Waker>),
+    /// The future resolved to this serialized value
+    Ready(Arc<Vec<u8>>),
+}
+
+/// The keyed server futures of a single request
+pub(crate) type KeyedEntries = Arc<Mutex<HashMap<String, KeyedEntry>>>;
+
+/// What a component should do to get the value for a key
+pub(crate) enum KeyedClaim {
+    /// No other component is resolving this key. The caller should run the future and resolve the key with the [`KeyedOwner`]
+    Owner(KeyedOwner),
+    /// Another component is already resolving this key. Await [`WaitForKeyed`] to get the value
+    Waiting,
+    /// The value is already resolved
+    Ready(Arc<Vec<u8>>),
+}
+
+/// Claim a key for the current request. Only the first component to claim a key runs its future.
+pub(crate) fn claim_keyed(context: &DioxusServerContext, key: &str) -> KeyedClaim {
+    let mut entries = context.keyed_entries.lock().unwrap();
+    match entries.get(key) {
+        Some(KeyedEntry::Ready(value)) => return KeyedClaim::Ready(value.clone()),
+        Some(KeyedEntry::Pending(_)) => return KeyedClaim::Waiting,
+        None => {}
+    }
+
+    // Check if a previous request already resolved this key
+    if let Some(value) = context
+        .get::<ServerFutureCache>()
+        .and_then(|cache| cache.get(key))
+    {
+        entries.insert(key.to_string(), KeyedEntry::Ready(value.clone()));
+        if let Ok(mut html_data) = context.html_data_mut() {
+            html_data.insert_keyed(key.to_string(), value.to_vec());
+        }
+        return KeyedClaim::Ready(value);
+    }
+
+    entries.insert(key.to_string(), KeyedEntry::Pending(Vec::new()));
+    KeyedClaim::Owner(KeyedOwner {
+        context: context.clone(),
+        key: key.to_string(),
+        resolved: false,
+    })
+}
+
+/// The component that runs the future for a key claimed with [`claim_keyed`].
+///
+/// If the owner is dropped before the key is resolved (for example because its future was cancelled), the key is released and every waiting component runs its own future instead.
+pub(crate) struct KeyedOwner {
+    context: DioxusServerContext,
+    key: String,
+    resolved: bool,
+}
+
+impl KeyedOwner {
+    /// Resolve the key, waking any components waiting for the value
+    pub(crate) fn resolve(mut self, value: Vec<u8>) {
+        self.resolved = true;
+        let value = Arc::new(value);
+        if let Some(cache) = self.context.get::<ServerFutureCache>() {
+            cache.insert(self.key.clone(), value.clone());
+        }
+        if let Ok(mut html_data) = self.context.html_data_mut() {
+            html_data.insert_keyed(self.key.clone(), value.to_vec());
+        }
+        let previous = self
+            .context
+            .keyed_entries
+            .lock()
+            .unwrap()
+            .insert(self.key.clone(), KeyedEntry::Ready(value));
+        wake_pending(previous);
+    }
+}
+
+impl Drop for KeyedOwner {
+    fn drop(&mut self) {
+        if self.resolved {
+            return;
+        }
+        let previous = {
+            let mut entries = self.context.keyed_entries.lock().unwrap();
+            match entries.get(&self.key) {
+                Some(KeyedEntry::Pending(_)) => entries.remove(&self.key),
+                _ => None,
+            }
+        };
+        wake_pending(previous);
+    }
+}
+
+/// Wake the components waiting for an entry that was pending
+fn wake_pending(entry: Option<KeyedEntry>) {
+    if let Some(KeyedEntry::Pending(wakers)) = entry {
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+/// A future that resolves once another component resolves a key. Resolves to `None` if the component that owned the key was dropped first
+pub(crate) struct WaitForKeyed {
+    pub(crate) entries: KeyedEntries,
+    pub(crate) key: String,
+}
+
+impl Future for WaitForKeyed {
+    type Output = Option<Arc<Vec<u8>>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get_mut(&self.key) {
+            Some(KeyedEntry::Ready(value)) => Poll::Ready(Some(value.clone())),
+            Some(KeyedEntry::Pending(wakers)) => {
+                wakers.push(cx.waker().clone());
+                Poll::Pending
+            }
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+/// A cache for keyed server futures that is shared between requests.
+///
+/// Enable it with [`crate::prelude::ServeConfigBuilder::cache_server_futures`]. Values expire after the time to live or when they are invalidated with [`ServerFutureCache::invalidate`]. Expired values are removed the next time they are read or another value is cached.
+#[derive(Clone)]
+pub struct ServerFutureCache {
+    entries: Arc<RwLock<HashMap<String, (Instant, Arc<Vec<u8>>)>>>,
+    ttl: Option<Duration>,
+}
+
+impl ServerFutureCache {
+    /// Create a new cache. If `ttl` is `None`, values are kept until they are invalidated
+    pub fn new(ttl: Option<Duration>) -> Self {
+        Self {
+            entries: Default::default(),
+            ttl,
+        }
+    }
+
+    /// Check if a value cached at some time has expired
+    fn is_expired(&self, created: Instant) -> bool {
+        self.ttl.map_or(false, |ttl| created.elapsed() >= ttl)
+    }
+
+    fn get(&self, key: &str) -> Option<Arc<Vec<u8>>> {
+        {
+            let entries = self.entries.read().unwrap();
+            let (created, value) = entries.get(key)?;
+            if !self.is_expired(*created) {
+                return Some(value.clone());
+            }
+        }
+        self.entries.write().unwrap().remove(key);
+        None
+    }
+
+    fn insert(&self, key: String, value: Arc<Vec<u8>>) {
+        let mut entries = self.entries.write().unwrap();
+        entries.retain(|_, (created, _)| !self.is_expired(*created));
+        entries.insert(key, (Instant::now(), value));
+    }
+
+    /// Get the number of values in the cache. This includes expired values that were not removed yet
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap().len()
+    }
+
+    /// Check if the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Invalidate the cached value for a key. The next request that uses the key will run the future again
+    pub fn invalidate(&self, key: &str) {
+        self.entries.write().unwrap().remove(key);
+    }
+
+    /// Invalidate all cached values
+    pub fn invalidate_all(&self) {
+        self.entries.write().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn wait(context: &DioxusServerContext, key: &str) -> WaitForKeyed {
+        WaitForKeyed {
+            entries: context.keyed_entries.clone(),
+            key: key.to_string(),
+        }
+    }
+
+    fn claim_owner(context: &DioxusServerContext, key: &str) -> KeyedOwner {
+        match claim_keyed(context, key) {
+            KeyedClaim::Owner(owner) => owner,
+            _ => panic!("the first claim of {key:?} should own the key"),
+        }
+    }
+
+    #[test]
+    fn keyed_futures_are_deduplicated() {
+        let context = DioxusServerContext::default();
+        let owner = claim_owner(&context, "key");
+        assert!(matches!(claim_keyed(&context, "key"), KeyedClaim::Waiting));
+        assert!(matches!(
+            claim_keyed(&context, "other"),
+            KeyedClaim::Owner(_)
+        ));
+
+        let waiting = wait(&context, "key");
+        let waiter = std::thread::spawn(move || block_on(waiting));
+        owner.resolve(vec![1, 2, 3]);
+        assert_eq!(waiter.join().unwrap().as_deref(), Some(&vec![1, 2, 3]));
+
+        match claim_keyed(&context, "key") {
+            KeyedClaim::Ready(value) => assert_eq!(*value, vec![1, 2, 3]),
+            _ => panic!("the key should be resolved"),
+        }
+    }
+
+    #[test]
+    fn cancelled_owners_release_the_key() {
+        let context = DioxusServerContext::default();
+        let owner = claim_owner(&context, "key");
+        let waiting = wait(&context, "key");
+        let waiter = std::thread::spawn(move || block_on(waiting));
+        drop(owner);
+
+        // waiters fall back to running their own future
+        assert_eq!(waiter.join().unwrap(), None);
+        assert!(matches!(claim_keyed(&context, "key"), KeyedClaim::Owner(_)));
+    }
+
+    #[test]
+    fn cached_values_are_shared_between_requests() {
+        let cache = ServerFutureCache::new(None);
+        let request = || {
+            let mut context = DioxusServerContext::default();
+            context.insert(cache.clone()).unwrap();
+            context
+        };
+
+        claim_owner(&request(), "key").resolve(vec![1]);
+        match claim_keyed(&request(), "key") {
+            KeyedClaim::Ready(value) => assert_eq!(*value, vec![1]),
+            _ => panic!("the key should be cached"),
+        }
+
+        cache.invalidate("key");
+        claim_owner(&request(), "key");
+    }
+
+    #[test]
+    fn expired_values_are_evicted() {
+        let cache = ServerFutureCache::new(Some(Duration::ZERO));
+        cache.insert("first".to_string(), Arc::new(vec![1]));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get("first"), None);
+        assert!(cache.is_empty());
+
+        // expired values are removed when other values are cached
+        cache.insert("first".to_string(), Arc::new(vec![1]));
+        cache.insert("second".to_string(), Arc::new(vec![2]));
+        assert_eq!(cache.len(), 1);
+
+        let cache = ServerFutureCache::new(Some(Duration::from_secs(60)));
+        cache.insert("key".to_string(), Arc::new(vec![1]));
+        assert_eq!(cache.get("key").as_deref(), Some(&vec![1]));
+    }
+}
@@ -0,0 +1,225 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+
+use super::{SessionData, SessionError, SessionStore};
+
+/// Browsers will drop cookies larger than this
+const MAX_COOKIE_SIZE: usize = 4096;
+
+/// The size of the random nonce at the start of an encrypted cookie
+const NONCE_SIZE: usize = 12;
+
+/// A session store that keeps the session data in a cookie on the client.
+///
+/// Cookies created with [`CookieStore::new`] are signed with HMAC-SHA256 so the client cannot modify them, but the client can read them. Cookies created with [`CookieStore::encrypted`] are encrypted with AES-256-GCM so the client can neither read nor modify them.
+///
+/// The session expiry is stored inside the cookie, so the `max_age` of the [`super::SessionConfig`] is enforced even if the client keeps the cookie around. Browsers limit cookies to about 4KB, so this store is only suitable for small sessions.
+#[derive(Clone)]
+pub struct CookieStore {
+    key: Vec<u8>,
+    cipher: Option<Aes256Gcm>,
+}
+
+impl CookieStore {
+    /// Create a new cookie store that signs cookies with the given key. The key should be at least 32 bytes of random data that is kept secret on the server.
+    ///
+    /// Signed cookies are **not** encrypted. Use [`CookieStore::encrypted`] if the session contains anything the client should not see.
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        let key = key.into();
+        if key.len() < 32 {
+            tracing::warn!("Cookie session keys should be at least 32 bytes long");
+        }
+        Self { key, cipher: None }
+    }
+
+    /// Create a new cookie store that encrypts cookies with a key derived from the given key. The key should be at least 32 bytes of random data that is kept secret on the server.
+    pub fn encrypted(key: impl Into<Vec<u8>>) -> Self {
+        let mut store = Self::new(key);
+        let mut mac = store.mac();
+        mac.update(b"dioxus-session-encryption");
+        let cipher = Aes256Gcm::new_from_slice(&mac.finalize().into_bytes())
+            .expect("HMAC-SHA256 output is a valid AES-256 key");
+        store.cipher = Some(cipher);
+        store
+    }
+
+    fn mac(&self) -> Hmac<Sha256> {
+        <Hmac<Sha256> as Mac>::new_from_slice(&self.key).expect("HMAC can take a key of any size")
+    }
+
+    /// Sign a payload and return the cookie value
+    fn sign(&self, payload: &[u8]) -> String {
+        let payload = URL_SAFE_NO_PAD.encode(payload);
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+        format!("{payload}.{signature}")
+    }
+
+    /// Verify a cookie value and return the payload if the signature is valid
+    fn verify(&self, cookie: &str) -> Option<Vec<u8>> {
+        let (payload, signature) = cookie.rsplit_once('.')?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        mac.verify_slice(&signature).ok()?;
+        URL_SAFE_NO_PAD.decode(payload).ok()
+    }
+
+    /// Encrypt a payload and return the cookie value
+    fn encrypt(&self, cipher: &Aes256Gcm, payload: &[u8]) -> Result<String, SessionError> {
+        let mut nonce = [0u8; NONCE_SIZE];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), payload)
+            .map_err(|_| SessionError::Store("failed to encrypt the session".to_string()))?;
+        let mut cookie = nonce.to_vec();
+        cookie.extend(ciphertext);
+        Ok(URL_SAFE_NO_PAD.encode(cookie))
+    }
+
+    /// Decrypt a cookie value and return the payload if it was encrypted with this key and not modified
+    fn decrypt(&self, cipher: &Aes256Gcm, cookie: &str) -> Option<Vec<u8>> {
+        let cookie = URL_SAFE_NO_PAD.decode(cookie).ok()?;
+        if cookie.len() < NONCE_SIZE {
+            return None;
+        }
+        let (nonce, ciphertext) = cookie.split_at(NONCE_SIZE);
+        cipher.decrypt(Nonce::from_slice(nonce), ciphertext).ok()
+    }
+
+    /// Turn a payload into a cookie value
+    fn encode(&self, payload: &[u8]) -> Result<String, SessionError> {
+        match &self.cipher {
+            Some(cipher) => self.encrypt(cipher, payload),
+            None => Ok(self.sign(payload)),
+        }
+    }
+
+    /// Get the payload from a cookie value if the cookie is valid
+    fn decode(&self, cookie: &str) -> Option<Vec<u8>> {
+        match &self.cipher {
+            Some(cipher) => self.decrypt(cipher, cookie),
+            None => self.verify(cookie),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionStore for CookieStore {
+    async fn load(&self, cookie: &str) -> Result<Option<SessionData>, SessionError> {
+        let Some(payload) = self.decode(cookie) else {
+            tracing::trace!("Rejected an invalid session cookie");
+            return Ok(None);
+        };
+        let data = SessionData::from_bytes(&payload)?;
+        if data.is_expired() {
+            tracing::trace!("Rejected an expired session cookie");
+            return Ok(None);
+        }
+        Ok(Some(data))
+    }
+
+    async fn store(
+        &self,
+        _cookie: Option<&str>,
+        data: &SessionData,
+    ) -> Result<String, SessionError> {
+        let cookie = self.encode(&data.to_bytes()?)?;
+        if cookie.len() > MAX_COOKIE_SIZE {
+            return Err(SessionError::Store(format!(
+                "session cookie is {} bytes which is larger than the {MAX_COOKIE_SIZE} bytes browsers accept",
+                cookie.len()
+            )));
+        }
+        Ok(cookie)
+    }
+
+    async fn destroy(&self, _cookie: &str) -> Result<(), SessionError> {
+        // The data only lives in the cookie which is expired by the session
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::session::tests::block_on;
+
+    fn session_data(max_age: Duration) -> SessionData {
+        let mut data = SessionData::default();
+        data.insert("secret", &"hunter2").unwrap();
+        data.expire_after(Some(max_age));
+        data
+    }
+
+    #[test]
+    fn signed_cookies_reject_tampering() {
+        let store = CookieStore::new([7; 32]);
+        let cookie = store.sign(b"hello world");
+        assert_eq!(store.verify(&cookie).as_deref(), Some(&b"hello world"[..]));
+
+        let (payload, signature) = cookie.rsplit_once('.').unwrap();
+        let forged = format!("{}.{signature}", URL_SAFE_NO_PAD.encode(b"hello there"));
+        assert_eq!(store.verify(&forged), None);
+        assert_eq!(store.verify(payload), None);
+
+        let other_key = CookieStore::new([8; 32]);
+        assert_eq!(other_key.verify(&cookie), None);
+    }
+
+    #[test]
+    fn encrypted_cookies_hide_and_protect_data() {
+        block_on(async {
+            let store = CookieStore::encrypted([7; 32]);
+            let data = session_data(Duration::from_secs(60));
+            let cookie = store.store(None, &data).await.unwrap();
+            assert_eq!(store.load(&cookie).await.unwrap(), Some(data.clone()));
+
+            // the payload can't be read from the cookie
+            let raw = URL_SAFE_NO_PAD.decode(&cookie).unwrap();
+            assert!(!raw.windows(7).any(|window| window == b"hunter2"));
+            // every cookie uses a new nonce
+            assert_ne!(store.store(None, &data).await.unwrap(), cookie);
+
+            // flipping any bit is rejected
+            let mut tampered = raw.clone();
+            *tampered.last_mut().unwrap() ^= 1;
+            let tampered = URL_SAFE_NO_PAD.encode(tampered);
+            assert_eq!(store.load(&tampered).await.unwrap(), None);
+            assert_eq!(store.load("short").await.unwrap(), None);
+
+            // other keys and signed stores can't read the cookie
+            let other_key = CookieStore::encrypted([8; 32]);
+            assert_eq!(other_key.load(&cookie).await.unwrap(), None);
+            let signed = CookieStore::new([7; 32]);
+            assert_eq!(signed.load(&cookie).await.unwrap(), None);
+        });
+    }
+
+    #[test]
+    fn cookie_store_enforces_max_age() {
+        block_on(async {
+            for store in [CookieStore::new([7; 32]), CookieStore::encrypted([7; 32])] {
+                let live = store
+                    .store(None, &session_data(Duration::from_secs(60)))
+                    .await
+                    .unwrap();
+                assert!(store.load(&live).await.unwrap().is_some());
+
+                let expired = store
+                    .store(None, &session_data(Duration::ZERO))
+                    .await
+                    .unwrap();
+                assert_eq!(store.load(&expired).await.unwrap(), None);
+            }
+        });
+    }
+}
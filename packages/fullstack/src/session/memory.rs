@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::RngCore;
+
+use super::{SessionData, SessionError, SessionStore};
+
+/// A session store that keeps all sessions in the memory of the server process.
+///
+/// Sessions that outlive the `max_age` of the [`super::SessionConfig`] are removed the next time they are loaded or another session is stored.
+///
+/// Sessions are lost when the server restarts and are not shared between multiple server instances. Use a custom [`SessionStore`] backed by a database if you need either.
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    sessions: Arc<RwLock<HashMap<String, SessionData>>>,
+}
+
+impl MemoryStore {
+    /// Create a new empty memory store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of sessions currently stored
+    pub fn len(&self) -> usize {
+        self.sessions.read().unwrap().len()
+    }
+
+    /// Check if there are no sessions stored
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionStore for MemoryStore {
+    async fn load(&self, cookie: &str) -> Result<Option<SessionData>, SessionError> {
+        let data = self.sessions.read().unwrap().get(cookie).cloned();
+        match data {
+            Some(data) if data.is_expired() => {
+                self.sessions.write().unwrap().remove(cookie);
+                Ok(None)
+            }
+            data => Ok(data),
+        }
+    }
+
+    async fn store(
+        &self,
+        cookie: Option<&str>,
+        data: &SessionData,
+    ) -> Result<String, SessionError> {
+        let mut sessions = self.sessions.write().unwrap();
+        sessions.retain(|_, data| !data.is_expired());
+        let id = match cookie {
+            Some(id) if sessions.contains_key(id) => id.to_string(),
+            _ => new_session_id(),
+        };
+        sessions.insert(id.clone(), data.clone());
+        Ok(id)
+    }
+
+    async fn destroy(&self, cookie: &str) -> Result<(), SessionError> {
+        self.sessions.write().unwrap().remove(cookie);
+        Ok(())
+    }
+}
+
+/// Create a new random session id
+pub(crate) fn new_session_id() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::session::tests::block_on;
+
+    #[test]
+    fn memory_store_round_trips() {
+        block_on(async {
+            let store = MemoryStore::new();
+            let mut data = SessionData::default();
+            data.insert("user", &"ferris").unwrap();
+
+            let id = store.store(None, &data).await.unwrap();
+            assert_eq!(store.load(&id).await.unwrap(), Some(data.clone()));
+            // storing an existing session keeps the id
+            assert_eq!(store.store(Some(&id), &data).await.unwrap(), id);
+            // unknown ids get a new session
+            assert_ne!(store.store(Some("unknown"), &data).await.unwrap(), id);
+            assert_eq!(store.load("unknown").await.unwrap(), None);
+
+            store.destroy(&id).await.unwrap();
+            assert_eq!(store.load(&id).await.unwrap(), None);
+        });
+    }
+
+    #[test]
+    fn memory_store_expires_sessions() {
+        block_on(async {
+            let store = MemoryStore::new();
+            let mut expired = SessionData::default();
+            expired.expire_after(Some(Duration::ZERO));
+            let expired_id = store.store(None, &expired).await.unwrap();
+            assert_eq!(store.load(&expired_id).await.unwrap(), None);
+            assert!(store.is_empty());
+
+            // expired sessions are cleaned up when any other session is stored
+            let expired_id = store.store(None, &expired).await.unwrap();
+            let mut live = SessionData::default();
+            live.expire_after(Some(Duration::from_secs(60)));
+            let live_id = store.store(None, &live).await.unwrap();
+            assert_eq!(store.len(), 1);
+            assert_eq!(store.load(&expired_id).await.unwrap(), None);
+            assert_eq!(store.load(&live_id).await.unwrap(), Some(live));
+        });
+    }
+}
@@ -0,0 +1,408 @@
+//! Cookie based sessions for server functions and server side rendering.
+//!
+//! Insert a [`SessionConfig`] into the [`DioxusServerContext`] (for example with `register_server_fns_with_handler` and `render_handler_with_context`) and then extract a [`Session`] in any server function:
+//!
+//! ```rust, ignore
+//! #[server]
+//! async fn login(name: String) -> Result<(), ServerFnError> {
+//!     let session: Session = extract().await?;
+//!     session.insert("user", &name).await?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Components can read session values during server side rendering with [`crate::prelude::use_session_value`], which serializes the value into the page so it hydrates without a mismatch.
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::server_context::{DioxusServerContext, FromServerContext};
+
+mod cookie;
+mod memory;
+mod store;
+
+pub use cookie::CookieStore;
+pub use memory::MemoryStore;
+pub use store::{SessionData, SessionStore};
+
+/// An error that occurred while loading or saving a session
+#[derive(Debug, thiserror::Error)]
+pub enum SessionError {
+    /// No [`SessionConfig`] was inserted into the server context
+    #[error("no SessionConfig found in the server context")]
+    MissingConfig,
+    /// A value could not be serialized or deserialized
+    #[error("failed to serialize session data: {0}")]
+    Serialization(String),
+    /// The session store failed
+    #[error("session store error: {0}")]
+    Store(String),
+    /// The request the session belongs to could not be read
+    #[error("failed to read the request: {0}")]
+    Request(String),
+}
+
+/// The `SameSite` attribute of the session cookie
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SameSite {
+    /// The cookie is only sent with same site requests
+    Strict,
+    /// The cookie is sent with same site requests and top level navigations
+    #[default]
+    Lax,
+    /// The cookie is sent with all requests. This requires the cookie to be secure
+    None,
+}
+
+/// Configuration for sessions. Insert this into the [`DioxusServerContext`] to enable the [`Session`] extractor.
+#[derive(Clone)]
+pub struct SessionConfig {
+    store: Arc<dyn SessionStore>,
+    cookie_name: String,
+    path: String,
+    max_age: Option<Duration>,
+    secure: bool,
+    http_only: bool,
+    same_site: SameSite,
+}
+
+impl SessionConfig {
+    /// Create a new session config that persists sessions in the given store
+    pub fn new(store: impl SessionStore) -> Self {
+        Self {
+            store: Arc::new(store),
+            cookie_name: "dioxus-session".to_string(),
+            path: "/".to_string(),
+            max_age: None,
+            secure: true,
+            http_only: true,
+            same_site: SameSite::default(),
+        }
+    }
+
+    /// Set the name of the session cookie (defaults to `dioxus-session`)
+    pub fn cookie_name(mut self, cookie_name: impl Into<String>) -> Self {
+        self.cookie_name = cookie_name.into();
+        self
+    }
+
+    /// Set the path the session cookie is sent for (defaults to `/`)
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Set how long the session lives. The cookie expires after this duration and the store rejects the session even if the client keeps sending the cookie. If this is not set, the cookie expires when the browser is closed
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Set if the cookie should only be sent over https (defaults to true)
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Set if the cookie should be hidden from javascript (defaults to true)
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+
+    /// Set the `SameSite` attribute of the cookie (defaults to [`SameSite::Lax`])
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = same_site;
+        self
+    }
+
+    /// Create the `Set-Cookie` header value for a cookie value
+    fn set_cookie_header(&self, value: &str, max_age: Option<Duration>) -> String {
+        let mut header = format!("{}={}; Path={}", self.cookie_name, value, self.path);
+        if let Some(max_age) = max_age {
+            header += &format!("; Max-Age={}", max_age.as_secs());
+        }
+        if self.secure {
+            header += "; Secure";
+        }
+        if self.http_only {
+            header += "; HttpOnly";
+        }
+        header += match self.same_site {
+            SameSite::Strict => "; SameSite=Strict",
+            SameSite::Lax => "; SameSite=Lax",
+            SameSite::None => "; SameSite=None",
+        };
+        header
+    }
+}
+
+/// Find the value of a cookie in the request headers
+fn find_cookie(headers: &http::HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(http::header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.trim_matches('"').to_string())
+}
+
+struct SessionInner {
+    cookie: Option<String>,
+    data: SessionData,
+}
+
+/// The session of the current request. Sessions are loaded from the [`SessionStore`] in the [`SessionConfig`] the first time they are extracted and shared for the rest of the request.
+///
+/// Changes to the session are saved to the store immediately and the updated cookie is added to the response.
+#[derive(Clone)]
+pub struct Session {
+    inner: Arc<RwLock<SessionInner>>,
+    config: SessionConfig,
+    response_parts: Arc<RwLock<http::response::Parts>>,
+}
+
+impl Session {
+    /// Load the session for the request in the server context, or return the session that was already loaded for this request
+    pub async fn load(context: &DioxusServerContext) -> Result<Self, SessionError> {
+        if let Some(session) = context.get::<Session>() {
+            return Ok(session);
+        }
+        let config = context
+            .get::<SessionConfig>()
+            .ok_or(SessionError::MissingConfig)?;
+        let cookie = {
+            let parts = context
+                .request_parts()
+                .map_err(|err| SessionError::Request(err.to_string()))?;
+            find_cookie(&parts.headers, &config.cookie_name)
+        };
+        let loaded = match &cookie {
+            Some(cookie) => config.store.load(cookie).await?,
+            None => None,
+        };
+        let (cookie, data) = match loaded {
+            // Check the expiry here too in case a custom store doesn't
+            Some(data) if !data.is_expired() => (cookie, data),
+            // The cookie was invalid or the session expired, so start a new session
+            _ => (None, SessionData::default()),
+        };
+        let session = Session {
+            inner: Arc::new(RwLock::new(SessionInner { cookie, data })),
+            config,
+            response_parts: context.response_parts.clone(),
+        };
+        let _ = context.clone().insert(session.clone());
+        Ok(session)
+    }
+
+    /// Get a value from the session
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.inner.read().unwrap().data.get(key)
+    }
+
+    /// Check if the session contains a value for a key
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.inner.read().unwrap().data.contains_key(key)
+    }
+
+    /// Get a snapshot of all the data in the session
+    pub fn data(&self) -> SessionData {
+        self.inner.read().unwrap().data.clone()
+    }
+
+    /// Insert a value into the session and save it
+    pub async fn insert<T: Serialize>(
+        &self,
+        key: impl Into<String>,
+        value: &T,
+    ) -> Result<(), SessionError> {
+        self.inner.write().unwrap().data.insert(key, value)?;
+        self.save().await
+    }
+
+    /// Remove a value from the session and save it
+    pub async fn remove(&self, key: &str) -> Result<(), SessionError> {
+        let removed = self.inner.write().unwrap().data.remove(key);
+        if removed {
+            self.save().await?;
+        }
+        Ok(())
+    }
+
+    /// Remove all data from the session, delete it from the store and expire the session cookie
+    pub async fn destroy(&self) -> Result<(), SessionError> {
+        let cookie = {
+            let mut inner = self.inner.write().unwrap();
+            inner.data.clear();
+            inner.cookie.take()
+        };
+        if let Some(cookie) = cookie {
+            self.config.store.destroy(&cookie).await?;
+        }
+        let header = self
+            .config
+            .set_cookie_header("", Some(Duration::from_secs(0)));
+        self.set_cookie(header);
+        Ok(())
+    }
+
+    /// Save the session to the store and add the cookie to the response
+    pub async fn save(&self) -> Result<(), SessionError> {
+        let (cookie, data) = {
+            let mut inner = self.inner.write().unwrap();
+            inner.data.expire_after(self.config.max_age);
+            (inner.cookie.clone(), inner.data.clone())
+        };
+        let cookie = self.config.store.store(cookie.as_deref(), &data).await?;
+        let header = self.config.set_cookie_header(&cookie, self.config.max_age);
+        self.inner.write().unwrap().cookie = Some(cookie);
+        self.set_cookie(header);
+        Ok(())
+    }
+
+    /// Add the session cookie to the response. Other cookies the handler set are kept, but an earlier session cookie from this request is replaced
+    fn set_cookie(&self, header: String) {
+        match http::HeaderValue::from_str(&header) {
+            Ok(value) => {
+                let mut response_parts = self.response_parts.write().unwrap();
+                let prefix = format!("{}=", self.config.cookie_name);
+                let other_cookies: Vec<_> = response_parts
+                    .headers
+                    .get_all(http::header::SET_COOKIE)
+                    .iter()
+                    .filter(|cookie| !cookie.as_bytes().starts_with(prefix.as_bytes()))
+                    .cloned()
+                    .collect();
+                response_parts.headers.remove(http::header::SET_COOKIE);
+                for cookie in other_cookies {
+                    response_parts
+                        .headers
+                        .append(http::header::SET_COOKIE, cookie);
+                }
+                response_parts
+                    .headers
+                    .append(http::header::SET_COOKIE, value);
+            }
+            Err(err) => tracing::error!("Invalid session cookie: {err}"),
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl FromServerContext for Session {
+    type Rejection = SessionError;
+
+    async fn from_request(req: &DioxusServerContext) -> Result<Self, Self::Rejection> {
+        Session::load(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run a future to completion on a new runtime
+    pub(super) fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn set_cookies(context: &DioxusServerContext) -> Vec<String> {
+        context
+            .response_parts()
+            .unwrap()
+            .headers
+            .get_all(http::header::SET_COOKIE)
+            .iter()
+            .map(|value| value.to_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn finds_session_cookie() {
+        let mut headers = http::HeaderMap::new();
+        headers.insert(
+            http::header::COOKIE,
+            http::HeaderValue::from_static("theme=dark; dioxus-session=abc.def; other=1"),
+        );
+        assert_eq!(
+            find_cookie(&headers, "dioxus-session").as_deref(),
+            Some("abc.def")
+        );
+        assert_eq!(find_cookie(&headers, "missing"), None);
+    }
+
+    #[test]
+    fn session_keeps_other_cookies() {
+        block_on(async {
+            let mut context = DioxusServerContext::default();
+            context
+                .insert(SessionConfig::new(MemoryStore::new()))
+                .unwrap();
+            context.response_parts_mut().unwrap().headers.append(
+                http::header::SET_COOKIE,
+                http::HeaderValue::from_static("theme=dark"),
+            );
+
+            let session = Session::load(&context).await.unwrap();
+            session.insert("user", &"ferris").await.unwrap();
+            session.insert("visits", &1).await.unwrap();
+
+            let cookies = set_cookies(&context);
+            assert_eq!(cookies.len(), 2);
+            assert_eq!(cookies[0], "theme=dark");
+            assert!(cookies[1].starts_with("dioxus-session="));
+        });
+    }
+
+    #[test]
+    fn session_round_trips_through_cookie() {
+        block_on(async {
+            let store = MemoryStore::new();
+            let config = SessionConfig::new(store.clone()).max_age(Duration::from_secs(60));
+
+            let mut context = DioxusServerContext::default();
+            context.insert(config.clone()).unwrap();
+            let session = Session::load(&context).await.unwrap();
+            session.insert("user", &"ferris").await.unwrap();
+            let cookie = set_cookies(&context)[0].clone();
+            assert!(cookie.contains("Max-Age=60"));
+            let cookie = cookie.split(';').next().unwrap().to_string();
+
+            // the next request sends the cookie back
+            let mut request = http::Request::new(());
+            request.headers_mut().insert(
+                http::header::COOKIE,
+                http::HeaderValue::from_str(&cookie).unwrap(),
+            );
+            let mut context =
+                DioxusServerContext::new(Arc::new(RwLock::new(request.into_parts().0)));
+            context.insert(config).unwrap();
+            let session = Session::load(&context).await.unwrap();
+            assert_eq!(session.get::<String>("user").as_deref(), Some("ferris"));
+
+            session.destroy().await.unwrap();
+            assert!(store.is_empty());
+            assert!(set_cookies(&context)[0].contains("Max-Age=0"));
+        });
+    }
+
+    #[test]
+    fn missing_config_is_an_error() {
+        block_on(async {
+            let context = DioxusServerContext::default();
+            assert!(matches!(
+                Session::load(&context).await,
+                Err(SessionError::MissingConfig)
+            ));
+        });
+    }
+}
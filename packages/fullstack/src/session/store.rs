@@ -0,0 +1,145 @@
+//! The storage abstraction sessions are persisted through.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::SessionError;
+
+/// The key-value data stored in a session. Values are stored in a serialized form so that any store can persist them without knowing their types.
+#[derive(serde::Serialize, serde::Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct SessionData {
+    values: HashMap<String, Vec<u8>>,
+    /// When the session expires in seconds since the unix epoch
+    #[serde(default)]
+    expires_at: Option<u64>,
+}
+
+impl SessionData {
+    /// Get and deserialize a value from the session data
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let bytes = self.values.get(key)?;
+        match ciborium::from_reader(Cursor::new(bytes)) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                tracing::error!("Failed to deserialize session value {key:?}: {err}");
+                None
+            }
+        }
+    }
+
+    /// Serialize and insert a value into the session data
+    pub fn insert<T: Serialize>(
+        &mut self,
+        key: impl Into<String>,
+        value: &T,
+    ) -> Result<(), SessionError> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(value, &mut bytes)
+            .map_err(|err| SessionError::Serialization(err.to_string()))?;
+        self.values.insert(key.into(), bytes);
+        Ok(())
+    }
+
+    /// Remove a value from the session data. Returns true if the value existed
+    pub fn remove(&mut self, key: &str) -> bool {
+        self.values.remove(key).is_some()
+    }
+
+    /// Check if the session data contains a key
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+
+    /// Remove all values from the session data
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    /// Check if the session data is empty
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Set the session to expire after a duration from now. If the duration is `None`, the session never expires on the server
+    pub fn expire_after(&mut self, max_age: Option<Duration>) {
+        self.expires_at = max_age.map(|max_age| unix_time().saturating_add(max_age.as_secs()));
+    }
+
+    /// Check if the session has expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .map_or(false, |expires_at| unix_time() >= expires_at)
+    }
+
+    /// Serialize the session data into bytes
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SessionError> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(self, &mut bytes)
+            .map_err(|err| SessionError::Serialization(err.to_string()))?;
+        Ok(bytes)
+    }
+
+    /// Deserialize session data from bytes created with [`SessionData::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SessionError> {
+        ciborium::from_reader(Cursor::new(bytes))
+            .map_err(|err| SessionError::Serialization(err.to_string()))
+    }
+}
+
+/// The current time in seconds since the unix epoch
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+/// A store that persists session data between requests.
+///
+/// The store is identified to the client with a cookie value. Server side stores (like [`super::MemoryStore`]) use the cookie value as a session id, while client side stores (like [`super::CookieStore`]) encode the session data into the cookie value itself.
+///
+/// Implement this trait to store sessions in a database, redis or any other backend.
+#[async_trait::async_trait]
+pub trait SessionStore: Send + Sync + 'static {
+    /// Load the session data associated with a cookie value. Returns `None` if the session does not exist, has expired (see [`SessionData::is_expired`]) or the cookie is invalid.
+    async fn load(&self, cookie: &str) -> Result<Option<SessionData>, SessionError>;
+
+    /// Persist the session data and return the cookie value that should be sent to the client.
+    ///
+    /// `cookie` is the cookie value the client sent with the request if the session already existed.
+    async fn store(&self, cookie: Option<&str>, data: &SessionData)
+        -> Result<String, SessionError>;
+
+    /// Remove the session associated with a cookie value.
+    async fn destroy(&self, cookie: &str) -> Result<(), SessionError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_data_round_trips() {
+        let mut data = SessionData::default();
+        data.insert("user", &"ferris".to_string()).unwrap();
+        data.insert("visits", &3u32).unwrap();
+        data.expire_after(Some(Duration::from_secs(60)));
+
+        let data = SessionData::from_bytes(&data.to_bytes().unwrap()).unwrap();
+        assert_eq!(data.get::<String>("user").as_deref(), Some("ferris"));
+        assert_eq!(data.get::<u32>("visits"), Some(3));
+        assert!(!data.is_expired());
+    }
+
+    #[test]
+    fn session_data_expires() {
+        let mut data = SessionData::default();
+        assert!(!data.is_expired());
+        data.expire_after(Some(Duration::ZERO));
+        assert!(data.is_expired());
+        data.expire_after(None);
+        assert!(!data.is_expired());
+    }
+}
@@ -32,6 +32,11 @@ impl ClipboardData {
         }
     }
 
+    /// The plain text that was copied, cut or pasted, if the renderer provides it
+    pub fn text(&self) -> Option<String> {
+        self.inner.text()
+    }
+
     /// Downcast this event to a concrete event type
     pub fn downcast<T: 'static>(&self) -> Option<&T> {
         self.inner.as_ref().as_any().downcast_ref::<T>()
@@ -41,17 +46,32 @@ impl ClipboardData {
 #[cfg(feature = "serialize")]
 /// A serialized version of ClipboardData
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Clone)]
-pub struct SerializedClipboardData {}
+pub struct SerializedClipboardData {
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[cfg(feature = "serialize")]
+impl SerializedClipboardData {
+    /// Create a new SerializedClipboardData
+    pub fn new(text: Option<String>) -> Self {
+        Self { text }
+    }
+}
 
 #[cfg(feature = "serialize")]
 impl From<&ClipboardData> for SerializedClipboardData {
-    fn from(_: &ClipboardData) -> Self {
-        Self {}
+    fn from(data: &ClipboardData) -> Self {
+        Self { text: data.text() }
     }
 }
 
 #[cfg(feature = "serialize")]
 impl HasClipboardData for SerializedClipboardData {
+    fn text(&self) -> Option<String> {
+        self.text.clone()
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -75,6 +95,11 @@ impl<'de> serde::Deserialize<'de> for ClipboardData {
 }
 
 pub trait HasClipboardData: std::any::Any {
+    /// The plain text that was copied, cut or pasted
+    fn text(&self) -> Option<String> {
+        None
+    }
+
     /// return self as Any
     fn as_any(&self) -> &dyn std::any::Any;
 }
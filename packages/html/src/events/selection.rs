@@ -14,6 +14,11 @@ impl SelectionData {
         }
     }
 
+    /// The text that is selected, if the renderer provides it
+    pub fn text(&self) -> Option<String> {
+        self.inner.text()
+    }
+
     /// Downcast this event to a concrete event type
     pub fn downcast<T: 'static>(&self) -> Option<&T> {
         self.inner.as_any().downcast_ref::<T>()
@@ -41,17 +46,32 @@ impl PartialEq for SelectionData {
 #[cfg(feature = "serialize")]
 /// A serialized version of SelectionData
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Clone)]
-pub struct SerializedSelectionData {}
+pub struct SerializedSelectionData {
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[cfg(feature = "serialize")]
+impl SerializedSelectionData {
+    /// Create a new SerializedSelectionData
+    pub fn new(text: Option<String>) -> Self {
+        Self { text }
+    }
+}
 
 #[cfg(feature = "serialize")]
 impl From<&SelectionData> for SerializedSelectionData {
-    fn from(_: &SelectionData) -> Self {
-        Self {}
+    fn from(data: &SelectionData) -> Self {
+        Self { text: data.text() }
     }
 }
 
 #[cfg(feature = "serialize")]
 impl HasSelectionData for SerializedSelectionData {
+    fn text(&self) -> Option<String> {
+        self.text.clone()
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -75,6 +95,11 @@ impl<'de> serde::Deserialize<'de> for SelectionData {
 }
 
 pub trait HasSelectionData: std::any::Any {
+    /// The text that is selected
+    fn text(&self) -> Option<String> {
+        None
+    }
+
     /// return self as Any
     fn as_any(&self) -> &dyn std::any::Any;
 }
@@ -50,4 +50,17 @@ pub trait FileEngine {
 
     // returns a file in platform's native representation
     async fn get_native_file(&self, file: &str) -> Option<Box<dyn Any>>;
+
+    // get the size of a file in bytes
+    async fn file_size(&self, file: &str) -> Option<u64> {
+        self.read_file(file).await.map(|bytes| bytes.len() as u64)
+    }
+
+    // read up to `length` bytes of a file starting at `offset`. Use this to stream large files instead of reading them into memory at once
+    async fn read_file_range(&self, file: &str, offset: u64, length: u64) -> Option<Vec<u8>> {
+        let bytes = self.read_file(file).await?;
+        let start = (offset as usize).min(bytes.len());
+        let end = start.saturating_add(length as usize).min(bytes.len());
+        Some(bytes[start..end].to_vec())
+    }
 }
@@ -137,6 +137,11 @@ trait_methods! {
     /// <https://developer.mozilla.org/en-US/docs/Web/API/Event/preventDefault>
     prevent_default: "dioxus-prevent-default";
 
+    /// Throttle events from this element in renderers that send events over a connection, like liveview.
+    ///
+    /// The value is a list of event names and the minimum number of milliseconds between each event, like `"onmousemove:50 onscroll:100"`. Only the latest event in each interval is sent. An interval of `0` sends every event.
+    throttle: "dioxus-throttle";
+
 
     /// <https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/accesskey>
     accesskey: "accesskey";
@@ -1652,6 +1657,11 @@ trait_methods! {
     /// <https://developer.mozilla.org/en-US/docs/Web/API/Event/preventDefault>
     prevent_default: "dioxus-prevent-default";
 
+    /// Throttle events from this element in renderers that send events over a connection, like liveview.
+    ///
+    /// The value is a list of event names and the minimum number of milliseconds between each event, like `"onmousemove:50 onscroll:100"`. Only the latest event in each interval is sent. An interval of `0` sends every event.
+    throttle: "dioxus-throttle";
+
     /// <https://developer.mozilla.org/en-US/docs/Web/SVG/Attribute/accent-height>
     accent_height: "accent-height";
 
@@ -2,7 +2,7 @@ use std::any::Any;
 use std::path::PathBuf;
 
 use tokio::fs::File;
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
 
 use crate::file_data::FileEngine;
 
@@ -47,4 +47,19 @@ impl FileEngine for NativeFileEngine {
         let file = File::open(file).await.ok()?;
         Some(Box::new(file))
     }
+
+    async fn file_size(&self, file: &str) -> Option<u64> {
+        let metadata = tokio::fs::metadata(file).await.ok()?;
+        Some(metadata.len())
+    }
+
+    async fn read_file_range(&self, file: &str, offset: u64, length: u64) -> Option<Vec<u8>> {
+        let mut file = File::open(file).await.ok()?;
+        file.seek(std::io::SeekFrom::Start(offset)).await.ok()?;
+
+        let mut contents = Vec::new();
+        file.take(length).read_to_end(&mut contents).await.ok()?;
+
+        Some(contents)
+    }
 }
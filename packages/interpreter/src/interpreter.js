@@ -1,12 +1,75 @@
 class InterpreterConfig {
-  constructor(intercept_link_redirects) {
+  constructor(intercept_link_redirects, throttle_events = false) {
     this.intercept_link_redirects = intercept_link_redirects;
+    // If high frequency events should be coalesced before they are sent to the renderer
+    this.throttle_events = throttle_events;
   }
 }
 
+// The number of milliseconds high frequency events are throttled to by default.
+// Other events are only throttled if the listening element requests it with the `dioxus-throttle` attribute
+const DEFAULT_THROTTLE_INTERVALS = {
+  mousemove: 16,
+  pointermove: 16,
+  touchmove: 16,
+  scroll: 16,
+  wheel: 16,
+  drag: 16,
+  dragover: 16,
+};
+
+// The events that are waiting for their throttle interval to end, by element and event name
+const throttled_events = new Map();
+
+// Find the element that owns the listener for an event: the closest element to the target that listens to the event
+function listening_element(target, name) {
+  let element = target instanceof Element ? target : target.parentElement;
+  while (element && !(element.dioxusListeners && element.dioxusListeners.has(name))) {
+    element = element.parentElement;
+  }
+  return element;
+}
+
+// Find the throttle interval the element that listens to an event asks for. The `dioxus-throttle` attribute looks like `onmousemove:50 onscroll:0`
+function throttle_interval(element, name) {
+  const requests = element && element.getAttribute(`dioxus-throttle`);
+  if (requests) {
+    for (const request of requests.split(/[\s,]+/)) {
+      const [event, interval] = request.split(":");
+      if (event === `on${name}`) {
+        return parseInt(interval) || 0;
+      }
+    }
+  }
+  return DEFAULT_THROTTLE_INTERVALS[name] || 0;
+}
+
+// Send the first event right away, and then at most one event (the latest one) each interval
+function throttle_event(key, interval, send) {
+  let state = throttled_events.get(key);
+  if (state) {
+    state.pending = send;
+    return;
+  }
+  state = { pending: null };
+  throttled_events.set(key, state);
+  send();
+  const tick = () => {
+    if (state.pending) {
+      const pending = state.pending;
+      state.pending = null;
+      pending();
+      setTimeout(tick, interval);
+    } else {
+      throttled_events.delete(key);
+    }
+  };
+  setTimeout(tick, interval);
+}
+
 // this handler is only provided on the desktop and liveview implementations since this
 // method is not used by the web implementation
-async function handler(event, name, bubbles, config) {
+async function handler(event, name, bubbles, config, throttled = false) {
   let target = event.target;
   if (target != null) {
     let preventDefaultRequests = null;
@@ -61,47 +124,22 @@ async function handler(event, name, bubbles, config) {
       event.preventDefault();
     }
 
-    let contents = await serialize_event(event);
-
-    // TODO: this should be liveview only
-    if (
-      target.tagName === "INPUT" &&
-      (event.type === "change" || event.type === "input")
-    ) {
-      const type = target.getAttribute("type");
-      if (type === "file") {
-        async function read_files() {
-          const files = target.files;
-          const file_contents = {};
-
-          for (let i = 0; i < files.length; i++) {
-            const file = files[i];
-
-            file_contents[file.name] = Array.from(
-              new Uint8Array(await file.arrayBuffer())
-            );
-          }
-          let file_engine = {
-            files: file_contents,
-          };
-          contents.files = file_engine;
-
-          if (realId === null) {
-            return;
-          }
-          const message = window.interpreter.serializeIpcMessage("user_event", {
-            name: name,
-            element: parseInt(realId),
-            data: contents,
-            bubbles,
-          });
-          window.ipc.postMessage(message);
-        }
-        read_files();
+    if (config.throttle_events && !throttled && realId != null) {
+      const listener = listening_element(target, name);
+      const interval = throttle_interval(listener, name);
+      if (interval > 0) {
+        const listenerId = listener ? listener.getAttribute(`data-dioxus-id`) : realId;
+        throttle_event(`${listenerId}:${name}`, interval, () =>
+          handler(event, name, bubbles, config, true)
+        );
         return;
       }
     }
 
+    let contents = await serialize_event(event);
+
+    /*POST_EVENT_SERIALIZATION*/
+
     if (
       target.tagName === "FORM" &&
       (event.type === "submit" || event.type === "input")
@@ -182,6 +220,11 @@ class ListenerMap {
   }
 
   create(event_name, element, bubbles, handler) {
+    // Remember which events the element listens to, so bubbling events can find the element that owns the listener
+    if (!element.dioxusListeners) {
+      element.dioxusListeners = new Set();
+    }
+    element.dioxusListeners.add(event_name);
     if (bubbles) {
       if (this.global[event_name] === undefined) {
         this.global[event_name] = {};
@@ -201,6 +244,9 @@ class ListenerMap {
   }
 
   remove(element, event_name, bubbles) {
+    if (element.dioxusListeners) {
+      element.dioxusListeners.delete(event_name);
+    }
     if (bubbles) {
       this.global[event_name].active--;
       if (this.global[event_name].active === 0) {
@@ -258,6 +304,65 @@ window.interpreter.initialize = function (root) {
   listeners.root = root;
 }
 
+// Forget every node, listener and template, and start again with an empty root. The new root is returned
+window.interpreter.reset = function (root) {
+  // Replacing the root removes the listeners attached to it and every child
+  const newRoot = root.cloneNode(false);
+  root.replaceWith(newRoot);
+  listeners.global = {};
+  listeners.local = {};
+  for (const id in templates) {
+    delete templates[id];
+  }
+  window.interpreter.initialize(newRoot);
+  return newRoot;
+}
+
+// Take over the nodes the server rendered with hydration ids instead of creating them.
+// `ids` maps each hydration id to the element id of the node
+window.interpreter.hydrate = function (ids, under) {
+  // Islands number their hydration ids separately, so skip the nodes of any island nested under the root
+  const island = under.closest("[data-dioxus-island]");
+  const islandOf = (node) =>
+    node.parentElement?.closest("[data-dioxus-island]") ?? null;
+  const hydrateNodes = under.querySelectorAll("[data-node-hydration]");
+  for (let i = 0; i < hydrateNodes.length; i++) {
+    const hydrateNode = hydrateNodes[i];
+    if (islandOf(hydrateNode) !== island) {
+      continue;
+    }
+    const split = hydrateNode.getAttribute("data-node-hydration").split(",");
+    const id = ids[parseInt(split[0])];
+    nodes[id] = hydrateNode;
+    if (split.length > 1) {
+      hydrateNode.listening = split.length - 1;
+      hydrateNode.setAttribute("data-dioxus-id", `${id}`);
+      for (let j = 1; j < split.length; j++) {
+        const [event_name, bubbles] = split[j].split(":");
+        listeners.create(event_name, hydrateNode, bubbles === "1", (event) => {
+          handler(event, event_name, bubbles === "1", config);
+        });
+      }
+    }
+  }
+
+  const treeWalker = document.createTreeWalker(under, NodeFilter.SHOW_COMMENT);
+  let currentNode = treeWalker.nextNode();
+  while (currentNode) {
+    const split = currentNode.textContent.split("node-id");
+    if (split.length > 1 && islandOf(currentNode) === island) {
+      let text = currentNode.nextSibling;
+      // Empty text is rendered as two markers with nothing between them
+      if (text === null || text.nodeType !== Node.TEXT_NODE) {
+        text = document.createTextNode("");
+        currentNode.after(text);
+      }
+      nodes[ids[parseInt(split[1])]] = text;
+    }
+    currentNode = treeWalker.nextNode();
+  }
+}
+
 window.interpreter.getClientRect = function (id) {
   const node = nodes[id];
   if (!node) {
@@ -417,12 +522,8 @@ async function serialize_event(event) {
     case "dragover":
     case "dragstart":
     case "drop": {
-      let files = null;
-      if (event.dataTransfer && event.dataTransfer.files) {
-        files = await serializeFileList(event.dataTransfer.files);
-      }
-
-      return { mouse: get_mouse_data(event), files };
+      // The platform attaches the dropped files after the event is serialized
+      return { mouse: get_mouse_data(event), files: null };
     }
     case "click":
     case "contextmenu":
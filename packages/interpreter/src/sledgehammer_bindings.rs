@@ -77,25 +77,56 @@ mod js {
         }
         return node;
     }
-    const listeners = new ListenerMap();
+    let listeners = new ListenerMap();
     let nodes = [];
     let stack = [];
     let root;
-    const templates = {};
+    let templates = {};
     let node, els, end, ptr_end, k;
+    // The state of every VirtualDom rendering on this page, so multiple VirtualDoms (like islands) don't share element ids
+    const doms = {};
+    let active_dom;
+    export function set_active_dom(id) {
+        if (active_dom === id) {
+            return;
+        }
+        if (active_dom !== undefined) {
+            doms[active_dom] = { listeners, nodes, stack, templates };
+        }
+        const state = doms[id];
+        if (state) {
+            listeners = state.listeners;
+            nodes = state.nodes;
+            stack = state.stack;
+            templates = state.templates;
+        } else {
+            listeners = new ListenerMap();
+            nodes = [];
+            stack = [];
+            templates = {};
+        }
+        active_dom = id;
+    }
     export function save_template(nodes, tmpl_id) {
         templates[tmpl_id] = nodes;
     }
-    export function hydrate(ids) {
-        console.log("hydrating", ids);
-        const hydrateNodes = document.querySelectorAll('[data-node-hydration]');
+    function islandOf(node) {
+        const parent = node.parentElement;
+        return parent ? parent.closest('[data-dioxus-island]') : null;
+    }
+    export function hydrate(ids, under) {
+        // Islands number their hydration ids separately, so skip the nodes of any island nested under the root
+        const island = under && under.closest ? under.closest('[data-dioxus-island]') : null;
+        const hydrateNodes = (under || document).querySelectorAll('[data-node-hydration]');
         for (let i = 0; i < hydrateNodes.length; i++) {
             const hydrateNode = hydrateNodes[i];
+            if (islandOf(hydrateNode) !== island) {
+                continue;
+            }
             const hydration = hydrateNode.getAttribute('data-node-hydration');
             const split = hydration.split(',');
             const id = ids[parseInt(split[0])];
             nodes[id] = hydrateNode;
-            console.log("hydrating node", hydrateNode, id);
             if (split.length > 1) {
                 hydrateNode.listening = split.length - 1;
                 hydrateNode.setAttribute('data-dioxus-id', id);
@@ -104,21 +135,19 @@ mod js {
                     const split2 = listener.split(':');
                     const event_name = split2[0];
                     const bubbles = split2[1] === '1';
-                    console.log("hydrating listener", event_name, bubbles);
                     listeners.create(event_name, hydrateNode, bubbles);
                 }
             }
         }
         const treeWalker = document.createTreeWalker(
-            document.body,
+            under || document.body,
             NodeFilter.SHOW_COMMENT,
         );
         let currentNode = treeWalker.nextNode();
         while (currentNode) {
             const id = currentNode.textContent;
             const split = id.split('node-id');
-            if (split.length > 1) {
-                console.log("hydrating text", currentNode.nextSibling, id);
+            if (split.length > 1 && islandOf(currentNode) === island) {
                 nodes[ids[parseInt(split[1])]] = currentNode.nextSibling;
             }
             currentNode = treeWalker.nextNode();
@@ -147,7 +176,10 @@ mod js {
         pub fn save_template(nodes: Vec<Node>, tmpl_id: u16);
 
         #[wasm_bindgen]
-        pub fn hydrate(ids: Vec<u32>);
+        pub fn hydrate(ids: Vec<u32>, under: Option<Node>);
+
+        #[wasm_bindgen]
+        pub fn set_active_dom(id: u32);
 
         #[wasm_bindgen]
         pub fn get_node(id: u32) -> Node;
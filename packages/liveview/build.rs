@@ -3,42 +3,30 @@ use minify_js::*;
 use std::io::Write;
 
 fn main() {
-    let serialize_file_uploads = r#"if (
+    // The files stay in the browser. The event only carries their names and sizes and the server reads the contents in chunks when the app asks for them
+    let serialize_file_uploads = r#"let fileList = null;
+          if (
             target.tagName === "INPUT" &&
+            target.getAttribute("type") === "file" &&
             (event.type === "change" || event.type === "input")
           ) {
-            const type = target.getAttribute("type");
-            if (type === "file") {
-              async function read_files() {
-                const files = target.files;
-                const file_contents = {};
-      
-                for (let i = 0; i < files.length; i++) {
-                  const file = files[i];
-      
-                  file_contents[file.name] = Array.from(
-                    new Uint8Array(await file.arrayBuffer())
-                  );
-                }
-                let file_engine = {
-                  files: file_contents,
-                };
-                contents.files = file_engine;
-      
-                if (realId === null) {
-                  return;
-                }
-                const message = window.interpreter.serializeIpcMessage("user_event", {
-                  name: name,
-                  element: parseInt(realId),
-                  data: contents,
-                  bubbles,
-                });
-                window.ipc.postMessage(message);
-              }
-              read_files();
+            fileList = target.files;
+          } else if (event.type === "drop" && event.dataTransfer) {
+            fileList = event.dataTransfer.files;
+          }
+          if (fileList !== null && fileList.length > 0) {
+            if (realId === null) {
               return;
             }
+            const message = window.interpreter.serializeIpcMessage("user_event", {
+              name: name,
+              element: parseInt(realId),
+              data: contents,
+              bubbles,
+              files: window.ipc.registerFiles(fileList),
+            });
+            window.ipc.postMessage(message);
+            return;
           }"#;
     let mut interpreter = SLEDGEHAMMER_JS
         .replace("/*POST_EVENT_SERIALIZATION*/", serialize_file_uploads)
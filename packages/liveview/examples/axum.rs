@@ -42,6 +42,30 @@ async fn main() {
                     )))
                 }),
             )
+            .route(
+                "/prerendered",
+                get({
+                    let view = view.clone();
+                    move || async move {
+                        // Render the app on the server so the page has content before the websocket connects
+                        let root = match view.prerender(app).await {
+                            Ok(prerendered) => prerendered.root(),
+                            Err(_) => r#"<div id="main"></div>"#.to_string(),
+                        };
+                        Html(format!(
+                            r#"
+        <!DOCTYPE html>
+        <html>
+            <head> <title>Dioxus LiveView with axum</title>  </head>
+            <body> {root} </body>
+            {glue}
+        </html>
+        "#,
+                            glue = dioxus_liveview::interpreter_glue("/ws")
+                        ))
+                    }
+                }),
+            )
             .route(
                 "/as-path",
                 get(move || async move {
@@ -12,11 +12,10 @@ pub fn axum_socket(ws: WebSocket) -> impl LiveViewSocket {
 }
 
 fn transform_rx(message: Result<Message, axum::Error>) -> Result<Vec<u8>, LiveViewError> {
-    message
+    // Events can be sent as text (JSON) or binary (CBOR) messages
+    Ok(message
         .map_err(|_| LiveViewError::SendingFailed)?
-        .into_text()
-        .map(|s| s.into_bytes())
-        .map_err(|_| LiveViewError::SendingFailed)
+        .into_data())
 }
 
 async fn transform_tx(message: Vec<u8>) -> Result<Message, axum::Error> {
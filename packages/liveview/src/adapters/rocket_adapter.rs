@@ -13,11 +13,10 @@ pub fn rocket_socket(stream: DuplexStream) -> impl LiveViewSocket {
 }
 
 fn transform_rx(message: Result<Message, Error>) -> Result<Vec<u8>, LiveViewError> {
-    message
+    // Events can be sent as text (JSON) or binary (CBOR) messages
+    Ok(message
         .map_err(|_| LiveViewError::SendingFailed)?
-        .into_text()
-        .map(|s| s.into_bytes())
-        .map_err(|_| LiveViewError::SendingFailed)
+        .into_data())
 }
 
 async fn transform_tx(message: Vec<u8>) -> Result<Message, Error> {
@@ -2,7 +2,10 @@
 
 use dioxus_html::*;
 
-use crate::element::LiveviewElement;
+use crate::{
+    element::LiveviewElement,
+    file_engine::{LiveviewDragData, LiveviewFormData},
+};
 
 pub(crate) struct SerializedHtmlEventConverter;
 
@@ -32,6 +35,9 @@ impl HtmlEventConverter for SerializedHtmlEventConverter {
     }
 
     fn convert_drag_data(&self, event: &PlatformEventData) -> DragData {
+        if let Some(drag) = event.downcast::<LiveviewDragData>() {
+            return DragData::new(drag.clone());
+        }
         event
             .downcast::<SerializedDragData>()
             .cloned()
@@ -48,6 +54,9 @@ impl HtmlEventConverter for SerializedHtmlEventConverter {
     }
 
     fn convert_form_data(&self, event: &PlatformEventData) -> FormData {
+        if let Some(form_data) = event.downcast::<LiveviewFormData>() {
+            return FormData::new(form_data.clone());
+        }
         event
             .downcast::<SerializedFormData>()
             .cloned()
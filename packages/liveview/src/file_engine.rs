@@ -0,0 +1,395 @@
+//! Read files the user selected in the browser from the server
+
+use std::{any::Any, rc::Rc, sync::Arc};
+
+use dioxus_html::{
+    geometry::{ClientPoint, Coordinates, ElementPoint, PagePoint, ScreenPoint},
+    input_data::{MouseButton, MouseButtonSet},
+    prelude::*,
+    EventData, FileEngine, HasFileData, PlatformEventData, SerializedDragData, SerializedFormData,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+
+/// The number of bytes the server asks the client for at once when it reads a file
+pub(crate) const FILE_CHUNK_SIZE: u64 = 64 * 1024;
+
+/// The largest file the server reads from the client by default
+pub(crate) const DEFAULT_MAX_UPLOAD_SIZE: u64 = 64 * 1024 * 1024;
+
+/// A file the user selected or dropped in the browser. The contents stay in the browser until the app reads them
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct FileInfo {
+    /// The id the client uses for the file
+    id: u64,
+    name: String,
+    size: u64,
+}
+
+/// A request for part of a file from the client
+pub(crate) struct FileRequest {
+    pub file: u64,
+    pub offset: u64,
+    pub length: u64,
+    pub reply: oneshot::Sender<Option<Vec<u8>>>,
+}
+
+/// Asks the client for part of a file
+#[derive(Serialize, Debug)]
+pub(crate) struct ReadFileRequest {
+    /// The id the client uses to send the chunk back
+    pub request: usize,
+    pub file: u64,
+    pub offset: u64,
+    pub length: u64,
+}
+
+/// Part of a file the client sent in response to a [`ReadFileRequest`]
+#[derive(Deserialize, Debug)]
+pub(crate) struct FileChunk {
+    pub request: usize,
+    /// The bytes of the chunk or `None` if the client could not read the file
+    pub data: Option<serde_bytes::ByteBuf>,
+}
+
+/// A [`FileEngine`] that reads files from the browser over the liveview socket in chunks
+pub(crate) struct LiveviewFileEngine {
+    files: Vec<FileInfo>,
+    requests: UnboundedSender<FileRequest>,
+}
+
+impl LiveviewFileEngine {
+    /// Create an engine for the files the client sent with an event. Files the client claims are larger than `max_size` are left out
+    pub(crate) fn new(
+        mut files: Vec<FileInfo>,
+        requests: UnboundedSender<FileRequest>,
+        max_size: u64,
+    ) -> Self {
+        files.retain(|file| {
+            let allowed = file.size <= max_size;
+            if !allowed {
+                tracing::warn!(
+                    "Rejecting the file {:?} because it is larger than the maximum upload size ({} > {} bytes)",
+                    file.name,
+                    file.size,
+                    max_size
+                );
+            }
+            allowed
+        });
+        Self { files, requests }
+    }
+
+    fn find(&self, name: &str) -> Option<&FileInfo> {
+        self.files.iter().find(|file| file.name == name)
+    }
+
+    /// Ask the client for one chunk of a file
+    async fn read_chunk(&self, file: u64, offset: u64, length: u64) -> Option<Vec<u8>> {
+        let (reply, rx) = oneshot::channel();
+        self.requests
+            .send(FileRequest {
+                file,
+                offset,
+                length,
+                reply,
+            })
+            .ok()?;
+        // If the client disconnects before it answers, the request is dropped
+        rx.await.ok().flatten()
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl FileEngine for LiveviewFileEngine {
+    fn files(&self) -> Vec<String> {
+        self.files.iter().map(|file| file.name.clone()).collect()
+    }
+
+    async fn read_file(&self, file: &str) -> Option<Vec<u8>> {
+        let size = self.find(file)?.size;
+        self.read_file_range(file, 0, size).await
+    }
+
+    async fn read_file_to_string(&self, file: &str) -> Option<String> {
+        self.read_file(file)
+            .await
+            .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+    }
+
+    async fn get_native_file(&self, file: &str) -> Option<Box<dyn Any>> {
+        self.read_file(file)
+            .await
+            .map(|val| Box::new(val) as Box<dyn Any>)
+    }
+
+    async fn file_size(&self, file: &str) -> Option<u64> {
+        self.find(file).map(|file| file.size)
+    }
+
+    async fn read_file_range(&self, file: &str, offset: u64, length: u64) -> Option<Vec<u8>> {
+        let file = self.find(file)?;
+        let end = offset.saturating_add(length).min(file.size);
+        // The size comes from the client, so the buffer only grows as chunks actually arrive
+        let mut contents =
+            Vec::with_capacity(FILE_CHUNK_SIZE.min(end.saturating_sub(offset)) as usize);
+        let mut position = offset;
+        while position < end {
+            let length = FILE_CHUNK_SIZE.min(end - position);
+            let mut chunk = self.read_chunk(file.id, position, length).await?;
+            // The file changed on the client since it was selected
+            if chunk.is_empty() {
+                break;
+            }
+            chunk.truncate(length as usize);
+            position += chunk.len() as u64;
+            contents.extend(chunk);
+        }
+        Some(contents)
+    }
+}
+
+/// Form data from the client with files that can be read through the socket
+#[derive(Clone)]
+pub(crate) struct LiveviewFormData {
+    data: SerializedFormData,
+    files: Arc<LiveviewFileEngine>,
+}
+
+impl HasFileData for LiveviewFormData {
+    fn files(&self) -> Option<Arc<dyn FileEngine>> {
+        Some(self.files.clone())
+    }
+}
+
+impl HasFormData for LiveviewFormData {
+    fn value(&self) -> String {
+        self.data.value()
+    }
+
+    fn values(&self) -> std::collections::HashMap<String, FormValue> {
+        self.data.values()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Drag data from the client with files that can be read through the socket
+#[derive(Clone)]
+pub(crate) struct LiveviewDragData {
+    data: SerializedDragData,
+    files: Arc<LiveviewFileEngine>,
+}
+
+impl HasFileData for LiveviewDragData {
+    fn files(&self) -> Option<Arc<dyn FileEngine>> {
+        Some(self.files.clone())
+    }
+}
+
+impl HasDragData for LiveviewDragData {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl HasMouseData for LiveviewDragData {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl InteractionLocation for LiveviewDragData {
+    fn client_coordinates(&self) -> ClientPoint {
+        self.data.client_coordinates()
+    }
+
+    fn page_coordinates(&self) -> PagePoint {
+        self.data.page_coordinates()
+    }
+
+    fn screen_coordinates(&self) -> ScreenPoint {
+        self.data.screen_coordinates()
+    }
+}
+
+impl InteractionElementOffset for LiveviewDragData {
+    fn element_coordinates(&self) -> ElementPoint {
+        self.data.element_coordinates()
+    }
+
+    fn coordinates(&self) -> Coordinates {
+        self.data.coordinates()
+    }
+}
+
+impl ModifiersInteraction for LiveviewDragData {
+    fn modifiers(&self) -> Modifiers {
+        self.data.modifiers()
+    }
+}
+
+impl PointerInteraction for LiveviewDragData {
+    fn held_buttons(&self) -> MouseButtonSet {
+        self.data.held_buttons()
+    }
+
+    fn trigger_button(&self) -> Option<MouseButton> {
+        self.data.trigger_button()
+    }
+}
+
+/// Attach the files the client sent with an event to the event data
+pub(crate) fn event_with_files(data: EventData, files: Arc<LiveviewFileEngine>) -> Rc<dyn Any> {
+    match data {
+        EventData::Form(data) => Rc::new(PlatformEventData::new(Box::new(LiveviewFormData {
+            data,
+            files,
+        }))),
+        EventData::Drag(data) => Rc::new(PlatformEventData::new(Box::new(LiveviewDragData {
+            data,
+            files,
+        }))),
+        data => data.into_any(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    /// Answer file requests like the browser would, with the contents of each file by id
+    fn fake_client(
+        files: Vec<Vec<u8>>,
+    ) -> (
+        UnboundedSender<FileRequest>,
+        tokio::task::JoinHandle<Vec<(u64, u64)>>,
+    ) {
+        let (tx, mut rx) = unbounded_channel::<FileRequest>();
+        let client = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            while let Some(request) = rx.recv().await {
+                requests.push((request.offset, request.length));
+                let chunk = files.get(request.file as usize).map(|contents| {
+                    let start = (request.offset as usize).min(contents.len());
+                    let end = (start + request.length as usize).min(contents.len());
+                    contents[start..end].to_vec()
+                });
+                let _ = request.reply.send(chunk);
+            }
+            requests
+        });
+        (tx, client)
+    }
+
+    fn file(id: u64, name: &str, size: u64) -> FileInfo {
+        FileInfo {
+            id,
+            name: name.to_string(),
+            size,
+        }
+    }
+
+    #[tokio::test]
+    async fn reads_files_in_chunks() {
+        let contents: Vec<u8> = (0..FILE_CHUNK_SIZE * 2 + 100)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let (tx, client) = fake_client(vec![contents.clone()]);
+        let engine = LiveviewFileEngine::new(
+            vec![file(0, "data.bin", contents.len() as u64)],
+            tx,
+            DEFAULT_MAX_UPLOAD_SIZE,
+        );
+
+        assert_eq!(engine.files(), vec!["data.bin".to_string()]);
+        assert_eq!(
+            engine.file_size("data.bin").await,
+            Some(contents.len() as u64)
+        );
+        assert_eq!(engine.read_file("data.bin").await, Some(contents.clone()));
+
+        // Ranges are clamped to the end of the file
+        let offset = FILE_CHUNK_SIZE - 10;
+        assert_eq!(
+            engine
+                .read_file_range("data.bin", offset, FILE_CHUNK_SIZE * 4)
+                .await,
+            Some(contents[offset as usize..].to_vec())
+        );
+        assert_eq!(engine.read_file("missing.bin").await, None);
+
+        drop(engine);
+        let requests = client.await.unwrap();
+        assert_eq!(
+            requests,
+            vec![
+                (0, FILE_CHUNK_SIZE),
+                (FILE_CHUNK_SIZE, FILE_CHUNK_SIZE),
+                (FILE_CHUNK_SIZE * 2, 100),
+                (offset, FILE_CHUNK_SIZE),
+                (offset + FILE_CHUNK_SIZE, 110),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn stops_reading_files_that_shrank() {
+        // The client only has 10 bytes of a file that was 100 bytes when it was selected
+        let (tx, _client) = fake_client(vec![vec![1; 10]]);
+        let engine = LiveviewFileEngine::new(
+            vec![file(0, "shrunk.txt", 100)],
+            tx,
+            DEFAULT_MAX_UPLOAD_SIZE,
+        );
+        assert_eq!(engine.read_file("shrunk.txt").await, Some(vec![1; 10]));
+    }
+
+    #[tokio::test]
+    async fn fails_reads_when_the_client_disconnects() {
+        let (tx, rx) = unbounded_channel();
+        drop(rx);
+        let engine =
+            LiveviewFileEngine::new(vec![file(0, "data.bin", 10)], tx, DEFAULT_MAX_UPLOAD_SIZE);
+        assert_eq!(engine.read_file("data.bin").await, None);
+
+        // A client that can't read the file answers with no data
+        let (tx, _client) = fake_client(Vec::new());
+        let engine =
+            LiveviewFileEngine::new(vec![file(3, "data.bin", 10)], tx, DEFAULT_MAX_UPLOAD_SIZE);
+        assert_eq!(engine.read_file("data.bin").await, None);
+    }
+
+    #[tokio::test]
+    async fn rejects_files_over_the_upload_limit() {
+        let (tx, client) = fake_client(vec![vec![1; 10], vec![2; 10]]);
+        let engine = LiveviewFileEngine::new(
+            vec![file(0, "huge.bin", u64::MAX), file(1, "small.bin", 10)],
+            tx,
+            DEFAULT_MAX_UPLOAD_SIZE,
+        );
+        assert_eq!(engine.files(), vec!["small.bin".to_string()]);
+        assert_eq!(engine.read_file("huge.bin").await, None);
+        assert_eq!(engine.file_size("huge.bin").await, None);
+        assert_eq!(engine.read_file("small.bin").await, Some(vec![2; 10]));
+
+        // The huge file is never requested from the client
+        drop(engine);
+        assert_eq!(client.await.unwrap(), vec![(0, 10)]);
+    }
+
+    #[tokio::test]
+    async fn reads_files_that_claim_a_huge_size_without_allocating_it() {
+        // Without a limit, the buffer still only grows with the data the client sends
+        let (tx, _client) = fake_client(vec![vec![1; 10]]);
+        let engine = LiveviewFileEngine::new(vec![file(0, "huge.bin", u64::MAX)], tx, u64::MAX);
+        assert_eq!(engine.read_file("huge.bin").await, Some(vec![1; 10]));
+        assert_eq!(
+            engine.read_file_range("huge.bin", u64::MAX - 5, 100).await,
+            Some(Vec::new())
+        );
+    }
+}
@@ -28,7 +28,12 @@ pub mod adapters {
 pub use adapters::*;
 
 mod element;
+mod file_engine;
+pub mod metrics;
+pub use metrics::*;
 pub mod pool;
+mod prerender;
+pub use prerender::PrerenderedLiveView;
 mod query;
 use futures_util::{SinkExt, StreamExt};
 pub use pool::*;
@@ -45,6 +50,8 @@ impl<T> WebsocketRx for T where T: StreamExt<Item = Result<String, LiveViewError
 pub enum LiveViewError {
     #[error("warp error")]
     SendingFailed,
+    #[error("the maximum number of liveview sessions is running")]
+    SessionLimitReached,
 }
 
 static MINIFIED: &str = include_str!("./minified.js");
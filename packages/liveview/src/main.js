@@ -1,4 +1,7 @@
-const config = new InterpreterConfig(false);
+const config = new InterpreterConfig(false, true);
+
+// The JSON serializer from the interpreter. Every connection starts with JSON until the server accepts a binary encoding
+const serializeJsonIpcMessage = window.interpreter.serializeIpcMessage;
 
 function main() {
   let root = window.document.getElementById("main");
@@ -10,21 +13,74 @@ function main() {
 class IPC {
   constructor(root) {
     window.interpreter.initialize(root);
-    const ws = new WebSocket(WS_ADDR);
+    this.root = root;
+    // The token of the session on the server. If the connection drops, we use it to resume the session.
+    // A server rendered page starts with the session that rendered it
+    this.sessionToken = root.getAttribute("data-dioxus-session");
+    // If the root contains server rendered nodes that we can attach to instead of rendering again
+    this.prerendered = this.sessionToken !== null;
+    this.retries = 0;
+    // If the server ended the session. We only reconnect once the user interacts with the page again
+    this.closed = false;
+    // The files the user selected or dropped, by the id the server uses to read them
+    this.files = new Map();
+    this.nextFileId = 0;
+    // If the server accepted CBOR, which can send file chunks as bytes
+    this.binaryEvents = false;
+    this.connect(false);
+  }
+
+  connect(reconnecting) {
+    // Reconnections include the session token in the url, so a load balancer can send them to the server that runs the session
+    let url = WS_ADDR;
+    if (this.sessionToken !== null) {
+      const withSession = new URL(WS_ADDR);
+      withSession.searchParams.set("dioxus-session", this.sessionToken);
+      url = withSession.toString();
+    }
+    const ws = new WebSocket(url);
     ws.binaryType = "arraybuffer";
+    // After reconnecting, the server sends every node again so we need to clear the page before applying the first edits.
+    // A prerendered page is only kept if the server tells us to hydrate it
+    let needsReset = reconnecting || this.prerendered;
+    let pingInterval;
 
     function ping() {
       ws.send("__ping__");
     }
 
     ws.onopen = () => {
+      this.retries = 0;
       // we ping every 30 seconds to keep the websocket alive
-      setInterval(ping, 30000);
-      ws.send(window.interpreter.serializeIpcMessage("initialize"));
+      pingInterval = setInterval(ping, 30000);
+      window.interpreter.serializeIpcMessage = serializeJsonIpcMessage;
+      this.binaryEvents = false;
+      if (this.sessionToken !== null) {
+        ws.send(
+          window.interpreter.serializeIpcMessage("resume", {
+            token: this.sessionToken,
+            encodings: ["cbor"],
+            hydrate: this.prerendered,
+          })
+        );
+      } else {
+        ws.send(
+          window.interpreter.serializeIpcMessage("initialize", {
+            encodings: ["cbor"],
+          })
+        );
+      }
     };
 
     ws.onerror = (err) => {
-      // todo: retry the connection
+      // onclose is called after an error, which will retry the connection
+    };
+
+    ws.onclose = () => {
+      clearInterval(pingInterval);
+      if (this.ws === ws && !this.closed) {
+        this.reconnect();
+      }
     };
 
     ws.onmessage = (message) => {
@@ -34,6 +90,11 @@ class IPC {
       // The first byte tells the shim if this is a binary of text frame
       if (binaryFrame) {
         // binary frame
+        if (needsReset) {
+          this.root = window.interpreter.reset(this.root);
+          this.prerendered = false;
+          needsReset = false;
+        }
         run_from_bytes(messageData);
       }
       else {
@@ -41,7 +102,7 @@ class IPC {
 
         let decoder = new TextDecoder("utf-8");
 
-        // Using decode method to get string output 
+        // Using decode method to get string output
         let str = decoder.decode(messageData);
         // Ignore pongs
         if (str != "__pong__") {
@@ -50,6 +111,33 @@ class IPC {
             case "query":
               Function("Eval", `"use strict";${event.data};`)();
               break;
+            case "session":
+              this.sessionToken = event.data;
+              break;
+            case "encoding":
+              if (event.data === "cbor") {
+                window.interpreter.serializeIpcMessage = (method, params = {}) =>
+                  encodeCbor({ method, params });
+                this.binaryEvents = true;
+              }
+              break;
+            case "rejected":
+              console.warn("The server is full, retrying later:", event.data);
+              break;
+            case "hydrate":
+              window.interpreter.hydrate(event.data, this.root);
+              this.prerendered = false;
+              needsReset = false;
+              break;
+            case "read_file":
+              this.readFile(event.data);
+              break;
+            case "closed":
+              this.closed = true;
+              this.sessionToken = null;
+              // A new session can't read the files of the old one
+              this.files.clear();
+              break;
           }
         }
       }
@@ -58,9 +146,142 @@ class IPC {
     this.ws = ws;
   }
 
+  reconnect() {
+    // Back off exponentially up to 30 seconds with some jitter so many clients don't reconnect at the same time
+    const delay = Math.min(30000, 500 * 2 ** this.retries) * (0.5 + Math.random() / 2);
+    this.retries++;
+    setTimeout(() => this.connect(true), delay);
+  }
+
+  // Keep the files of an event so the server can read them later and describe them for the server
+  registerFiles(fileList) {
+    const files = [];
+    for (const file of fileList) {
+      const id = this.nextFileId++;
+      this.files.set(id, file);
+      files.push({ id, name: file.name, size: file.size });
+    }
+    return files;
+  }
+
+  // Send part of a file the server asked for
+  async readFile(request) {
+    const file = this.files.get(request.file);
+    let data = null;
+    if (file !== undefined) {
+      const chunk = file.slice(request.offset, request.offset + request.length);
+      data = new Uint8Array(await chunk.arrayBuffer());
+      // JSON has no byte strings
+      if (!this.binaryEvents) {
+        data = Array.from(data);
+      }
+    }
+    this.postMessage(
+      window.interpreter.serializeIpcMessage("file_chunk", {
+        request: request.request,
+        data,
+      })
+    );
+  }
+
   postMessage(msg) {
-    this.ws.send(msg);
+    // Events that happen while we are disconnected are dropped
+    if (this.ws.readyState === WebSocket.OPEN) {
+      this.ws.send(msg);
+    } else if (this.closed) {
+      // The server ended the session, start a new one now that the user is back
+      this.closed = false;
+      this.connect(true);
+    }
+  }
+}
+
+// A minimal CBOR (RFC 8949) encoder for the messages we send to the server.
+// Like JSON.stringify, undefined values in objects are skipped
+function encodeCbor(value) {
+  const bytes = [];
+  const encoder = new TextEncoder();
+
+  function writeHeader(major, length) {
+    if (length < 24) {
+      bytes.push((major << 5) | length);
+    } else if (length < 0x100) {
+      bytes.push((major << 5) | 24, length);
+    } else if (length < 0x10000) {
+      bytes.push((major << 5) | 25, length >> 8, length & 0xff);
+    } else if (length < 0x100000000) {
+      bytes.push(
+        (major << 5) | 26,
+        (length >>> 24) & 0xff,
+        (length >> 16) & 0xff,
+        (length >> 8) & 0xff,
+        length & 0xff
+      );
+    } else {
+      const high = Math.floor(length / 0x100000000);
+      bytes.push((major << 5) | 27);
+      for (const part of [high, length >>> 0]) {
+        bytes.push(
+          (part >>> 24) & 0xff,
+          (part >> 16) & 0xff,
+          (part >> 8) & 0xff,
+          part & 0xff
+        );
+      }
+    }
   }
+
+  function write(value) {
+    if (value === null || value === undefined) {
+      bytes.push(0xf6);
+    } else if (value === false) {
+      bytes.push(0xf4);
+    } else if (value === true) {
+      bytes.push(0xf5);
+    } else if (typeof value === "number") {
+      if (Number.isSafeInteger(value)) {
+        if (value >= 0) {
+          writeHeader(0, value);
+        } else {
+          writeHeader(1, -1 - value);
+        }
+      } else {
+        const view = new DataView(new ArrayBuffer(8));
+        view.setFloat64(0, value);
+        bytes.push(0xfb, ...new Uint8Array(view.buffer));
+      }
+    } else if (typeof value === "string") {
+      const encoded = encoder.encode(value);
+      writeHeader(3, encoded.length);
+      for (const byte of encoded) {
+        bytes.push(byte);
+      }
+    } else if (value instanceof Uint8Array) {
+      writeHeader(2, value.length);
+      for (const byte of value) {
+        bytes.push(byte);
+      }
+    } else if (Array.isArray(value)) {
+      writeHeader(4, value.length);
+      for (const item of value) {
+        write(item);
+      }
+    } else if (typeof value.toJSON === "function") {
+      write(value.toJSON());
+    } else {
+      const entries = Object.entries(value).filter(
+        ([_, item]) => item !== undefined && typeof item !== "function"
+      );
+      writeHeader(5, entries.length);
+      for (const [key, item] of entries) {
+        write(key);
+        write(item);
+      }
+    }
+  }
+
+  write(value);
+  return new Uint8Array(bytes);
 }
 
-main();
\ No newline at end of file
+main();
@@ -0,0 +1,249 @@
+//! Metrics about the sessions running in a [`crate::LiveViewPool`]
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// Receives metrics from a [`crate::LiveViewPool`] as sessions run.
+///
+/// Implement this trait to export liveview metrics to a system like Prometheus. Every method has an empty default implementation, so you only need to implement the metrics you care about. Counters like events and mutation bytes are reported as they happen, so the metrics system can compute rates like events per second.
+///
+/// The methods are called from the worker threads of the pool, so they should be cheap.
+///
+/// ```rust, ignore
+/// struct Prometheus {
+///     active_sessions: prometheus::IntGauge,
+///     events: prometheus::IntCounter,
+///     mutation_bytes: prometheus::IntCounter,
+/// }
+///
+/// impl LiveViewMetrics for Prometheus {
+///     fn session_started(&self, _: &SessionStats) {
+///         self.active_sessions.inc();
+///     }
+///
+///     fn session_ended(&self, _: &SessionStats) {
+///         self.active_sessions.dec();
+///     }
+///
+///     fn event_handled(&self, _: &SessionStats) {
+///         self.events.inc();
+///     }
+///
+///     fn mutations_sent(&self, _: &SessionStats, bytes: usize) {
+///         self.mutation_bytes.inc_by(bytes as u64);
+///     }
+/// }
+///
+/// let pool = LiveViewPool::new().metrics(Prometheus::new());
+/// ```
+pub trait LiveViewMetrics: Send + Sync + 'static {
+    /// A new session was started
+    fn session_started(&self, _session: &SessionStats) {}
+
+    /// A session ended because the client disconnected, the session was idle for too long, or the connection failed
+    fn session_ended(&self, _session: &SessionStats) {}
+
+    /// A client was rejected because the pool already runs the maximum number of sessions
+    fn session_rejected(&self) {}
+
+    /// A session handled an event or query result from the client
+    fn event_handled(&self, _session: &SessionStats) {}
+
+    /// A session sent mutations to the client
+    fn mutations_sent(&self, _session: &SessionStats, _bytes: usize) {}
+}
+
+/// Accounting for a single liveview session
+#[derive(Debug, Clone)]
+pub struct SessionStats {
+    /// An id for the session that is unique within the pool
+    pub id: u64,
+    /// When the session started
+    pub started: Instant,
+    /// The last time the client sent an event to the session
+    pub last_active: Instant,
+    /// If a client is currently connected to the session
+    pub connected: bool,
+    /// The number of events and query results the client sent
+    pub events: u64,
+    /// The number of bytes of mutations sent to the client
+    pub mutation_bytes: u64,
+    /// The time the session spent handling events and rendering
+    pub cpu_time: Duration,
+    /// An estimate of the memory the VirtualDom of the session uses in bytes. See [`dioxus_core::VirtualDom::allocated_bytes`]
+    pub allocated_bytes: usize,
+}
+
+impl SessionStats {
+    fn new(id: u64) -> Self {
+        let now = Instant::now();
+        Self {
+            id,
+            started: now,
+            last_active: now,
+            connected: true,
+            events: 0,
+            mutation_bytes: 0,
+            cpu_time: Duration::ZERO,
+            allocated_bytes: 0,
+        }
+    }
+}
+
+/// Totals for every session in a [`crate::LiveViewPool`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// The number of sessions that are currently running, including sessions that are waiting for their client to reconnect
+    pub active_sessions: usize,
+    /// The number of sessions that were started
+    pub total_sessions: u64,
+    /// The number of clients that were rejected because the pool was full
+    pub rejected_sessions: u64,
+    /// The number of events and query results every session handled
+    pub events: u64,
+    /// The number of bytes of mutations every session sent
+    pub mutation_bytes: u64,
+}
+
+/// The accounting shared between a pool and its sessions
+#[derive(Default)]
+pub(crate) struct PoolMetrics {
+    active_sessions: AtomicUsize,
+    total_sessions: AtomicU64,
+    rejected_sessions: AtomicU64,
+    events: AtomicU64,
+    mutation_bytes: AtomicU64,
+    next_id: AtomicU64,
+    sessions: Mutex<HashMap<u64, SessionStats>>,
+    exporter: Option<Arc<dyn LiveViewMetrics>>,
+}
+
+impl PoolMetrics {
+    pub(crate) fn new(exporter: Option<Arc<dyn LiveViewMetrics>>) -> Self {
+        Self {
+            exporter,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn stats(&self) -> PoolStats {
+        PoolStats {
+            active_sessions: self.active_sessions.load(Ordering::Relaxed),
+            total_sessions: self.total_sessions.load(Ordering::Relaxed),
+            rejected_sessions: self.rejected_sessions.load(Ordering::Relaxed),
+            events: self.events.load(Ordering::Relaxed),
+            mutation_bytes: self.mutation_bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) fn sessions(&self) -> Vec<SessionStats> {
+        self.sessions.lock().unwrap().values().cloned().collect()
+    }
+
+    /// Start tracking a new session if there are less than `max_sessions` active sessions
+    pub(crate) fn start_session(
+        self: &Arc<Self>,
+        max_sessions: Option<usize>,
+    ) -> Option<SessionMonitor> {
+        let reserved =
+            self.active_sessions
+                .fetch_update(
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                    |active| match max_sessions {
+                        Some(max) if active >= max => None,
+                        _ => Some(active + 1),
+                    },
+                );
+        if reserved.is_err() {
+            self.rejected_sessions.fetch_add(1, Ordering::Relaxed);
+            if let Some(exporter) = &self.exporter {
+                exporter.session_rejected();
+            }
+            return None;
+        }
+
+        self.total_sessions.fetch_add(1, Ordering::Relaxed);
+        let stats = SessionStats::new(self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(stats.id, stats.clone());
+        if let Some(exporter) = &self.exporter {
+            exporter.session_started(&stats);
+        }
+
+        Some(SessionMonitor {
+            stats,
+            pool: self.clone(),
+        })
+    }
+}
+
+/// Tracks the stats of one session. The session stops being counted as active when the monitor is dropped
+pub(crate) struct SessionMonitor {
+    pub(crate) stats: SessionStats,
+    pool: Arc<PoolMetrics>,
+}
+
+impl SessionMonitor {
+    /// Track a session that is not part of a pool
+    pub(crate) fn standalone() -> Self {
+        Arc::new(PoolMetrics::default())
+            .start_session(None)
+            .unwrap()
+    }
+
+    pub(crate) fn event_handled(&mut self, cpu_time: Duration) {
+        self.stats.events += 1;
+        self.stats.cpu_time += cpu_time;
+        self.stats.last_active = Instant::now();
+        self.pool.events.fetch_add(1, Ordering::Relaxed);
+        if let Some(exporter) = &self.pool.exporter {
+            exporter.event_handled(&self.stats);
+        }
+    }
+
+    pub(crate) fn rendered(&mut self, cpu_time: Duration, allocated_bytes: usize, bytes: usize) {
+        self.stats.cpu_time += cpu_time;
+        self.stats.allocated_bytes = allocated_bytes;
+        if bytes > 0 {
+            self.stats.mutation_bytes += bytes as u64;
+            self.pool
+                .mutation_bytes
+                .fetch_add(bytes as u64, Ordering::Relaxed);
+            if let Some(exporter) = &self.pool.exporter {
+                exporter.mutations_sent(&self.stats, bytes);
+            }
+        }
+        self.sync();
+    }
+
+    pub(crate) fn set_connected(&mut self, connected: bool) {
+        self.stats.connected = connected;
+        self.sync();
+    }
+
+    /// Share the latest stats with the pool
+    fn sync(&self) {
+        if let Some(stats) = self.pool.sessions.lock().unwrap().get_mut(&self.stats.id) {
+            *stats = self.stats.clone();
+        }
+    }
+}
+
+impl Drop for SessionMonitor {
+    fn drop(&mut self) {
+        self.pool.sessions.lock().unwrap().remove(&self.stats.id);
+        self.pool.active_sessions.fetch_sub(1, Ordering::SeqCst);
+        if let Some(exporter) = &self.pool.exporter {
+            exporter.session_ended(&self.stats);
+        }
+    }
+}
@@ -2,21 +2,45 @@ use crate::{
     element::LiveviewElement,
     eval::init_eval,
     events::SerializedHtmlEventConverter,
+    file_engine::{
+        event_with_files, FileChunk, FileInfo, FileRequest, LiveviewFileEngine, ReadFileRequest,
+    },
+    metrics::{LiveViewMetrics, PoolMetrics, PoolStats, SessionMonitor, SessionStats},
+    prerender::{HydrationIds, PrerenderedLiveView},
     query::{QueryEngine, QueryResult},
     LiveViewError,
 };
 use dioxus_core::{prelude::*, BorrowedAttributeValue, Mutations};
 use dioxus_html::{event_bubbles, EventData, HtmlEvent, MountedData, PlatformEventData};
 use dioxus_interpreter_js::binary_protocol::Channel;
-use futures_util::{pin_mut, SinkExt, StreamExt};
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
 use rustc_hash::FxHashMap;
 use serde::Serialize;
-use std::{rc::Rc, time::Duration};
+use slab::Slab;
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::sync::{
+    mpsc::{UnboundedReceiver, UnboundedSender},
+    oneshot,
+};
 use tokio_util::task::LocalPoolHandle;
 
+/// The sessions that are kept alive so a client can reconnect to them, by session token
+type Sessions = Arc<Mutex<HashMap<String, UnboundedSender<Connection>>>>;
+
 #[derive(Clone)]
 pub struct LiveViewPool {
     pub(crate) pool: LocalPoolHandle,
+    sessions: Sessions,
+    reconnect_grace_period: Option<Duration>,
+    max_sessions: Option<usize>,
+    metrics: Arc<PoolMetrics>,
+    options: SessionOptions,
 }
 
 impl Default for LiveViewPool {
@@ -32,9 +56,78 @@ impl LiveViewPool {
 
         LiveViewPool {
             pool: LocalPoolHandle::new(16),
+            sessions: Default::default(),
+            reconnect_grace_period: None,
+            max_sessions: None,
+            metrics: Default::default(),
+            options: SessionOptions::default(),
         }
     }
 
+    /// Run sessions on this many worker threads. The default is 16.
+    ///
+    /// Every session stays on the worker it started on, so a single busy session only slows down the sessions on the same worker.
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.pool = LocalPoolHandle::new(workers);
+        self
+    }
+
+    /// Reject new clients while this many sessions are running.
+    ///
+    /// Rejected clients are told that the server is full and retry with an exponential backoff. Clients that resume a session are never rejected.
+    pub fn max_sessions(mut self, max_sessions: usize) -> Self {
+        self.max_sessions = Some(max_sessions);
+        self
+    }
+
+    /// End sessions that did not receive any events from their client for this long.
+    ///
+    /// The client does not reconnect after an idle timeout until the user interacts with the page again.
+    pub fn idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.options.idle_timeout = Some(idle_timeout);
+        self
+    }
+
+    /// Export metrics about the sessions in this pool
+    pub fn metrics(mut self, metrics: impl LiveViewMetrics) -> Self {
+        self.metrics = Arc::new(PoolMetrics::new(Some(Arc::new(metrics))));
+        self
+    }
+
+    /// Get the totals for every session in this pool
+    pub fn stats(&self) -> PoolStats {
+        self.metrics.stats()
+    }
+
+    /// Get the stats of every session that is currently running in this pool
+    pub fn sessions(&self) -> Vec<SessionStats> {
+        self.metrics.sessions()
+    }
+
+    /// Keep the VirtualDom of a client alive for this long after its websocket disconnects.
+    ///
+    /// If the client reconnects within the grace period, it resumes the same session instead of starting the app from scratch. The client reconnects automatically with an exponential backoff.
+    ///
+    /// Sessions only live in the process that started them. If you run several servers behind a load balancer, route websockets with the same `dioxus-session` query parameter to the same server. Reconnecting clients add it to the websocket url.
+    pub fn reconnect_grace_period(mut self, grace_period: Duration) -> Self {
+        self.reconnect_grace_period = Some(grace_period);
+        self
+    }
+
+    /// Only read files the user uploads if they are at most this many bytes. Larger files are left out of the files of the event. The default is 64 MiB.
+    pub fn max_upload_size(mut self, bytes: u64) -> Self {
+        self.options.max_upload_size = bytes;
+        self
+    }
+
+    /// Allow clients to send events and query results encoded as CBOR instead of JSON. This is enabled by default.
+    ///
+    /// The encoding is negotiated when the client connects, so clients that only support JSON keep working.
+    pub fn binary_events(mut self, enabled: bool) -> Self {
+        self.options.binary_events = enabled;
+        self
+    }
+
     pub async fn launch(
         &self,
         ws: impl LiveViewSocket,
@@ -58,12 +151,204 @@ impl LiveViewPool {
         ws: impl LiveViewSocket,
         make_app: F,
     ) -> Result<(), LiveViewError> {
-        match self.pool.spawn_pinned(move || run(make_app(), ws)).await {
+        let mut ws: BoxedLiveViewSocket = Box::pin(ws);
+
+        // The first message from the client tells us if it wants to resume a session
+        let first_message = match ws.next().await {
+            Some(Ok(message)) => message,
+            _ => return Ok(()),
+        };
+        let mut connection = match serde_json::from_slice::<IpcMessage>(&first_message) {
+            Ok(IpcMessage::Initialize(params)) => Connection {
+                ws,
+                encodings: params.encodings,
+                hydrate: false,
+            },
+            Ok(IpcMessage::Resume {
+                token,
+                encodings,
+                hydrate,
+            }) => {
+                let mut connection = Connection {
+                    ws,
+                    encodings,
+                    hydrate,
+                };
+                let session = self.sessions.lock().unwrap().get(&token).cloned();
+                if let Some(session) = session {
+                    match session.send(connection) {
+                        Ok(()) => {
+                            tracing::trace!("Resuming liveview session");
+                            return Ok(());
+                        }
+                        // The session ended before we could hand off the socket, start a new one instead
+                        Err(err) => connection = err.0,
+                    }
+                }
+                connection
+            }
+            _ => Connection {
+                ws,
+                encodings: Vec::new(),
+                hydrate: false,
+            },
+        };
+
+        let Some(monitor) = self.metrics.start_session(self.max_sessions) else {
+            return reject(connection.ws).await;
+        };
+
+        let options = self.options;
+        let result = match self.reconnect_grace_period {
+            Some(grace_period) => {
+                let (token, sockets) = self.register_session();
+                let sessions = self.sessions.clone();
+                self.pool
+                    .spawn_pinned(move || async move {
+                        let mut session = LiveViewSession::new(make_app(), monitor, options);
+                        let result = session
+                            .run_resumable(connection, &token, sockets, grace_period)
+                            .await;
+                        sessions.lock().unwrap().remove(&token);
+                        result
+                    })
+                    .await
+            }
+            None => {
+                self.pool
+                    .spawn_pinned(move || async move {
+                        let mut session = LiveViewSession::new(make_app(), monitor, options);
+                        session.start_connection(&mut connection, None).await?;
+                        session
+                            .run_connection(&mut connection.ws, None)
+                            .await
+                            .map(|_| ())
+                    })
+                    .await
+            }
+        };
+
+        match result {
             Ok(Ok(_)) => Ok(()),
             Ok(Err(e)) => Err(e),
             Err(_) => Err(LiveViewError::SendingFailed),
         }
     }
+
+    /// Render an app to HTML so the page has content before the websocket connects.
+    ///
+    /// The VirtualDom that rendered the HTML is kept alive in the pool. When the page connects to the websocket, it attaches to the rendered HTML instead of rendering the app again. If the page doesn't connect within 30 seconds, the VirtualDom is dropped and the page renders from scratch when it connects.
+    ///
+    /// Put [`PrerenderedLiveView::root`] in your page instead of the empty root element:
+    ///
+    /// ```rust, ignore
+    /// let prerendered = pool.prerender(app).await?;
+    /// Html(format!(
+    ///     "<html><body>{}</body>{}</html>",
+    ///     prerendered.root(),
+    ///     interpreter_glue("/ws")
+    /// ))
+    /// ```
+    pub async fn prerender(
+        &self,
+        app: fn(Scope<()>) -> Element,
+    ) -> Result<PrerenderedLiveView, LiveViewError> {
+        self.prerender_with_props(app, ()).await
+    }
+
+    /// Render an app with props to HTML so the page has content before the websocket connects. See [`LiveViewPool::prerender`].
+    pub async fn prerender_with_props<T: Send + 'static>(
+        &self,
+        app: fn(Scope<T>) -> Element,
+        props: T,
+    ) -> Result<PrerenderedLiveView, LiveViewError> {
+        self.prerender_virtualdom(move || VirtualDom::new_with_props(app, props))
+            .await
+    }
+
+    /// Render a VirtualDom to HTML so the page has content before the websocket connects. See [`LiveViewPool::prerender`].
+    pub async fn prerender_virtualdom<F: FnOnce() -> VirtualDom + Send + 'static>(
+        &self,
+        make_app: F,
+    ) -> Result<PrerenderedLiveView, LiveViewError> {
+        let Some(monitor) = self.metrics.start_session(self.max_sessions) else {
+            return Err(LiveViewError::SessionLimitReached);
+        };
+
+        let (token, mut sockets) = self.register_session();
+        let (html_tx, html_rx) = tokio::sync::oneshot::channel();
+        let sessions = self.sessions.clone();
+        let options = self.options;
+        let grace_period = self.reconnect_grace_period;
+        let session_token = token.clone();
+        // The session outlives this request, so the task is not awaited
+        drop(self.pool.spawn_pinned(move || async move {
+            let mut session = LiveViewSession::new(make_app(), monitor, options);
+            let _ = html_tx.send(session.prerender());
+
+            // Wait for the page to connect
+            let first_connection = tokio::time::timeout(PRERENDER_CONNECT_TIMEOUT, sockets.recv());
+            if let Ok(Some(mut connection)) = first_connection.await {
+                let result = match grace_period {
+                    Some(grace_period) => {
+                        session
+                            .run_resumable(connection, &token, sockets, grace_period)
+                            .await
+                    }
+                    None => {
+                        sessions.lock().unwrap().remove(&token);
+                        match session.start_connection(&mut connection, None).await {
+                            Ok(()) => session
+                                .run_connection(&mut connection.ws, None)
+                                .await
+                                .map(|_| ()),
+                            Err(err) => Err(err),
+                        }
+                    }
+                };
+                if let Err(err) = result {
+                    tracing::trace!("Prerendered liveview session failed: {err}");
+                }
+            }
+            sessions.lock().unwrap().remove(&token);
+        }));
+
+        let html = html_rx.await.map_err(|_| LiveViewError::SendingFailed)?;
+        Ok(PrerenderedLiveView {
+            html,
+            session: session_token,
+        })
+    }
+
+    /// Create a token for a new session and register the session so clients can connect to it with the token
+    fn register_session(&self) -> (String, UnboundedReceiver<Connection>) {
+        let token = new_session_token();
+        let (session_tx, session_rx) = tokio::sync::mpsc::unbounded_channel();
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(token.clone(), session_tx);
+        (token, session_rx)
+    }
+}
+
+/// How long a prerendered session waits for the page to connect
+const PRERENDER_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Tell a client that the pool is full and close the connection
+async fn reject(mut ws: BoxedLiveViewSocket) -> Result<(), LiveViewError> {
+    tracing::warn!("Rejecting liveview client because the maximum number of sessions is running");
+    let update = ClientUpdate::Rejected("too many sessions".to_string());
+    ws.send(text_frame(&serde_json::to_string(&update).unwrap()))
+        .await?;
+    _ = ws.close().await;
+    Err(LiveViewError::SessionLimitReached)
+}
+
+/// Create a random token that identifies a session
+fn new_session_token() -> String {
+    use rand::distributions::{Alphanumeric, DistString};
+    Alphanumeric.sample_string(&mut rand::thread_rng(), 32)
 }
 
 /// A LiveViewSocket is a Sink and Stream of Strings that Dioxus uses to communicate with the client
@@ -109,6 +394,121 @@ impl<S> LiveViewSocket for S where
 {
 }
 
+/// The object safe part of a [`LiveViewSocket`]. The `*Ext` supertraits of [`LiveViewSocket`] can't be used as a trait object
+trait DynLiveViewSocket:
+    Sink<Vec<u8>, Error = LiveViewError> + Stream<Item = Result<Vec<u8>, LiveViewError>> + Send
+{
+}
+
+impl<S> DynLiveViewSocket for S where
+    S: Sink<Vec<u8>, Error = LiveViewError> + Stream<Item = Result<Vec<u8>, LiveViewError>> + Send
+{
+}
+
+/// A type erased socket that can be handed to a session on another thread
+type BoxedLiveViewSocket = Pin<Box<dyn DynLiveViewSocket>>;
+
+/// A socket that connected to the pool and the event encodings the client supports
+struct Connection {
+    ws: BoxedLiveViewSocket,
+    encodings: Vec<String>,
+    /// If the client has the HTML of a prerendered session and wants to attach to it
+    hydrate: bool,
+}
+
+/// How the client encodes the messages it sends to the server
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EventEncoding {
+    Json,
+    Cbor,
+}
+
+impl EventEncoding {
+    /// Decode a message from the client.
+    ///
+    /// Messages that start with `{` are always JSON. A client may send JSON before it knows that the server accepted the binary encoding.
+    fn decode(self, message: &[u8]) -> Option<IpcMessage> {
+        match self {
+            EventEncoding::Cbor if message.first() != Some(&b'{') => {
+                ciborium::from_reader(message).ok()
+            }
+            _ => serde_json::from_str(&String::from_utf8_lossy(message)).ok(),
+        }
+    }
+}
+
+/// The settings of a pool that apply to each session
+#[derive(Clone, Copy, Debug)]
+struct SessionOptions {
+    /// If the client may send events encoded as CBOR
+    binary_events: bool,
+    /// How long the session may go without events before it ends
+    idle_timeout: Option<Duration>,
+    /// The largest file the session reads from the client
+    max_upload_size: u64,
+}
+
+impl Default for SessionOptions {
+    fn default() -> Self {
+        Self {
+            binary_events: true,
+            idle_timeout: None,
+            max_upload_size: crate::file_engine::DEFAULT_MAX_UPLOAD_SIZE,
+        }
+    }
+}
+
+/// Why a connection to a client ended
+enum ConnectionEnd {
+    /// The client disconnected
+    Disconnected,
+    /// The client reconnected to the session with a new socket
+    Replaced(Connection),
+    /// The client did not send any events for longer than the idle timeout
+    Idle,
+}
+
+/// The parameters the client sends when it first connects
+#[derive(serde::Deserialize, Debug, Default)]
+struct InitializeParams {
+    /// The encodings the client can use to send events, in order of preference
+    #[serde(default)]
+    encodings: Vec<String>,
+}
+
+/// An event from the client with the files that were selected or dropped with it
+#[derive(serde::Deserialize, Debug)]
+struct LiveviewEvent {
+    #[serde(flatten)]
+    event: HtmlEvent,
+    /// The files stay in the browser. The session reads them in chunks when the app asks for their contents
+    #[serde(default)]
+    files: Vec<FileInfo>,
+}
+
+// desktop uses this wrapper struct thing around the actual event itself
+// this is sorta driven by tao/wry
+#[derive(serde::Deserialize, Debug)]
+#[serde(tag = "method", content = "params")]
+enum IpcMessage {
+    #[serde(rename = "user_event")]
+    Event(LiveviewEvent),
+    #[serde(rename = "query")]
+    Query(QueryResult),
+    #[serde(rename = "initialize")]
+    Initialize(InitializeParams),
+    #[serde(rename = "resume")]
+    Resume {
+        token: String,
+        #[serde(default)]
+        encodings: Vec<String>,
+        #[serde(default)]
+        hydrate: bool,
+    },
+    #[serde(rename = "file_chunk")]
+    FileChunk(FileChunk),
+}
+
 /// The primary event loop for the VirtualDom waiting for user input
 ///
 /// This function makes it easy to integrate Dioxus LiveView with any socket-based framework.
@@ -116,136 +516,399 @@ impl<S> LiveViewSocket for S where
 /// As long as your framework can provide a Sink and Stream of Bytes, you can use this function.
 ///
 /// You might need to transform the error types of the web backend into the LiveView error type.
-pub async fn run(mut vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), LiveViewError> {
+pub async fn run(vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), LiveViewError> {
+    LiveViewSession::new(
+        vdom,
+        SessionMonitor::standalone(),
+        SessionOptions::default(),
+    )
+    .run(ws)
+    .await
+}
+
+/// A VirtualDom and the state needed to send its edits to a client. The session outlives a single connection if the client can reconnect.
+struct LiveViewSession {
+    vdom: VirtualDom,
+    edit_channel: Channel,
+    templates: FxHashMap<String, u16>,
+    max_template_count: u16,
+    query_engine: QueryEngine,
+    query_rx: UnboundedReceiver<String>,
+    /// The file engines of events send requests for file chunks here
+    file_tx: UnboundedSender<FileRequest>,
+    file_rx: UnboundedReceiver<FileRequest>,
+    /// File chunks the client was asked for but did not send yet
+    pending_reads: Slab<oneshot::Sender<Option<Vec<u8>>>>,
     #[cfg(all(feature = "hot-reload", debug_assertions))]
-    let mut hot_reload_rx = {
-        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-        dioxus_hot_reload::connect(move |template| {
-            let _ = tx.send(template);
-        });
-        rx
-    };
+    hot_reload_rx: UnboundedReceiver<dioxus_hot_reload::HotReloadMsg>,
+    /// If the VirtualDom was already built and sent to a client
+    built: bool,
+    /// If the VirtualDom was rendered to HTML that no client attached to yet
+    prerendered: bool,
+    options: SessionOptions,
+    /// The encoding the connected client uses for events
+    encoding: EventEncoding,
+    monitor: SessionMonitor,
+}
 
-    let mut templates: FxHashMap<String, u16> = Default::default();
-    let mut max_template_count = 0;
-
-    // Create the a proxy for query engine
-    let (query_tx, mut query_rx) = tokio::sync::mpsc::unbounded_channel();
-    let query_engine = QueryEngine::new(query_tx);
-    vdom.base_scope().provide_context(query_engine.clone());
-    init_eval(vdom.base_scope());
-
-    // pin the futures so we can use select!
-    pin_mut!(ws);
-
-    let mut edit_channel = Channel::default();
-    if let Some(edits) = {
-        let mutations = vdom.rebuild();
-        apply_edits(
-            mutations,
-            &mut edit_channel,
-            &mut templates,
-            &mut max_template_count,
-        )
-    } {
-        // send the initial render to the client
-        ws.send(edits).await?;
-    }
-
-    // desktop uses this wrapper struct thing around the actual event itself
-    // this is sorta driven by tao/wry
-    #[derive(serde::Deserialize, Debug)]
-    #[serde(tag = "method", content = "params")]
-    enum IpcMessage {
-        #[serde(rename = "user_event")]
-        Event(HtmlEvent),
-        #[serde(rename = "query")]
-        Query(QueryResult),
-    }
-
-    loop {
+impl LiveViewSession {
+    fn new(vdom: VirtualDom, monitor: SessionMonitor, options: SessionOptions) -> Self {
         #[cfg(all(feature = "hot-reload", debug_assertions))]
-        let hot_reload_wait = hot_reload_rx.recv();
-        #[cfg(not(all(feature = "hot-reload", debug_assertions)))]
-        let hot_reload_wait: std::future::Pending<Option<()>> = std::future::pending();
-
-        tokio::select! {
-            // poll any futures or suspense
-            _ = vdom.wait_for_work() => {}
-
-            evt = ws.next() => {
-                match evt.as_ref().map(|o| o.as_deref()) {
-                    // respond with a pong every ping to keep the websocket alive
-                    Some(Ok(b"__ping__")) => {
-                        ws.send(text_frame("__pong__")).await?;
-                    }
-                    Some(Ok(evt)) => {
-                        if let Ok(message) = serde_json::from_str::<IpcMessage>(&String::from_utf8_lossy(evt)) {
-                            match message {
-                                IpcMessage::Event(evt) => {
-                                    // Intercept the mounted event and insert a custom element type
-                                    if let EventData::Mounted = &evt.data {
-                                        let element = LiveviewElement::new(evt.element, query_engine.clone());
-                                        vdom.handle_event(
-                                            &evt.name,
-                                            Rc::new(PlatformEventData::new(Box::new(MountedData::new(element)))),
-                                            evt.element,
-                                            evt.bubbles,
-                                        );
-                                    } else {
-                                        vdom.handle_event(
-                                            &evt.name,
-                                            evt.data.into_any(),
-                                            evt.element,
-                                            evt.bubbles,
-                                        );
+        let hot_reload_rx = {
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            dioxus_hot_reload::connect(move |template| {
+                let _ = tx.send(template);
+            });
+            rx
+        };
+
+        // Create the a proxy for query engine
+        let (query_tx, query_rx) = tokio::sync::mpsc::unbounded_channel();
+        let query_engine = QueryEngine::new(query_tx);
+        vdom.base_scope().provide_context(query_engine.clone());
+        init_eval(vdom.base_scope());
+
+        let (file_tx, file_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        Self {
+            vdom,
+            edit_channel: Channel::default(),
+            templates: Default::default(),
+            max_template_count: 0,
+            query_engine,
+            query_rx,
+            file_tx,
+            file_rx,
+            pending_reads: Slab::new(),
+            #[cfg(all(feature = "hot-reload", debug_assertions))]
+            hot_reload_rx,
+            built: false,
+            prerendered: false,
+            options,
+            encoding: EventEncoding::Json,
+            monitor,
+        }
+    }
+
+    /// Pick the encoding the client will use to send events from the encodings it supports and tell the client about it
+    async fn negotiate_encoding(
+        &mut self,
+        ws: &mut BoxedLiveViewSocket,
+        encodings: &[String],
+    ) -> Result<(), LiveViewError> {
+        self.encoding = EventEncoding::Json;
+        if self.options.binary_events && encodings.iter().any(|encoding| encoding == "cbor") {
+            self.encoding = EventEncoding::Cbor;
+            let update = ClientUpdate::Encoding("cbor".to_string());
+            ws.send(text_frame(&serde_json::to_string(&update).unwrap()))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Build the VirtualDom and render it to HTML with hydration ids
+    fn prerender(&mut self) -> String {
+        let start = Instant::now();
+        let _ = self.vdom.rebuild();
+        self.built = true;
+        self.prerendered = true;
+        let mut renderer = dioxus_ssr::Renderer::new();
+        renderer.pre_render = true;
+        let html = renderer.render(&self.vdom);
+        self.rendered(start, None);
+        html
+    }
+
+    /// Prepare a new connection: tell the client its session token, negotiate the event encoding and send the current state of the VirtualDom
+    async fn start_connection(
+        &mut self,
+        connection: &mut Connection,
+        token: Option<&str>,
+    ) -> Result<(), LiveViewError> {
+        // The last client will never answer the file reads it was asked for
+        self.pending_reads.clear();
+
+        let ws = &mut connection.ws;
+        if let Some(token) = token {
+            let update = ClientUpdate::Session(token.to_string());
+            ws.send(text_frame(&serde_json::to_string(&update).unwrap()))
+                .await?;
+        }
+        self.negotiate_encoding(ws, &connection.encodings).await?;
+        if std::mem::take(&mut self.prerendered) && connection.hydrate {
+            self.send_hydration(ws).await
+        } else {
+            self.send_rebuild(ws).await
+        }
+    }
+
+    /// Attach a client to the prerendered HTML of the VirtualDom
+    async fn send_hydration(&mut self, ws: &mut BoxedLiveViewSocket) -> Result<(), LiveViewError> {
+        let hydration = HydrationIds::new(&self.vdom);
+        let update = ClientUpdate::Hydrate(hydration.ids);
+        ws.send(text_frame(&serde_json::to_string(&update).unwrap()))
+            .await?;
+
+        // The client only has the nodes, so it still needs every template to create new nodes
+        let templates = Mutations {
+            templates: self.vdom.replay().templates,
+            ..Default::default()
+        };
+        if let Some(edits) = apply_edits(
+            templates,
+            &mut self.edit_channel,
+            &mut self.templates,
+            &mut self.max_template_count,
+        ) {
+            ws.send(edits).await?;
+        }
+
+        for id in hydration.mounted {
+            let element = LiveviewElement::new(id, self.query_engine.clone());
+            self.vdom.handle_event(
+                "mounted",
+                Rc::new(PlatformEventData::new(Box::new(MountedData::new(element)))),
+                id,
+                false,
+            );
+        }
+        Ok(())
+    }
+
+    /// Send every node in the VirtualDom to a client that has no state
+    async fn send_rebuild(&mut self, ws: &mut BoxedLiveViewSocket) -> Result<(), LiveViewError> {
+        let start = Instant::now();
+        let edits = if self.built {
+            // The client starts from scratch, so forget about the templates and strings we sent to the last client
+            self.edit_channel = Channel::default();
+            self.templates.clear();
+            self.max_template_count = 0;
+            apply_edits(
+                self.vdom.replay(),
+                &mut self.edit_channel,
+                &mut self.templates,
+                &mut self.max_template_count,
+            )
+        } else {
+            self.built = true;
+            apply_edits(
+                self.vdom.rebuild(),
+                &mut self.edit_channel,
+                &mut self.templates,
+                &mut self.max_template_count,
+            )
+        };
+        self.rendered(start, edits.as_ref());
+        if let Some(edits) = edits {
+            // send the initial render to the client
+            ws.send(edits).await?;
+        }
+        Ok(())
+    }
+
+    /// Run the session until the client disconnects
+    async fn run(&mut self, ws: impl LiveViewSocket) -> Result<(), LiveViewError> {
+        let mut ws: BoxedLiveViewSocket = Box::pin(ws);
+        self.send_rebuild(&mut ws).await?;
+        self.run_connection(&mut ws, None).await.map(|_| ())
+    }
+
+    /// Record the time and memory a render used and the size of the edits it created
+    fn rendered(&mut self, start: Instant, edits: Option<&Vec<u8>>) {
+        self.monitor.rendered(
+            start.elapsed(),
+            self.vdom.allocated_bytes(),
+            edits.map(|edits| edits.len()).unwrap_or_default(),
+        );
+    }
+
+    /// Run the session until the client disconnects for longer than the grace period
+    async fn run_resumable(
+        &mut self,
+        mut connection: Connection,
+        token: &str,
+        mut sockets: UnboundedReceiver<Connection>,
+        grace_period: Duration,
+    ) -> Result<(), LiveViewError> {
+        loop {
+            self.monitor.set_connected(true);
+            let run = async {
+                self.start_connection(&mut connection, Some(token)).await?;
+                self.run_connection(&mut connection.ws, Some(&mut sockets))
+                    .await
+            };
+            match run.await {
+                // The client reconnected while the old connection was still open
+                Ok(ConnectionEnd::Replaced(new_connection)) => {
+                    connection = new_connection;
+                    continue;
+                }
+                Ok(ConnectionEnd::Disconnected) => tracing::trace!("Liveview client disconnected"),
+                Ok(ConnectionEnd::Idle) => return Ok(()),
+                Err(err) => tracing::trace!("Liveview connection failed: {err}"),
+            }
+            self.monitor.set_connected(false);
+
+            // Wait for the client to reconnect
+            match tokio::time::timeout(grace_period, sockets.recv()).await {
+                Ok(Some(new_connection)) => connection = new_connection,
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Handle events from a connected client until it disconnects, reconnects with a new socket, or goes idle
+    async fn run_connection(
+        &mut self,
+        ws: &mut BoxedLiveViewSocket,
+        mut sockets: Option<&mut UnboundedReceiver<Connection>>,
+    ) -> Result<ConnectionEnd, LiveViewError> {
+        loop {
+            let idle_deadline = self
+                .options
+                .idle_timeout
+                .map(|timeout| self.monitor.stats.last_active + timeout);
+            let idle = async move {
+                match idle_deadline {
+                    Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            #[cfg(all(feature = "hot-reload", debug_assertions))]
+            let hot_reload_wait = self.hot_reload_rx.recv();
+            #[cfg(not(all(feature = "hot-reload", debug_assertions)))]
+            let hot_reload_wait: std::future::Pending<Option<()>> = std::future::pending();
+
+            let new_socket = async {
+                match sockets.as_mut() {
+                    Some(sockets) => sockets.recv().await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            tokio::select! {
+                // poll any futures or suspense
+                _ = self.vdom.wait_for_work() => {}
+
+                evt = ws.next() => {
+                    match evt.as_ref().map(|o| o.as_deref()) {
+                        // respond with a pong every ping to keep the websocket alive
+                        Some(Ok(b"__ping__")) => {
+                            ws.send(text_frame("__pong__")).await?;
+                        }
+                        Some(Ok(evt)) => {
+                            if let Some(message) = self.encoding.decode(evt) {
+                                let start = Instant::now();
+                                match message {
+                                    IpcMessage::Event(LiveviewEvent { event: evt, files }) => {
+                                        // Intercept the mounted event and insert a custom element type
+                                        if let EventData::Mounted = &evt.data {
+                                            let element = LiveviewElement::new(evt.element, self.query_engine.clone());
+                                            self.vdom.handle_event(
+                                                &evt.name,
+                                                Rc::new(PlatformEventData::new(Box::new(MountedData::new(element)))),
+                                                evt.element,
+                                                evt.bubbles,
+                                            );
+                                        } else {
+                                            let data = if files.is_empty() {
+                                                evt.data.into_any()
+                                            } else {
+                                                let files = LiveviewFileEngine::new(files, self.file_tx.clone(), self.options.max_upload_size);
+                                                event_with_files(evt.data, Arc::new(files))
+                                            };
+                                            self.vdom.handle_event(
+                                                &evt.name,
+                                                data,
+                                                evt.element,
+                                                evt.bubbles,
+                                            );
+                                        }
+                                    }
+                                    IpcMessage::Query(result) => {
+                                        self.query_engine.send(result);
+                                    },
+                                    IpcMessage::FileChunk(chunk) => {
+                                        if let Some(reply) = self.pending_reads.try_remove(chunk.request) {
+                                            _ = reply.send(chunk.data.map(|data| data.into_vec()));
+                                        }
+                                    }
+                                    IpcMessage::Initialize(params) => {
+                                        self.negotiate_encoding(ws, &params.encodings).await?;
+                                        continue;
                                     }
+                                    // The session was already chosen when the client connected
+                                    IpcMessage::Resume { .. } => continue,
                                 }
-                                IpcMessage::Query(result) => {
-                                    query_engine.send(result);
-                                },
+                                self.monitor.event_handled(start.elapsed());
                             }
                         }
+                        // log this I guess? when would we get an error here?
+                        Some(Err(_e)) => {}
+                        None => return Ok(ConnectionEnd::Disconnected),
                     }
-                    // log this I guess? when would we get an error here?
-                    Some(Err(_e)) => {}
-                    None => return Ok(()),
                 }
-            }
 
-            // handle any new queries
-            Some(query) = query_rx.recv() => {
-                ws.send(text_frame(&serde_json::to_string(&ClientUpdate::Query(query)).unwrap())).await?;
-            }
+                // handle any new queries
+                Some(query) = self.query_rx.recv() => {
+                    ws.send(text_frame(&serde_json::to_string(&ClientUpdate::Query(query)).unwrap())).await?;
+                }
 
-            Some(msg) = hot_reload_wait => {
-                #[cfg(all(feature = "hot-reload", debug_assertions))]
-                match msg{
-                    dioxus_hot_reload::HotReloadMsg::UpdateTemplate(new_template) => {
-                        vdom.replace_template(new_template);
+                // ask the client for the file chunks the app wants to read
+                Some(request) = self.file_rx.recv() => {
+                    let update = ClientUpdate::ReadFile(ReadFileRequest {
+                        request: self.pending_reads.insert(request.reply),
+                        file: request.file,
+                        offset: request.offset,
+                        length: request.length,
+                    });
+                    ws.send(text_frame(&serde_json::to_string(&update).unwrap())).await?;
+                }
+
+                Some(new_connection) = new_socket => {
+                    return Ok(ConnectionEnd::Replaced(new_connection));
+                }
+
+                _ = idle => {
+                    tracing::trace!("Ending idle liveview session");
+                    let update = ClientUpdate::Closed("idle".to_string());
+                    ws.send(text_frame(&serde_json::to_string(&update).unwrap())).await?;
+                    _ = ws.close().await;
+                    return Ok(ConnectionEnd::Idle);
+                }
+
+                Some(msg) = hot_reload_wait => {
+                    #[cfg(all(feature = "hot-reload", debug_assertions))]
+                    match msg{
+                        dioxus_hot_reload::HotReloadMsg::UpdateTemplate(new_template) => {
+                            self.vdom.replace_template(new_template);
+                        }
+                        dioxus_hot_reload::HotReloadMsg::Shutdown => {
+                            std::process::exit(0);
+                        },
                     }
-                    dioxus_hot_reload::HotReloadMsg::Shutdown => {
-                        std::process::exit(0);
-                    },
+                    #[cfg(not(all(feature = "hot-reload", debug_assertions)))]
+                    let () = msg;
                 }
-                #[cfg(not(all(feature = "hot-reload", debug_assertions)))]
-                let () = msg;
             }
-        }
 
-        let edits = vdom
-            .render_with_deadline(tokio::time::sleep(Duration::from_millis(10)))
-            .await;
+            let start = Instant::now();
+            let edits = self
+                .vdom
+                .render_with_deadline(tokio::time::sleep(Duration::from_millis(10)))
+                .await;
 
-        if let Some(edits) = {
-            apply_edits(
+            let edits = apply_edits(
                 edits,
-                &mut edit_channel,
-                &mut templates,
-                &mut max_template_count,
-            )
-        } {
-            ws.send(edits).await?;
+                &mut self.edit_channel,
+                &mut self.templates,
+                &mut self.max_template_count,
+            );
+            self.rendered(start, edits.as_ref());
+            if let Some(edits) = edits {
+                ws.send(edits).await?;
+            }
         }
     }
 }
@@ -389,4 +1052,488 @@ fn apply_edits(
 enum ClientUpdate {
     #[serde(rename = "query")]
     Query(String),
+    /// The token the client can use to resume this session if it disconnects
+    #[serde(rename = "session")]
+    Session(String),
+    /// The encoding the client should use to send events
+    #[serde(rename = "encoding")]
+    Encoding(String),
+    /// The server is full and did not start a session for the client
+    #[serde(rename = "rejected")]
+    Rejected(String),
+    /// The server ended the session, with the reason why
+    #[serde(rename = "closed")]
+    Closed(String),
+    /// The element ids of the prerendered nodes, in the order of their hydration ids
+    #[serde(rename = "hydrate")]
+    Hydrate(Vec<u32>),
+    /// Ask the client for part of a file it sent with an event
+    #[serde(rename = "read_file")]
+    ReadFile(ReadFileRequest),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LiveViewError, LiveViewMetrics, LiveViewPool, SessionStats};
+    use dioxus::prelude::*;
+    use dioxus_core::ElementId;
+    use dioxus_html::{EventData, HtmlEvent, SerializedMouseData};
+    use futures_util::{Sink, Stream};
+    use serde_json::{json, Value};
+    use std::{
+        pin::Pin,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Poll},
+        time::Duration,
+    };
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+    /// The server end of an in-memory websocket
+    struct TestSocket {
+        rx: UnboundedReceiver<Vec<u8>>,
+        tx: UnboundedSender<Vec<u8>>,
+    }
+
+    impl Stream for TestSocket {
+        type Item = Result<Vec<u8>, LiveViewError>;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            self.rx.poll_recv(cx).map(|message| message.map(Ok))
+        }
+    }
+
+    impl Sink<Vec<u8>> for TestSocket {
+        type Error = LiveViewError;
+
+        fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(self: Pin<&mut Self>, item: Vec<u8>) -> Result<(), Self::Error> {
+            self.tx.send(item).map_err(|_| LiveViewError::SendingFailed)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    /// The browser end of an in-memory websocket
+    struct TestClient {
+        tx: UnboundedSender<Vec<u8>>,
+        rx: UnboundedReceiver<Vec<u8>>,
+    }
+
+    impl TestClient {
+        fn connect() -> (TestSocket, TestClient) {
+            let (client_tx, server_rx) = unbounded_channel();
+            let (server_tx, client_rx) = unbounded_channel();
+            (
+                TestSocket {
+                    rx: server_rx,
+                    tx: server_tx,
+                },
+                TestClient {
+                    tx: client_tx,
+                    rx: client_rx,
+                },
+            )
+        }
+
+        fn send_json(&self, message: Value) {
+            self.tx.send(message.to_string().into_bytes()).unwrap();
+        }
+
+        fn send_cbor(&self, message: Value) {
+            let mut bytes = Vec::new();
+            ciborium::into_writer(&message, &mut bytes).unwrap();
+            self.tx.send(bytes).unwrap();
+        }
+
+        async fn recv(&mut self) -> Option<Vec<u8>> {
+            tokio::time::timeout(Duration::from_secs(5), self.rx.recv())
+                .await
+                .expect("the server did not send a message")
+        }
+
+        /// Receive a json update from the server
+        async fn update(&mut self) -> Value {
+            let frame = self.recv().await.expect("the server closed the socket");
+            assert_eq!(frame[0], 0, "expected a text frame");
+            serde_json::from_slice(&frame[1..]).unwrap()
+        }
+
+        /// Receive a binary frame of edits from the server
+        async fn edits(&mut self) -> Vec<u8> {
+            let frame = self.recv().await.expect("the server closed the socket");
+            assert_eq!(frame[0], 1, "expected a binary frame");
+            frame
+        }
+    }
+
+    fn click(element: usize) -> Value {
+        let event = HtmlEvent {
+            element: ElementId(element),
+            name: "click".to_string(),
+            bubbles: true,
+            data: EventData::Mouse(SerializedMouseData::default()),
+        };
+        json!({ "method": "user_event", "params": event })
+    }
+
+    fn initialize(encodings: &[&str]) -> Value {
+        json!({ "method": "initialize", "params": { "encodings": encodings } })
+    }
+
+    fn counter(cx: Scope) -> Element {
+        let count = use_state(cx, || 0);
+        render! {
+            button { onclick: move |_| count.modify(|count| count + 1), "{count}" }
+        }
+    }
+
+    fn launch(
+        pool: &LiveViewPool,
+        app: fn(Scope) -> Element,
+    ) -> (
+        TestClient,
+        tokio::task::JoinHandle<Result<(), LiveViewError>>,
+    ) {
+        let (socket, client) = TestClient::connect();
+        let pool = pool.clone();
+        let session = tokio::spawn(async move { pool.launch(socket, app).await });
+        (client, session)
+    }
+
+    #[tokio::test]
+    async fn negotiates_binary_events() {
+        let pool = LiveViewPool::new().workers(1);
+        let (mut client, session) = launch(&pool, counter);
+        client.send_json(initialize(&["cbor", "json"]));
+        assert_eq!(
+            client.update().await,
+            json!({ "type": "encoding", "data": "cbor" })
+        );
+        client.edits().await;
+
+        client.send_cbor(click(1));
+        client.edits().await;
+
+        // JSON messages are still accepted after switching to CBOR
+        client.send_json(click(1));
+        client.edits().await;
+        assert_eq!(pool.stats().events, 2);
+
+        drop(client);
+        session.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_json_events() {
+        let pool = LiveViewPool::new().workers(1).binary_events(false);
+        let (mut client, session) = launch(&pool, counter);
+        client.send_json(initialize(&["cbor", "json"]));
+        // The server never announces an encoding, so the client keeps sending JSON
+        client.edits().await;
+        client.send_json(click(1));
+        client.edits().await;
+
+        // CBOR is ignored
+        client.send_cbor(click(1));
+        client.send_json(json!({ "method": "initialize", "params": {} }));
+        client.send_json(click(1));
+        client.edits().await;
+        assert_eq!(pool.stats().events, 2);
+
+        drop(client);
+        session.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn sends_throttle_settings_to_the_client() {
+        fn app(cx: Scope) -> Element {
+            render! {
+                div { throttle: "onmousemove:50", onmousemove: |_| {} }
+            }
+        }
+
+        let pool = LiveViewPool::new().workers(1);
+        let (mut client, session) = launch(&pool, app);
+        client.send_json(initialize(&[]));
+        let edits = String::from_utf8_lossy(&client.edits().await).into_owned();
+        assert!(edits.contains("dioxus-throttle"));
+        assert!(edits.contains("onmousemove:50"));
+
+        drop(client);
+        session.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_sessions_over_the_limit() {
+        let pool = LiveViewPool::new().workers(1).max_sessions(1);
+        let (mut first, first_session) = launch(&pool, counter);
+        first.send_json(initialize(&[]));
+        first.edits().await;
+
+        let (mut second, second_session) = launch(&pool, counter);
+        second.send_json(initialize(&[]));
+        assert_eq!(
+            second.update().await,
+            json!({ "type": "rejected", "data": "too many sessions" })
+        );
+        assert!(matches!(
+            second_session.await.unwrap(),
+            Err(LiveViewError::SessionLimitReached)
+        ));
+        assert!(matches!(
+            pool.prerender(counter).await,
+            Err(LiveViewError::SessionLimitReached)
+        ));
+
+        // Once the first session ends, there is room for a new one
+        drop(first);
+        first_session.await.unwrap().unwrap();
+        let (mut third, third_session) = launch(&pool, counter);
+        third.send_json(initialize(&[]));
+        third.edits().await;
+
+        let stats = pool.stats();
+        assert_eq!(stats.active_sessions, 1);
+        assert_eq!(stats.total_sessions, 2);
+        assert_eq!(stats.rejected_sessions, 2);
+
+        drop(third);
+        third_session.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn ends_idle_sessions() {
+        let pool = LiveViewPool::new()
+            .workers(1)
+            .idle_timeout(Duration::from_millis(200));
+        let (mut client, session) = launch(&pool, counter);
+        client.send_json(initialize(&[]));
+        client.edits().await;
+
+        // Events keep the session alive
+        for _ in 0..3 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            client.send_json(click(1));
+            client.edits().await;
+        }
+
+        assert_eq!(
+            client.update().await,
+            json!({ "type": "closed", "data": "idle" })
+        );
+        session.await.unwrap().unwrap();
+        assert_eq!(pool.stats().active_sessions, 0);
+        assert!(pool.sessions().is_empty());
+    }
+
+    #[derive(Default)]
+    struct CountingMetrics {
+        started: AtomicUsize,
+        ended: AtomicUsize,
+        rejected: AtomicUsize,
+        events: AtomicUsize,
+        mutation_bytes: AtomicUsize,
+    }
+
+    impl LiveViewMetrics for Arc<CountingMetrics> {
+        fn session_started(&self, _: &SessionStats) {
+            self.started.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn session_ended(&self, _: &SessionStats) {
+            self.ended.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn session_rejected(&self) {
+            self.rejected.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn event_handled(&self, _: &SessionStats) {
+            self.events.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn mutations_sent(&self, _: &SessionStats, bytes: usize) {
+            self.mutation_bytes.fetch_add(bytes, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_metrics() {
+        let metrics = Arc::new(CountingMetrics::default());
+        let pool = LiveViewPool::new()
+            .workers(1)
+            .max_sessions(1)
+            .metrics(metrics.clone());
+        let (mut client, session) = launch(&pool, counter);
+        client.send_json(initialize(&[]));
+        let mut bytes = client.edits().await.len();
+        client.send_json(click(1));
+        bytes += client.edits().await.len();
+
+        let (mut rejected, rejected_session) = launch(&pool, counter);
+        rejected.send_json(initialize(&[]));
+        rejected.update().await;
+        assert!(rejected_session.await.unwrap().is_err());
+
+        let sessions = pool.sessions();
+        assert_eq!(sessions.len(), 1);
+        assert!(sessions[0].connected);
+        assert_eq!(sessions[0].events, 1);
+        assert_eq!(sessions[0].mutation_bytes, bytes as u64);
+        assert!(sessions[0].allocated_bytes > 0);
+
+        drop(client);
+        session.await.unwrap().unwrap();
+        assert_eq!(metrics.started.load(Ordering::SeqCst), 1);
+        assert_eq!(metrics.ended.load(Ordering::SeqCst), 1);
+        assert_eq!(metrics.rejected.load(Ordering::SeqCst), 1);
+        assert_eq!(metrics.events.load(Ordering::SeqCst), 1);
+        assert_eq!(metrics.mutation_bytes.load(Ordering::SeqCst), bytes);
+
+        let stats = pool.stats();
+        assert_eq!(stats.active_sessions, 0);
+        assert_eq!(stats.total_sessions, 1);
+        assert_eq!(stats.rejected_sessions, 1);
+        assert_eq!(stats.events, 1);
+        assert_eq!(stats.mutation_bytes, bytes as u64);
+        assert!(pool.sessions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn prerendered_sessions_hydrate() {
+        let pool = LiveViewPool::new().workers(1);
+        let prerendered = pool.prerender(counter).await.unwrap();
+        assert!(prerendered.html.contains("data-node-hydration"));
+        assert!(prerendered.html.contains(">0<"));
+        assert!(prerendered
+            .root()
+            .contains(&format!(r#"data-dioxus-session="{}""#, prerendered.session)));
+
+        // The page attaches to the prerendered nodes instead of rebuilding them
+        let (socket, mut client) = TestClient::connect();
+        client.send_json(json!({
+            "method": "resume",
+            "params": { "token": prerendered.session, "hydrate": true }
+        }));
+        pool.launch(socket, counter).await.unwrap();
+        let hydrate = client.update().await;
+        assert_eq!(hydrate["type"], "hydrate");
+        assert!(!hydrate["data"].as_array().unwrap().is_empty());
+        // The templates the client needs to create new nodes
+        client.edits().await;
+
+        client.send_json(click(1));
+        client.edits().await;
+        assert_eq!(pool.stats().events, 1);
+
+        // A page that lost the prerendered HTML gets a full rebuild
+        let prerendered = pool.prerender(counter).await.unwrap();
+        let (socket, mut client) = TestClient::connect();
+        client.send_json(json!({
+            "method": "resume",
+            "params": { "token": prerendered.session }
+        }));
+        pool.launch(socket, counter).await.unwrap();
+        client.edits().await;
+        assert_eq!(pool.stats().active_sessions, 2);
+    }
+
+    fn labeled_counter(cx: Scope) -> Element {
+        let count = use_state(cx, || 0);
+        render! {
+            button { onclick: move |_| count.modify(|count| count + 1), "count is {count}" }
+        }
+    }
+
+    /// Launch a session that can be resumed, and return the token the server sent the client
+    async fn launch_resumable(
+        pool: &LiveViewPool,
+    ) -> (
+        TestClient,
+        String,
+        tokio::task::JoinHandle<Result<(), LiveViewError>>,
+    ) {
+        let (mut client, session) = launch(pool, labeled_counter);
+        client.send_json(initialize(&[]));
+        let update = client.update().await;
+        assert_eq!(update["type"], "session");
+        let token = update["data"].as_str().unwrap().to_string();
+        let edits = String::from_utf8_lossy(&client.edits().await).into_owned();
+        assert!(edits.contains("count is 0"));
+        (client, token, session)
+    }
+
+    /// Connect a new socket that asks to resume a session
+    async fn resume(pool: &LiveViewPool, token: &str) -> TestClient {
+        let (socket, client) = TestClient::connect();
+        client.send_json(json!({ "method": "resume", "params": { "token": token } }));
+        let pool = pool.clone();
+        tokio::spawn(async move { pool.launch(socket, labeled_counter).await });
+        client
+    }
+
+    #[tokio::test]
+    async fn resumes_sessions_within_the_grace_period() {
+        let pool = LiveViewPool::new()
+            .workers(1)
+            .reconnect_grace_period(Duration::from_secs(5));
+        let (mut client, token, session) = launch_resumable(&pool).await;
+        for _ in 0..2 {
+            client.send_json(click(1));
+            client.edits().await;
+        }
+        drop(client);
+
+        // The new socket picks up the same VirtualDom
+        let mut client = resume(&pool, &token).await;
+        assert_eq!(
+            client.update().await,
+            json!({ "type": "session", "data": token })
+        );
+        let edits = String::from_utf8_lossy(&client.edits().await).into_owned();
+        assert!(edits.contains("count is 2"), "{edits}");
+        client.send_json(click(1));
+        let edits = String::from_utf8_lossy(&client.edits().await).into_owned();
+        assert!(edits.contains("count is 3"), "{edits}");
+
+        let stats = pool.stats();
+        assert_eq!(stats.total_sessions, 1);
+        assert_eq!(stats.events, 3);
+        assert!(!session.is_finished());
+    }
+
+    #[tokio::test]
+    async fn starts_a_new_session_after_the_grace_period() {
+        let pool = LiveViewPool::new()
+            .workers(1)
+            .reconnect_grace_period(Duration::from_millis(100));
+        let (mut client, token, session) = launch_resumable(&pool).await;
+        client.send_json(click(1));
+        client.edits().await;
+        drop(client);
+
+        // The old session ends once the grace period is over
+        session.await.unwrap().unwrap();
+        assert!(pool.sessions().is_empty());
+
+        let mut client = resume(&pool, &token).await;
+        let update = client.update().await;
+        assert_eq!(update["type"], "session");
+        assert_ne!(update["data"], token.as_str());
+        let edits = String::from_utf8_lossy(&client.edits().await).into_owned();
+        assert!(edits.contains("count is 0"), "{edits}");
+        assert_eq!(pool.stats().total_sessions, 2);
+    }
 }
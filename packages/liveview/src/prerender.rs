@@ -0,0 +1,118 @@
+use dioxus_core::{
+    AttributeValue, DynamicNode, ElementId, RenderReturn, ScopeState, TemplateAttribute,
+    TemplateNode, VNode, VirtualDom,
+};
+
+/// The HTML of a liveview app rendered on the server. The client attaches to this HTML when the websocket connects instead of rendering the app from scratch.
+///
+/// Created with [`crate::LiveViewPool::prerender`].
+#[derive(Debug, Clone)]
+pub struct PrerenderedLiveView {
+    /// The HTML of the app with hydration ids
+    pub html: String,
+    /// The token of the session that owns the rendered VirtualDom
+    pub session: String,
+}
+
+impl PrerenderedLiveView {
+    /// The root element of the app with the rendered HTML inside. Use this in your page instead of the empty `<div id="main"></div>`.
+    pub fn root(&self) -> String {
+        format!(
+            r#"<div id="main" data-dioxus-session="{}">{}</div>"#,
+            self.session, self.html
+        )
+    }
+}
+
+/// The nodes of a VirtualDom in the order the SSR renderer writes hydration ids
+#[derive(Default)]
+pub(crate) struct HydrationIds {
+    /// The element id of each hydration id
+    pub ids: Vec<u32>,
+    /// The elements with an `onmounted` listener
+    pub mounted: Vec<ElementId>,
+}
+
+impl HydrationIds {
+    pub(crate) fn new(dom: &VirtualDom) -> Self {
+        let mut ids = Self::default();
+        ids.scope(dom, dom.base_scope());
+        ids
+    }
+
+    fn scope(&mut self, dom: &VirtualDom, scope: &ScopeState) {
+        if let RenderReturn::Ready(vnode) = scope.root_node() {
+            self.vnode(dom, vnode);
+        }
+    }
+
+    fn vnode(&mut self, dom: &VirtualDom, vnode: &VNode) {
+        for (i, root) in vnode.template.get().roots.iter().enumerate() {
+            let root_id = vnode.root_ids.borrow().get(i).copied();
+            self.template_node(dom, vnode, root, root_id);
+        }
+    }
+
+    fn template_node(
+        &mut self,
+        dom: &VirtualDom,
+        vnode: &VNode,
+        node: &TemplateNode,
+        root_id: Option<ElementId>,
+    ) {
+        match node {
+            TemplateNode::Element {
+                children, attrs, ..
+            } => {
+                let mut mounted_id = root_id;
+                for attr in *attrs {
+                    if let TemplateAttribute::Dynamic { id } = attr {
+                        let attribute = &vnode.dynamic_attrs[*id];
+                        let id = attribute.mounted_element();
+                        attribute.attribute_type().for_each(|attribute| {
+                            mounted_id = Some(id);
+                            if let AttributeValue::Listener(_) = attribute.value {
+                                if attribute.name == "onmounted" {
+                                    self.mounted.push(id);
+                                }
+                            }
+                        });
+                    }
+                }
+                if let Some(id) = mounted_id {
+                    self.ids.push(id.0 as u32);
+                }
+                for child in *children {
+                    self.template_node(dom, vnode, child, None);
+                }
+            }
+            TemplateNode::Dynamic { id } | TemplateNode::DynamicText { id } => {
+                self.dynamic_node(dom, &vnode.dynamic_nodes[*id]);
+            }
+            _ => {}
+        }
+    }
+
+    fn dynamic_node(&mut self, dom: &VirtualDom, node: &DynamicNode) {
+        match node {
+            DynamicNode::Text(text) => {
+                self.ids
+                    .push(text.mounted_element().unwrap_or_default().0 as u32);
+            }
+            DynamicNode::Placeholder(placeholder) => {
+                self.ids
+                    .push(placeholder.mounted_element().unwrap_or_default().0 as u32);
+            }
+            DynamicNode::Component(component) => {
+                if let Some(scope) = component.mounted_scope().and_then(|id| dom.get_scope(id)) {
+                    self.scope(dom, scope);
+                }
+            }
+            DynamicNode::Fragment(fragment) => {
+                for vnode in *fragment {
+                    self.vnode(dom, vnode);
+                }
+            }
+        }
+    }
+}
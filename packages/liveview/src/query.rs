@@ -28,13 +28,10 @@ let dioxus = {
 
     send: function (value) {
         window.ipc.postMessage(
-            JSON.stringify({
-                "method":"query",
-                "params": {
-                    "id": _request_id,
-                    "data": value,
-                    "returned_value": false
-                }
+            window.interpreter.serializeIpcMessage("query", {
+                "id": _request_id,
+                "data": value,
+                "returned_value": false
             })
         );
     }
@@ -112,16 +109,12 @@ impl QueryEngine {
     
                     {script}
                 }})().then((result)=>{{
-                    let returned_value = {{
-                        "method":"query",
-                        "params": {{
+                    window.ipc.postMessage(
+                        window.interpreter.serializeIpcMessage("query", {{
                             "id": {request_id},
                             "data": result,
                             "returned_value": true
-                        }}
-                    }};
-                    window.ipc.postMessage(
-                        JSON.stringify(returned_value)
+                        }})
                     );
                 }})
             }})();"#
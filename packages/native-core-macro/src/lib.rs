@@ -340,32 +340,48 @@ pub fn partial_derive_state(_: TokenStream, input: TokenStream) -> TokenStream {
                     let (#(#split_views,)*) = data;
                     let tree = run_view.tree.clone();
                     let node_types = run_view.node_type.clone();
-                    dioxus_native_core::prelude::run_pass(type_id, dependants.clone(), pass_direction, run_view, |id, context| {
-                        let node_data: &NodeType<_> = node_types.get(id).unwrap_or_else(|err| panic!("Failed to get node type {:?}", err));
+                    let mut runner = dioxus_native_core::prelude::PassRunner::new(type_id, dependants.clone(), pass_direction, run_view);
+                    while let Some(level) = runner.next_level() {
                         // get all of the states from the tree view
-                        // Safety: No node has itself as a parent or child.
-                        let raw_myself: Option<*mut Self> = (&mut #this_view).get(id).ok().map(|c| c as *mut _);
-                        #get_node_view
-                        #get_parent_view
-                        #get_child_view
+                        // Safety: No node has itself as a parent or child and nodes at the same height are never parents or children of each other.
+                        let raw_level: Vec<_> = level.iter().map(|&id| {
+                            let node_data: *const NodeType<_> = node_types.get(id).unwrap_or_else(|err| panic!("Failed to get node type {:?}", err));
+                            let raw_myself: Option<*mut Self> = (&mut #this_view).get(id).ok().map(|c| c as *mut _);
+                            #get_node_view
+                            #get_parent_view
+                            #get_child_view
+                            (id, node_data, raw_myself, raw_node, raw_parent, raw_children)
+                        }).collect();
 
-                        let myself: Option<&mut Self> = unsafe { raw_myself.map(|val| &mut *val) };
-                        #deref_node_view
-                        #deref_parent_view
-                        #deref_child_view
+                        // Safety: The pointers are valid until the level is finished and each node only mutably borrows its own state
+                        let updates = unsafe { runner.update_level(raw_level, |raw, context| {
+                            let (id, node_data, raw_myself, raw_node, raw_parent, raw_children) = raw;
+                            let node_data: &NodeType<_> = unsafe { &*node_data };
+                            let myself: Option<&mut Self> = unsafe { raw_myself.map(|val| &mut *val) };
+                            #deref_node_view
+                            #deref_parent_view
+                            #deref_child_view
 
-                        let view = NodeView::new(id, node_data, &node_mask);
-                        if let Some(myself) = myself {
-                            myself
-                                .update(view, node, parent, children, context)
-                        }
-                        else {
-                            (&mut #this_view).add_component_unchecked(
-                                id,
-                                Self::create(view, node, parent, children, context));
-                            true
+                            let view = NodeView::new(id, node_data, &node_mask);
+                            if let Some(myself) = myself {
+                                dioxus_native_core::prelude::NodeUpdate::Updated(myself.update(view, node, parent, children, context))
+                            }
+                            else {
+                                dioxus_native_core::prelude::NodeUpdate::Created(Self::create(view, node, parent, children, context))
+                            }
+                        }) };
+
+                        for (id, update) in level.into_iter().zip(updates) {
+                            let changed = match update {
+                                dioxus_native_core::prelude::NodeUpdate::Updated(changed) => changed,
+                                dioxus_native_core::prelude::NodeUpdate::Created(state) => {
+                                    (&mut #this_view).add_component_unchecked(id, state);
+                                    true
+                                }
+                            };
+                            runner.finish(id, changed);
                         }
-                    })
+                    }
                 }).into_workload_system().unwrap()
             }
         }
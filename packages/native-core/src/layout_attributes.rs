@@ -1,8 +1,8 @@
 //! Utility functions for applying layout attributes to taffy layout
 
 /*
-- [ ] pub display: Display, ----> taffy doesnt support all display types
-- [x] pub position: Position,  --> taffy doesnt support everything
+- [x] pub display: Display, ----> taffy doesnt support all display types, so block and inline layouts use flexbox
+- [x] pub position: Position,  --> taffy doesnt support everything, so fixed is absolute and static and sticky are relative
 - [x] pub direction: Direction,
 
 - [x] pub flex_direction: FlexDirection,
@@ -32,9 +32,9 @@
 - [x] pub inset: Rect<Dimension>,
 - [x] pub border: Rect<Dimension>,
 
-- [ ] pub size: Size<Dimension>, ----> seems to only be relevant for input?
-- [ ] pub min_size: Size<Dimension>,
-- [ ] pub max_size: Size<Dimension>,
+- [x] pub size: Size<Dimension>,
+- [x] pub min_size: Size<Dimension>,
+- [x] pub max_size: Size<Dimension>,
 
 - [x] pub aspect_ratio: Number,
 */
@@ -112,15 +112,23 @@ pub fn apply_layout_attributes_cfg(
                     display::DisplayInside::Grid => {
                         style.display = Display::Grid;
                     }
+                    // taffy doesn't have flow layout, so block level elements are laid out as a flex column and inline elements as a flex row
+                    display::DisplayInside::Flow | display::DisplayInside::FlowRoot => {
+                        style.display = Display::Flex;
+                        if pair.outside == display::DisplayOutside::Block {
+                            style.flex_direction = FlexDirection::Column;
+                        }
+                    }
                     _ => {}
                 },
                 _ => {}
             },
             Property::Position(position) => {
                 style.position = match position {
-                    position::Position::Relative => Position::Relative,
-                    position::Position::Absolute => Position::Absolute,
-                    _ => return,
+                    position::Position::Static
+                    | position::Position::Relative
+                    | position::Position::Sticky(_) => Position::Relative,
+                    position::Position::Absolute | position::Position::Fixed => Position::Absolute,
                 }
             }
             Property::Top(top) => style.inset.top = convert_length_percentage_or_auto(top),
@@ -456,11 +464,25 @@ pub fn apply_layout_attributes_cfg(
             Property::Height(height) => {
                 style.size.height = convert_size(height);
             }
+            Property::MinWidth(width) => {
+                style.min_size.width = convert_size(width);
+            }
+            Property::MinHeight(height) => {
+                style.min_size.height = convert_size(height);
+            }
+            Property::MaxWidth(width) => {
+                style.max_size.width = convert_max_size(width);
+            }
+            Property::MaxHeight(height) => {
+                style.max_size.height = convert_max_size(height);
+            }
             _ => (),
         }
         // currently not implemented in lightningcss
         if name == "aspect-ratio" {
-            if let Ok(ratio) = Ratio::parse_string(value) {
+            if value.trim() == "auto" {
+                style.aspect_ratio = None;
+            } else if let Ok(ratio) = Ratio::parse_string(value) {
                 style.aspect_ratio = Some(ratio.0 / ratio.1);
             }
         }
@@ -557,6 +579,19 @@ fn convert_size(size: size::Size) -> Dimension {
     }
 }
 
+fn convert_max_size(size: size::MaxSize) -> Dimension {
+    match size {
+        size::MaxSize::None => Dimension::Auto,
+        size::MaxSize::LengthPercentage(length) => convert_dimension(length),
+        size::MaxSize::MinContent(_) => Dimension::Auto, // Unimplemented, so default auto
+        size::MaxSize::MaxContent(_) => Dimension::Auto, // Unimplemented, so default auto
+        size::MaxSize::FitContent(_) => Dimension::Auto, // Unimplemented, so default auto
+        size::MaxSize::FitContentFunction(_) => Dimension::Auto, // Unimplemented, so default auto
+        size::MaxSize::Stretch(_) => Dimension::Auto,    // Unimplemented, so default auto
+        size::MaxSize::Contain => Dimension::Auto,       // Unimplemented, so default auto
+    }
+}
+
 fn convert_grid_placement(input: grid::GridLine) -> GridPlacement {
     match input {
         grid::GridLine::Auto => GridPlacement::Auto,
@@ -639,3 +674,23 @@ pub fn parse_value(value: &str) -> Option<Dimension> {
         None
     }
 }
+
+#[test]
+fn applies_sizing_and_display_attributes() {
+    let mut style = Style::default();
+    apply_layout_attributes("display", "block", &mut style);
+    apply_layout_attributes("position", "fixed", &mut style);
+    apply_layout_attributes("min-width", "10px", &mut style);
+    apply_layout_attributes("max-height", "50%", &mut style);
+    apply_layout_attributes("aspect-ratio", "16 / 9", &mut style);
+
+    assert_eq!(style.display, Display::Flex);
+    assert_eq!(style.flex_direction, FlexDirection::Column);
+    assert_eq!(style.position, Position::Absolute);
+    assert_eq!(style.min_size.width, Dimension::Points(10.0));
+    assert_eq!(style.max_size.height, Dimension::Percent(0.5));
+    assert_eq!(style.aspect_ratio, Some(16.0 / 9.0));
+
+    apply_layout_attributes("max-height", "none", &mut style);
+    assert_eq!(style.max_size.height, Dimension::Auto);
+}
@@ -39,8 +39,8 @@ pub mod prelude {
     pub use crate::dioxus::*;
     pub use crate::node::{ElementNode, FromAnyValue, NodeType, OwnedAttributeView, TextNode};
     pub use crate::node_ref::{AttributeMaskBuilder, NodeMaskBuilder, NodeView};
-    pub use crate::passes::{run_pass, PassDirection, RunPassView, TypeErasedState};
     pub use crate::passes::{Dependancy, DependancyView, Dependants, State};
+    pub use crate::passes::{NodeUpdate, PassDirection, PassRunner, RunPassView, TypeErasedState};
     pub use crate::real_dom::{NodeImmutable, NodeMut, NodeRef, RealDom};
     pub use crate::NodeId;
     pub use crate::SendAnyMap;
@@ -23,17 +23,6 @@ impl DirtyNodes {
     pub fn add_node(&mut self, node_id: NodeId) {
         self.nodes_dirty.insert(node_id);
     }
-
-    pub fn is_empty(&self) -> bool {
-        self.nodes_dirty.is_empty()
-    }
-
-    pub fn pop(&mut self) -> Option<NodeId> {
-        self.nodes_dirty.iter().next().copied().map(|id| {
-            self.nodes_dirty.remove(&id);
-            id
-        })
-    }
 }
 
 /// Tracks the dirty nodes sorted by height for each pass. We resolve passes based on the height of the node in order to avoid resolving any node twice in a pass.
@@ -66,30 +55,16 @@ impl DirtyNodeStates {
         }
     }
 
-    fn pop_front(&self, pass_id: TypeId) -> Option<(u16, NodeId)> {
+    /// Remove every dirty node at the lowest or highest height for a pass. Nodes at the same height never depend on each other, so they can be updated together
+    fn pop_level(&self, pass_id: TypeId, lowest: bool) -> Option<(u16, Vec<NodeId>)> {
         let mut values = self.dirty.get(&pass_id)?.write();
-        let mut value = values.first_entry()?;
-        let height = *value.key();
-        let ids = value.get_mut();
-        let id = ids.pop()?;
-        if ids.is_empty() {
-            value.remove_entry();
-        }
-
-        Some((height, id))
-    }
-
-    fn pop_back(&self, pass_id: TypeId) -> Option<(u16, NodeId)> {
-        let mut values = self.dirty.get(&pass_id)?.write();
-        let mut value = values.last_entry()?;
-        let height = *value.key();
-        let ids = value.get_mut();
-        let id = ids.pop()?;
-        if ids.is_empty() {
-            value.remove_entry();
-        }
+        let (height, nodes) = if lowest {
+            values.pop_first()?
+        } else {
+            values.pop_last()?
+        };
 
-        Some((height, id))
+        Some((height, nodes.nodes_dirty.into_iter().collect()))
     }
 }
 
@@ -186,48 +161,113 @@ pub struct RunPassView<'a, V: FromAnyValue + Send + Sync = ()> {
     any_map: UniqueView<'a, SendAnyMapWrapper>,
 }
 
+/// The number of dirty nodes at one height before the nodes are updated on multiple threads
+#[cfg(feature = "parallel")]
+const PARALLEL_LEVEL_SIZE: usize = 64;
+
+/// The result of updating the state of a single node
+#[doc(hidden)]
+pub enum NodeUpdate<S> {
+    /// The existing state was updated. Contains if the state changed
+    Updated(bool),
+    /// The node did not have the state yet
+    Created(S),
+}
+
+/// Raw pointers to the states and node data a node is updated with. Only created by [`PassRunner::update_level`], which
+/// requires the caller to guarantee the pointers can be used from other threads
+#[cfg(feature = "parallel")]
+struct RawNodeStates<T>(T);
+
+#[cfg(feature = "parallel")]
+unsafe impl<T> Send for RawNodeStates<T> {}
+
 // This is used by the macro
-/// Updates the given pass, marking any nodes that were changed
+/// Updates the given pass one height at a time, marking any nodes that were changed
 #[doc(hidden)]
-pub fn run_pass<V: FromAnyValue + Send + Sync>(
+pub struct PassRunner<'a, V: FromAnyValue + Send + Sync = ()> {
     type_id: TypeId,
     dependants: Arc<Dependants>,
     pass_direction: PassDirection,
-    view: RunPassView<V>,
-    mut update_node: impl FnMut(NodeId, &SendAnyMap) -> bool,
-) {
-    let RunPassView {
-        tree,
-        dirty_nodes_result: nodes_updated,
-        node_states: dirty,
-        any_map: ctx,
-        ..
-    } = view;
-    let ctx = ctx.as_ref();
-    match pass_direction {
-        PassDirection::ParentToChild => {
-            while let Some((height, id)) = dirty.pop_front(type_id) {
-                if (update_node)(id, ctx) {
-                    nodes_updated.insert(id);
-                    dependants.mark_dirty(&dirty, id, &tree, height);
-                }
-            }
+    view: RunPassView<'a, V>,
+}
+
+impl<'a, V: FromAnyValue + Send + Sync> PassRunner<'a, V> {
+    pub fn new(
+        type_id: TypeId,
+        dependants: Arc<Dependants>,
+        pass_direction: PassDirection,
+        view: RunPassView<'a, V>,
+    ) -> Self {
+        Self {
+            type_id,
+            dependants,
+            pass_direction,
+            view,
         }
-        PassDirection::ChildToParent => {
-            while let Some((height, id)) = dirty.pop_back(type_id) {
-                if (update_node)(id, ctx) {
-                    nodes_updated.insert(id);
-                    dependants.mark_dirty(&dirty, id, &tree, height);
-                }
-            }
+    }
+
+    /// Take the next set of dirty nodes that can be updated together
+    pub fn next_level(&mut self) -> Option<Vec<NodeId>> {
+        // parents are resolved before their children in parent to child passes and after their children otherwise
+        let lowest = self.pass_direction == PassDirection::ParentToChild;
+        let (_, nodes) = self.view.node_states.pop_level(self.type_id, lowest)?;
+        debug_assert!(
+            self.is_independent(&nodes),
+            "nodes in the same level depend on each other"
+        );
+        Some(nodes)
+    }
+
+    /// Check that no node in a level is the parent of another node in the level in the light or shadow tree
+    fn is_independent(&self, nodes: &[NodeId]) -> bool {
+        let level: FxHashSet<_> = nodes.iter().copied().collect();
+        nodes.iter().all(|&id| {
+            [false, true].into_iter().all(|enter_shadow_dom| {
+                !matches!(
+                    self.view.tree.parent_id_advanced(id, enter_shadow_dom),
+                    Some(parent) if level.contains(&parent)
+                )
+            })
+        })
+    }
+
+    /// Update every node in a level. Large levels are updated in parallel if the parallel feature is enabled
+    ///
+    /// # Safety
+    /// The nodes may be sent to other threads, so any pointers in them must stay valid for the level and must not
+    /// alias the pointers of any other node in the level
+    pub unsafe fn update_level<T, R: Send>(
+        &self,
+        nodes: Vec<T>,
+        update_node: impl Fn(T, &SendAnyMap) -> R + Sync,
+    ) -> Vec<R> {
+        let ctx = self.view.any_map.as_ref();
+
+        #[cfg(feature = "parallel")]
+        if nodes.len() >= PARALLEL_LEVEL_SIZE {
+            use rayon::prelude::*;
+            return nodes
+                .into_iter()
+                .map(RawNodeStates)
+                .collect::<Vec<_>>()
+                .into_par_iter()
+                .map(|RawNodeStates(node)| update_node(node, ctx))
+                .collect();
         }
-        PassDirection::AnyOrder => {
-            while let Some((height, id)) = dirty.pop_back(type_id) {
-                if (update_node)(id, ctx) {
-                    nodes_updated.insert(id);
-                    dependants.mark_dirty(&dirty, id, &tree, height);
-                }
-            }
+
+        nodes
+            .into_iter()
+            .map(|node| update_node(node, ctx))
+            .collect()
+    }
+
+    /// Mark a node in the current level as resolved. If the node changed, the states that depend on it become dirty
+    pub fn finish(&self, id: NodeId, changed: bool) {
+        if changed {
+            self.view.dirty_nodes_result.insert(id);
+            self.dependants
+                .mark_dirty(&self.view.node_states, id, &self.view.tree);
         }
     }
 }
@@ -250,14 +290,18 @@ pub struct Dependants {
 }
 
 impl Dependants {
-    fn mark_dirty(&self, dirty: &DirtyNodeStates, id: NodeId, tree: &impl TreeRef, height: u16) {
+    /// Mark the states that depend on a node dirty. The nodes are inserted at their height in the tree, which is not
+    /// always one more or less than the height of this node when a dependency crosses into a shadow tree
+    fn mark_dirty(&self, dirty: &DirtyNodeStates, id: NodeId, tree: &impl TreeRef) {
         for &Dependant {
             type_id,
             enter_shadow_dom,
         } in &self.child
         {
             for id in tree.children_ids_advanced(id, enter_shadow_dom) {
-                dirty.insert(type_id, id, height + 1);
+                if let Some(height) = tree.height(id) {
+                    dirty.insert(type_id, id, height);
+                }
             }
         }
 
@@ -267,12 +311,16 @@ impl Dependants {
         } in &self.parent
         {
             if let Some(id) = tree.parent_id_advanced(id, enter_shadow_dom) {
-                dirty.insert(type_id, id, height - 1);
+                if let Some(height) = tree.height(id) {
+                    dirty.insert(type_id, id, height);
+                }
             }
         }
 
-        for dependant in &self.node {
-            dirty.insert(*dependant, id, height);
+        if let Some(height) = tree.height(id) {
+            for dependant in &self.node {
+                dirty.insert(*dependant, id, height);
+            }
         }
     }
 }
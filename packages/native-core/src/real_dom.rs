@@ -332,7 +332,6 @@ impl<V: FromAnyValue + Send + Sync> RealDom<V> {
             };
         }
 
-        let passes = std::mem::take(&mut self.dirty_nodes.passes_updated);
         let nodes_updated = std::mem::take(&mut self.dirty_nodes.nodes_updated);
 
         for (node_id, mask) in &nodes_updated {
@@ -358,6 +357,9 @@ impl<V: FromAnyValue + Send + Sync> RealDom<V> {
             }
         }
 
+        // custom elements may create nodes in their shadow trees when their attributes change, so the passes are
+        // collected after the watchers run
+        let passes = std::mem::take(&mut self.dirty_nodes.passes_updated);
         let dirty_nodes =
             DirtyNodeStates::with_passes(self.dirty_nodes.passes.iter().map(|p| p.this_type_id));
         let tree = self.tree_ref();
@@ -20,7 +20,7 @@ pub trait Text {
 
 impl Text for str {
     fn line(&self, number: usize) -> Option<&str> {
-        self.lines().nth(number)
+        self.split('\n').nth(number)
     }
 
     fn length(&self) -> usize {
@@ -28,7 +28,8 @@ impl Text for str {
     }
 
     fn line_count(&self) -> usize {
-        self.lines().count()
+        // text that ends with a line break has an empty last line the cursor can move to
+        self.split('\n').count()
     }
 
     fn character(&self, idx: usize) -> Option<char> {
@@ -36,9 +37,10 @@ impl Text for str {
     }
 
     fn len_before_line(&self, line: usize) -> usize {
-        self.lines()
+        // include the line break at the end of each line
+        self.split('\n')
             .take(line)
-            .map(|l| l.chars().count())
+            .map(|l| l.chars().count() + 1)
             .sum::<usize>()
     }
 }
@@ -53,14 +55,22 @@ pub trait TextEditable<T: Text + ?Sized>: AsRef<T> {
 
 impl TextEditable<str> for String {
     fn insert_character(&mut self, idx: usize, text: char) {
-        self.insert(idx, text);
+        self.insert(byte_index(self, idx), text);
     }
 
     fn delete_range(&mut self, range: Range<usize>) {
+        let range = byte_index(self, range.start)..byte_index(self, range.end);
         self.replace_range(range, "");
     }
 }
 
+/// Convert a character index into a byte index in the string
+fn byte_index(text: &str, idx: usize) -> usize {
+    text.char_indices()
+        .nth(idx)
+        .map_or(text.len(), |(byte_idx, _)| byte_idx)
+}
+
 /// A cursor position
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Pos {
@@ -358,6 +368,22 @@ impl Cursor {
         }
     }
 
+    /// Insert text at the cursor, replacing the selected text. Characters that would make the text longer than the max length are left out.
+    pub fn insert_text<T: Text + ?Sized>(
+        &mut self,
+        inserted: &str,
+        text: &mut impl TextEditable<T>,
+        max_text_length: usize,
+    ) {
+        self.start.realize_col(text.as_ref());
+        self.delete_selection(text);
+        let room = max_text_length.saturating_sub(text.as_ref().length());
+        for character in inserted.chars().take(room) {
+            text.insert_character(self.start.idx(text.as_ref()), character);
+            self.start.right(text.as_ref());
+        }
+    }
+
     /// Modify the end selection position
     pub fn with_end(&mut self, f: impl FnOnce(&mut Pos)) {
         let mut new = self.end.take().unwrap_or_else(|| self.start.clone());
@@ -539,3 +565,51 @@ fn cursor_input() {
 
     assert_eq!(text.to_string(), goal_text);
 }
+
+#[test]
+fn cursor_multiline_input() {
+    let mut cursor = Cursor::from_start(Pos::new(0, 0));
+    let mut text = "héllo".to_string();
+
+    let mut press = |code, key| {
+        cursor.handle_input(&code, &key, &Modifiers::empty(), &mut text, 100);
+    };
+    press(keyboard_types::Code::End, keyboard_types::Key::End);
+    press(keyboard_types::Code::Enter, keyboard_types::Key::Enter);
+    press(
+        keyboard_types::Code::KeyW,
+        keyboard_types::Key::Character("w".to_string()),
+    );
+    press(keyboard_types::Code::ArrowUp, keyboard_types::Key::ArrowUp);
+    press(
+        keyboard_types::Code::KeyX,
+        keyboard_types::Key::Character("x".to_string()),
+    );
+    assert_eq!(text, "hxéllo\nw");
+
+    // deleting at the start of a line joins it with the line above
+    let mut cursor = Cursor::from_start(Pos::new(0, 1));
+    cursor.handle_input(
+        &keyboard_types::Code::Backspace,
+        &keyboard_types::Key::Backspace,
+        &Modifiers::empty(),
+        &mut text,
+        100,
+    );
+    assert_eq!(text, "hxéllow");
+}
+
+#[test]
+fn cursor_insert_text() {
+    let mut text = "hello world".to_string();
+    let mut cursor = Cursor::new(Pos::new(6, 0), Pos::new(11, 0));
+    cursor.insert_text("there\nfriend", &mut text, 100);
+    assert_eq!(text, "hello there\nfriend");
+    assert_eq!(cursor, Cursor::from_start(Pos::new(6, 1)));
+
+    // text past the max length is left out
+    let mut text = "ab".to_string();
+    let mut cursor = Cursor::from_start(Pos::new(1, 0));
+    cursor.insert_text("xyz", &mut text, 4);
+    assert_eq!(text, "axyb");
+}
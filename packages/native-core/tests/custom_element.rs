@@ -387,3 +387,94 @@ fn slotless_custom_element() {
         rdom.update_state(ctx);
     });
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component)]
+pub struct LightTreeSize {
+    nodes: usize,
+    updates: usize,
+}
+
+#[partial_derive_state]
+impl State for LightTreeSize {
+    type ParentDependencies = ();
+    type ChildDependencies = (Self,);
+    type NodeDependencies = ();
+
+    // Only count the nodes in the light dom
+    const TRAVERSE_SHADOW_DOM: bool = false;
+
+    const NODE_MASK: NodeMaskBuilder<'static> = NodeMaskBuilder::new();
+
+    fn update<'a>(
+        &mut self,
+        _: NodeView,
+        _: <Self::NodeDependencies as Dependancy>::ElementBorrowed<'a>,
+        _: Option<<Self::ParentDependencies as Dependancy>::ElementBorrowed<'a>>,
+        children: Vec<<Self::ChildDependencies as Dependancy>::ElementBorrowed<'a>>,
+        _: &SendAnyMap,
+    ) -> bool {
+        self.nodes = 1 + children.iter().map(|(c,)| c.nodes).sum::<usize>();
+        self.updates += 1;
+        true
+    }
+
+    fn create<'a>(
+        node_view: NodeView<()>,
+        node: <Self::NodeDependencies as Dependancy>::ElementBorrowed<'a>,
+        parent: Option<<Self::ParentDependencies as Dependancy>::ElementBorrowed<'a>>,
+        children: Vec<<Self::ChildDependencies as Dependancy>::ElementBorrowed<'a>>,
+        context: &SendAnyMap,
+    ) -> Self {
+        let mut myself = Self::default();
+        myself.update(node_view, node, parent, children, context);
+        myself
+    }
+}
+
+#[test]
+fn wide_shadow_tree_updates_each_node_once() {
+    // Enough custom elements that each height is updated in parallel when the parallel feature is enabled
+    const ELEMENTS: usize = 100;
+
+    fn element(tag: &str) -> ElementNode {
+        ElementNode {
+            tag: tag.into(),
+            namespace: None,
+            attributes: Default::default(),
+            listeners: Default::default(),
+        }
+    }
+
+    let mut rdom = RealDom::new([
+        LightTreeSize::to_type_erased(),
+        LayoutState::to_type_erased(),
+    ]);
+    rdom.register_custom_element::<CustomElementWithSlot>();
+    let root = rdom.root_id();
+    let mut light_children = Vec::new();
+    for _ in 0..ELEMENTS {
+        let light_child = rdom.create_node(element("testing132")).id();
+        let mut custom_element = rdom.create_node(element("customelementslot"));
+        custom_element.add_child(light_child);
+        let custom_element = custom_element.id();
+        rdom.get_mut(root).unwrap().add_child(custom_element);
+        light_children.push(light_child);
+    }
+
+    rdom.update_state(SendAnyMap::new());
+
+    // the light children are below the slot in the shadow tree, but their light dom parent is the custom element
+    let light_child = rdom.get(light_children[0]).unwrap();
+    assert_eq!(light_child.height(), 4);
+    let root = rdom.get(root).unwrap();
+    assert_eq!(
+        *root.get::<LightTreeSize>().unwrap(),
+        LightTreeSize {
+            nodes: 1 + ELEMENTS * 2,
+            updates: 1
+        }
+    );
+    rdom.traverse_depth_first_advanced(true, |node| {
+        assert_eq!(node.get::<LightTreeSize>().unwrap().updates, 1);
+    });
+}
@@ -438,3 +438,95 @@ fn up_pass() {
     let grandchild2 = tree.get(grandchild2).unwrap();
     assert_eq!(grandchild2.get().as_deref(), Some(&AddNumber(1)));
 }
+
+#[test]
+fn wide_up_pass_updates_dirty_subtree() {
+    // Enough siblings that each height is updated in parallel when the parallel feature is enabled
+    const CHILDREN: usize = 200;
+
+    #[derive(Debug, Default, Clone, PartialEq, Component)]
+    struct SubtreeSize {
+        nodes: usize,
+        updates: usize,
+    }
+
+    #[partial_derive_state]
+    impl State for SubtreeSize {
+        type ChildDependencies = (SubtreeSize,);
+        type NodeDependencies = ();
+        type ParentDependencies = ();
+
+        const NODE_MASK: NodeMaskBuilder<'static> = NodeMaskBuilder::new();
+
+        fn update<'a>(
+            &mut self,
+            _: NodeView,
+            _: <Self::NodeDependencies as Dependancy>::ElementBorrowed<'a>,
+            _: Option<<Self::ParentDependencies as Dependancy>::ElementBorrowed<'a>>,
+            children: Vec<<Self::ChildDependencies as Dependancy>::ElementBorrowed<'a>>,
+            _: &SendAnyMap,
+        ) -> bool {
+            self.nodes = 1 + children.iter().map(|(c,)| c.nodes).sum::<usize>();
+            self.updates += 1;
+            true
+        }
+
+        fn create<'a>(
+            node_view: NodeView<()>,
+            node: <Self::NodeDependencies as Dependancy>::ElementBorrowed<'a>,
+            parent: Option<<Self::ParentDependencies as Dependancy>::ElementBorrowed<'a>>,
+            children: Vec<<Self::ChildDependencies as Dependancy>::ElementBorrowed<'a>>,
+            context: &SendAnyMap,
+        ) -> Self {
+            let mut myself = Self::default();
+            myself.update(node_view, node, parent, children, context);
+            myself
+        }
+    }
+
+    let mut tree: RealDom = RealDom::new([SubtreeSize::to_type_erased()]);
+    let mut children = Vec::new();
+    for _ in 0..CHILDREN {
+        let grandchild = tree.create_node(create_blank_element()).id();
+        let mut child = tree.create_node(create_blank_element());
+        child.add_child(grandchild);
+        let child = child.id();
+        tree.get_mut(tree.root_id()).unwrap().add_child(child);
+        children.push(child);
+    }
+
+    tree.update_state(SendAnyMap::new());
+
+    let root = tree.get(tree.root_id()).unwrap();
+    assert_eq!(root.get::<SubtreeSize>().unwrap().nodes, 1 + CHILDREN * 2);
+    for &child in &children {
+        let child = tree.get(child).unwrap();
+        assert_eq!(
+            child.get().as_deref(),
+            Some(&SubtreeSize {
+                nodes: 2,
+                updates: 1
+            })
+        );
+    }
+
+    // only the nodes above the new node should be updated
+    let new_node = tree.create_node(create_blank_element()).id();
+    tree.get_mut(children[0]).unwrap().add_child(new_node);
+    tree.update_state(SendAnyMap::new());
+
+    let root = tree.get(tree.root_id()).unwrap();
+    assert_eq!(root.get::<SubtreeSize>().unwrap().nodes, 2 + CHILDREN * 2);
+    let changed = tree.get(children[0]).unwrap();
+    assert_eq!(
+        changed.get().as_deref(),
+        Some(&SubtreeSize {
+            nodes: 3,
+            updates: 2
+        })
+    );
+    for &child in &children[1..] {
+        let child = tree.get(child).unwrap();
+        assert_eq!(child.get::<SubtreeSize>().unwrap().updates, 1);
+    }
+}
@@ -0,0 +1,77 @@
+//! Copy selected text to the system clipboard.
+
+use std::io::Write;
+
+use shipyard::Unique;
+
+/// A clipboard the renderer copies selected text to
+pub trait Clipboard: Send + Sync {
+    /// Replace the contents of the clipboard with some text
+    fn set_text(&self, text: &str);
+}
+
+/// Copies text with the OSC 52 escape sequence. The terminal sets the system clipboard, so this works over ssh, but
+/// some terminals disable the sequence or ask the user for permission first.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Osc52Clipboard;
+
+impl Clipboard for Osc52Clipboard {
+    fn set_text(&self, text: &str) {
+        // failing to copy should not take down the app
+        let _ = write_osc52(&mut std::io::stdout().lock(), text);
+    }
+}
+
+/// Write the escape sequence that sets the clipboard of the terminal
+fn write_osc52(out: &mut impl Write, text: &str) -> std::io::Result<()> {
+    write!(out, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    out.flush()
+}
+
+/// The clipboard from the config, stored in the dom so the input handler can reach it. It is only stored if copy on
+/// select is enabled
+#[derive(Unique, Clone)]
+pub(crate) struct ClipboardContext {
+    pub(crate) clipboard: &'static dyn Clipboard,
+}
+
+/// Encode bytes with the standard base64 alphabet and padding
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| {
+            group | (*byte as u32) << (16 - i * 8)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - i * 6)) as usize & 0x3f] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[test]
+fn base64_encoding() {
+    assert_eq!(base64(b""), "");
+    assert_eq!(base64(b"f"), "Zg==");
+    assert_eq!(base64(b"fo"), "Zm8=");
+    assert_eq!(base64(b"foo"), "Zm9v");
+    assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    assert_eq!(base64("héllo".as_bytes()), "aMOpbGxv");
+}
+
+#[test]
+fn osc52_sets_the_clipboard() {
+    let mut out = Vec::new();
+    write_osc52(&mut out, "hi").unwrap();
+    assert_eq!(out, b"\x1b]52;c;aGk=\x07");
+
+    // line breaks and other control characters are encoded, so they can't end the sequence early
+    let mut out = Vec::new();
+    write_osc52(&mut out, "a\x07b\nc").unwrap();
+    assert_eq!(out, b"\x1b]52;c;YQdiCmM=\x07");
+}
@@ -1,3 +1,5 @@
+use crate::{Clipboard, Osc52Clipboard, Theme};
+
 #[derive(Clone, Copy)]
 #[non_exhaustive]
 pub struct Config {
@@ -7,6 +9,12 @@ pub struct Config {
     pub(crate) ctrl_c_quit: bool,
     /// Controls if the terminal should dislay anything, usefull for testing.
     pub(crate) headless: bool,
+    /// The theme `var(--name)` in style attributes resolves against. If this is not set, the theme is detected from the terminal.
+    pub(crate) theme: Option<&'static Theme>,
+    /// The clipboard text selected with the mouse is copied to. If this is not set, text is copied with [`Osc52Clipboard`].
+    pub(crate) clipboard: Option<&'static dyn Clipboard>,
+    /// Controls if text selected with the mouse is copied to the clipboard when the mouse button is released.
+    pub(crate) copy_on_select: bool,
 }
 
 impl Config {
@@ -34,6 +42,60 @@ impl Config {
             ..self
         }
     }
+
+    /// Set the theme `var(--name)` in style attributes resolves against.
+    ///
+    /// The theme is kept for the rest of the program so the config stays `Copy`. Set it once when the app starts.
+    pub fn with_theme(self, theme: Theme) -> Self {
+        Self {
+            theme: Some(Box::leak(Box::new(theme))),
+            ..self
+        }
+    }
+
+    /// Set the clipboard text selected with the mouse is copied to when [`Config::with_copy_on_select`] is set.
+    ///
+    /// The clipboard is kept for the rest of the program so the config stays `Copy`. Set it once when the app starts.
+    pub fn with_clipboard(self, clipboard: impl Clipboard + 'static) -> Self {
+        Self {
+            clipboard: Some(Box::leak(Box::new(clipboard))),
+            ..self
+        }
+    }
+
+    /// Copy text selected with the mouse to the clipboard and send `oncopy` events when the mouse button is released.
+    /// This replaces whatever was on the clipboard, so it is off by default.
+    pub fn with_copy_on_select(self) -> Self {
+        Self {
+            copy_on_select: true,
+            ..self
+        }
+    }
+
+    /// Replace the automatic rendering mode with the one detected from the terminal. Headless renderers don't have a
+    /// terminal, so they use rgb colors to keep the output the same everywhere, and don't copy to the clipboard.
+    pub(crate) fn resolve(mut self) -> Self {
+        if let RenderingMode::Auto = self.rendering_mode {
+            self.rendering_mode = if self.headless {
+                RenderingMode::Rgb
+            } else {
+                RenderingMode::detect()
+            };
+        }
+        if self.clipboard.is_none() && !self.headless {
+            self.clipboard = Some(&Osc52Clipboard);
+        }
+        self
+    }
+
+    /// The theme from the config, or the theme detected from the terminal. Headless renderers use the dark theme.
+    pub(crate) fn resolve_theme(&self) -> Theme {
+        match self.theme {
+            Some(theme) => theme.clone(),
+            None if self.headless => Theme::dark(),
+            None => Theme::detect(),
+        }
+    }
 }
 
 impl Default for Config {
@@ -42,17 +104,106 @@ impl Default for Config {
             rendering_mode: Default::default(),
             ctrl_c_quit: true,
             headless: false,
+            theme: None,
+            clipboard: None,
+            copy_on_select: false,
         }
     }
 }
 
-#[derive(Clone, Copy, Default)]
+/// The colors the renderer draws with. New modes may be added, so matches on this need a wildcard arm
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum RenderingMode {
+    /// detect the colors the terminal supports from the environment and terminfo when rendering starts
+    #[default]
+    Auto,
+    /// no colors, for terminals without color support or when `NO_COLOR` is set
+    Monochrome,
     /// only 16 colors by accessed by name, no alpha support
     BaseColors,
     /// 8 bit colors, will be downsampled from rgb colors
     Ansi,
     /// 24 bit colors, most terminals support this
-    #[default]
     Rgb,
 }
+
+impl RenderingMode {
+    /// Detect the colors the terminal supports from the `NO_COLOR`, `COLORTERM` and `TERM` environment variables and the terminfo database
+    pub fn detect() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let terminfo_colors = var("TERM").and_then(|term| crate::terminfo::colors(&term));
+        Self::from_environment(var, terminfo_colors)
+    }
+
+    fn from_environment(
+        var: impl Fn(&str) -> Option<String>,
+        terminfo_colors: Option<i32>,
+    ) -> Self {
+        // https://no-color.org
+        if var("NO_COLOR").is_some() {
+            return RenderingMode::Monochrome;
+        }
+        if let Some(colorterm) = var("COLORTERM") {
+            if colorterm == "truecolor" || colorterm == "24bit" {
+                return RenderingMode::Rgb;
+            }
+        }
+        let term = var("TERM").unwrap_or_default();
+        if term == "dumb" {
+            return RenderingMode::Monochrome;
+        }
+        if term.ends_with("-direct") || term.contains("truecolor") {
+            return RenderingMode::Rgb;
+        }
+        match terminfo_colors {
+            Some(colors) if colors >= 1 << 24 => RenderingMode::Rgb,
+            Some(colors) if colors >= 256 => RenderingMode::Ansi,
+            Some(colors) if colors >= 8 => RenderingMode::BaseColors,
+            Some(_) => RenderingMode::Monochrome,
+            None if term.contains("256color") => RenderingMode::Ansi,
+            None if term.is_empty() => RenderingMode::Rgb,
+            None => RenderingMode::BaseColors,
+        }
+    }
+}
+
+#[test]
+fn detect_rendering_mode() {
+    fn detect(vars: &[(&str, &str)], terminfo_colors: Option<i32>) -> RenderingMode {
+        RenderingMode::from_environment(
+            |name| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            },
+            terminfo_colors,
+        )
+    }
+
+    assert_eq!(
+        detect(&[("NO_COLOR", "1"), ("COLORTERM", "truecolor")], None),
+        RenderingMode::Monochrome
+    );
+    assert_eq!(
+        detect(&[("COLORTERM", "truecolor"), ("TERM", "xterm")], Some(8)),
+        RenderingMode::Rgb
+    );
+    assert_eq!(detect(&[("TERM", "dumb")], None), RenderingMode::Monochrome);
+    assert_eq!(
+        detect(&[("TERM", "xterm-256color")], None),
+        RenderingMode::Ansi
+    );
+    assert_eq!(
+        detect(&[("TERM", "xterm-256color")], Some(256)),
+        RenderingMode::Ansi
+    );
+    assert_eq!(
+        detect(&[("TERM", "xterm")], Some(8)),
+        RenderingMode::BaseColors
+    );
+    assert_eq!(
+        detect(&[("TERM", "vt100")], Some(0)),
+        RenderingMode::Monochrome
+    );
+}
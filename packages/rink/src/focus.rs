@@ -1,4 +1,6 @@
 use crate::prevent_default::PreventDefault;
+use crate::pseudo_class::{set_pseudo_class, PseudoClass};
+use crate::scroll::ScrollState;
 
 use dioxus_native_core::{
     node_ref::{AttributeMaskBuilder, NodeMaskBuilder},
@@ -72,12 +74,12 @@ impl State for Focus {
 
     type ParentDependencies = ();
     type ChildDependencies = ();
-    type NodeDependencies = ();
+    type NodeDependencies = (ScrollState,);
 
     fn update<'a>(
         &mut self,
         node_view: NodeView,
-        _: <Self::NodeDependencies as Dependancy>::ElementBorrowed<'a>,
+        (scroll,): <Self::NodeDependencies as Dependancy>::ElementBorrowed<'a>,
         _: Option<<Self::ParentDependencies as Dependancy>::ElementBorrowed<'a>>,
         _: Vec<<Self::ChildDependencies as Dependancy>::ElementBorrowed<'a>>,
         _: &SendAnyMap,
@@ -110,6 +112,9 @@ impl State for Focus {
                 .is_some()
             {
                 FocusLevel::Focusable
+            } else if scroll.scrollable() {
+                // scroll containers can be focused to scroll them with the keyboard
+                FocusLevel::Focusable
             } else {
                 FocusLevel::Unfocusable
             },
@@ -257,11 +262,13 @@ impl FocusState {
                 panic!()
             }
             node.insert(Focused(true));
-            if let Some(old) = self.last_focused_id.replace(id) {
+            let old = self.last_focused_id.replace(id);
+            if let Some(old) = old {
                 let mut focused_borrow: ViewMut<Focused> = rdom.raw_world().borrow().unwrap();
                 let focused = (&mut focused_borrow).get(old).unwrap();
                 focused.0 = false;
             }
+            update_focus_style(rdom, old, id);
             // reset the position to the currently focused element
             while self.focus_iter.next(rdom).id() != id {}
             self.dirty = true;
@@ -272,10 +279,12 @@ impl FocusState {
     }
 
     pub(crate) fn set_focus(&mut self, rdom: &mut RealDom, id: NodeId) {
-        if let Some(old) = self.last_focused_id.replace(id) {
+        let old = self.last_focused_id.replace(id);
+        if let Some(old) = old {
             let mut node = rdom.get_mut(old).unwrap();
             node.insert(Focused(false));
         }
+        update_focus_style(rdom, old, id);
         let mut node = rdom.get_mut(id).unwrap();
         node.insert(Focused(true));
         self.focus_level = node.get::<Focus>().unwrap().level;
@@ -290,3 +299,13 @@ impl FocusState {
         old
     }
 }
+
+/// Move the :focus styles from the element that lost focus to the element that gained it
+fn update_focus_style(rdom: &mut RealDom, old: Option<NodeId>, new: NodeId) {
+    if let Some(mut node) = old.and_then(|old| rdom.get_mut(old)) {
+        set_pseudo_class(&mut node, PseudoClass::Focus, false);
+    }
+    if let Some(mut node) = rdom.get_mut(new) {
+        set_pseudo_class(&mut node, PseudoClass::Focus, true);
+    }
+}
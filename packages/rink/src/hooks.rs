@@ -3,12 +3,15 @@ use crossterm::event::{
     MouseEventKind,
 };
 use dioxus_html::{
-    HasFileData, HasFormData, HasKeyboardData, HasWheelData, SerializedFocusData,
-    SerializedKeyboardData, SerializedMouseData, SerializedWheelData,
+    HasFileData, HasFormData, HasKeyboardData, HasWheelData, SerializedClipboardData,
+    SerializedFocusData, SerializedKeyboardData, SerializedMouseData, SerializedScrollData,
+    SerializedSelectionData, SerializedWheelData,
 };
 use dioxus_native_core::prelude::*;
 use dioxus_native_core::real_dom::NodeImmutable;
+use dioxus_native_core::tree::TreeRef;
 use rustc_hash::{FxHashMap, FxHashSet};
+use shipyard::{UniqueView, UniqueViewMut};
 
 use dioxus_html::geometry::euclid::{Point2D, Rect, Size2D};
 use dioxus_html::geometry::{
@@ -28,11 +31,18 @@ use std::{
     time::{Duration, Instant},
 };
 use taffy::geometry::{Point, Size};
+use taffy::style::Position;
 use taffy::{prelude::Layout, Taffy};
 
+use crate::clipboard::ClipboardContext;
 use crate::focus::{Focus, Focused};
-use crate::layout::TaffyLayout;
-use crate::{get_abs_layout, layout_to_screen_space, FocusState};
+use crate::layout::{PossiblyUninitalized, TaffyLayout};
+use crate::prevent_default::PreventDefault;
+use crate::pseudo_class::{set_pseudo_class, PseudoClass, HOVER_STYLE};
+use crate::scroll::{scroll_by, scroll_with_key, ScrollState};
+use crate::selection::{inside_widget, listening_ancestor, node_at, TextSelection};
+use crate::style_attributes::StyleModifier;
+use crate::{get_abs_layout, layout_to_screen_space, screen_to_layout_space, FocusState};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Event {
@@ -48,7 +58,10 @@ pub enum EventData {
     Keyboard(SerializedKeyboardData),
     Focus(SerializedFocusData),
     Wheel(SerializedWheelData),
+    Scroll(SerializedScrollData),
     Form(FormData),
+    Clipboard(SerializedClipboardData),
+    Selection(SerializedSelectionData),
 }
 
 impl EventData {
@@ -58,7 +71,10 @@ impl EventData {
             EventData::Keyboard(k) => Rc::new(k),
             EventData::Focus(f) => Rc::new(f),
             EventData::Wheel(w) => Rc::new(w),
+            EventData::Scroll(s) => Rc::new(s),
             EventData::Form(f) => Rc::new(f),
+            EventData::Clipboard(c) => Rc::new(c),
+            EventData::Selection(s) => Rc::new(s),
         }
     }
 }
@@ -105,20 +121,45 @@ pub struct InnerInputState {
     wheel: Option<SerializedWheelData>,
     last_key_pressed: Option<(SerializedKeyboardData, Instant)>,
     pub(crate) focus_state: FocusState,
+    /// The elements with :hover styles that the mouse is over
+    hovered: FxHashSet<NodeId>,
+    /// The cursor of the element under the mouse
+    cursor: Option<String>,
+    /// If the cursor changed since the terminal was last told about it
+    cursor_changed: bool,
+    /// If the text selected with the mouse changed since the last render
+    selection_changed: bool,
     // subscribers: Vec<Rc<dyn Fn() + 'static>>,
 }
 
 impl InnerInputState {
     fn create(rdom: &mut RealDom) -> Self {
+        rdom.raw_world().add_unique(TextSelection::default());
         Self {
             mouse: None,
             wheel: None,
             last_key_pressed: None,
             // subscribers: Vec::new(),
             focus_state: FocusState::create(rdom),
+            hovered: FxHashSet::default(),
+            cursor: None,
+            cursor_changed: false,
+            selection_changed: false,
         }
     }
 
+    /// Get the cursor of the element under the mouse if it changed since the last call
+    pub(crate) fn take_cursor_change(&mut self) -> Option<Option<&str>> {
+        let changed = std::mem::take(&mut self.cursor_changed);
+        changed.then_some(self.cursor.as_deref())
+    }
+
+    pub(crate) fn clean_selection(&mut self) -> bool {
+        let old = self.selection_changed;
+        self.selection_changed = false;
+        old
+    }
+
     // stores current input state and transforms events based on that state
     fn apply_event(&mut self, evt: &mut EventCore) {
         match evt.1 {
@@ -215,6 +256,25 @@ impl InnerInputState {
             self.apply_event(e);
         }
 
+        // the arrow, page, home and end keys scroll the focused scroll container
+        if let Some(id) = self.focus_state.last_focused_id {
+            let prevent_default = dom
+                .get(id)
+                .and_then(|node| node.get::<PreventDefault>().map(|p| *p))
+                == Some(PreventDefault::KeyDown);
+            if !prevent_default {
+                let mut scrolled = false;
+                for e in evts.iter() {
+                    if let EventData::Keyboard(k) = &e.1 {
+                        scrolled |= scroll_with_key(dom, layout, id, k.code());
+                    }
+                }
+                if scrolled {
+                    resolved_events.push(scroll_event(id));
+                }
+            }
+        }
+
         self.resolve_mouse_events(previous_mouse, resolved_events, layout, dom);
 
         if old_focus != self.focus_state.last_focused_id {
@@ -255,22 +315,6 @@ impl InnerInputState {
         layout: &Taffy,
         dom: &mut RealDom,
     ) {
-        fn layout_contains_point(layout: &Layout, point: ScreenPoint) -> bool {
-            let Point { x, y } = layout.location;
-            let (x, y) = (
-                layout_to_screen_space(x).round(),
-                layout_to_screen_space(y).round(),
-            );
-            let Size { width, height } = layout.size;
-            let (width, height) = (
-                layout_to_screen_space(width).round(),
-                layout_to_screen_space(height).round(),
-            );
-
-            let layout_rect = Rect::new(Point2D::new(x, y), Size2D::new(width, height));
-            layout_rect.contains(point.cast())
-        }
-
         fn try_create_event(
             name: &'static str,
             data: EventData,
@@ -523,6 +567,27 @@ impl InnerInputState {
                                 );
                             }
                         }
+
+                        // scroll the innermost scroll container under the mouse that can still scroll in that direction
+                        let mut containers = Vec::new();
+                        dom.traverse_depth_first(|node| {
+                            let scrollable =
+                                node.get::<ScrollState>().map_or(false, |s| s.scrollable());
+                            if scrollable
+                                && layout_contains_point(&get_abs_layout(node, layout), new_pos)
+                            {
+                                containers.push(node.id());
+                            }
+                        });
+                        let delta = w.delta().strip_units();
+                        let (dx, dy) = (delta.x.round() as i32, delta.y.round() as i32);
+                        if let Some(id) = containers
+                            .into_iter()
+                            .rev()
+                            .find(|id| scroll_by(dom, layout, *id, dx, dy))
+                        {
+                            resolved_events.push(scroll_event(id));
+                        }
                     }
                 }
             }
@@ -573,14 +638,82 @@ impl InnerInputState {
                 }
             }
 
+            // update the elements with :hover styles and the cursor
+            if old_pos != Some(new_pos) {
+                let top = update_hovered(&mut self.hovered, new_pos, layout, dom);
+                let cursor = top.and_then(|id| {
+                    let node = dom.get(id)?;
+                    let style = node.get::<StyleModifier>()?;
+                    style.modifier.cursor.clone()
+                });
+                if cursor != self.cursor {
+                    self.cursor = cursor;
+                    self.cursor_changed = true;
+                }
+            }
+
+            // select text by dragging with the primary button
+            {
+                let primary = DioxusMouseButton::Primary;
+                let cell = (new_pos.x.max(0.0) as u16, new_pos.y.max(0.0) as u16);
+                let dom: &RealDom = dom;
+                let mut selection: UniqueViewMut<TextSelection> = dom.raw_world().borrow().unwrap();
+                if mouse_data.held_buttons().contains(primary) {
+                    if !previous_buttons.contains(primary) {
+                        self.selection_changed |= selection.clear();
+                        // widgets like inputs handle selecting their own text
+                        let origin = node_at(dom, layout, cell);
+                        if !origin.map_or(false, |id| inside_widget(dom, id)) {
+                            selection.start(cell, origin);
+                        }
+                    } else {
+                        self.selection_changed |= selection.extend(cell);
+                    }
+                } else if previous_buttons.contains(primary) {
+                    self.selection_changed |= selection.extend(cell);
+                    if selection.finish() {
+                        let text = selection.selected_text(dom, layout);
+                        // the clipboard is only in the dom if copy on select is enabled
+                        let clipboard = dom.raw_world().borrow::<UniqueView<ClipboardContext>>();
+                        if let Ok(ctx) = &clipboard {
+                            ctx.clipboard.set_text(&text);
+                        }
+                        if let Some(origin) = selection.origin() {
+                            let mut events = vec![(
+                                "select",
+                                EventData::Selection(SerializedSelectionData::new(Some(
+                                    text.clone(),
+                                ))),
+                            )];
+                            if clipboard.is_ok() {
+                                events.push((
+                                    "copy",
+                                    EventData::Clipboard(SerializedClipboardData::new(Some(
+                                        text.clone(),
+                                    ))),
+                                ));
+                            }
+                            for (name, data) in events {
+                                if let Some(id) = listening_ancestor(dom, origin, name) {
+                                    resolved_events.push(Event {
+                                        name,
+                                        id,
+                                        data,
+                                        bubbles: event_bubbles(name),
+                                    });
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
             // update focus
             if was_released {
                 let mut focus_id = None;
                 dom.traverse_depth_first(|node| {
-                    let node_layout = layout
-                        .layout(node.get::<TaffyLayout>().unwrap().node.unwrap())
-                        .unwrap();
-                    let currently_contains = layout_contains_point(node_layout, new_pos);
+                    let node_layout = get_abs_layout(node, layout);
+                    let currently_contains = layout_contains_point(&node_layout, new_pos);
 
                     if currently_contains && node.get::<Focus>().unwrap().level.focusable() {
                         focus_id = Some(node.id());
@@ -598,6 +731,136 @@ impl InnerInputState {
     // }
 }
 
+fn scroll_event(id: NodeId) -> Event {
+    Event {
+        name: "scroll",
+        id,
+        data: EventData::Scroll(SerializedScrollData {}),
+        bubbles: event_bubbles("scroll"),
+    }
+}
+
+/// Mark the elements with :hover styles under the mouse as hovered and unmark the elements the mouse left. Returns the
+/// topmost node under the mouse
+fn update_hovered(
+    hovered: &mut FxHashSet<NodeId>,
+    mouse: ScreenPoint,
+    layout: &Taffy,
+    dom: &mut RealDom,
+) -> Option<NodeId> {
+    let mut now_hovered = FxHashSet::default();
+    let mut top = None;
+    let root = dom.root_id();
+    hit_test_hover(
+        dom,
+        layout,
+        root,
+        Point::ZERO,
+        mouse,
+        &mut now_hovered,
+        &mut top,
+    );
+
+    for id in hovered.difference(&now_hovered) {
+        if let Some(mut node) = dom.get_mut(*id) {
+            set_pseudo_class(&mut node, PseudoClass::Hover, false);
+        }
+    }
+    for id in now_hovered.difference(hovered) {
+        if let Some(mut node) = dom.get_mut(*id) {
+            set_pseudo_class(&mut node, PseudoClass::Hover, true);
+        }
+    }
+    *hovered = now_hovered;
+    top
+}
+
+/// Find the nodes with :hover styles and the topmost node under the mouse. Only the subtrees under the mouse are visited:
+/// children are inside their parent unless they are absolutely positioned, and elements with hidden overflow clip their
+/// children
+fn hit_test_hover(
+    dom: &RealDom,
+    layout: &Taffy,
+    id: NodeId,
+    parent_location: Point<f32>,
+    mouse: ScreenPoint,
+    hovered: &mut FxHashSet<NodeId>,
+    top: &mut Option<NodeId>,
+) {
+    let Some(node) = dom.get(id) else {
+        return;
+    };
+    let Some(PossiblyUninitalized::Initialized(taffy_node)) =
+        node.get::<TaffyLayout>().map(|l| l.node)
+    else {
+        return;
+    };
+    let Ok(node_layout) = layout.layout(taffy_node) else {
+        return;
+    };
+    let mut location = Point {
+        x: parent_location.x + node_layout.location.x,
+        y: parent_location.y + node_layout.location.y,
+    };
+    let contains = layout_contains_point(
+        &Layout {
+            location,
+            ..*node_layout
+        },
+        mouse,
+    );
+
+    if contains {
+        // nodes are visited in the order they are drawn, so the last node under the mouse is on top
+        *top = Some(id);
+        let has_hover_style = match &*node.node_type() {
+            NodeType::Element(el) => el.attributes.keys().any(|a| a.name == HOVER_STYLE),
+            _ => false,
+        };
+        if has_hover_style {
+            hovered.insert(id);
+        }
+    }
+
+    let scroll = node.get::<ScrollState>().map(|scroll| *scroll);
+    if !contains && scroll.map_or(false, |scroll| scroll.clips()) {
+        return;
+    }
+    // children of scroll containers move up and left as the container scrolls
+    if let Some(scroll) = scroll {
+        location.x -= screen_to_layout_space(scroll.left);
+        location.y -= screen_to_layout_space(scroll.top);
+    }
+    for child in dom.tree_ref().children_ids_advanced(id, true) {
+        let absolute = || {
+            dom.get(child).map_or(false, |child| {
+                child
+                    .get::<TaffyLayout>()
+                    .map_or(false, |l| l.style.position == Position::Absolute)
+            })
+        };
+        if contains || absolute() {
+            hit_test_hover(dom, layout, child, location, mouse, hovered, top);
+        }
+    }
+}
+
+fn layout_contains_point(layout: &Layout, point: ScreenPoint) -> bool {
+    let Point { x, y } = layout.location;
+    let (x, y) = (
+        layout_to_screen_space(x).round(),
+        layout_to_screen_space(y).round(),
+    );
+    let Size { width, height } = layout.size;
+    let (width, height) = (
+        layout_to_screen_space(width).round(),
+        layout_to_screen_space(height).round(),
+    );
+
+    let layout_rect = Rect::new(Point2D::new(x, y), Size2D::new(width, height));
+    layout_rect.contains(point.cast())
+}
+
 pub struct RinkInputHandler {
     state: Rc<RefCell<InnerInputState>>,
     queued_events: Rc<RefCell<Vec<EventCore>>>,
@@ -698,6 +961,11 @@ impl RinkInputHandler {
 fn get_event(evt: TermEvent) -> Option<(&'static str, EventData)> {
     let (name, data): (&str, EventData) = match evt {
         TermEvent::Key(k) => ("keydown", translate_key_event(k)?),
+        // pasted text is sent to the focused element when bracketed paste is enabled
+        TermEvent::Paste(text) => (
+            "paste",
+            EventData::Clipboard(SerializedClipboardData::new(Some(text))),
+        ),
         TermEvent::Mouse(m) => {
             let (x, y) = (m.column.into(), m.row.into());
             let alt = m.modifiers.contains(KeyModifiers::ALT);
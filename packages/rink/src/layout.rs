@@ -10,6 +10,10 @@ use dioxus_native_core::prelude::*;
 use dioxus_native_core_macro::partial_derive_state;
 use taffy::prelude::*;
 
+use crate::pseudo_class::{
+    for_each_active_declaration, FOCUSED, FOCUS_STYLE, HOVERED, HOVER_STYLE,
+};
+use crate::scroll::{Overflow, ScrollState};
 use crate::{screen_to_layout_space, unit_to_layout_space};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -42,10 +46,11 @@ pub(crate) struct TaffyLayout {
 impl State for TaffyLayout {
     type ChildDependencies = (Self,);
     type ParentDependencies = ();
-    type NodeDependencies = ();
+    type NodeDependencies = (ScrollState,);
 
     const NODE_MASK: NodeMaskBuilder<'static> = NodeMaskBuilder::new()
         .with_attrs(AttributeMaskBuilder::Some(SORTED_LAYOUT_ATTRS))
+        .with_tag()
         .with_text();
 
     // The layout state should be effected by the shadow dom
@@ -54,7 +59,7 @@ impl State for TaffyLayout {
     fn update<'a>(
         &mut self,
         node_view: NodeView,
-        _: <Self::NodeDependencies as Dependancy>::ElementBorrowed<'a>,
+        (scroll,): <Self::NodeDependencies as Dependancy>::ElementBorrowed<'a>,
         _: Option<<Self::ParentDependencies as Dependancy>::ElementBorrowed<'a>>,
         children: Vec<<Self::ChildDependencies as Dependancy>::ElementBorrowed<'a>>,
         ctx: &SendAnyMap,
@@ -86,26 +91,55 @@ impl State for TaffyLayout {
                 changed = true;
             }
         } else {
+            let config = LayoutConfigeration {
+                border_widths: BorderWidths {
+                    thin: 1.0,
+                    medium: 1.0,
+                    thick: 1.0,
+                },
+            };
+
+            // tables are laid out with flexbox: rows stack vertically and the cells in a row share its width
+            if let Some(tag) = node_view.tag() {
+                apply_table_layout(tag, &node_view, &mut style);
+            }
+
             // gather up all the styles from the attribute list
             if let Some(attributes) = node_view.attributes() {
+                let mut attributes: Vec<_> = attributes
+                    .filter(|OwnedAttributeView { value, .. }| value.as_custom().is_none())
+                    .collect();
+                // display sets the flex direction of block elements, so it must be applied before flex-direction
+                attributes.sort_by_key(|OwnedAttributeView { attribute, .. }| {
+                    attribute.name != "display"
+                });
                 for OwnedAttributeView {
                     attribute, value, ..
                 } in attributes
                 {
-                    if value.as_custom().is_none() {
-                        apply_layout_attributes_cfg(
-                            &attribute.name,
-                            &value.to_string(),
-                            &mut style,
-                            &LayoutConfigeration {
-                                border_widths: BorderWidths {
-                                    thin: 1.0,
-                                    medium: 1.0,
-                                    thick: 1.0,
-                                },
-                            },
-                        );
-                    }
+                    apply_layout_attributes_cfg(
+                        &attribute.name,
+                        &value.to_string(),
+                        &mut style,
+                        &config,
+                    );
+                }
+            }
+
+            // apply the styles of the active :hover and :focus states last so they override the normal styles
+            for_each_active_declaration(&node_view, |name, value| {
+                apply_layout_attributes_cfg(name, value, &mut style, &config);
+            });
+
+            // overflow: scroll always shows a scrollbar, so reserve space for it inside the padding
+            if scroll.y == Overflow::Scroll {
+                if let LengthPercentage::Points(p) = &mut style.padding.right {
+                    *p += 1.0;
+                }
+            }
+            if scroll.x == Overflow::Scroll {
+                if let LengthPercentage::Points(p) = &mut style.padding.bottom {
+                    *p += 1.0;
                 }
             }
 
@@ -223,6 +257,50 @@ impl State for TaffyLayout {
     }
 }
 
+/// Apply the default layout of table elements. Cells without an explicit width split the row evenly, weighted by their colspan.
+fn apply_table_layout(tag: &str, node_view: &NodeView, style: &mut Style) {
+    match tag {
+        "table" | "thead" | "tbody" | "tfoot" => {
+            style.display = Display::Flex;
+            style.flex_direction = FlexDirection::Column;
+        }
+        "tr" => {
+            style.display = Display::Flex;
+            style.flex_direction = FlexDirection::Row;
+        }
+        "td" | "th" => {
+            let mut colspan = 1.0;
+            let mut has_width = false;
+            if let Some(attributes) = node_view.attributes() {
+                for OwnedAttributeView {
+                    attribute, value, ..
+                } in attributes
+                {
+                    match attribute.name.as_str() {
+                        "colspan" => {
+                            if let Some(span) = value
+                                .as_text()
+                                .and_then(|span| span.trim().parse::<f32>().ok())
+                                .or_else(|| value.as_int().map(|span| span as f32))
+                                .or_else(|| value.as_float().map(|span| span as f32))
+                            {
+                                colspan = span.max(1.0);
+                            }
+                        }
+                        "width" => has_width = true,
+                        _ => {}
+                    }
+                }
+            }
+            if !has_width {
+                style.flex_basis = Dimension::Points(0.0);
+                style.flex_grow = colspan;
+            }
+        }
+        _ => {}
+    }
+}
+
 // these are the attributes in layout_attiributes in native-core
 const SORTED_LAYOUT_ATTRS: &[&str] = &[
     "align-content",
@@ -237,6 +315,7 @@ const SORTED_LAYOUT_ATTRS: &[&str] = &[
     "animation-name",
     "animation-play-state",
     "animation-timing-function",
+    "aspect-ratio",
     "backface-visibility",
     "border",
     "border-bottom",
@@ -277,6 +356,7 @@ const SORTED_LAYOUT_ATTRS: &[&str] = &[
     "caption-side",
     "clear",
     "clip",
+    "colspan",
     "column-count",
     "column-fill",
     "column-gap",
@@ -377,4 +457,8 @@ const SORTED_LAYOUT_ATTRS: &[&str] = &[
     "word-spacing",
     "word-wrap",
     "z-index",
+    HOVER_STYLE,
+    FOCUS_STYLE,
+    HOVERED,
+    FOCUSED,
 ];
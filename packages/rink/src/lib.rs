@@ -4,9 +4,13 @@
 
 use crate::focus::Focus;
 use anyhow::Result;
+use clipboard::ClipboardContext;
 use crossterm::{
     cursor::{MoveTo, RestorePosition, SavePosition, Show},
-    event::{DisableMouseCapture, EnableMouseCapture, Event as TermEvent, KeyCode, KeyModifiers},
+    event::{
+        DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event as TermEvent, KeyCode, KeyModifiers,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -17,7 +21,12 @@ use futures::{channel::mpsc::UnboundedSender, pin_mut, Future, StreamExt};
 use futures_channel::mpsc::unbounded;
 use layout::TaffyLayout;
 use prevent_default::PreventDefault;
-use ratatui::{backend::CrosstermBackend, Terminal};
+use ratatui::{
+    backend::{CrosstermBackend, TestBackend},
+    buffer::Buffer,
+    Terminal,
+};
+use scroll::ScrollState;
 use std::{io, time::Duration};
 use std::{
     pin::Pin,
@@ -27,23 +36,34 @@ use std::{rc::Rc, sync::RwLock};
 use style_attributes::StyleModifier;
 pub use taffy::{geometry::Point, prelude::*};
 use tokio::select;
+use widget::Viewport;
 use widgets::{register_widgets, RinkWidgetResponder, RinkWidgetTraitObject};
 
+mod clipboard;
 mod config;
 mod focus;
 mod hooks;
 mod layout;
 mod prevent_default;
+mod pseudo_class;
 pub mod query;
 mod render;
+mod scroll;
+mod selection;
+mod snapshot;
 mod style;
 mod style_attributes;
+mod terminfo;
+mod theme;
 mod widget;
 mod widgets;
 
+pub use clipboard::{Clipboard, Osc52Clipboard};
 pub use config::*;
 pub use hooks::*;
 pub use query::Query;
+pub use snapshot::*;
+pub use theme::Theme;
 
 // the layout space has a multiplier of 10 to minimize rounding errors
 pub(crate) fn screen_to_layout_space(screen: u16) -> f32 {
@@ -87,7 +107,43 @@ pub fn render<R: Driver>(
         UnboundedSender<InputEvent>,
     ) -> R,
 ) -> Result<()> {
+    run(cfg, None, create_renderer).map(|_| ())
+}
+
+/// Render into a virtual terminal of a fixed size without touching the real terminal. Events can be sent to the app with [`TuiContext::inject_event`].
+///
+/// Returns the last frame that was drawn before the app quit with [`TuiContext::quit`], or an error if the app doesn't quit
+/// within [`SNAPSHOT_TIMEOUT`].
+pub fn render_snapshot<R: Driver>(
+    cfg: Config,
+    width: u16,
+    height: u16,
+    create_renderer: impl FnOnce(
+        &Arc<RwLock<RealDom>>,
+        &Arc<Mutex<Taffy>>,
+        UnboundedSender<InputEvent>,
+    ) -> R,
+) -> Result<Snapshot> {
+    let area = ratatui::layout::Rect::new(0, 0, width, height);
+    let buffer = run(cfg.with_headless(), Some(area), create_renderer)?
+        .unwrap_or_else(|| Buffer::empty(area));
+    Ok(Snapshot::from_buffer(&buffer))
+}
+
+/// Run the app until it quits. If a virtual size is passed, the app is drawn to a virtual terminal of that size and the last frame is returned
+fn run<R: Driver>(
+    cfg: Config,
+    virtual_size: Option<ratatui::layout::Rect>,
+    create_renderer: impl FnOnce(
+        &Arc<RwLock<RealDom>>,
+        &Arc<Mutex<Taffy>>,
+        UnboundedSender<InputEvent>,
+    ) -> R,
+) -> Result<Option<Buffer>> {
+    let cfg = cfg.resolve();
+    let theme = Arc::new(cfg.resolve_theme());
     let mut rdom = RealDom::new([
+        ScrollState::to_type_erased(),
         TaffyLayout::to_type_erased(),
         Focus::to_type_erased(),
         StyleModifier::to_type_erased(),
@@ -128,11 +184,15 @@ pub fn render<R: Driver>(
     let taffy = Arc::new(Mutex::new(Taffy::new()));
     let mut renderer = create_renderer(&rdom, &taffy, event_tx_clone);
 
-    // insert the query engine into the rdom
+    // insert the query engine and clipboard into the rdom
     let query_engine = Query::new(rdom.clone(), taffy.clone());
     {
         let mut rdom = rdom.write().unwrap();
         rdom.raw_world_mut().add_unique(query_engine);
+        if let Some(clipboard) = cfg.clipboard.filter(|_| cfg.copy_on_select) {
+            rdom.raw_world_mut()
+                .add_unique(ClipboardContext { clipboard });
+        }
     }
 
     tokio::runtime::Builder::new_current_thread()
@@ -143,6 +203,7 @@ pub fn render<R: Driver>(
                 renderer.update(&rdom);
                 let mut any_map = SendAnyMap::new();
                 any_map.insert(taffy.clone());
+                any_map.insert(theme.clone());
                 let mut rdom = rdom.write().unwrap();
                 let _ = rdom.update_state(any_map);
             }
@@ -154,6 +215,7 @@ pub fn render<R: Driver>(
                     stdout,
                     EnterAlternateScreen,
                     EnableMouseCapture,
+                    EnableBracketedPaste,
                     MoveTo(0, 1000)
                 )
                 .unwrap();
@@ -163,10 +225,20 @@ pub fn render<R: Driver>(
             if let Some(terminal) = &mut terminal {
                 terminal.clear().unwrap();
             }
+            let mut virtual_terminal = virtual_size.map(|size| {
+                Terminal::new(TestBackend::new(size.width, size.height))
+                    .expect("failed to create the virtual terminal")
+            });
 
             let mut to_rerender = FxDashSet::default();
             to_rerender.insert(rdom.read().unwrap().root_id());
             let mut updated = true;
+            // if any state changed since the last layout. Some changes, like scroll offsets, don't dirty taffy
+            let mut state_changed = true;
+            // if the mouse cursor was changed and needs to be reset when the app exits
+            let mut cursor_set = false;
+            // apps drawn to a virtual terminal for a snapshot fail instead of running forever if they never quit
+            let deadline = virtual_size.map(|_| tokio::time::Instant::now() + SNAPSHOT_TIMEOUT);
 
             loop {
                 /*
@@ -180,45 +252,12 @@ pub fn render<R: Driver>(
 
                 if !to_rerender.is_empty() || updated {
                     updated = false;
-                    fn resize(dims: ratatui::layout::Rect, taffy: &mut Taffy, rdom: &RealDom) {
-                        let width = screen_to_layout_space(dims.width);
-                        let height = screen_to_layout_space(dims.height);
-                        let root_node = rdom
-                            .get(rdom.root_id())
-                            .unwrap()
-                            .get::<TaffyLayout>()
-                            .unwrap()
-                            .node
-                            .unwrap();
-
-                        // the root node fills the entire area
-                        let mut style = taffy.style(root_node).unwrap().clone();
-                        let new_size = Size {
-                            width: Dimension::Points(width),
-                            height: Dimension::Points(height),
-                        };
-                        if style.size != new_size {
-                            style.size = new_size;
-                            taffy.set_style(root_node, style).unwrap();
-                        }
-
-                        let size = Size {
-                            width: AvailableSpace::Definite(width),
-                            height: AvailableSpace::Definite(height),
-                        };
-                        taffy.compute_layout(root_node, size).unwrap();
-                    }
                     if let Some(terminal) = &mut terminal {
                         execute!(terminal.backend_mut(), SavePosition).unwrap();
-                        terminal.draw(|frame| {
-                            let rdom = rdom.write().unwrap();
-                            let mut taffy = taffy.lock().expect("taffy lock poisoned");
-                            // size is guaranteed to not change when rendering
-                            resize(frame.size(), &mut taffy, &rdom);
-                            let root = rdom.get(rdom.root_id()).unwrap();
-                            render::render_vnode(frame, &taffy, root, cfg, Point::ZERO);
-                        })?;
+                        terminal.draw(|frame| draw(frame, &rdom, &taffy, &cfg, state_changed))?;
                         execute!(terminal.backend_mut(), RestorePosition, Show).unwrap();
+                    } else if let Some(terminal) = &mut virtual_terminal {
+                        terminal.draw(|frame| draw(frame, &rdom, &taffy, &cfg, state_changed))?;
                     } else {
                         let rdom = rdom.read().unwrap();
                         resize(
@@ -230,8 +269,10 @@ pub fn render<R: Driver>(
                             },
                             &mut taffy.lock().expect("taffy lock poisoned"),
                             &rdom,
+                            state_changed,
                         );
                     }
+                    state_changed = false;
                 }
 
                 let mut event_recieved = None;
@@ -243,6 +284,11 @@ pub fn render<R: Driver>(
                     select! {
                         _ = wait => {
 
+                        },
+                        _ = wait_until(deadline) => {
+                            return Err(anyhow::anyhow!(
+                                "the app did not quit within {SNAPSHOT_TIMEOUT:?}. Call TuiContext::quit once the frame to snapshot is drawn"
+                            ));
                         },
                         evt = raw_event_reciever.next() => {
                             match evt.as_ref().unwrap() {
@@ -287,6 +333,13 @@ pub fn render<R: Driver>(
                             &mut rdom.write().unwrap(),
                         );
                         updated |= handler.state().focus_state.clean();
+                        updated |= handler.state().clean_selection();
+                        if let Some(cursor) = handler.state().take_cursor_change() {
+                            if let Some(terminal) = &mut terminal {
+                                set_mouse_cursor(terminal.backend_mut(), cursor)?;
+                                cursor_set = true;
+                            }
+                        }
 
                         for e in evts {
                             bubble_event_to_widgets(&mut rdom.write().unwrap(), &e);
@@ -299,8 +352,10 @@ pub fn render<R: Driver>(
                     let mut rdom = rdom.write().unwrap();
                     let mut any_map = SendAnyMap::new();
                     any_map.insert(taffy.clone());
+                    any_map.insert(theme.clone());
                     let (new_to_rerender, dirty) = rdom.update_state(any_map);
                     to_rerender = new_to_rerender;
+                    state_changed |= !dirty.is_empty();
                     let text_mask = NodeMaskBuilder::new().with_text().build();
                     for (id, mask) in dirty {
                         if mask.overlaps(&text_mask) {
@@ -311,19 +366,93 @@ pub fn render<R: Driver>(
             }
 
             if let Some(terminal) = &mut terminal {
+                if cursor_set {
+                    set_mouse_cursor(terminal.backend_mut(), None)?;
+                }
                 disable_raw_mode()?;
                 execute!(
                     terminal.backend_mut(),
                     LeaveAlternateScreen,
-                    DisableMouseCapture
+                    DisableMouseCapture,
+                    DisableBracketedPaste
                 )?;
                 terminal.show_cursor()?;
             }
 
-            Ok(())
+            Ok(virtual_terminal.map(|terminal| terminal.backend().buffer().clone()))
         })
 }
 
+/// Wait until the deadline, or forever if there is no deadline
+async fn wait_until(deadline: Option<tokio::time::Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Set the shape of the mouse pointer with OSC 22. Terminals that don't support it ignore the sequence
+fn set_mouse_cursor(out: &mut impl io::Write, cursor: Option<&str>) -> io::Result<()> {
+    let name = match cursor {
+        None | Some("auto") => "default",
+        Some(name) => name,
+    };
+    write!(out, "\x1b]22;{name}\x1b\\")?;
+    out.flush()
+}
+
+fn resize(dims: ratatui::layout::Rect, taffy: &mut Taffy, rdom: &RealDom, state_changed: bool) {
+    let width = screen_to_layout_space(dims.width);
+    let height = screen_to_layout_space(dims.height);
+    let root_node = rdom
+        .get(rdom.root_id())
+        .unwrap()
+        .get::<TaffyLayout>()
+        .unwrap()
+        .node
+        .unwrap();
+
+    // the root node fills the entire area
+    let mut style = taffy.style(root_node).unwrap().clone();
+    let new_size = Size {
+        width: Dimension::Points(width),
+        height: Dimension::Points(height),
+    };
+    if style.size != new_size {
+        style.size = new_size;
+        taffy.set_style(root_node, style).unwrap();
+    }
+
+    // taffy marks every ancestor of a changed node dirty. If the root is clean and no state changed, nothing in the tree
+    // changed and the last layout is still correct
+    if !state_changed && !taffy.dirty(root_node).unwrap_or(true) {
+        return;
+    }
+
+    // clean subtrees are read from taffy's cache, so only the dirty nodes are laid out again
+    let size = Size {
+        width: AvailableSpace::Definite(width),
+        height: AvailableSpace::Definite(height),
+    };
+    taffy.compute_layout(root_node, size).unwrap();
+}
+
+fn draw(
+    frame: &mut ratatui::Frame,
+    rdom: &Arc<RwLock<RealDom>>,
+    taffy: &Arc<Mutex<Taffy>>,
+    cfg: &Config,
+    state_changed: bool,
+) {
+    let rdom = rdom.write().unwrap();
+    let mut taffy = taffy.lock().expect("taffy lock poisoned");
+    // size is guaranteed to not change when rendering
+    resize(frame.size(), &mut taffy, &rdom, state_changed);
+    let root = rdom.get(rdom.root_id()).unwrap();
+    let viewport = Viewport::new(frame.size());
+    render::render_vnode(frame, &taffy, root, cfg, Point::ZERO, viewport);
+}
+
 #[derive(Debug)]
 pub enum InputEvent {
     UserInput(TermEvent),
@@ -390,6 +519,12 @@ pub(crate) fn get_abs_layout(node: NodeRef, taffy: &Taffy) -> Layout {
             .unwrap();
         node_layout.location.x += parent_layout.location.x;
         node_layout.location.y += parent_layout.location.y;
+        // children of scroll containers move up and left as the container scrolls
+        let scroll = parent.get::<ScrollState>().map(|scroll| *scroll);
+        if let Some(scroll) = scroll {
+            node_layout.location.x -= screen_to_layout_space(scroll.left);
+            node_layout.location.y -= screen_to_layout_space(scroll.top);
+        }
     }
     node_layout
 }
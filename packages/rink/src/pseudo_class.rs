@@ -0,0 +1,112 @@
+//! Styles that only apply while an element is hovered or focused, like the `:hover` and `:focus` pseudo-classes on web
+//!
+//! The `hover-style` and `focus-style` attributes take a list of CSS declarations in the same format as the `style`
+//! attribute:
+//!
+//! ```rust, ignore
+//! rsx! {
+//!     button {
+//!         "hover-style": "background-color: blue; color: white",
+//!         "focus-style": "border-style: double",
+//!         "Click me"
+//!     }
+//! }
+//! ```
+//!
+//! Rink marks elements that are hovered or focused with an attribute, so the layout and style states update the same
+//! way they do when any other attribute changes.
+
+use dioxus_native_core::{
+    node::{OwnedAttributeDiscription, OwnedAttributeView},
+    node_ref::NodeView,
+    real_dom::{NodeMut, NodeTypeMut},
+};
+
+pub(crate) const HOVER_STYLE: &str = "hover-style";
+pub(crate) const FOCUS_STYLE: &str = "focus-style";
+pub(crate) const HOVERED: &str = "rink-hovered";
+pub(crate) const FOCUSED: &str = "rink-focused";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum PseudoClass {
+    Hover,
+    Focus,
+}
+
+impl PseudoClass {
+    /// The attribute rink sets while the pseudo-class is active
+    fn marker(self) -> &'static str {
+        match self {
+            PseudoClass::Hover => HOVERED,
+            PseudoClass::Focus => FOCUSED,
+        }
+    }
+
+    /// The attribute with the styles for the pseudo-class
+    fn style_attribute(self) -> &'static str {
+        match self {
+            PseudoClass::Hover => HOVER_STYLE,
+            PseudoClass::Focus => FOCUS_STYLE,
+        }
+    }
+}
+
+/// Mark a node as hovered or focused. This only marks the node as dirty if the state changed
+pub(crate) fn set_pseudo_class(node: &mut NodeMut, class: PseudoClass, active: bool) {
+    if let NodeTypeMut::Element(mut el) = node.node_type_mut() {
+        let name = OwnedAttributeDiscription {
+            name: class.marker().to_string(),
+            namespace: None,
+        };
+        let was_active = el.get_attribute(&name).is_some();
+        if active && !was_active {
+            el.set_attribute(name, "true".to_string());
+        } else if !active && was_active {
+            el.remove_attribute(&name);
+        }
+    }
+}
+
+/// Call `f` with the name and value of every declaration in the styles of the pseudo-classes that are active on the node.
+/// Focus styles are applied after hover styles, so they win if both set the same property
+pub(crate) fn for_each_active_declaration(node_view: &NodeView, mut f: impl FnMut(&str, &str)) {
+    let Some(attributes) = node_view.attributes() else {
+        return;
+    };
+    let attributes: Vec<OwnedAttributeView> = attributes.collect();
+    let is_set = |name: &str| attributes.iter().any(|a| a.attribute.name == name);
+
+    for class in [PseudoClass::Hover, PseudoClass::Focus] {
+        if !is_set(class.marker()) {
+            continue;
+        }
+        let styles = attributes
+            .iter()
+            .find(|a| a.attribute.name == class.style_attribute())
+            .and_then(|a| a.value.as_text());
+        if let Some(styles) = styles {
+            for (name, value) in parse_declarations(styles) {
+                f(name, value);
+            }
+        }
+    }
+}
+
+/// Split a list of CSS declarations like `color: red; width: 10px` into names and values
+fn parse_declarations(styles: &str) -> impl Iterator<Item = (&str, &str)> {
+    styles.split(';').filter_map(|declaration| {
+        let (name, value) = declaration.split_once(':')?;
+        let (name, value) = (name.trim(), value.trim());
+        (!name.is_empty() && !value.is_empty()).then_some((name, value))
+    })
+}
+
+#[test]
+fn parses_declarations() {
+    let declarations: Vec<_> =
+        parse_declarations("color: red; background-color:blue ;; width:").collect();
+    assert_eq!(
+        declarations,
+        [("color", "red"), ("background-color", "blue")]
+    );
+}
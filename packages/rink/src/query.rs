@@ -8,7 +8,7 @@ use taffy::{
     Taffy,
 };
 
-use crate::{get_abs_layout, layout_to_screen_space};
+use crate::{get_abs_layout, layout_to_screen_space, scroll::scroll_into_view};
 
 /// Allows querying the layout of nodes after rendering. It will only provide a correct value after a node is rendered.
 /// Provided as a root context for all tui applictions.
@@ -61,6 +61,17 @@ impl Query {
             id,
         )
     }
+
+    /// Scroll the scroll containers around a node so the node is visible. Returns false if the node doesn't exist
+    pub fn scroll_into_view(&self, id: NodeId) -> bool {
+        let mut rdom = self.rdom.write().expect("rdom lock poisoned");
+        let stretch = self.stretch.lock().expect("taffy lock poisoned");
+        if rdom.get(id).is_none() {
+            return false;
+        }
+        scroll_into_view(&mut rdom, &stretch, id);
+        true
+    }
 }
 
 pub struct ElementRef<'a> {
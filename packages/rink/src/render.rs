@@ -1,5 +1,9 @@
 use dioxus_native_core::{prelude::*, tree::TreeRef};
-use ratatui::{layout::Rect, style::Color};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier},
+};
+use shipyard::UniqueView;
 use taffy::{
     geometry::Point,
     prelude::{Dimension, Layout, Size},
@@ -9,10 +13,12 @@ use taffy::{
 use crate::{
     focus::Focused,
     layout::TaffyLayout,
-    layout_to_screen_space,
+    layout_to_screen_space, screen_to_layout_space,
+    scroll::{resolve_edges, scroll_metrics, Overflow, ScrollMetrics, ScrollState},
+    selection::TextSelection,
     style::{RinkColor, RinkStyle},
     style_attributes::{BorderEdge, BorderStyle, StyleModifier},
-    widget::{RinkBuffer, RinkCell, RinkWidget, WidgetWithContext},
+    widget::{RinkBuffer, RinkCell, RinkWidget, Viewport, WidgetWithContext},
     Config,
 };
 
@@ -22,8 +28,9 @@ pub(crate) fn render_vnode(
     frame: &mut ratatui::Frame,
     layout: &Taffy,
     node: NodeRef,
-    cfg: Config,
+    cfg: &Config,
     parent_location: Point<f32>,
+    viewport: Viewport,
 ) {
     if let NodeType::Placeholder = &*node.node_type() {
         return;
@@ -46,53 +53,241 @@ pub(crate) fn render_vnode(
 
     match &*node.node_type() {
         NodeType::Text(text) => {
-            #[derive(Default)]
             struct Label<'a> {
                 text: &'a str,
                 style: RinkStyle,
+                selection: Option<&'a TextSelection>,
+                offset: [i32; 2],
             }
 
             impl<'a> RinkWidget for Label<'a> {
                 fn render(self, area: Rect, mut buf: RinkBuffer) {
-                    for (i, c) in self.text.char_indices() {
+                    for (i, c) in self.text.chars().enumerate() {
+                        let x = area.left() + i as u16;
                         let mut new_cell = RinkCell::default();
                         new_cell.set_style(self.style);
                         new_cell.symbol = c.to_string();
-                        buf.set(area.left() + i as u16, area.top(), new_cell);
+                        // text selected with the mouse is drawn with the colors swapped
+                        let screen_x = x as i32 - self.offset[0];
+                        let screen_y = area.top() as i32 - self.offset[1];
+                        if let (Some(selection), Ok(screen_x), Ok(screen_y)) = (
+                            self.selection,
+                            u16::try_from(screen_x),
+                            u16::try_from(screen_y),
+                        ) {
+                            if selection.contains(screen_x, screen_y) {
+                                new_cell.modifier.insert(Modifier::REVERSED);
+                            }
+                        }
+                        buf.set(x, area.top(), new_cell);
                     }
                 }
             }
 
+            let selection: Option<UniqueView<TextSelection>> =
+                node.real_dom().raw_world().borrow().ok();
             let label = Label {
                 text: &text.text,
                 style: node.get::<StyleModifier>().unwrap().core,
+                selection: selection.as_deref(),
+                offset: viewport.offset,
             };
             let area = Rect::new(x, y, width, height);
 
             // the renderer will panic if a node is rendered out of range even if the size is zero
-            if area.width > 0 && area.height > 0 {
-                frame.render_widget(WidgetWithContext::new(label, cfg), area);
+            if area.width > 0 && area.height > 0 && in_viewport(area, viewport) {
+                frame.render_widget(
+                    WidgetWithContext::new(label, cfg.rendering_mode).with_viewport(viewport),
+                    area,
+                );
             }
         }
         NodeType::Element { .. } => {
             let area = Rect::new(x, y, width, height);
 
             // the renderer will panic if a node is rendered out of range even if the size is zero
-            if area.width > 0 && area.height > 0 {
-                frame.render_widget(WidgetWithContext::new(node, cfg), area);
+            if area.width > 0 && area.height > 0 && in_viewport(area, viewport) {
+                frame.render_widget(
+                    WidgetWithContext::new(node, cfg.rendering_mode).with_viewport(viewport),
+                    area,
+                );
             }
 
+            let scroll = *node.get::<ScrollState>().unwrap();
+            let inner = padding_box(node, area);
+            let child_viewport = if scroll.clips() {
+                scrolled_viewport(inner, scroll, viewport)
+            } else {
+                viewport
+            };
+
             let node_id = node.id();
             let rdom = node.real_dom();
             for child_id in rdom.tree_ref().children_ids_advanced(node_id, true) {
                 let c = rdom.get(child_id).unwrap();
-                render_vnode(frame, layout, c, cfg, location);
+                render_vnode(frame, layout, c, cfg, location, child_viewport);
+            }
+
+            // scrollbars are drawn on top of the children
+            if scroll.clips() && area.width > 0 && area.height > 0 && in_viewport(area, viewport) {
+                let scrollbars = Scrollbars {
+                    state: scroll,
+                    metrics: scroll_metrics(&node, layout),
+                    inner,
+                    style: node.get::<StyleModifier>().unwrap().core,
+                };
+                frame.render_widget(
+                    WidgetWithContext::new(scrollbars, cfg.rendering_mode).with_viewport(viewport),
+                    area,
+                );
             }
         }
         NodeType::Placeholder => unreachable!(),
     }
 }
 
+/// Check if any part of an area is visible through the viewport
+fn in_viewport(area: Rect, viewport: Viewport) -> bool {
+    let x = area.x as i32 - viewport.offset[0];
+    let y = area.y as i32 - viewport.offset[1];
+    let clip = viewport.clip;
+    x < clip.right() as i32
+        && x + area.width as i32 > clip.x as i32
+        && y < clip.bottom() as i32
+        && y + area.height as i32 > clip.y as i32
+}
+
+/// The padding box of an element: the area inside its borders
+fn padding_box(node: NodeRef, area: Rect) -> Rect {
+    let style = &node.get::<TaffyLayout>().unwrap().style;
+    let border = resolve_edges(&style.border, screen_to_layout_space(area.width));
+    let to_cells = |edge: f32| layout_to_screen_space(edge).round() as u16;
+    let (left, right) = (to_cells(border.left), to_cells(border.right));
+    let (top, bottom) = (to_cells(border.top), to_cells(border.bottom));
+
+    Rect::new(
+        area.x + left,
+        area.y + top,
+        area.width.saturating_sub(left + right),
+        area.height.saturating_sub(top + bottom),
+    )
+}
+
+/// The viewport of the children of a scroll container: they are clipped to the padding box of the container and moved
+/// by its scroll offset
+fn scrolled_viewport(inner: Rect, scroll: ScrollState, viewport: Viewport) -> Viewport {
+    // the padding box of the container on the screen, clipped to the viewport of the container
+    let clip = viewport.clip;
+    let left = (inner.left() as i32 - viewport.offset[0]).max(clip.left() as i32);
+    let top = (inner.top() as i32 - viewport.offset[1]).max(clip.top() as i32);
+    let right = (inner.right() as i32 - viewport.offset[0]).min(clip.right() as i32);
+    let bottom = (inner.bottom() as i32 - viewport.offset[1]).min(clip.bottom() as i32);
+
+    Viewport {
+        offset: [
+            viewport.offset[0] + scroll.left as i32,
+            viewport.offset[1] + scroll.top as i32,
+        ],
+        clip: Rect::new(
+            left.max(0) as u16,
+            top.max(0) as u16,
+            (right - left).max(0) as u16,
+            (bottom - top).max(0) as u16,
+        ),
+    }
+}
+
+/// The scrollbars of a scroll container. The vertical scrollbar is drawn in the rightmost column of the padding box and
+/// the horizontal scrollbar in the bottom row
+struct Scrollbars {
+    state: ScrollState,
+    metrics: ScrollMetrics,
+    /// The padding box of the container
+    inner: Rect,
+    style: RinkStyle,
+}
+
+impl RinkWidget for Scrollbars {
+    fn render(self, _: Rect, mut buf: RinkBuffer) {
+        const TRACK_VERTICAL: &str = "│";
+        const TRACK_HORIZONTAL: &str = "─";
+        const THUMB: &str = "█";
+
+        let Scrollbars {
+            state,
+            metrics,
+            inner,
+            style,
+        } = self;
+        if inner.area() == 0 {
+            return;
+        }
+
+        // overflow: auto only shows a scrollbar when the content doesn't fit
+        let vertical =
+            state.y == Overflow::Scroll || (state.y == Overflow::Auto && metrics.max_top() > 0);
+        let horizontal =
+            state.x == Overflow::Scroll || (state.x == Overflow::Auto && metrics.max_left() > 0);
+
+        // the position and length of the thumb in a track
+        let thumb = |track: u16, viewport: u16, content: u16, offset: u16, max_offset: u16| {
+            let length = ((viewport as u32 * track as u32) / (content.max(1) as u32))
+                .clamp(1, track as u32) as u16;
+            let position = if max_offset == 0 {
+                0
+            } else {
+                ((offset as u32 * (track - length) as u32) / max_offset as u32) as u16
+            };
+            position..position + length
+        };
+
+        let mut cell = RinkCell::default();
+        cell.set_style(style);
+
+        if vertical {
+            let x = inner.right() - 1;
+            let track = inner.height - u16::from(horizontal && inner.height > 1);
+            let thumb = thumb(
+                track,
+                metrics.viewport.height,
+                metrics.content.height,
+                state.top,
+                metrics.max_top(),
+            );
+            for i in 0..track {
+                cell.symbol = if thumb.contains(&i) {
+                    THUMB
+                } else {
+                    TRACK_VERTICAL
+                }
+                .to_string();
+                buf.set(x, inner.top() + i, cell.clone());
+            }
+        }
+
+        if horizontal {
+            let y = inner.bottom() - 1;
+            let track = inner.width - u16::from(vertical && inner.width > 1);
+            let thumb = thumb(
+                track,
+                metrics.viewport.width,
+                metrics.content.width,
+                state.left,
+                metrics.max_left(),
+            );
+            for i in 0..track {
+                cell.symbol = if thumb.contains(&i) {
+                    THUMB
+                } else {
+                    TRACK_HORIZONTAL
+                }
+                .to_string();
+                buf.set(inner.left() + i, y, cell.clone());
+            }
+        }
+    }
+}
+
 impl RinkWidget for NodeRef<'_> {
     fn render(self, area: Rect, mut buf: RinkBuffer<'_>) {
         use ratatui::symbols::line::*;
@@ -265,6 +460,33 @@ impl RinkWidget for NodeRef<'_> {
             return;
         }
 
+        // the shadow is drawn first so the element covers it
+        if let Some(shadow) = self.get::<StyleModifier>().unwrap().modifier.box_shadow {
+            let color = shadow
+                .color
+                .or(self.get::<StyleModifier>().unwrap().core.fg);
+            let new_cell = RinkCell {
+                bg: color.unwrap_or(RinkColor {
+                    color: Color::Black,
+                    alpha: 255,
+                }),
+                ..Default::default()
+            };
+            for x in area.left()..area.right() {
+                for y in area.top()..area.bottom() {
+                    let shadow_x = x as i32 + shadow.offset[0] as i32;
+                    let shadow_y = y as i32 + shadow.offset[1] as i32;
+                    if let (Ok(shadow_x), Ok(shadow_y)) =
+                        (u16::try_from(shadow_x), u16::try_from(shadow_y))
+                    {
+                        if !area.intersects(Rect::new(shadow_x, shadow_y, 1, 1)) {
+                            buf.set(shadow_x, shadow_y, new_cell.clone());
+                        }
+                    }
+                }
+            }
+        }
+
         // todo: only render inside borders
         for x in area.left()..area.right() {
             for y in area.top()..area.bottom() {
@@ -425,5 +647,38 @@ impl RinkWidget for NodeRef<'_> {
                 &color,
             );
         }
+
+        // the outline is drawn in the cells around the element, outside of its layout
+        let outline = &style.modifier.outline;
+        if let Some(symbols) = outline.style.symbol_set().filter(|_| outline.is_visible()) {
+            let gap = outline.offset as i32 + 1;
+            let left = area.left() as i32 - gap;
+            let top = area.top() as i32 - gap;
+            let right = area.right() as i32 - 1 + gap;
+            let bottom = area.bottom() as i32 - 1 + gap;
+
+            let mut new_cell = RinkCell::default();
+            if let Some(c) = outline.color.or(style.core.fg) {
+                new_cell.fg = c;
+            }
+            let mut set = |x: i32, y: i32, symbol: &str| {
+                if let (Ok(x), Ok(y)) = (u16::try_from(x), u16::try_from(y)) {
+                    new_cell.symbol = symbol.to_string();
+                    buf.set(x, y, new_cell.clone());
+                }
+            };
+            for x in left + 1..right {
+                set(x, top, symbols.horizontal);
+                set(x, bottom, symbols.horizontal);
+            }
+            for y in top + 1..bottom {
+                set(left, y, symbols.vertical);
+                set(right, y, symbols.vertical);
+            }
+            set(left, top, symbols.top_left);
+            set(right, top, symbols.top_right);
+            set(left, bottom, symbols.bottom_left);
+            set(right, bottom, symbols.bottom_right);
+        }
     }
 }
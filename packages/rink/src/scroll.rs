@@ -0,0 +1,400 @@
+//! Scrollable containers, like elements with `overflow: scroll` or `overflow: auto` on the web
+//!
+//! Elements that don't have `overflow: visible` clip their children to their padding box. Elements with `overflow:
+//! scroll` or `overflow: auto` can be scrolled with the mouse wheel, or with the arrow, page, home and end keys while
+//! they are focused:
+//!
+//! ```rust, ignore
+//! rsx! {
+//!     div {
+//!         height: "10px",
+//!         overflow_y: "scroll",
+//!         for i in 0..100 {
+//!             div { "item {i}" }
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! The scroll offset is stored in attributes on the element, so the scroll state updates and the element is rerendered
+//! the same way as when any other attribute changes.
+
+use dioxus_html::input_data::keyboard_types::Code;
+use dioxus_native_core::{
+    node::OwnedAttributeDiscription,
+    node_ref::{AttributeMaskBuilder, NodeMaskBuilder, NodeView},
+    prelude::*,
+    real_dom::{NodeImmutable, NodeTypeMut},
+    tree::TreeRef,
+};
+use dioxus_native_core_macro::partial_derive_state;
+use shipyard::Component;
+use taffy::{
+    geometry::Point,
+    prelude::{LengthPercentage, Rect, Size},
+    Taffy,
+};
+
+use crate::{
+    layout::TaffyLayout, layout_to_screen_space, screen_to_layout_space, unit_to_layout_space,
+};
+
+pub(crate) const SCROLL_LEFT: &str = "rink-scroll-left";
+pub(crate) const SCROLL_TOP: &str = "rink-scroll-top";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub(crate) enum Overflow {
+    #[default]
+    Visible,
+    Hidden,
+    Scroll,
+    Auto,
+}
+
+impl Overflow {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "visible" => Some(Overflow::Visible),
+            "hidden" | "clip" => Some(Overflow::Hidden),
+            "scroll" => Some(Overflow::Scroll),
+            "auto" => Some(Overflow::Auto),
+            _ => None,
+        }
+    }
+
+    /// Content outside of the element is hidden
+    pub fn clips(self) -> bool {
+        self != Overflow::Visible
+    }
+
+    /// The user can scroll the element
+    pub fn scrollable(self) -> bool {
+        matches!(self, Overflow::Scroll | Overflow::Auto)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Component)]
+pub(crate) struct ScrollState {
+    pub x: Overflow,
+    pub y: Overflow,
+    /// The horizontal scroll offset in cells
+    pub left: u16,
+    /// The vertical scroll offset in cells
+    pub top: u16,
+}
+
+impl ScrollState {
+    pub fn clips(&self) -> bool {
+        self.x.clips() || self.y.clips()
+    }
+
+    pub fn scrollable(&self) -> bool {
+        self.x.scrollable() || self.y.scrollable()
+    }
+}
+
+#[partial_derive_state]
+impl State for ScrollState {
+    type ParentDependencies = ();
+    type ChildDependencies = ();
+    type NodeDependencies = ();
+
+    const NODE_MASK: NodeMaskBuilder<'static> =
+        NodeMaskBuilder::new().with_attrs(AttributeMaskBuilder::Some(&[
+            "overflow",
+            "overflow-x",
+            "overflow-y",
+            SCROLL_LEFT,
+            SCROLL_TOP,
+        ]));
+
+    fn update<'a>(
+        &mut self,
+        node_view: NodeView,
+        _: <Self::NodeDependencies as Dependancy>::ElementBorrowed<'a>,
+        _: Option<<Self::ParentDependencies as Dependancy>::ElementBorrowed<'a>>,
+        _: Vec<<Self::ChildDependencies as Dependancy>::ElementBorrowed<'a>>,
+        _: &SendAnyMap,
+    ) -> bool {
+        let mut new = ScrollState::default();
+        if let Some(attributes) = node_view.attributes() {
+            for attribute in attributes {
+                let Some(value) = attribute.value.as_text() else {
+                    continue;
+                };
+                match attribute.attribute.name.as_str() {
+                    "overflow" => {
+                        // the shorthand sets both axes, or the x axis and then the y axis
+                        let mut values = value.split_whitespace().filter_map(Overflow::parse);
+                        if let Some(x) = values.next() {
+                            new.x = x;
+                            new.y = values.next().unwrap_or(x);
+                        }
+                    }
+                    "overflow-x" => new.x = Overflow::parse(value).unwrap_or_default(),
+                    "overflow-y" => new.y = Overflow::parse(value).unwrap_or_default(),
+                    SCROLL_LEFT => new.left = value.parse().unwrap_or_default(),
+                    SCROLL_TOP => new.top = value.parse().unwrap_or_default(),
+                    _ => {}
+                }
+            }
+        }
+
+        // like on the web, if one axis clips, the other axis can't be visible
+        match (new.x, new.y) {
+            (Overflow::Visible, y) if y.clips() => new.x = Overflow::Auto,
+            (x, Overflow::Visible) if x.clips() => new.y = Overflow::Auto,
+            _ => {}
+        }
+
+        if *self != new {
+            *self = new;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// The size of a scroll container and its content
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct ScrollMetrics {
+    /// The size of the padding box the content is visible through in cells
+    pub viewport: Size<u16>,
+    /// The size of the content in cells. This is never smaller than the viewport
+    pub content: Size<u16>,
+}
+
+impl ScrollMetrics {
+    pub fn max_left(&self) -> u16 {
+        self.content.width.saturating_sub(self.viewport.width)
+    }
+
+    pub fn max_top(&self) -> u16 {
+        self.content.height.saturating_sub(self.viewport.height)
+    }
+}
+
+/// Resolve the widths of the edges of a border or padding in layout space
+pub(crate) fn resolve_edges(edges: &Rect<LengthPercentage>, width: f32) -> Rect<f32> {
+    let resolve = |edge: LengthPercentage| match edge {
+        LengthPercentage::Points(p) => unit_to_layout_space(p),
+        LengthPercentage::Percent(p) => p * width,
+    };
+    Rect {
+        left: resolve(edges.left),
+        right: resolve(edges.right),
+        top: resolve(edges.top),
+        bottom: resolve(edges.bottom),
+    }
+}
+
+/// Measure the viewport and content of a node after layout
+pub(crate) fn scroll_metrics(node: &NodeRef, taffy: &Taffy) -> ScrollMetrics {
+    let layout = node.get::<TaffyLayout>().unwrap();
+    let taffy_node = layout.node.unwrap();
+    let size = taffy.layout(taffy_node).unwrap().size;
+    let border = resolve_edges(&layout.style.border, size.width);
+    let padding = resolve_edges(&layout.style.padding, size.width);
+
+    let viewport = Size {
+        width: (size.width - border.left - border.right).max(0.0),
+        height: (size.height - border.top - border.bottom).max(0.0),
+    };
+    let mut content = viewport;
+    for child in taffy.children(taffy_node).unwrap() {
+        let child = taffy.layout(child).unwrap();
+        content.width = content
+            .width
+            .max(child.location.x + child.size.width + padding.right - border.left);
+        content.height = content
+            .height
+            .max(child.location.y + child.size.height + padding.bottom - border.top);
+    }
+
+    let to_cells = |size: Size<f32>| Size {
+        width: layout_to_screen_space(size.width).round() as u16,
+        height: layout_to_screen_space(size.height).round() as u16,
+    };
+    ScrollMetrics {
+        viewport: to_cells(viewport),
+        content: to_cells(content),
+    }
+}
+
+/// Read the scroll offset from the attributes of a node. Unlike the [`ScrollState`], this includes changes made since
+/// the last time the state was updated
+fn current_offset(node: &NodeRef) -> (u16, u16) {
+    let mut offset = (0, 0);
+    if let NodeType::Element(el) = &*node.node_type() {
+        for (attribute, value) in &el.attributes {
+            let value = value.as_text().and_then(|v| v.parse().ok());
+            match attribute.name.as_str() {
+                SCROLL_LEFT => offset.0 = value.unwrap_or_default(),
+                SCROLL_TOP => offset.1 = value.unwrap_or_default(),
+                _ => {}
+            }
+        }
+    }
+    offset
+}
+
+/// Scroll a node to an offset in cells. The offset is clamped to the content of the node. Returns true if the offset
+/// changed
+pub(crate) fn scroll_to(
+    rdom: &mut RealDom,
+    taffy: &Taffy,
+    id: NodeId,
+    left: i32,
+    top: i32,
+) -> bool {
+    let Some(node) = rdom.get(id) else {
+        return false;
+    };
+    let metrics = scroll_metrics(&node, taffy);
+    let current = current_offset(&node);
+    let left = left.clamp(0, metrics.max_left() as i32) as u16;
+    let top = top.clamp(0, metrics.max_top() as i32) as u16;
+    if (left, top) == current {
+        return false;
+    }
+
+    let mut node = rdom.get_mut(id).unwrap();
+    if let NodeTypeMut::Element(mut el) = node.node_type_mut() {
+        for (name, value) in [(SCROLL_LEFT, left), (SCROLL_TOP, top)] {
+            el.set_attribute(
+                OwnedAttributeDiscription {
+                    name: name.to_string(),
+                    namespace: None,
+                },
+                value.to_string(),
+            );
+        }
+    }
+    true
+}
+
+/// Scroll a node by a number of cells on the axes the user can scroll. Returns true if the offset changed
+pub(crate) fn scroll_by(rdom: &mut RealDom, taffy: &Taffy, id: NodeId, dx: i32, dy: i32) -> bool {
+    let Some(node) = rdom.get(id) else {
+        return false;
+    };
+    let Some(state) = node.get::<ScrollState>().map(|state| *state) else {
+        return false;
+    };
+    let (left, top) = current_offset(&node);
+    let dx = if state.x.scrollable() { dx } else { 0 };
+    let dy = if state.y.scrollable() { dy } else { 0 };
+    scroll_to(rdom, taffy, id, left as i32 + dx, top as i32 + dy)
+}
+
+/// Scroll a focused scroll container with the arrow, page, home and end keys. Returns true if the offset changed
+pub(crate) fn scroll_with_key(rdom: &mut RealDom, taffy: &Taffy, id: NodeId, code: Code) -> bool {
+    let Some(node) = rdom.get(id) else {
+        return false;
+    };
+    if !node.get::<ScrollState>().map_or(false, |s| s.scrollable()) {
+        return false;
+    }
+    let metrics = scroll_metrics(&node, taffy);
+    // keep one line of the last page visible when moving by a page
+    let page = metrics.viewport.height.saturating_sub(1).max(1) as i32;
+    let content = metrics.content.height as i32;
+
+    let (dx, dy) = match code {
+        Code::ArrowUp => (0, -1),
+        Code::ArrowDown => (0, 1),
+        Code::ArrowLeft => (-1, 0),
+        Code::ArrowRight => (1, 0),
+        Code::PageUp => (0, -page),
+        Code::PageDown => (0, page),
+        Code::Home => (0, -content),
+        Code::End => (0, content),
+        _ => return false,
+    };
+    scroll_by(rdom, taffy, id, dx, dy)
+}
+
+/// Scroll every scroll container around a node so the node is visible
+pub(crate) fn scroll_into_view(rdom: &mut RealDom, taffy: &Taffy, id: NodeId) {
+    let Some(node) = rdom.get(id) else {
+        return;
+    };
+    let size = taffy
+        .layout(node.get::<TaffyLayout>().unwrap().node.unwrap())
+        .unwrap()
+        .size;
+    // the position of the node relative to the container that is being scrolled in layout space
+    let mut location = Point { x: 0.0, y: 0.0 };
+    let mut current = id;
+
+    loop {
+        let Some(node) = rdom.get(current) else {
+            break;
+        };
+        let node_location = taffy
+            .layout(node.get::<TaffyLayout>().unwrap().node.unwrap())
+            .unwrap()
+            .location;
+        location.x += node_location.x;
+        location.y += node_location.y;
+        let Some(parent) = rdom.tree_ref().parent_id_advanced(current, true) else {
+            break;
+        };
+        current = parent;
+
+        let parent = rdom.get(parent).unwrap();
+        if !parent
+            .get::<ScrollState>()
+            .map_or(false, |state| state.clips())
+        {
+            continue;
+        }
+        let border = {
+            let layout = parent.get::<TaffyLayout>().unwrap();
+            let parent_size = taffy.layout(layout.node.unwrap()).unwrap().size;
+            resolve_edges(&layout.style.border, parent_size.width)
+        };
+        let metrics = scroll_metrics(&parent, taffy);
+        let (left, top) = current_offset(&parent);
+
+        // the edges of the node relative to the viewport of the container in cells
+        let start =
+            |location: f32, border: f32| layout_to_screen_space(location - border).round() as i32;
+        let end = |location: f32, size: f32, border: f32| {
+            layout_to_screen_space(location + size - border).round() as i32
+        };
+        let fit = |offset: u16, start: i32, end: i32, viewport: u16| {
+            let offset = offset as i32;
+            if start < offset {
+                start
+            } else if end > offset + viewport as i32 {
+                // align the end of the node with the end of the viewport, unless the node is larger than the viewport
+                (end - viewport as i32).min(start)
+            } else {
+                offset
+            }
+        };
+        let new_left = fit(
+            left,
+            start(location.x, border.left),
+            end(location.x, size.width, border.left),
+            metrics.viewport.width,
+        );
+        let new_top = fit(
+            top,
+            start(location.y, border.top),
+            end(location.y, size.height, border.top),
+            metrics.viewport.height,
+        );
+
+        let new_offset = if scroll_to(rdom, taffy, current, new_left, new_top) {
+            current_offset(&rdom.get(current).unwrap())
+        } else {
+            (left, top)
+        };
+        // the rest of the ancestors see the node where it is after this container scrolled
+        location.x -= screen_to_layout_space(new_offset.0);
+        location.y -= screen_to_layout_space(new_offset.1);
+    }
+}
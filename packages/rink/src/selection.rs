@@ -0,0 +1,201 @@
+//! Select text on the screen by dragging the mouse.
+
+use dioxus_native_core::{prelude::*, real_dom::NodeImmutable, tree::TreeRef};
+use shipyard::Unique;
+use taffy::{prelude::Layout, Taffy};
+
+use crate::{get_abs_layout, layout_to_screen_space, widgets::RinkWidgetTraitObject};
+
+/// The text selected with the mouse. The selection covers every cell between the anchor and the focus in reading order.
+#[derive(Unique, Debug, Default, Clone, PartialEq)]
+pub(crate) struct TextSelection {
+    /// The cell the selection started in
+    anchor: Option<(u16, u16)>,
+    /// The cell the selection ends in
+    focus: Option<(u16, u16)>,
+    /// The node the selection started in
+    origin: Option<NodeId>,
+    /// If the mouse is still held down
+    dragging: bool,
+}
+
+impl TextSelection {
+    /// Start a new selection at a cell
+    pub(crate) fn start(&mut self, cell: (u16, u16), origin: Option<NodeId>) {
+        *self = Self {
+            anchor: Some(cell),
+            focus: Some(cell),
+            origin,
+            dragging: true,
+        };
+    }
+
+    /// Move the end of the selection while the mouse is held down. Returns true if the selection changed
+    pub(crate) fn extend(&mut self, cell: (u16, u16)) -> bool {
+        if !self.dragging || self.focus == Some(cell) {
+            return false;
+        }
+        self.focus = Some(cell);
+        true
+    }
+
+    /// Stop dragging. Returns true if any text is selected
+    pub(crate) fn finish(&mut self) -> bool {
+        let was_dragging = std::mem::take(&mut self.dragging);
+        was_dragging && !self.is_empty()
+    }
+
+    /// Remove the selection. Returns true if any text was selected
+    pub(crate) fn clear(&mut self) -> bool {
+        let was_selected = !self.is_empty();
+        *self = Self::default();
+        was_selected
+    }
+
+    /// A click without dragging does not select anything
+    pub(crate) fn is_empty(&self) -> bool {
+        self.anchor == self.focus
+    }
+
+    pub(crate) fn origin(&self) -> Option<NodeId> {
+        self.origin
+    }
+
+    /// The first and last selected cells in reading order as (row, column)
+    fn range(&self) -> Option<((u16, u16), (u16, u16))> {
+        if self.is_empty() {
+            return None;
+        }
+        let (anchor, focus) = (self.anchor?, self.focus?);
+        let (anchor, focus) = ((anchor.1, anchor.0), (focus.1, focus.0));
+        Some((anchor.min(focus), anchor.max(focus)))
+    }
+
+    /// Check if a cell on the screen is selected
+    pub(crate) fn contains(&self, x: u16, y: u16) -> bool {
+        self.range()
+            .map_or(false, |(first, last)| (first..=last).contains(&(y, x)))
+    }
+
+    /// The selected text. Text on different rows is separated by line breaks
+    pub(crate) fn selected_text(&self, dom: &RealDom, layout: &Taffy) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+
+        // the selected characters as (row, column, character)
+        let mut selected = Vec::new();
+        dom.traverse_depth_first(|node| {
+            let node_type = node.node_type();
+            let NodeType::Text(text) = &*node_type else {
+                return;
+            };
+            let (x, y) = screen_position(&get_abs_layout(node, layout));
+            // text scrolled above the screen can't be selected
+            let Ok(y) = u16::try_from(y) else {
+                return;
+            };
+            for (i, c) in text.text.chars().enumerate() {
+                let Ok(x) = u16::try_from(x + i as i32) else {
+                    continue;
+                };
+                if self.contains(x, y) {
+                    selected.push((y, x, c));
+                }
+            }
+        });
+        selected.sort_by_key(|(y, x, _)| (*y, *x));
+
+        let mut output = String::new();
+        let mut last_row = None;
+        for (y, _, c) in selected {
+            if last_row.map_or(false, |row| row != y) {
+                output.push('\n');
+            }
+            last_row = Some(y);
+            output.push(c);
+        }
+        output
+    }
+}
+
+/// The deepest node under a cell on the screen
+pub(crate) fn node_at(dom: &RealDom, layout: &Taffy, cell: (u16, u16)) -> Option<NodeId> {
+    let mut found = None;
+    dom.traverse_depth_first(|node| {
+        let node_layout = get_abs_layout(node, layout);
+        let (x, y) = screen_position(&node_layout);
+        let width = layout_to_screen_space(node_layout.size.width).round() as i32;
+        let height = layout_to_screen_space(node_layout.size.height).round() as i32;
+        if (x..x + width).contains(&(cell.0 as i32)) && (y..y + height).contains(&(cell.1 as i32)) {
+            found = Some(node.id());
+        }
+    });
+    found
+}
+
+/// Check if a node is part of a widget. Widgets like inputs handle selecting their own text
+pub(crate) fn inside_widget(dom: &RealDom, id: NodeId) -> bool {
+    let tree = dom.tree_ref();
+    let mut current = Some(id);
+    while let Some(id) = current {
+        if dom
+            .get(id)
+            .map_or(false, |node| node.get::<RinkWidgetTraitObject>().is_some())
+        {
+            return true;
+        }
+        current = tree.parent_id_advanced(id, true);
+    }
+    false
+}
+
+/// The nearest node, starting from a node and moving up the tree, that listens for an event
+pub(crate) fn listening_ancestor(dom: &RealDom, id: NodeId, event: &str) -> Option<NodeId> {
+    let tree = dom.tree_ref();
+    let mut current = Some(id);
+    while let Some(id) = current {
+        let listening = dom.get(id).map_or(false, |node| match &*node.node_type() {
+            NodeType::Element(el) => el.listeners.contains(event),
+            _ => false,
+        });
+        if listening {
+            return Some(id);
+        }
+        current = tree.parent_id_advanced(id, true);
+    }
+    None
+}
+
+/// The position of a node on the screen. Nodes scrolled out of view can be above or left of the screen
+fn screen_position(layout: &Layout) -> (i32, i32) {
+    (
+        layout_to_screen_space(layout.location.x).round() as i32,
+        layout_to_screen_space(layout.location.y).round() as i32,
+    )
+}
+
+#[test]
+fn selection_in_reading_order() {
+    let mut selection = TextSelection::default();
+    assert!(!selection.contains(0, 0));
+
+    // dragging backwards from the middle of the second row to the middle of the first row
+    selection.start((2, 1), None);
+    assert!(selection.is_empty());
+    assert!(selection.extend((3, 0)));
+    assert!(!selection.extend((3, 0)));
+    assert!(selection.contains(3, 0));
+    assert!(selection.contains(10, 0));
+    assert!(selection.contains(0, 1));
+    assert!(selection.contains(2, 1));
+    assert!(!selection.contains(2, 0));
+    assert!(!selection.contains(3, 1));
+
+    // the selection stays after the mouse is released, but does not move
+    assert!(selection.finish());
+    assert!(!selection.extend((0, 0)));
+    assert!(selection.contains(3, 0));
+    assert!(selection.clear());
+    assert!(!selection.contains(3, 0));
+}
@@ -0,0 +1,251 @@
+//! Capture the output of the renderer for snapshot tests.
+
+use std::{fmt::Display, fmt::Write, path::Path, time::Duration};
+
+use ratatui::buffer::Buffer;
+pub use ratatui::style::{Color, Modifier};
+
+/// Set this environment variable to create missing snapshots and overwrite stored snapshots with the current output instead of comparing them
+pub const UPDATE_SNAPSHOTS_ENV: &str = "DIOXUS_UPDATE_SNAPSHOTS";
+
+/// How long an app rendered with [`crate::render_snapshot`] can run before it fails for not quitting
+pub const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A single cell of a [`Snapshot`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotCell {
+    /// The text drawn in the cell
+    pub symbol: String,
+    /// The foreground color of the cell
+    pub fg: Color,
+    /// The background color of the cell
+    pub bg: Color,
+    /// The text modifiers (bold, italic, etc.) of the cell
+    pub modifier: Modifier,
+}
+
+impl SnapshotCell {
+    fn is_styled(&self) -> bool {
+        self.fg != Color::Reset || self.bg != Color::Reset || !self.modifier.is_empty()
+    }
+
+    fn same_style(&self, other: &Self) -> bool {
+        self.fg == other.fg && self.bg == other.bg && self.modifier == other.modifier
+    }
+}
+
+/// The grid of cells drawn by the renderer in a virtual terminal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    width: u16,
+    height: u16,
+    cells: Vec<SnapshotCell>,
+}
+
+impl Snapshot {
+    pub(crate) fn from_buffer(buffer: &Buffer) -> Self {
+        let area = buffer.area;
+        let cells = buffer
+            .content
+            .iter()
+            .map(|cell| SnapshotCell {
+                symbol: cell.symbol.clone(),
+                fg: cell.fg,
+                bg: cell.bg,
+                modifier: cell.modifier,
+            })
+            .collect();
+        Self {
+            width: area.width,
+            height: area.height,
+            cells,
+        }
+    }
+
+    /// The width of the virtual terminal in cells
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// The height of the virtual terminal in cells
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Get the cell at a position
+    pub fn cell(&self, x: u16, y: u16) -> Option<&SnapshotCell> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.cells
+            .get(y as usize * self.width as usize + x as usize)
+    }
+
+    /// The rows of cells from top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[SnapshotCell]> {
+        self.cells.chunks(self.width.max(1) as usize)
+    }
+
+    /// The text of each row with trailing whitespace removed
+    pub fn lines(&self) -> Vec<String> {
+        self.rows()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.symbol.as_str())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    /// The text of the snapshot followed by a line for each run of styled cells in the format `row:start..end fg=.. bg=.. modifier=..`
+    pub fn to_styled_string(&self) -> String {
+        let mut output = self.to_string();
+        output.push_str("\n---");
+        for (y, row) in self.rows().enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                let cell = &row[x];
+                let end = x + row[x..]
+                    .iter()
+                    .take_while(|other| cell.same_style(other))
+                    .count();
+                if cell.is_styled() {
+                    write!(output, "\n{y}:{x}..{end}").unwrap();
+                    if cell.fg != Color::Reset {
+                        write!(output, " fg={:?}", cell.fg).unwrap();
+                    }
+                    if cell.bg != Color::Reset {
+                        write!(output, " bg={:?}", cell.bg).unwrap();
+                    }
+                    if !cell.modifier.is_empty() {
+                        let names: Vec<_> =
+                            cell.modifier.iter_names().map(|(name, _)| name).collect();
+                        write!(output, " modifier={}", names.join("|")).unwrap();
+                    }
+                }
+                x = end;
+            }
+        }
+        output
+    }
+
+    /// Compare the text of the snapshot to the snapshot stored at a path and panic with a diff if they are different.
+    ///
+    /// If the [`UPDATE_SNAPSHOTS_ENV`] environment variable is set, the snapshot is written to the path instead. A missing snapshot
+    /// fails the assertion unless the variable is set, so snapshots aren't silently created on CI.
+    pub fn assert_snapshot(&self, path: impl AsRef<Path>) {
+        assert_matches_file(&self.to_string(), path.as_ref());
+    }
+
+    /// Like [`Snapshot::assert_snapshot`], but also compares the colors and modifiers of the cells
+    pub fn assert_styled_snapshot(&self, path: impl AsRef<Path>) {
+        assert_matches_file(&self.to_styled_string(), path.as_ref());
+    }
+}
+
+impl Display for Snapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.lines().join("\n"))
+    }
+}
+
+fn assert_matches_file(actual: &str, path: &Path) {
+    let actual = actual.trim_end_matches('\n');
+    let update = std::env::var_os(UPDATE_SNAPSHOTS_ENV).is_some();
+    if !update && !path.exists() {
+        panic!(
+            "snapshot {} does not exist. Set {UPDATE_SNAPSHOTS_ENV}=1 to create it\n{actual}",
+            path.display()
+        );
+    }
+    if update {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(path, format!("{actual}\n")).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(path).unwrap();
+    let expected = expected.replace("\r\n", "\n");
+    let expected = expected.trim_end_matches('\n');
+    if let Some(diff) = diff(expected, actual) {
+        panic!(
+            "snapshot {} does not match the output (- expected, + actual). Set {UPDATE_SNAPSHOTS_ENV}=1 to update it\n{diff}",
+            path.display()
+        );
+    }
+}
+
+/// A line by line diff of two snapshots, or None if they are the same
+fn diff(expected: &str, actual: &str) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+    let expected: Vec<_> = expected.lines().collect();
+    let actual: Vec<_> = actual.lines().collect();
+    let mut output = String::new();
+    for i in 0..expected.len().max(actual.len()) {
+        let line = i + 1;
+        match (expected.get(i), actual.get(i)) {
+            (Some(expected), Some(actual)) if expected == actual => {
+                writeln!(output, "{line:>4}   |{expected}").unwrap();
+            }
+            (expected, actual) => {
+                if let Some(expected) = expected {
+                    writeln!(output, "{line:>4} - |{expected}").unwrap();
+                }
+                if let Some(actual) = actual {
+                    writeln!(output, "{line:>4} + |{actual}").unwrap();
+                }
+            }
+        }
+    }
+    Some(output)
+}
+
+#[test]
+fn snapshot_text_and_styles() {
+    let mut buffer = Buffer::empty(ratatui::layout::Rect::new(0, 0, 4, 2));
+    buffer.set_string(
+        1,
+        0,
+        "hi",
+        ratatui::style::Style::default()
+            .fg(Color::Red)
+            .add_modifier(Modifier::BOLD),
+    );
+    buffer.set_string(0, 1, "x", ratatui::style::Style::default());
+    let snapshot = Snapshot::from_buffer(&buffer);
+
+    assert_eq!(snapshot.lines(), [" hi", "x"]);
+    assert_eq!(snapshot.cell(1, 0).unwrap().fg, Color::Red);
+    assert!(snapshot.cell(4, 0).is_none());
+    assert_eq!(
+        snapshot.to_styled_string(),
+        " hi\nx\n---\n0:1..3 fg=Red modifier=BOLD"
+    );
+}
+
+#[test]
+fn snapshot_diff() {
+    assert_eq!(diff("a\nb", "a\nb"), None);
+    assert_eq!(
+        diff("a\nb", "a\nc\nd").unwrap(),
+        "   1   |a\n   2 - |b\n   2 + |c\n   3 + |d\n"
+    );
+}
+
+#[test]
+fn missing_snapshots_fail() {
+    if std::env::var_os(UPDATE_SNAPSHOTS_ENV).is_some() {
+        return;
+    }
+    let path =
+        std::env::temp_dir().join(format!("rink-missing-snapshot-{}.txt", std::process::id()));
+    let result = std::panic::catch_unwind(|| assert_matches_file("hello", &path));
+    assert!(result.is_err());
+    assert!(!path.exists());
+}
@@ -326,7 +326,8 @@ fn to_rgb(c: Color) -> [u8; 3] {
                 [r as u8, g as u8, b as u8]
             }
             232..=255 => {
-                let l = (idx - 232) / 24;
+                // 24 step grayscale from 8 to 238
+                let l = 8 + (idx - 232) * 10;
                 [l; 3]
             }
             // rink will never generate these colors, but they might be on the screen from another program
@@ -336,37 +337,78 @@ fn to_rgb(c: Color) -> [u8; 3] {
     }
 }
 
+/// The 16 base colors in the order of their ansi index
+const BASE_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// An approximation of how different two colors look to people. Red and blue are weighted based on how red the colors are ("redmean")
+fn perceptual_distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    let mean_r = (a[0] as i32 + b[0] as i32) / 2;
+    let [dr, dg, db] = [0, 1, 2].map(|i| a[i] as i32 - b[i] as i32);
+    (((512 + mean_r) * dr * dr) / 256 + 4 * dg * dg + ((767 - mean_r) * db * db) / 256) as u32
+}
+
+/// The closest color in a palette
+fn closest(rgb: [u8; 3], palette: impl IntoIterator<Item = Color>) -> Color {
+    palette
+        .into_iter()
+        .min_by_key(|c| perceptual_distance(rgb, to_rgb(*c)))
+        .unwrap_or(Color::Reset)
+}
+
+fn to_ansi(rgb: [u8; 3]) -> Color {
+    // 16-231: 6 × 6 × 6 color cube
+    // 232-255: 24 step grayscale
+    if rgb[0] == rgb[1] && rgb[1] == rgb[2] {
+        let step = (rgb[0].saturating_sub(3) / 10).min(23);
+        Color::Indexed(232 + step)
+    } else {
+        // the closest color is made of the levels on either side of each channel
+        let [r, g, b] = rgb.map(|c| {
+            let lower = c as u16 * 5 / 255;
+            [lower, (lower + 1).min(5)]
+        });
+        let candidates = r.into_iter().flat_map(|r| {
+            g.into_iter()
+                .flat_map(move |g| b.into_iter().map(move |b| 16 + r * 36 + g * 6 + b))
+        });
+        closest(rgb, candidates.map(|idx| Color::Indexed(idx as u8)))
+    }
+}
+
 pub fn convert(mode: RenderingMode, c: Color) -> Color {
     if let Color::Reset = c {
         c
     } else {
         match mode {
+            crate::RenderingMode::Monochrome => Color::Reset,
             crate::RenderingMode::BaseColors => match c {
-                Color::Rgb(_, _, _) => panic!("cannot convert rgb color to base color"),
-                Color::Indexed(_) => panic!("cannot convert Ansi color to base color"),
+                Color::Rgb(_, _, _) | Color::Indexed(_) => closest(to_rgb(c), BASE_COLORS),
                 _ => c,
             },
-            crate::RenderingMode::Rgb => {
+            crate::RenderingMode::Rgb | crate::RenderingMode::Auto => {
                 let rgb = to_rgb(c);
                 Color::Rgb(rgb[0], rgb[1], rgb[2])
             }
             crate::RenderingMode::Ansi => match c {
                 Color::Indexed(_) => c,
-                _ => {
-                    let rgb = to_rgb(c);
-                    // 16-231: 6 × 6 × 6 color cube
-                    // 232-255: 23 step grayscale
-                    if rgb[0] == rgb[1] && rgb[1] == rgb[2] {
-                        let idx = 232 + (rgb[0] as u16 * 23 / 255) as u8;
-                        Color::Indexed(idx)
-                    } else {
-                        let r = (rgb[0] as u16 * 5) / 255;
-                        let g = (rgb[1] as u16 * 5) / 255;
-                        let b = (rgb[2] as u16 * 5) / 255;
-                        let idx = 16 + r * 36 + g * 6 + b;
-                        Color::Indexed(idx as u8)
-                    }
-                }
+                _ => to_ansi(to_rgb(c)),
             },
         }
     }
@@ -450,3 +492,78 @@ impl From<RinkStyle> for Style {
         }
     }
 }
+
+#[test]
+fn downgrade_to_base_colors() {
+    assert_eq!(
+        convert(RenderingMode::BaseColors, Color::Rgb(250, 10, 10)),
+        Color::Red
+    );
+    assert_eq!(
+        convert(RenderingMode::BaseColors, Color::Rgb(10, 10, 10)),
+        Color::Black
+    );
+    assert_eq!(
+        convert(RenderingMode::BaseColors, Color::Rgb(240, 240, 240)),
+        Color::White
+    );
+    assert_eq!(
+        convert(RenderingMode::BaseColors, Color::Indexed(21)),
+        Color::Blue
+    );
+    assert_eq!(convert(RenderingMode::BaseColors, Color::Cyan), Color::Cyan);
+    assert_eq!(
+        convert(RenderingMode::Monochrome, Color::Rgb(250, 10, 10)),
+        Color::Reset
+    );
+}
+
+#[test]
+fn downgrade_to_ansi_is_perceptual() {
+    // the closest of the neighboring levels in the color cube keeps the hue of the color
+    let converted = convert(RenderingMode::Ansi, Color::Rgb(40, 100, 40));
+    let Color::Indexed(idx) = converted else {
+        panic!("color is not indexed")
+    };
+    let rgb = to_rgb(converted);
+    assert!((16..232).contains(&idx));
+    assert!(rgb[1] > rgb[0] && rgb[1] > rgb[2]);
+}
+
+#[test]
+fn downgrade_truecolor_through_every_mode() {
+    // each step down to a smaller palette keeps the closest color to the original
+    let colors = [
+        (Color::Rgb(250, 10, 10), Color::Indexed(196), Color::Red),
+        (Color::Rgb(0, 130, 0), Color::Indexed(34), Color::Green),
+        (Color::Rgb(10, 10, 240), Color::Indexed(21), Color::Blue),
+        (Color::Rgb(250, 250, 0), Color::Indexed(226), Color::Yellow),
+        (Color::Rgb(128, 128, 128), Color::Indexed(244), Color::Gray),
+    ];
+    for (truecolor, ansi, base) in colors {
+        assert_eq!(convert(RenderingMode::Rgb, truecolor), truecolor);
+        assert_eq!(convert(RenderingMode::Ansi, truecolor), ansi);
+        assert_eq!(convert(RenderingMode::BaseColors, ansi), base);
+        assert_eq!(convert(RenderingMode::BaseColors, truecolor), base);
+        assert_eq!(convert(RenderingMode::Monochrome, ansi), Color::Reset);
+    }
+
+    // indexed colors are expanded to rgb on truecolor terminals
+    assert_eq!(
+        convert(RenderingMode::Rgb, Color::Indexed(196)),
+        Color::Rgb(255, 0, 0)
+    );
+    assert_eq!(
+        convert(RenderingMode::Rgb, Color::Indexed(244)),
+        Color::Rgb(128, 128, 128)
+    );
+    // the terminal's own colors are never changed
+    for mode in [
+        RenderingMode::Rgb,
+        RenderingMode::Ansi,
+        RenderingMode::BaseColors,
+        RenderingMode::Monochrome,
+    ] {
+        assert_eq!(convert(mode, Color::Reset), Color::Reset);
+    }
+}
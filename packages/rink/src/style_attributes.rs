@@ -1,34 +1,19 @@
 /*
-- [ ] pub display: Display,
-- [x] pub position_type: PositionType,  --> kinda, taffy doesnt support everything
-- [ ] pub direction: Direction,
+Layout attributes are applied to taffy in native-core's layout_attributes module. This module handles the attributes that change how an element is drawn.
 
-- [x] pub flex_direction: FlexDirection,
-- [x] pub flex_wrap: FlexWrap,
-- [x] pub flex_grow: f32,
-- [x] pub flex_shrink: f32,
-- [x] pub flex_basis: Dimension,
-
-- [x] pub overflow: Overflow, ---> kinda implemented... taffy doesnt have support for directional overflow
-
-- [x] pub align_items: AlignItems,
-- [x] pub align_self: AlignSelf,
-- [x] pub align_content: AlignContent,
-
-- [x] pub margin: Rect<Dimension>,
-- [x] pub padding: Rect<Dimension>,
-
-- [x] pub justify_content: JustifyContent,
-- [ ] pub position: Rect<Dimension>,
-- [x] pub border: Rect<Dimension>,
-
-- [ ] pub size: Size<Dimension>, ----> ??? seems to only be relevant for input?
-- [ ] pub min_size: Size<Dimension>,
-- [ ] pub max_size: Size<Dimension>,
-
-- [ ] pub aspect_ratio: Number,
+- [x] color, background-color
+- [x] border, border-style, border-color, border-width, border-radius
+- [x] font-style, font-weight, text-decoration
+- [x] opacity ----> only blends the colors of the element with the terminal background
+- [x] hover-style and focus-style ----> the :hover and :focus pseudo-classes
+- [x] var(--name) ----> resolved against the theme in the config
+- [x] outline ----> drawn with line characters in the cells around the element
+- [x] box-shadow ----> only the offset and color of the first shadow, drawn as a background behind the element
+- [x] cursor ----> sent to terminals that support changing the mouse pointer with OSC 22
 */
 
+use std::{borrow::Cow, str::FromStr, sync::Arc};
+
 use dioxus_native_core::{
     layout_attributes::parse_value,
     node::OwnedAttributeView,
@@ -39,7 +24,11 @@ use dioxus_native_core_macro::partial_derive_state;
 use shipyard::Component;
 use taffy::prelude::*;
 
+use crate::pseudo_class::{
+    for_each_active_declaration, FOCUSED, FOCUS_STYLE, HOVERED, HOVER_STYLE,
+};
 use crate::style::{RinkColor, RinkStyle};
+use crate::theme::Theme;
 
 #[derive(Default, Clone, PartialEq, Debug, Component)]
 pub struct StyleModifier {
@@ -64,8 +53,11 @@ impl State for StyleModifier {
         _: <Self::NodeDependencies as Dependancy>::ElementBorrowed<'a>,
         parent: Option<<Self::ParentDependencies as Dependancy>::ElementBorrowed<'a>>,
         _: Vec<<Self::ChildDependencies as Dependancy>::ElementBorrowed<'a>>,
-        _: &SendAnyMap,
+        ctx: &SendAnyMap,
     ) -> bool {
+        // custom properties like var(--fg) are resolved against the theme
+        let theme: Option<&Arc<Theme>> = ctx.get();
+
         let mut new = StyleModifier::default();
         if parent.is_some() {
             new.core.fg = None;
@@ -97,16 +89,31 @@ impl State for StyleModifier {
             } in attrs
             {
                 if let Some(text) = value.as_text() {
-                    apply_style_attributes(&attribute.name, text, &mut new);
+                    apply_style_attributes(&attribute.name, &resolve(theme, text), &mut new);
                 }
             }
         }
 
-        // keep the text styling from the parent element
+        // apply the styles of the active :hover and :focus states last so they override the normal styles
+        for_each_active_declaration(&node_view, |name, value| {
+            apply_style_attributes(name, &resolve(theme, value), &mut new);
+        });
+
+        // opacity fades the colors of the element into the background of the terminal
+        if let Some(opacity) = new.modifier.opacity {
+            for color in [&mut new.core.fg, &mut new.core.bg].into_iter().flatten() {
+                color.alpha = (color.alpha as f32 * opacity).round() as u8;
+            }
+        }
+
+        // keep the text styling and cursor from the parent element
         if let Some((parent,)) = parent {
             let mut new_style = new.core.merge(parent.core);
             new_style.bg = new.core.bg;
             new.core = new_style;
+            if new.modifier.cursor.is_none() {
+                new.modifier.cursor = parent.modifier.cursor.clone();
+            }
         }
         if &mut new != self {
             *self = new;
@@ -129,9 +136,56 @@ impl State for StyleModifier {
     }
 }
 
+fn resolve<'a>(theme: Option<&Arc<Theme>>, value: &'a str) -> Cow<'a, str> {
+    match theme {
+        Some(theme) => theme.resolve(value),
+        None => Cow::Borrowed(value),
+    }
+}
+
 #[derive(Default, Clone, PartialEq, Debug)]
 pub struct TuiModifier {
     pub borders: Borders,
+    pub opacity: Option<f32>,
+    pub outline: Outline,
+    pub box_shadow: Option<BoxShadow>,
+    /// The name of the mouse cursor over the element, like `pointer`. Elements without a cursor use the cursor of their parent
+    pub cursor: Option<String>,
+}
+
+/// A line drawn in the cells around an element
+#[derive(Clone, PartialEq, Debug)]
+pub struct Outline {
+    pub color: Option<RinkColor>,
+    pub style: BorderStyle,
+    pub width: Dimension,
+    /// The number of cells between the element and the outline
+    pub offset: u16,
+}
+
+impl Default for Outline {
+    fn default() -> Self {
+        Self {
+            color: None,
+            style: BorderStyle::None,
+            width: Dimension::Points(1.0),
+            offset: 0,
+        }
+    }
+}
+
+impl Outline {
+    pub fn is_visible(&self) -> bool {
+        self.style.symbol_set().is_some() && self.width != Dimension::Points(0.0)
+    }
+}
+
+/// A shadow behind an element. Terminals can't blur, so only the offset and color are used
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BoxShadow {
+    /// The number of cells the shadow is moved right and down from the element
+    pub offset: [i16; 2],
+    pub color: Option<RinkColor>,
 }
 
 #[derive(Default, Clone, PartialEq, Debug)]
@@ -186,6 +240,26 @@ pub enum BorderStyle {
     None,
 }
 
+impl FromStr for BorderStyle {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "dotted" => BorderStyle::Dotted,
+            "dashed" => BorderStyle::Dashed,
+            "solid" => BorderStyle::Solid,
+            "double" => BorderStyle::Double,
+            "groove" => BorderStyle::Groove,
+            "ridge" => BorderStyle::Ridge,
+            "inset" => BorderStyle::Inset,
+            "outset" => BorderStyle::Outset,
+            "none" => BorderStyle::None,
+            "hidden" => BorderStyle::Hidden,
+            _ => return Err(()),
+        })
+    }
+}
+
 impl BorderStyle {
     pub fn symbol_set(&self) -> Option<ratatui::symbols::line::Set> {
         use ratatui::symbols::line::*;
@@ -279,7 +353,7 @@ pub fn apply_style_attributes(
         | "border-width" => apply_border(name, value, style),
 
         "bottom" => {}
-        "box-shadow" => {}
+        "box-shadow" => style.modifier.box_shadow = parse_box_shadow(value),
         "box-sizing" => {}
         "caption-side" => {}
         "clear" => {}
@@ -297,7 +371,13 @@ pub fn apply_style_attributes(
         "counter-increment" => {}
         "counter-reset" => {}
 
-        "cursor" => {}
+        "cursor" => {
+            // the last cursor in the list is the fallback keyword after any images
+            let cursor = value.rsplit(',').next().unwrap_or_default().trim();
+            if !cursor.is_empty() && cursor.chars().all(|c| c.is_ascii_alphabetic() || c == '-') {
+                style.modifier.cursor = Some(cursor.to_string());
+            }
+        }
 
         "empty-cells" => {}
 
@@ -311,11 +391,20 @@ pub fn apply_style_attributes(
 
         "list-style" | "list-style-image" | "list-style-position" | "list-style-type" => {}
 
-        "opacity" => {}
+        "opacity" => {
+            let opacity = match value.trim().strip_suffix('%') {
+                Some(percent) => percent.trim().parse::<f32>().map(|p| p / 100.0),
+                None => value.trim().parse::<f32>(),
+            };
+            if let Ok(opacity) = opacity {
+                style.modifier.opacity = Some(opacity.clamp(0.0, 1.0));
+            }
+        }
         "order" => {}
-        "outline" => {}
 
-        "outline-color" | "outline-offset" | "outline-style" | "outline-width" => {}
+        "outline" | "outline-color" | "outline-offset" | "outline-style" | "outline-width" => {
+            apply_outline(name, value, style)
+        }
 
         "page-break-after" | "page-break-before" | "page-break-inside" => {}
 
@@ -373,19 +462,7 @@ fn apply_background(name: &str, value: &str, style: &mut StyleModifier) {
 
 fn apply_border(name: &str, value: &str, style: &mut StyleModifier) {
     fn parse_border_style(v: &str) -> BorderStyle {
-        match v {
-            "dotted" => BorderStyle::Dotted,
-            "dashed" => BorderStyle::Dashed,
-            "solid" => BorderStyle::Solid,
-            "double" => BorderStyle::Double,
-            "groove" => BorderStyle::Groove,
-            "ridge" => BorderStyle::Ridge,
-            "inset" => BorderStyle::Inset,
-            "outset" => BorderStyle::Outset,
-            "none" => BorderStyle::None,
-            "hidden" => BorderStyle::Hidden,
-            _ => todo!(),
-        }
+        v.parse().unwrap_or(BorderStyle::None)
     }
     match name {
         "border" => {}
@@ -554,6 +631,94 @@ fn apply_border(name: &str, value: &str, style: &mut StyleModifier) {
     }
 }
 
+fn apply_outline(name: &str, value: &str, style: &mut StyleModifier) {
+    fn parse_outline_style(v: &str) -> Option<BorderStyle> {
+        match v {
+            // terminals have no focus ring, so the automatic style is a solid line
+            "auto" => Some(BorderStyle::Solid),
+            _ => v.parse().ok(),
+        }
+    }
+    let outline = &mut style.modifier.outline;
+    match name {
+        "outline" => {
+            *outline = Outline::default();
+            for v in value.split(' ').filter(|v| !v.is_empty()) {
+                if let Some(s) = parse_outline_style(v) {
+                    outline.style = s;
+                } else if let Some(w) = parse_length(v) {
+                    outline.width = Dimension::Points(w);
+                } else if let Ok(c) = v.parse() {
+                    outline.color = Some(c);
+                }
+            }
+        }
+        "outline-color" => {
+            if let Ok(c) = value.parse() {
+                outline.color = Some(c);
+            }
+        }
+        "outline-offset" => {
+            if let Some(offset) = parse_length(value) {
+                outline.offset = offset.max(0.0).round() as u16;
+            }
+        }
+        "outline-style" => {
+            if let Some(s) = parse_outline_style(value) {
+                outline.style = s;
+            }
+        }
+        "outline-width" => {
+            if let Some(w) = parse_length(value) {
+                outline.width = Dimension::Points(w);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Parse the offset and color of the first shadow in a box-shadow
+fn parse_box_shadow(value: &str) -> Option<BoxShadow> {
+    // split the first shadow into parts, keeping commas and spaces inside colors like rgb(0, 0, 0)
+    let mut parts = vec![String::new()];
+    let mut depth = 0;
+    for c in value.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => break,
+            ' ' if depth == 0 => {
+                parts.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        parts.last_mut().unwrap().push(c);
+    }
+    parts.retain(|part| !part.is_empty());
+
+    // inset shadows are drawn inside of the element, which a terminal can't distinguish from a background
+    if parts.iter().any(|part| part == "none" || part == "inset") {
+        return None;
+    }
+    let lengths: Vec<f32> = parts.iter().filter_map(|part| parse_length(part)).collect();
+    let [x, y, ..] = lengths[..] else {
+        return None;
+    };
+    Some(BoxShadow {
+        offset: [x.round() as i16, y.round() as i16],
+        color: parts.iter().find_map(|part| part.parse().ok()),
+    })
+}
+
+/// Parse a length in cells. One pixel is one cell
+fn parse_length(value: &str) -> Option<f32> {
+    match parse_value(value) {
+        Some(Dimension::Points(points)) => Some(points),
+        _ => value.parse::<f32>().ok().filter(|length| *length == 0.0),
+    }
+}
+
 fn apply_animation(name: &str, _value: &str, _style: &mut StyleModifier) {
     match name {
         "animation" => {}
@@ -582,7 +747,7 @@ fn apply_font(name: &str, value: &str, style: &mut StyleModifier) {
             "oblique" => style.core = style.core.add_modifier(Modifier::ITALIC),
             _ => (),
         },
-        "font-variant" => todo!(),
+        "font-variant" => (),
         "font-weight" => match value {
             "bold" => style.core = style.core.add_modifier(Modifier::BOLD),
             "normal" => style.core = style.core.remove_modifier(Modifier::BOLD),
@@ -596,8 +761,8 @@ fn apply_text(name: &str, value: &str, style: &mut StyleModifier) {
     use ratatui::style::Modifier;
 
     match name {
-        "text-align" => todo!(),
-        "text-align-last" => todo!(),
+        "text-align" => (),
+        "text-align-last" => (),
         "text-decoration" | "text-decoration-line" => {
             for v in value.split(' ') {
                 match v {
@@ -607,20 +772,18 @@ fn apply_text(name: &str, value: &str, style: &mut StyleModifier) {
                 }
             }
         }
-        "text-decoration-color" => todo!(),
-        "text-decoration-style" => todo!(),
-        "text-indent" => todo!(),
-        "text-justify" => todo!(),
-        "text-overflow" => todo!(),
-        "text-shadow" => todo!(),
-        "text-transform" => todo!(),
-        _ => todo!(),
+        "text-decoration-color" => (),
+        "text-decoration-style" => (),
+        "text-indent" => (),
+        "text-justify" => (),
+        "text-overflow" => (),
+        "text-shadow" => (),
+        "text-transform" => (),
+        _ => (),
     }
 }
 
-fn apply_transition(_name: &str, _value: &str, _style: &mut StyleModifier) {
-    todo!()
-}
+fn apply_transition(_name: &str, _value: &str, _style: &mut StyleModifier) {}
 
 const SORTED_STYLE_ATTRS: &[&str] = &[
     "animation",
@@ -824,4 +987,56 @@ const SORTED_STYLE_ATTRS: &[&str] = &[
     "text-overflow",
     "text-shadow",
     "text-transform",
+    HOVER_STYLE,
+    FOCUS_STYLE,
+    HOVERED,
+    FOCUSED,
 ];
+
+#[test]
+fn parses_outline_box_shadow_and_cursor() {
+    let mut style = StyleModifier::default();
+    apply_style_attributes("outline", "2px dashed red", &mut style);
+    apply_style_attributes("outline-offset", "1px", &mut style);
+    assert_eq!(style.modifier.outline.style, BorderStyle::Dashed);
+    assert_eq!(style.modifier.outline.width, Dimension::Points(2.0));
+    assert_eq!(style.modifier.outline.color, "red".parse().ok());
+    assert_eq!(style.modifier.outline.offset, 1);
+    assert!(style.modifier.outline.is_visible());
+    apply_style_attributes("outline-width", "0", &mut style);
+    assert!(!style.modifier.outline.is_visible());
+
+    apply_style_attributes(
+        "box-shadow",
+        "2px 1px 4px rgba(0, 0, 0, 50%), 5px 5px blue",
+        &mut style,
+    );
+    assert_eq!(
+        style.modifier.box_shadow,
+        Some(BoxShadow {
+            offset: [2, 1],
+            color: "rgba(0, 0, 0, 50%)".parse().ok(),
+        })
+    );
+    apply_style_attributes("box-shadow", "inset 1px 1px red", &mut style);
+    assert_eq!(style.modifier.box_shadow, None);
+
+    apply_style_attributes("cursor", "url(hand.png), pointer", &mut style);
+    assert_eq!(style.modifier.cursor.as_deref(), Some("pointer"));
+}
+
+#[test]
+fn unsupported_styles_are_ignored() {
+    let mut style = StyleModifier::default();
+    for (name, value) in [
+        ("text-align", "center"),
+        ("text-overflow", "ellipsis"),
+        ("text-transform", "uppercase"),
+        ("font-variant", "small-caps"),
+        ("transition", "color 1s"),
+        ("border-style", "wavy"),
+    ] {
+        apply_style_attributes(name, value, &mut style);
+    }
+    assert_eq!(style, StyleModifier::default());
+}
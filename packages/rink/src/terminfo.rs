@@ -0,0 +1,114 @@
+//! Read the number of colors a terminal supports from the compiled terminfo database.
+
+use std::path::PathBuf;
+
+/// The magic number of the legacy terminfo format with 16 bit numbers
+const MAGIC_16_BIT: i16 = 0o432;
+/// The magic number of the extended terminfo format with 32 bit numbers
+const MAGIC_32_BIT: i16 = 0o1036;
+/// The index of the `colors` capability in the numbers section
+const COLORS_INDEX: usize = 13;
+
+/// The number of colors the terminal supports according to its terminfo entry
+pub(crate) fn colors(term: &str) -> Option<i32> {
+    let first = term.chars().next()?;
+    let mut dirs = Vec::new();
+    if let Some(dir) = std::env::var_os("TERMINFO") {
+        dirs.push(PathBuf::from(dir));
+    }
+    if let Some(home) = std::env::var_os("HOME") {
+        dirs.push(PathBuf::from(home).join(".terminfo"));
+    }
+    if let Some(extra) = std::env::var_os("TERMINFO_DIRS") {
+        dirs.extend(std::env::split_paths(&extra).filter(|dir| !dir.as_os_str().is_empty()));
+    }
+    dirs.extend(
+        [
+            "/etc/terminfo",
+            "/lib/terminfo",
+            "/usr/share/terminfo",
+            "/usr/lib/terminfo",
+        ]
+        .map(PathBuf::from),
+    );
+
+    dirs.into_iter().find_map(|dir| {
+        // entries are stored by their first letter, or its hex code on some systems
+        [first.to_string(), format!("{:x}", first as u32)]
+            .into_iter()
+            .find_map(|prefix| std::fs::read(dir.join(prefix).join(term)).ok())
+            .and_then(|entry| parse_colors(&entry))
+    })
+}
+
+/// Parse the `colors` capability from a compiled terminfo entry
+fn parse_colors(entry: &[u8]) -> Option<i32> {
+    let short = |i: usize| -> Option<i16> {
+        let bytes = entry.get(i * 2..i * 2 + 2)?;
+        Some(i16::from_le_bytes([bytes[0], bytes[1]]))
+    };
+    let number_size = match short(0)? {
+        MAGIC_16_BIT => 2,
+        MAGIC_32_BIT => 4,
+        _ => return None,
+    };
+    // sizes in the header are never negative in a valid entry
+    let size = |i: usize| usize::try_from(short(i)?).ok();
+    let names_size = size(1)?;
+    let bools_count = size(2)?;
+    let numbers_count = size(3)?;
+    if COLORS_INDEX >= numbers_count {
+        return None;
+    }
+
+    let mut numbers_start = 12 + names_size + bools_count;
+    // the numbers section is aligned to an even byte
+    numbers_start += numbers_start % 2;
+    let start = numbers_start + COLORS_INDEX * number_size;
+    let bytes = entry.get(start..start + number_size)?;
+    let colors = if number_size == 2 {
+        i16::from_le_bytes([bytes[0], bytes[1]]) as i32
+    } else {
+        i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    };
+    // negative numbers mean the capability is missing
+    (colors >= 0).then_some(colors)
+}
+
+#[test]
+fn parse_terminfo_colors() {
+    fn entry(magic: i16, colors: i32) -> Vec<u8> {
+        let names = b"test|a test terminal\0";
+        let bools = [1u8; 3];
+        let mut entry = Vec::new();
+        for header in [magic, names.len() as i16, bools.len() as i16, 14, 0, 0] {
+            entry.extend(header.to_le_bytes());
+        }
+        entry.extend(names);
+        entry.extend(bools);
+        if entry.len() % 2 == 1 {
+            entry.push(0);
+        }
+        for i in 0..14 {
+            let value = if i == COLORS_INDEX as i32 { colors } else { -1 };
+            if magic == MAGIC_16_BIT {
+                entry.extend((value as i16).to_le_bytes());
+            } else {
+                entry.extend(value.to_le_bytes());
+            }
+        }
+        entry
+    }
+
+    assert_eq!(parse_colors(&entry(MAGIC_16_BIT, 256)), Some(256));
+    assert_eq!(parse_colors(&entry(MAGIC_32_BIT, 1 << 24)), Some(1 << 24));
+    assert_eq!(parse_colors(&entry(MAGIC_16_BIT, -1)), None);
+    assert_eq!(parse_colors(b"not a terminfo entry"), None);
+
+    // a corrupt header with negative section sizes is rejected
+    for i in 1..4 {
+        let mut corrupt = entry(MAGIC_16_BIT, 256);
+        corrupt[i * 2..i * 2 + 2].copy_from_slice(&(-2i16).to_le_bytes());
+        assert_eq!(parse_colors(&corrupt), None);
+    }
+}
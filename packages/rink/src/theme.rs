@@ -0,0 +1,210 @@
+//! Themes resolve CSS custom properties like `var(--fg)` in style attributes so apps can adapt to light and dark terminals.
+
+use std::borrow::Cow;
+
+use rustc_hash::FxHashMap;
+
+/// The maximum number of variables that can reference each other before resolution stops
+const MAX_VARIABLE_DEPTH: usize = 8;
+
+/// A set of CSS custom properties that `var(--name)` and `var(--name, fallback)` in style attributes resolve against.
+///
+/// ```rust
+/// use plasmo::{Config, Theme};
+///
+/// let theme = Theme::dark().with_variable("--accent", "orchid");
+/// let cfg = Config::new().with_theme(theme);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Theme {
+    variables: FxHashMap<String, String>,
+}
+
+impl Theme {
+    /// Create a theme without any variables
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A theme for terminals with a dark background
+    pub fn dark() -> Self {
+        Self::new()
+            .with_variable("--fg", "#e4e4e4")
+            .with_variable("--bg", "#1c1c1c")
+            .with_variable("--muted", "#808080")
+            .with_variable("--border", "#585858")
+            .with_variable("--accent", "#5fafff")
+            .with_variable("--success", "#5fd75f")
+            .with_variable("--warning", "#ffd75f")
+            .with_variable("--error", "#ff5f5f")
+    }
+
+    /// A theme for terminals with a light background
+    pub fn light() -> Self {
+        Self::new()
+            .with_variable("--fg", "#1c1c1c")
+            .with_variable("--bg", "#eeeeee")
+            .with_variable("--muted", "#6c6c6c")
+            .with_variable("--border", "#bcbcbc")
+            .with_variable("--accent", "#005fd7")
+            .with_variable("--success", "#008700")
+            .with_variable("--warning", "#af8700")
+            .with_variable("--error", "#d70000")
+    }
+
+    /// Pick the light or dark theme based on the background color the terminal reports in the `COLORFGBG` environment variable. Defaults to the dark theme.
+    pub fn detect() -> Self {
+        match std::env::var("COLORFGBG") {
+            Ok(colors) if is_light_background(&colors) => Self::light(),
+            _ => Self::dark(),
+        }
+    }
+
+    /// Set a variable. The name should include the leading `--`
+    pub fn with_variable(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.variables.insert(name.into(), value.into());
+        self
+    }
+
+    /// Get the value of a variable
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.variables.get(name).map(|value| value.as_str())
+    }
+
+    /// Replace every `var(..)` in a value with the value of the variable, or the fallback if the theme does not define it
+    pub(crate) fn resolve<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        if !value.contains("var(") {
+            return Cow::Borrowed(value);
+        }
+        Cow::Owned(self.resolve_with_depth(value, 0))
+    }
+
+    fn resolve_with_depth(&self, value: &str, depth: usize) -> String {
+        let mut resolved = String::new();
+        let mut rest = value;
+        while let Some(start) = rest.find("var(") {
+            resolved.push_str(&rest[..start]);
+            let inner_start = start + "var(".len();
+
+            // find the closing parenthesis, skipping any nested in the fallback
+            let mut open = 1;
+            let mut end = None;
+            for (i, c) in rest[inner_start..].char_indices() {
+                match c {
+                    '(' => open += 1,
+                    ')' => {
+                        open -= 1;
+                        if open == 0 {
+                            end = Some(inner_start + i);
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            let Some(end) = end else {
+                // an unclosed var is left as is
+                resolved.push_str(&rest[start..]);
+                return resolved;
+            };
+
+            let inner = &rest[inner_start..end];
+            let (name, fallback) = match inner.split_once(',') {
+                Some((name, fallback)) => (name.trim(), Some(fallback.trim())),
+                None => (inner.trim(), None),
+            };
+            if let Some(value) = self.get(name).or(fallback) {
+                if depth < MAX_VARIABLE_DEPTH {
+                    resolved.push_str(&self.resolve_with_depth(value, depth + 1));
+                }
+            }
+            rest = &rest[end + 1..];
+        }
+        resolved.push_str(rest);
+        resolved
+    }
+}
+
+/// `COLORFGBG` is set to `fg;bg` or `fg;default;bg` where the colors are indexes in the 16 color palette
+fn is_light_background(colors: &str) -> bool {
+    match colors
+        .rsplit(';')
+        .next()
+        .and_then(|bg| bg.parse::<u8>().ok())
+    {
+        Some(bg) => bg == 7 || (9..=15).contains(&bg),
+        None => false,
+    }
+}
+
+#[test]
+fn resolve_variables() {
+    let theme = Theme::new()
+        .with_variable("--fg", "red")
+        .with_variable("--text", "var(--fg)");
+
+    assert_eq!(theme.resolve("blue"), "blue");
+    assert_eq!(theme.resolve("var(--fg)"), "red");
+    assert_eq!(theme.resolve("var(--text)"), "red");
+    assert_eq!(theme.resolve("var(--missing, green)"), "green");
+    assert_eq!(
+        theme.resolve("var(--missing, rgb(1, 2, 3))"),
+        "rgb(1, 2, 3)"
+    );
+    assert_eq!(theme.resolve("1px solid var(--fg)"), "1px solid red");
+    assert_eq!(theme.resolve("var(--missing)"), "");
+
+    // variables that reference each other stop resolving instead of looping forever
+    let cyclic = Theme::new().with_variable("--a", "var(--a)");
+    assert_eq!(cyclic.resolve("var(--a)"), "");
+}
+
+#[test]
+fn resolve_nested_fallbacks() {
+    let theme = Theme::new()
+        .with_variable("--fg", "red")
+        .with_variable("--border", "var(--missing, var(--fg))");
+
+    // the fallback is only used when the variable is missing, and may itself use variables
+    assert_eq!(theme.resolve("var(--fg, blue)"), "red");
+    assert_eq!(theme.resolve("var(--missing, var(--fg))"), "red");
+    assert_eq!(
+        theme.resolve("var(--missing, var(--also-missing, blue))"),
+        "blue"
+    );
+    assert_eq!(theme.resolve("var(--border)"), "red");
+    assert_eq!(
+        theme.resolve("var(--fg) var(--missing, 2px) solid"),
+        "red 2px solid"
+    );
+    // an unclosed var is kept as written
+    assert_eq!(theme.resolve("var(--fg"), "var(--fg");
+}
+
+#[test]
+fn resolved_variables_parse_as_colors() {
+    use crate::style::RinkColor;
+    use ratatui::style::Color;
+
+    let parse = |theme: &Theme, value: &str| theme.resolve(value).parse::<RinkColor>().ok();
+    assert_eq!(
+        parse(&Theme::dark(), "var(--error)").map(|c| c.color),
+        Some(Color::Rgb(0xff, 0x5f, 0x5f))
+    );
+    assert_eq!(
+        parse(&Theme::light(), "var(--error)").map(|c| c.color),
+        Some(Color::Rgb(0xd7, 0, 0))
+    );
+    assert_eq!(
+        parse(&Theme::new(), "var(--error, blue)").map(|c| c.color),
+        Some(Color::Blue)
+    );
+}
+
+#[test]
+fn detect_light_background() {
+    assert!(is_light_background("0;15"));
+    assert!(is_light_background("0;default;7"));
+    assert!(!is_light_background("15;0"));
+    assert!(!is_light_background("default"));
+}
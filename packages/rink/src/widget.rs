@@ -7,36 +7,72 @@ use ratatui::{
 
 use crate::{
     style::{convert, RinkColor, RinkStyle},
-    Config,
+    RenderingMode,
 };
 
+/// The part of the screen a node is visible through and how far the scroll containers around it are scrolled
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Viewport {
+    /// The number of cells the content is moved up and left
+    pub offset: [i32; 2],
+    /// The area of the screen the content is clipped to
+    pub clip: Rect,
+}
+
+impl Viewport {
+    pub fn new(area: Rect) -> Self {
+        Self {
+            offset: [0, 0],
+            clip: area,
+        }
+    }
+}
+
 pub struct RinkBuffer<'a> {
     buf: &'a mut Buffer,
-    cfg: Config,
+    rendering_mode: RenderingMode,
+    viewport: Viewport,
 }
 
 impl<'a> RinkBuffer<'a> {
-    fn new(buf: &'a mut Buffer, cfg: Config) -> RinkBuffer<'a> {
-        Self { buf, cfg }
+    fn new(
+        buf: &'a mut Buffer,
+        rendering_mode: RenderingMode,
+        viewport: Option<Viewport>,
+    ) -> RinkBuffer<'a> {
+        let viewport = viewport.unwrap_or_else(|| Viewport::new(*buf.area()));
+        Self {
+            buf,
+            rendering_mode,
+            viewport,
+        }
     }
 
     pub fn set(&mut self, x: u16, y: u16, new: RinkCell) {
-        let area = self.buf.area();
-        if x < area.x || x >= area.width + area.x || y < area.y || y >= area.height + area.y {
+        let x = x as i32 - self.viewport.offset[0];
+        let y = y as i32 - self.viewport.offset[1];
+        let contains = |area: &Rect| {
+            x >= area.x as i32
+                && x < (area.width + area.x) as i32
+                && y >= area.y as i32
+                && y < (area.height + area.y) as i32
+        };
+        if !contains(self.buf.area()) || !contains(&self.viewport.clip) {
             // panic!("({x}, {y}) is not in {area:?}");
             return;
         }
+        let (x, y) = (x as u16, y as u16);
         let cell = self.buf.get_mut(x, y);
-        cell.bg = convert(self.cfg.rendering_mode, new.bg.blend(cell.bg));
+        cell.bg = convert(self.rendering_mode, new.bg.blend(cell.bg));
         if new.symbol.is_empty() {
             if !cell.symbol.is_empty() {
                 // allows text to "shine through" transparent backgrounds
-                cell.fg = convert(self.cfg.rendering_mode, new.bg.blend(cell.fg));
+                cell.fg = convert(self.rendering_mode, new.bg.blend(cell.fg));
             }
         } else {
             cell.modifier = new.modifier;
             cell.symbol = new.symbol;
-            cell.fg = convert(self.cfg.rendering_mode, new.fg.blend(cell.bg));
+            cell.fg = convert(self.rendering_mode, new.fg.blend(cell.bg));
         }
     }
 }
@@ -47,18 +83,32 @@ pub trait RinkWidget {
 
 pub struct WidgetWithContext<T: RinkWidget> {
     widget: T,
-    config: Config,
+    rendering_mode: RenderingMode,
+    viewport: Option<Viewport>,
 }
 
 impl<T: RinkWidget> WidgetWithContext<T> {
-    pub fn new(widget: T, config: Config) -> WidgetWithContext<T> {
-        WidgetWithContext { widget, config }
+    pub fn new(widget: T, rendering_mode: RenderingMode) -> WidgetWithContext<T> {
+        WidgetWithContext {
+            widget,
+            rendering_mode,
+            viewport: None,
+        }
+    }
+
+    /// Scroll and clip the widget. Without a viewport, the widget can draw anywhere on the screen
+    pub(crate) fn with_viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = Some(viewport);
+        self
     }
 }
 
 impl<T: RinkWidget> Widget for WidgetWithContext<T> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        self.widget.render(area, RinkBuffer::new(buf, self.config));
+        self.widget.render(
+            area,
+            RinkBuffer::new(buf, self.rendering_mode, self.viewport),
+        );
     }
 }
 
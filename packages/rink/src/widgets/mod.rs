@@ -3,8 +3,11 @@ mod checkbox;
 mod input;
 mod number;
 mod password;
+mod progress;
+mod select;
 mod slider;
 mod text_like;
+mod textarea;
 mod textbox;
 
 use std::sync::{Arc, RwLock};
@@ -23,6 +26,10 @@ pub(crate) fn register_widgets(rdom: &mut RealDom, sender: UnboundedSender<Event
     rdom.raw_world().add_unique(WidgetContext { sender });
 
     rdom.register_custom_element::<RinkWidgetWrapper<input::Input>>();
+    rdom.register_custom_element::<RinkWidgetWrapper<select::Select>>();
+    rdom.register_custom_element::<RinkWidgetWrapper<textarea::TextArea>>();
+    rdom.register_custom_element::<RinkWidgetWrapper<progress::Progress>>();
+    rdom.register_custom_element::<RinkWidgetWrapper<progress::Meter>>();
 }
 
 trait RinkWidget: Sync + Send + CustomElement + 'static {
@@ -1,4 +1,4 @@
-use dioxus_html::{input_data::keyboard_types::Key, HasKeyboardData};
+use dioxus_html::{input_data::keyboard_types::Key, HasClipboardData, HasKeyboardData};
 use dioxus_native_core::{
     custom_element::CustomElement,
     real_dom::{NodeImmutable, RealDom},
@@ -86,6 +86,18 @@ impl RinkWidget for Number {
             }
         }
 
+        // only numbers can be pasted
+        if event.name == "paste" {
+            if let EventData::Clipboard(data) = &event.data {
+                let is_num = data
+                    .text()
+                    .map_or(false, |text| text.parse::<f64>().is_ok());
+                if !is_num {
+                    return;
+                }
+            }
+        }
+
         self.text.handle_event(event, node)
     }
 }
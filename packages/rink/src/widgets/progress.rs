@@ -0,0 +1,220 @@
+use std::marker::PhantomData;
+
+use dioxus_native_core::{
+    custom_element::CustomElement,
+    node::{OwnedAttributeDiscription, OwnedAttributeValue},
+    prelude::{ElementNode, NodeType},
+    real_dom::{ElementNodeMut, NodeImmutable, NodeMut, NodeTypeMut},
+    NodeId,
+};
+
+use super::RinkWidget;
+
+const TRACK_COLOR: &str = "rgba(10, 10, 10, 0.5)";
+
+/// Controls what a [`Gauge`] measures and how it is colored
+pub(crate) trait GaugeKind: Default + Send + Sync + 'static {
+    const NAME: &'static str;
+
+    /// The portion of the gauge that is filled (between 0 and 1) and the color of the filled portion
+    fn fill(el: &ElementNodeMut) -> (f64, &'static str);
+}
+
+/// A `<progress>` element. A progress bar without a value is indeterminate and is drawn empty
+#[derive(Debug, Default)]
+pub(crate) struct ProgressKind;
+
+impl GaugeKind for ProgressKind {
+    const NAME: &'static str = "progress";
+
+    fn fill(el: &ElementNodeMut) -> (f64, &'static str) {
+        let max = number_attr(el, "max")
+            .filter(|max| *max > 0.0)
+            .unwrap_or(1.0);
+        let portion = number_attr(el, "value")
+            .map(|value| value.clamp(0.0, max) / max)
+            .unwrap_or_default();
+        (portion, "green")
+    }
+}
+
+/// A `<meter>` element. The color depends on which region the value falls in, relative to the optimum
+#[derive(Debug, Default)]
+pub(crate) struct MeterKind;
+
+impl GaugeKind for MeterKind {
+    const NAME: &'static str = "meter";
+
+    fn fill(el: &ElementNodeMut) -> (f64, &'static str) {
+        let min = number_attr(el, "min").unwrap_or(0.0);
+        let max = number_attr(el, "max").unwrap_or(1.0).max(min);
+        let value = number_attr(el, "value").unwrap_or(0.0).clamp(min, max);
+        let low = number_attr(el, "low").unwrap_or(min).clamp(min, max);
+        let high = number_attr(el, "high").unwrap_or(max).clamp(low, max);
+        let optimum = number_attr(el, "optimum")
+            .unwrap_or((min + max) / 2.0)
+            .clamp(min, max);
+
+        let portion = if max > min {
+            (value - min) / (max - min)
+        } else {
+            0.0
+        };
+
+        // the regions follow the html spec: the region containing the optimum is best, the regions next to it are
+        // suboptimal and the region on the other side is the worst
+        let color = if optimum < low {
+            if value <= low {
+                "green"
+            } else if value <= high {
+                "yellow"
+            } else {
+                "red"
+            }
+        } else if optimum > high {
+            if value >= high {
+                "green"
+            } else if value >= low {
+                "yellow"
+            } else {
+                "red"
+            }
+        } else if value >= low && value <= high {
+            "green"
+        } else {
+            "yellow"
+        };
+
+        (portion, color)
+    }
+}
+
+pub(crate) type Progress = Gauge<ProgressKind>;
+pub(crate) type Meter = Gauge<MeterKind>;
+
+fn number_attr(el: &ElementNodeMut, name: &str) -> Option<f64> {
+    match el.get_attribute(&OwnedAttributeDiscription {
+        name: name.to_string(),
+        namespace: None,
+    })? {
+        OwnedAttributeValue::Text(text) => text.trim().parse().ok(),
+        OwnedAttributeValue::Float(float) => Some(*float),
+        OwnedAttributeValue::Int(int) => Some(*int as f64),
+        _ => None,
+    }
+}
+
+fn has_style(el: &ElementNodeMut, name: &str) -> bool {
+    el.get_attribute(&OwnedAttributeDiscription {
+        name: name.to_string(),
+        namespace: Some("style".to_string()),
+    })
+    .is_some()
+}
+
+/// A horizontal bar that is filled to show a value
+#[derive(Debug, Default)]
+pub(crate) struct Gauge<K: GaugeKind> {
+    div_wrapper: NodeId,
+    filled: NodeId,
+    _marker: PhantomData<K>,
+}
+
+impl<K: GaugeKind> Gauge<K> {
+    fn write_value(&self, mut root: NodeMut) {
+        let (width, height, portion, color) = {
+            let node_type = root.node_type_mut();
+            let NodeTypeMut::Element(el) = node_type else {
+                panic!("{} must be an element", K::NAME)
+            };
+            let width = if has_style(&el, "width") {
+                "100%"
+            } else {
+                "10px"
+            };
+            let height = if has_style(&el, "height") {
+                "100%"
+            } else {
+                "1px"
+            };
+            let (portion, color) = K::fill(&el);
+            (width, height, portion, color)
+        };
+
+        let rdom = root.real_dom_mut();
+        if let Some(mut wrapper) = rdom.get_mut(self.div_wrapper) {
+            if let NodeTypeMut::Element(mut el) = wrapper.node_type_mut() {
+                el.set_attribute(("width", "style"), width.to_string());
+                el.set_attribute(("height", "style"), height.to_string());
+            }
+        }
+        if let Some(mut filled) = rdom.get_mut(self.filled) {
+            if let NodeTypeMut::Element(mut el) = filled.node_type_mut() {
+                el.set_attribute(
+                    ("width", "style"),
+                    format!("{}%", (portion * 100.0).clamp(0.0, 100.0)),
+                );
+                el.set_attribute(("background-color", "style"), color.to_string());
+            }
+        }
+    }
+}
+
+impl<K: GaugeKind> CustomElement for Gauge<K> {
+    const NAME: &'static str = K::NAME;
+
+    fn roots(&self) -> Vec<NodeId> {
+        vec![self.div_wrapper]
+    }
+
+    fn create(mut root: NodeMut) -> Self {
+        let rdom = root.real_dom_mut();
+
+        let filled = rdom.create_node(NodeType::Element(ElementNode {
+            tag: "div".to_string(),
+            attributes: [
+                (("width", "style").into(), "0%".to_string().into()),
+                (("height", "style").into(), "100%".to_string().into()),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        }));
+        let filled_id = filled.id();
+
+        let mut div_wrapper = rdom.create_node(NodeType::Element(ElementNode {
+            tag: "div".to_string(),
+            attributes: [
+                (("display", "style").into(), "flex".to_string().into()),
+                (("flex-direction", "style").into(), "row".to_string().into()),
+                (
+                    ("background-color", "style").into(),
+                    TRACK_COLOR.to_string().into(),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        }));
+        div_wrapper.add_child(filled_id);
+        let div_wrapper_id = div_wrapper.id();
+
+        Self {
+            div_wrapper: div_wrapper_id,
+            filled: filled_id,
+            _marker: PhantomData,
+        }
+    }
+
+    fn attributes_changed(
+        &mut self,
+        root: NodeMut,
+        _: &dioxus_native_core::node_ref::AttributeMask,
+    ) {
+        self.write_value(root);
+    }
+}
+
+impl<K: GaugeKind> RinkWidget for Gauge<K> {
+    fn handle_event(&mut self, _: &crate::Event, _: NodeMut) {}
+}
@@ -0,0 +1,480 @@
+use std::collections::HashMap;
+
+use dioxus_html::{
+    input_data::keyboard_types::Key, prelude::*, HasKeyboardData, SerializedKeyboardData,
+    SerializedMouseData,
+};
+use dioxus_native_core::{
+    custom_element::CustomElement,
+    node::{OwnedAttributeDiscription, OwnedAttributeValue},
+    prelude::{ElementNode, NodeType},
+    real_dom::{NodeImmutable, NodeMut, NodeRef, NodeTypeMut, RealDom},
+    NodeId,
+};
+use shipyard::UniqueView;
+
+use crate::hooks::FormData;
+use crate::{query::get_layout, Event, EventData, Query};
+
+use super::{RinkWidget, WidgetContext};
+
+/// An `<option>` in the light DOM of the select
+#[derive(Debug, Clone, PartialEq)]
+struct SelectOption {
+    label: String,
+    value: String,
+    disabled: bool,
+    selected: bool,
+}
+
+fn attribute<'a>(el: &'a ElementNode, name: &str) -> Option<&'a OwnedAttributeValue> {
+    el.attributes.get(&OwnedAttributeDiscription {
+        name: name.to_string(),
+        namespace: None,
+    })
+}
+
+/// Boolean attributes are true if they are set to anything other than false
+fn bool_attr(el: &ElementNode, name: &str) -> bool {
+    match attribute(el, name) {
+        Some(OwnedAttributeValue::Bool(value)) => *value,
+        Some(OwnedAttributeValue::Text(value)) => value != "false",
+        Some(_) => true,
+        None => false,
+    }
+}
+
+fn text_content(node: NodeRef) -> String {
+    match &*node.node_type() {
+        NodeType::Text(text) => text.text.clone(),
+        NodeType::Element(_) => node
+            .children()
+            .into_iter()
+            .map(text_content)
+            .collect::<String>(),
+        NodeType::Placeholder => String::new(),
+    }
+}
+
+/// Collect the options (including the options in option groups) in the light DOM of a select
+fn collect_options(node: NodeRef, group_disabled: bool, options: &mut Vec<SelectOption>) {
+    for child in node.children() {
+        let node_type = child.node_type();
+        let NodeType::Element(el) = &*node_type else {
+            continue;
+        };
+        match el.tag.as_str() {
+            "option" => {
+                let label = text_content(child).trim().to_string();
+                let value = attribute(el, "value")
+                    .and_then(|value| value.as_text())
+                    .map(|value| value.to_string())
+                    .unwrap_or_else(|| label.clone());
+                options.push(SelectOption {
+                    label,
+                    value,
+                    disabled: group_disabled || bool_attr(el, "disabled"),
+                    selected: bool_attr(el, "selected"),
+                });
+            }
+            "optgroup" => {
+                collect_options(child, group_disabled || bool_attr(el, "disabled"), options)
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Select {
+    div_wrapper: NodeId,
+    label_text: NodeId,
+    arrow_text: NodeId,
+    list: NodeId,
+    /// The hidden slot the options in the light DOM are placed in
+    slot: NodeId,
+    options: Vec<SelectOption>,
+    selected: Option<usize>,
+    /// The option the keyboard is on while the select is open
+    highlighted: usize,
+    open: bool,
+}
+
+impl Select {
+    /// Read the options from the light DOM. If `from_attributes` is true, the selection is reset from the value
+    /// attribute and the selected attributes of the options
+    fn update_options(&mut self, rdom: &RealDom, id: NodeId, from_attributes: bool) {
+        let root = rdom.get(id).unwrap();
+        let mut options = Vec::new();
+        collect_options(root, false, &mut options);
+        self.options = options;
+
+        let value = match &*root.node_type() {
+            NodeType::Element(el) => attribute(el, "value")
+                .and_then(|value| value.as_text())
+                .map(|value| value.to_string()),
+            _ => None,
+        };
+
+        if from_attributes {
+            let by_value =
+                value.and_then(|value| self.options.iter().position(|o| o.value == value));
+            let by_flag = self.options.iter().rposition(|o| o.selected);
+            if let Some(selected) = by_value.or(by_flag) {
+                self.selected = Some(selected);
+            }
+        }
+
+        if self.selected.filter(|i| *i < self.options.len()).is_none() {
+            self.selected = self.options.iter().position(|o| !o.disabled);
+        }
+        self.highlighted = self.highlighted.min(self.options.len().saturating_sub(1));
+    }
+
+    fn value(&self) -> String {
+        self.selected
+            .and_then(|i| self.options.get(i))
+            .map(|o| o.value.clone())
+            .unwrap_or_default()
+    }
+
+    /// The next enabled option in a direction, starting from an option
+    fn step(&self, from: usize, forward: bool) -> Option<usize> {
+        if forward {
+            (from + 1..self.options.len()).find(|i| !self.options[*i].disabled)
+        } else {
+            (0..from).rev().find(|i| !self.options[*i].disabled)
+        }
+    }
+
+    /// The next enabled option after the current one whose label starts with a character
+    fn find_by_char(&self, from: usize, c: char) -> Option<usize> {
+        let len = self.options.len();
+        let c = c.to_lowercase().collect::<String>();
+        (1..=len).map(|offset| (from + offset) % len).find(|i| {
+            let option = &self.options[*i];
+            !option.disabled && option.label.to_lowercase().starts_with(&c)
+        })
+    }
+
+    fn write_value(&self, rdom: &mut RealDom, id: NodeId) {
+        let label = self
+            .selected
+            .and_then(|i| self.options.get(i))
+            .map(|o| o.label.clone())
+            .unwrap_or_default();
+        let arrow = if self.open { "▴" } else { "▾" };
+        for (text_id, value) in [
+            (self.label_text, label),
+            (self.arrow_text, arrow.to_string()),
+        ] {
+            if let Some(mut text) = rdom.get_mut(text_id) {
+                let NodeTypeMut::Text(mut text) = text.node_type_mut() else {
+                    panic!("select label must be a text node")
+                };
+                if text.text() != value {
+                    *text.text_mut() = value;
+                }
+            }
+        }
+
+        // size the select to fit the longest option unless it is sized with styles
+        let styled_width = match &*rdom.get(id).unwrap().node_type() {
+            NodeType::Element(el) => el.attributes.contains_key(&OwnedAttributeDiscription {
+                name: "width".to_string(),
+                namespace: Some("style".to_string()),
+            }),
+            _ => false,
+        };
+        let width = if styled_width {
+            "100%".to_string()
+        } else {
+            let longest = self
+                .options
+                .iter()
+                .map(|o| o.label.chars().count())
+                .max()
+                .unwrap_or_default();
+            format!("{}px", longest + 2)
+        };
+        if let Some(mut wrapper) = rdom.get_mut(self.div_wrapper) {
+            if let NodeTypeMut::Element(mut el) = wrapper.node_type_mut() {
+                el.set_attribute(("width", "style"), width);
+            }
+        }
+
+        let old_rows = rdom.get(self.list).unwrap().child_ids();
+        for row in old_rows {
+            if let Some(mut row) = rdom.get_mut(row) {
+                row.remove();
+            }
+        }
+        if self.open {
+            for (i, option) in self.options.iter().enumerate() {
+                let mut attributes: Vec<(OwnedAttributeDiscription, OwnedAttributeValue)> =
+                    vec![(("height", "style").into(), "1px".to_string().into())];
+                if i == self.highlighted {
+                    attributes.push((
+                        ("background-color", "style").into(),
+                        "rgba(255, 255, 255, 50%)".to_string().into(),
+                    ));
+                }
+                if option.disabled {
+                    attributes.push((("color", "style").into(), "gray".to_string().into()));
+                }
+                let text_id = rdom.create_node(option.label.clone()).id();
+                let mut row = rdom.create_node(NodeType::Element(ElementNode {
+                    tag: "div".to_string(),
+                    attributes: attributes.into_iter().collect(),
+                    ..Default::default()
+                }));
+                row.add_child(text_id);
+                let row_id = row.id();
+                rdom.get_mut(self.list).unwrap().add_child(row_id);
+            }
+        }
+    }
+
+    fn send_events(&self, rdom: &mut RealDom, id: NodeId) {
+        let world = rdom.raw_world_mut();
+        let ctx: UniqueView<WidgetContext> = world.borrow().expect("expected widget context");
+        for name in ["input", "change"] {
+            ctx.send(Event {
+                id,
+                name,
+                data: EventData::Form(FormData {
+                    value: self.value(),
+                    values: HashMap::new(),
+                    files: None,
+                }),
+                bubbles: true,
+            });
+        }
+    }
+
+    /// Select an option and fire the input and change events if the selection changed
+    fn choose(&mut self, rdom: &mut RealDom, id: NodeId, option: usize) {
+        if self.options.get(option).filter(|o| !o.disabled).is_none() {
+            return;
+        }
+        if self.selected != Some(option) {
+            self.selected = Some(option);
+            self.send_events(rdom, id);
+        }
+    }
+
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+        if open {
+            self.highlighted = self.selected.unwrap_or_default();
+        }
+    }
+
+    fn handle_keydown(&mut self, mut root: NodeMut, data: &SerializedKeyboardData) {
+        let id = root.id();
+        let rdom = root.real_dom_mut();
+        let current = if self.open {
+            self.highlighted
+        } else {
+            self.selected.unwrap_or_default()
+        };
+
+        let target = match data.key() {
+            Key::ArrowDown | Key::ArrowRight => self.step(current, true),
+            Key::ArrowUp | Key::ArrowLeft => self.step(current, false),
+            Key::Home => (0..self.options.len()).find(|i| !self.options[*i].disabled),
+            Key::End => (0..self.options.len())
+                .rev()
+                .find(|i| !self.options[*i].disabled),
+            Key::Character(c) if c == " " => {
+                if self.open {
+                    self.choose(rdom, id, self.highlighted);
+                }
+                self.set_open(!self.open);
+                None
+            }
+            Key::Enter => {
+                if self.open {
+                    self.choose(rdom, id, self.highlighted);
+                }
+                self.set_open(!self.open);
+                None
+            }
+            Key::Escape => {
+                self.set_open(false);
+                None
+            }
+            Key::Character(c) => c.chars().next().and_then(|c| self.find_by_char(current, c)),
+            _ => None,
+        };
+
+        if let Some(target) = target {
+            if self.open {
+                self.highlighted = target;
+            } else {
+                self.choose(rdom, id, target);
+            }
+        }
+        self.write_value(rdom, id);
+    }
+
+    fn handle_click(&mut self, mut root: NodeMut, data: &SerializedMouseData) {
+        let id = root.id();
+        let rdom = root.real_dom_mut();
+        if self.open {
+            let list_layout = {
+                let query: UniqueView<Query> = rdom.raw_world().borrow().unwrap();
+                let taffy = query.stretch.lock().unwrap();
+                get_layout(rdom.get(self.list).unwrap(), &taffy).unwrap()
+            };
+            let row = data.client_coordinates().y - list_layout.location.y as f64;
+            if row >= 0.0 {
+                self.choose(rdom, id, row as usize);
+            }
+            self.set_open(false);
+        } else {
+            self.set_open(true);
+        }
+        self.write_value(rdom, id);
+    }
+}
+
+impl CustomElement for Select {
+    const NAME: &'static str = "select";
+
+    fn roots(&self) -> Vec<NodeId> {
+        vec![self.div_wrapper]
+    }
+
+    fn slot(&self) -> Option<NodeId> {
+        Some(self.slot)
+    }
+
+    fn create(mut root: NodeMut) -> Self {
+        let rdom = root.real_dom_mut();
+
+        let label_text = rdom.create_node(String::new()).id();
+        let mut label = rdom.create_node(NodeType::Element(ElementNode {
+            tag: "div".to_string(),
+            attributes: [(("flex-grow", "style").into(), "1".to_string().into())]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        }));
+        label.add_child(label_text);
+        let label_id = label.id();
+        let arrow_text = rdom.create_node("▾".to_string()).id();
+
+        let mut header = rdom.create_node(NodeType::Element(ElementNode {
+            tag: "div".to_string(),
+            attributes: [
+                (("display", "style").into(), "flex".to_string().into()),
+                (("flex-direction", "style").into(), "row".to_string().into()),
+                (("height", "style").into(), "1px".to_string().into()),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        }));
+        header.add_child(label_id);
+        header.add_child(arrow_text);
+        let header_id = header.id();
+
+        let list_id = rdom
+            .create_node(NodeType::Element(ElementNode {
+                tag: "div".to_string(),
+                attributes: [
+                    (("display", "style").into(), "flex".to_string().into()),
+                    (
+                        ("flex-direction", "style").into(),
+                        "column".to_string().into(),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            }))
+            .id();
+
+        let slot_id = rdom
+            .create_node(NodeType::Element(ElementNode {
+                tag: "div".to_string(),
+                attributes: [(("display", "style").into(), "none".to_string().into())]
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            }))
+            .id();
+
+        let mut div_wrapper = rdom.create_node(NodeType::Element(ElementNode {
+            tag: "div".to_string(),
+            attributes: [
+                (("display", "style").into(), "flex".to_string().into()),
+                (
+                    ("flex-direction", "style").into(),
+                    "column".to_string().into(),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        }));
+        div_wrapper.add_child(header_id);
+        div_wrapper.add_child(list_id);
+        div_wrapper.add_child(slot_id);
+        let div_wrapper_id = div_wrapper.id();
+
+        root.add_event_listener("keydown");
+        root.add_event_listener("click");
+        root.add_event_listener("focusout");
+
+        Self {
+            div_wrapper: div_wrapper_id,
+            label_text,
+            arrow_text,
+            list: list_id,
+            slot: slot_id,
+            ..Default::default()
+        }
+    }
+
+    fn attributes_changed(
+        &mut self,
+        mut root: NodeMut,
+        _: &dioxus_native_core::node_ref::AttributeMask,
+    ) {
+        let id = root.id();
+        let rdom = root.real_dom_mut();
+        self.update_options(rdom, id, true);
+        self.write_value(rdom, id);
+    }
+}
+
+impl RinkWidget for Select {
+    fn handle_event(&mut self, event: &crate::Event, mut node: NodeMut) {
+        // the options may have changed without the attributes of the select changing
+        let id = node.id();
+        self.update_options(node.real_dom_mut(), id, false);
+
+        match event.name {
+            "keydown" => {
+                if let EventData::Keyboard(data) = &event.data {
+                    self.handle_keydown(node, data);
+                }
+            }
+
+            "click" => {
+                if let EventData::Mouse(data) = &event.data {
+                    self.handle_click(node, data);
+                }
+            }
+
+            "focusout" if self.open => {
+                self.set_open(false);
+                self.write_value(node.real_dom_mut(), id);
+            }
+
+            _ => {}
+        }
+    }
+}
@@ -208,11 +208,28 @@ impl<C: TextLikeController> TextLike<C> {
 
         let rdom = root.real_dom_mut();
         self.write_value(rdom, id);
-        let world = rdom.raw_world_mut();
+        self.move_terminal_cursor(rdom);
+    }
 
-        // move cursor to new position
+    fn handle_paste(&mut self, mut root: NodeMut, pasted: &str) {
+        // inputs are only one line tall
+        let pasted: String = pasted
+            .chars()
+            .filter(|c| !matches!(c, '\n' | '\r'))
+            .collect();
+        self.cursor
+            .insert_text(&pasted, &mut self.text, self.max_len.unwrap_or(1000));
+
+        let id = root.id();
+        let rdom = root.real_dom_mut();
+        self.write_value(rdom, id);
+        self.move_terminal_cursor(rdom);
+    }
+
+    /// Move the terminal cursor to the cursor in the input
+    fn move_terminal_cursor(&self, rdom: &RealDom) {
         let taffy = {
-            let query: UniqueView<Query> = world.borrow().unwrap();
+            let query: UniqueView<Query> = rdom.raw_world().borrow().unwrap();
             query.stretch.clone()
         };
 
@@ -264,26 +281,7 @@ impl<C: TextLikeController> TextLike<C> {
 
         // move cursor to new position
         let rdom = root.real_dom_mut();
-        let world = rdom.raw_world_mut();
-        let taffy = {
-            let query: UniqueView<Query> = world.borrow().unwrap();
-            query.stretch.clone()
-        };
-
-        let taffy = taffy.lock().unwrap();
-
-        let layout = get_layout(rdom.get(self.div_wrapper).unwrap(), &taffy).unwrap();
-        let Point { x, y } = layout.location;
-
-        let Pos { col, row } = self.cursor.start;
-        let (x, y) = (col as u16 + x as u16, row as u16 + y as u16);
-        if let Ok(pos) = crossterm::cursor::position() {
-            if pos != (x, y) {
-                execute!(stdout(), MoveTo(x, y)).unwrap();
-            }
-        } else {
-            execute!(stdout(), MoveTo(x, y)).unwrap();
-        }
+        self.move_terminal_cursor(rdom);
 
         self.write_value(rdom, id)
     }
@@ -360,6 +358,7 @@ impl<C: TextLikeController + Send + Sync + Default + 'static> CustomElement for
         div_wrapper.add_event_listener("mouseleave");
         div_wrapper.add_event_listener("mouseenter");
         root.add_event_listener("keydown");
+        root.add_event_listener("paste");
         root.add_event_listener("focusout");
 
         Self {
@@ -426,6 +425,14 @@ impl<C: TextLikeController + Send + Sync + Default + 'static> RinkWidget for Tex
                 }
             }
 
+            "paste" => {
+                if let EventData::Clipboard(data) = &event.data {
+                    if let Some(text) = data.text() {
+                        self.handle_paste(node, &text);
+                    }
+                }
+            }
+
             "mousemove" => {
                 if let EventData::Mouse(data) = &event.data {
                     self.handle_mousemove(node, data);
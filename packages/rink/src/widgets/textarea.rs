@@ -0,0 +1,639 @@
+use std::{collections::HashMap, io::stdout};
+
+use crossterm::{cursor::MoveTo, execute};
+use dioxus_html::{
+    input_data::keyboard_types::Key, prelude::*, HasKeyboardData, HasWheelData,
+    SerializedKeyboardData, SerializedMouseData, SerializedWheelData,
+};
+use dioxus_native_core::{
+    custom_element::CustomElement,
+    node::OwnedAttributeDiscription,
+    prelude::{ElementNode, NodeType},
+    real_dom::{ElementNodeMut, NodeImmutable, NodeMut, NodeTypeMut, RealDom},
+    utils::cursor::{Cursor, Pos},
+    NodeId,
+};
+use shipyard::UniqueView;
+use taffy::geometry::Point;
+
+use crate::hooks::FormData;
+use crate::layout::{PossiblyUninitalized, TaffyLayout};
+use crate::{query::get_layout, Event, EventData, Query};
+
+use super::{RinkWidget, WidgetContext};
+
+/// A line of the text after it is soft wrapped to the width of the textarea
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct VisualLine {
+    /// The line in the text this is a part of
+    row: usize,
+    /// The column in the line this part starts at
+    start: usize,
+    /// The column in the line this part ends at
+    end: usize,
+}
+
+/// Wrap the lines of the text to a width. Lines are broken after the last space that fits, or in the middle of words
+/// that are longer than the width
+fn wrap(text: &str, width: usize) -> Vec<VisualLine> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    for (row, line) in text.split('\n').enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        let mut start = 0;
+        while chars.len() - start > width {
+            let end = (start + 1..=start + width)
+                .rev()
+                .find(|end| chars[end - 1] == ' ')
+                .unwrap_or(start + width);
+            lines.push(VisualLine { row, start, end });
+            start = end;
+        }
+        lines.push(VisualLine {
+            row,
+            start,
+            end: chars.len(),
+        });
+    }
+    lines
+}
+
+#[derive(Debug)]
+pub(crate) struct TextArea {
+    text: String,
+    div_wrapper: NodeId,
+    cursor: Cursor,
+    dragging: bool,
+    /// The first visual line that is visible
+    scroll_top: usize,
+    max_len: Option<usize>,
+    /// The default width in characters
+    cols: usize,
+    /// The default height in lines
+    rows: usize,
+    /// If the text was edited since the textarea was focused
+    changed: bool,
+}
+
+impl Default for TextArea {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            div_wrapper: Default::default(),
+            cursor: Cursor::default(),
+            dragging: false,
+            scroll_top: 0,
+            max_len: None,
+            cols: 20,
+            rows: 2,
+            changed: false,
+        }
+    }
+}
+
+impl TextArea {
+    fn attribute(el: &ElementNodeMut, name: &str, namespace: Option<&str>) -> Option<String> {
+        el.get_attribute(&OwnedAttributeDiscription {
+            name: name.to_string(),
+            namespace: namespace.map(|namespace| namespace.to_string()),
+        })
+        .and_then(|value| value.as_text())
+        .map(|value| value.to_string())
+    }
+
+    fn update_value_attr(&mut self, el: &ElementNodeMut) {
+        if let Some(value) = Self::attribute(el, "value", None) {
+            if value != self.text {
+                self.text = value;
+                // keep the cursor inside the new text
+                let (first, last) = self.selection();
+                let len = self.text.chars().count();
+                if last > len || first > len {
+                    self.cursor = Cursor::from_start(self.pos_at(len));
+                }
+            }
+        }
+    }
+
+    fn update_max_length_attr(&mut self, el: &ElementNodeMut) {
+        self.max_len = Self::attribute(el, "maxlength", None).and_then(|v| v.parse().ok());
+    }
+
+    fn update_size_attr(&mut self, el: &mut ElementNodeMut) {
+        self.cols = Self::attribute(el, "cols", None)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(20);
+        self.rows = Self::attribute(el, "rows", None)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(2);
+        el.set_attribute(
+            OwnedAttributeDiscription {
+                name: "border-style".to_string(),
+                namespace: Some("style".to_string()),
+            },
+            "solid".to_string(),
+        );
+    }
+
+    /// Size the wrapper to `cols` and `rows` unless the textarea is sized with styles
+    fn wrapper_size(&self, el: &ElementNodeMut) -> (String, String) {
+        let width = if Self::attribute(el, "width", Some("style")).is_some() {
+            "100%".to_string()
+        } else {
+            format!("{}px", self.cols)
+        };
+        let height = if Self::attribute(el, "height", Some("style")).is_some() {
+            "100%".to_string()
+        } else {
+            format!("{}px", self.rows)
+        };
+        (width, height)
+    }
+
+    /// The character indexes of the start and end of the selection
+    fn selection(&self) -> (usize, usize) {
+        let text = self.text.as_str();
+        (self.cursor.first().idx(text), self.cursor.last().idx(text))
+    }
+
+    /// The position of a character index in the text
+    fn pos_at(&self, idx: usize) -> Pos {
+        let mut remaining = idx;
+        for (row, line) in self.text.split('\n').enumerate() {
+            let len = line.chars().count();
+            if remaining <= len {
+                return Pos::new(remaining, row);
+            }
+            remaining -= len + 1;
+        }
+        Pos::new(0, 0)
+    }
+
+    /// The size of the wrapper in cells after layout, or the default size before the textarea is laid out
+    fn size(&self, rdom: &RealDom) -> (usize, usize) {
+        let query: UniqueView<Query> = rdom.raw_world().borrow().unwrap();
+        let taffy = query.stretch.lock().unwrap();
+        let node = rdom.get(self.div_wrapper).unwrap();
+        match node.get::<TaffyLayout>().map(|layout| layout.node) {
+            Some(PossiblyUninitalized::Initialized(node)) => {
+                let size = taffy.layout(node).unwrap().size;
+                let width = crate::layout_to_screen_space(size.width).round() as usize;
+                let height = crate::layout_to_screen_space(size.height).round() as usize;
+                if width > 0 && height > 0 {
+                    return (width, height);
+                }
+                (self.cols, self.rows)
+            }
+            _ => (self.cols, self.rows),
+        }
+    }
+
+    /// The visual line and column of a position in the text
+    fn visual_pos(lines: &[VisualLine], pos: &Pos, text: &str) -> (usize, usize) {
+        let col = pos.col(text);
+        lines
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, line)| line.row == pos.row && line.start <= col)
+            .map(|(i, line)| (i, col - line.start))
+            .unwrap_or_default()
+    }
+
+    /// The position in the text of a visual line and column
+    fn text_pos(lines: &[VisualLine], row: usize, col: usize) -> Pos {
+        match lines.get(row).or(lines.last()) {
+            Some(line) => Pos::new((line.start + col).min(line.end), line.row),
+            None => Pos::new(0, 0),
+        }
+    }
+
+    /// Scroll so the cursor is visible
+    fn scroll_to_cursor(&mut self, lines: &[VisualLine], height: usize) {
+        let (row, _) = Self::visual_pos(lines, &self.cursor.start, &self.text);
+        if row < self.scroll_top {
+            self.scroll_top = row;
+        } else if row >= self.scroll_top + height {
+            self.scroll_top = row + 1 - height;
+        }
+        self.scroll_top = self
+            .scroll_top
+            .min(lines.len().saturating_sub(height.max(1)));
+    }
+
+    fn write_value(&mut self, rdom: &mut RealDom) {
+        let (width, height) = self.size(rdom);
+        let lines = wrap(&self.text, width);
+        self.scroll_top = self
+            .scroll_top
+            .min(lines.len().saturating_sub(height.max(1)));
+        let (first, last) = self.selection();
+
+        // the character index each line of the text starts at
+        let mut line_starts = Vec::new();
+        let mut idx = 0;
+        for line in self.text.split('\n') {
+            line_starts.push(idx);
+            idx += line.chars().count() + 1;
+        }
+
+        let old_children = rdom.get(self.div_wrapper).unwrap().child_ids();
+        for child in old_children {
+            if let Some(mut child) = rdom.get_mut(child) {
+                child.remove();
+            }
+        }
+
+        for line in lines.iter().skip(self.scroll_top).take(height) {
+            let text_line = self.text.split('\n').nth(line.row).unwrap_or_default();
+            let chars: Vec<char> = text_line.chars().collect();
+            let line_start = line_starts[line.row];
+            // the part of the selection in this line
+            let highlight_start =
+                first.clamp(line_start + line.start, line_start + line.end) - line_start;
+            let highlight_end =
+                last.clamp(line_start + line.start, line_start + line.end) - line_start;
+            let parts = [
+                (line.start, highlight_start, false),
+                (highlight_start, highlight_end, true),
+                (highlight_end, line.end, false),
+            ];
+
+            let line_div_id = rdom
+                .create_node(NodeType::Element(ElementNode {
+                    tag: "div".to_string(),
+                    attributes: [
+                        (("height", "style").into(), "1px".to_string().into()),
+                        (("flex-shrink", "style").into(), "0".to_string().into()),
+                    ]
+                    .into_iter()
+                    .collect(),
+                    ..Default::default()
+                }))
+                .id();
+
+            for (start, end, highlighted) in parts {
+                if start >= end {
+                    continue;
+                }
+                let text: String = chars[start..end].iter().collect();
+                let text_id = rdom.create_node(text).id();
+                let child = if highlighted {
+                    let mut span = rdom.create_node(NodeType::Element(ElementNode {
+                        tag: "span".to_string(),
+                        attributes: [(
+                            ("background-color", "style").into(),
+                            "rgba(255, 255, 255, 50%)".to_string().into(),
+                        )]
+                        .into_iter()
+                        .collect(),
+                        ..Default::default()
+                    }));
+                    span.add_child(text_id);
+                    span.id()
+                } else {
+                    text_id
+                };
+                rdom.get_mut(line_div_id).unwrap().add_child(child);
+            }
+            rdom.get_mut(self.div_wrapper)
+                .unwrap()
+                .add_child(line_div_id);
+        }
+    }
+
+    fn send_event(&self, rdom: &mut RealDom, id: NodeId, name: &'static str) {
+        let world = rdom.raw_world_mut();
+        let ctx: UniqueView<WidgetContext> = world.borrow().expect("expected widget context");
+        ctx.send(Event {
+            id,
+            name,
+            data: EventData::Form(FormData {
+                value: self.text.clone(),
+                values: HashMap::new(),
+                files: None,
+            }),
+            bubbles: true,
+        });
+    }
+
+    /// Move the terminal cursor to the cursor in the textarea
+    fn move_terminal_cursor(&self, rdom: &RealDom) {
+        let (width, _) = self.size(rdom);
+        let lines = wrap(&self.text, width);
+        let (row, col) = Self::visual_pos(&lines, &self.cursor.start, &self.text);
+
+        let query: UniqueView<Query> = rdom.raw_world().borrow().unwrap();
+        let taffy = query.stretch.lock().unwrap();
+        let layout = get_layout(rdom.get(self.div_wrapper).unwrap(), &taffy).unwrap();
+        let Point { x, y } = layout.location;
+
+        let (x, y) = (
+            col as u16 + x as u16,
+            (row - self.scroll_top.min(row)) as u16 + y as u16,
+        );
+        if let Ok(pos) = crossterm::cursor::position() {
+            if pos != (x, y) {
+                execute!(stdout(), MoveTo(x, y)).unwrap();
+            }
+        } else {
+            execute!(stdout(), MoveTo(x, y)).unwrap();
+        }
+    }
+
+    fn handle_keydown(&mut self, mut root: NodeMut, data: &SerializedKeyboardData) {
+        let key = data.key();
+        let modifiers = data.modifiers();
+        let code = data.code();
+
+        let id = root.id();
+        let rdom = root.real_dom_mut();
+        let (width, height) = self.size(rdom);
+
+        let old_text = self.text.clone();
+        match key {
+            // the cursor moves between the visual lines of wrapped text
+            Key::ArrowUp | Key::ArrowDown | Key::PageUp | Key::PageDown => {
+                let lines = wrap(&self.text, width);
+                let moving = if modifiers.contains(Modifiers::SHIFT) {
+                    self.cursor
+                        .end
+                        .clone()
+                        .unwrap_or_else(|| self.cursor.start.clone())
+                } else {
+                    self.cursor.start.clone()
+                };
+                let (row, col) = Self::visual_pos(&lines, &moving, &self.text);
+                let distance = if matches!(key, Key::PageUp | Key::PageDown) {
+                    height.max(1)
+                } else {
+                    1
+                };
+                let row = if matches!(key, Key::ArrowUp | Key::PageUp) {
+                    row.saturating_sub(distance)
+                } else {
+                    (row + distance).min(lines.len().saturating_sub(1))
+                };
+                let new = Self::text_pos(&lines, row, col);
+                self.cursor
+                    .move_cursor(|pos| *pos = new, modifiers.contains(Modifiers::SHIFT));
+            }
+            _ => {
+                self.cursor.handle_input(
+                    &code,
+                    &key,
+                    &modifiers,
+                    &mut self.text,
+                    self.max_len.unwrap_or(usize::MAX),
+                );
+            }
+        }
+
+        let lines = wrap(&self.text, width);
+        self.scroll_to_cursor(&lines, height);
+        self.write_value(rdom);
+        if self.text != old_text {
+            self.changed = true;
+            self.send_event(rdom, id, "input");
+        }
+        self.move_terminal_cursor(rdom);
+    }
+
+    fn handle_paste(&mut self, mut root: NodeMut, pasted: &str) {
+        let id = root.id();
+        let rdom = root.real_dom_mut();
+        let (width, height) = self.size(rdom);
+
+        // terminals send line breaks as carriage returns
+        let pasted = pasted.replace("\r\n", "\n").replace('\r', "\n");
+        let old_text = self.text.clone();
+        self.cursor
+            .insert_text(&pasted, &mut self.text, self.max_len.unwrap_or(usize::MAX));
+
+        let lines = wrap(&self.text, width);
+        self.scroll_to_cursor(&lines, height);
+        self.write_value(rdom);
+        if self.text != old_text {
+            self.changed = true;
+            self.send_event(rdom, id, "input");
+        }
+        self.move_terminal_cursor(rdom);
+    }
+
+    /// The position in the text under the mouse
+    fn mouse_pos(&self, rdom: &RealDom, data: &SerializedMouseData) -> Pos {
+        let (width, _) = self.size(rdom);
+        let lines = wrap(&self.text, width);
+        let offset = data.element_coordinates();
+        Self::text_pos(
+            &lines,
+            self.scroll_top + offset.y.max(0.0) as usize,
+            offset.x.max(0.0) as usize,
+        )
+    }
+
+    fn handle_mousedown(&mut self, mut root: NodeMut, data: &SerializedMouseData) {
+        let rdom = root.real_dom_mut();
+        self.cursor = Cursor::from_start(self.mouse_pos(rdom, data));
+        self.dragging = true;
+        self.write_value(rdom);
+        self.move_terminal_cursor(rdom);
+    }
+
+    fn handle_mousemove(&mut self, mut root: NodeMut, data: &SerializedMouseData) {
+        if self.dragging {
+            let rdom = root.real_dom_mut();
+            let new = self.mouse_pos(rdom, data);
+            if new != self.cursor.start {
+                self.cursor.end = Some(new);
+            }
+            self.write_value(rdom);
+        }
+    }
+
+    fn handle_wheel(&mut self, mut root: NodeMut, data: &SerializedWheelData) {
+        let rdom = root.real_dom_mut();
+        let (width, height) = self.size(rdom);
+        let lines = wrap(&self.text, width);
+        let delta = data.delta().strip_units().y.round() as i64;
+        let max = lines.len().saturating_sub(height.max(1));
+        self.scroll_top = (self.scroll_top as i64 + delta).clamp(0, max as i64) as usize;
+        self.write_value(rdom);
+    }
+}
+
+impl CustomElement for TextArea {
+    const NAME: &'static str = "textarea";
+
+    fn roots(&self) -> Vec<NodeId> {
+        vec![self.div_wrapper]
+    }
+
+    fn create(mut root: NodeMut) -> Self {
+        let node_type = root.node_type();
+        let NodeType::Element(el) = &*node_type else {
+            panic!("textarea must be an element")
+        };
+
+        let value = el
+            .attributes
+            .get(&OwnedAttributeDiscription {
+                name: "value".to_string(),
+                namespace: None,
+            })
+            .and_then(|value| value.as_text())
+            .map(|value| value.to_string());
+
+        drop(node_type);
+
+        let rdom = root.real_dom_mut();
+        let mut div_wrapper = rdom.create_node(NodeType::Element(ElementNode {
+            tag: "div".to_string(),
+            attributes: [
+                (("display", "style").into(), "flex".to_string().into()),
+                (
+                    ("flex-direction", "style").into(),
+                    "column".to_string().into(),
+                ),
+                (("overflow", "style").into(), "hidden".to_string().into()),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        }));
+        div_wrapper.add_event_listener("mousemove");
+        div_wrapper.add_event_listener("mousedown");
+        div_wrapper.add_event_listener("mouseup");
+        div_wrapper.add_event_listener("mouseleave");
+        div_wrapper.add_event_listener("mouseenter");
+        let div_wrapper_id = div_wrapper.id();
+
+        root.add_event_listener("keydown");
+        root.add_event_listener("paste");
+        root.add_event_listener("wheel");
+        root.add_event_listener("focusout");
+
+        Self {
+            text: value.unwrap_or_default(),
+            div_wrapper: div_wrapper_id,
+            ..Default::default()
+        }
+    }
+
+    fn attributes_changed(
+        &mut self,
+        mut root: NodeMut,
+        attributes: &dioxus_native_core::node_ref::AttributeMask,
+    ) {
+        let (width, height) = {
+            let node_type = root.node_type_mut();
+            let NodeTypeMut::Element(mut el) = node_type else {
+                panic!("textarea must be an element")
+            };
+            // only reset the text when the value attribute changes, not when other attributes like the border style
+            // the textarea sets on itself change
+            if attributes.contains("value") {
+                self.update_value_attr(&el);
+            }
+            if attributes.contains("maxlength") {
+                self.update_max_length_attr(&el);
+            }
+            if attributes.contains("cols") || attributes.contains("rows") {
+                self.update_size_attr(&mut el);
+            }
+            self.wrapper_size(&el)
+        };
+
+        let rdom = root.real_dom_mut();
+        if let Some(mut wrapper) = rdom.get_mut(self.div_wrapper) {
+            if let NodeTypeMut::Element(mut el) = wrapper.node_type_mut() {
+                el.set_attribute(("width", "style"), width);
+                el.set_attribute(("height", "style"), height);
+            }
+        }
+        self.write_value(rdom);
+    }
+}
+
+impl RinkWidget for TextArea {
+    fn handle_event(&mut self, event: &crate::Event, mut node: NodeMut) {
+        match event.name {
+            "keydown" => {
+                if let EventData::Keyboard(data) = &event.data {
+                    self.handle_keydown(node, data);
+                }
+            }
+
+            "paste" => {
+                if let EventData::Clipboard(data) = &event.data {
+                    if let Some(text) = data.text() {
+                        self.handle_paste(node, &text);
+                    }
+                }
+            }
+
+            "mousemove" => {
+                if let EventData::Mouse(data) = &event.data {
+                    self.handle_mousemove(node, data);
+                }
+            }
+
+            "mousedown" => {
+                if let EventData::Mouse(data) = &event.data {
+                    self.handle_mousedown(node, data);
+                }
+            }
+
+            "wheel" => {
+                if let EventData::Wheel(data) = &event.data {
+                    self.handle_wheel(node, data);
+                }
+            }
+
+            "mouseup" | "mouseleave" | "mouseenter" => {
+                self.dragging = false;
+            }
+
+            "focusout" => {
+                if self.changed {
+                    self.changed = false;
+                    let id = node.id();
+                    self.send_event(node.real_dom_mut(), id, "change");
+                }
+                execute!(stdout(), MoveTo(0, 1000)).unwrap();
+            }
+
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn wraps_lines() {
+    let lines = wrap("hello world\nabcdefgh", 6);
+    assert_eq!(
+        lines,
+        [
+            VisualLine {
+                row: 0,
+                start: 0,
+                end: 6
+            },
+            VisualLine {
+                row: 0,
+                start: 6,
+                end: 11
+            },
+            VisualLine {
+                row: 1,
+                start: 0,
+                end: 6
+            },
+            VisualLine {
+                row: 1,
+                start: 6,
+                end: 8
+            },
+        ]
+    );
+}
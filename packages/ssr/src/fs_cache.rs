@@ -13,6 +13,8 @@ pub struct RenderFreshness {
     age: u64,
     /// The maximum age of the rendered response
     max_age: Option<u64>,
+    /// If the rendered response is older than the maximum age
+    stale: bool,
 }
 
 impl RenderFreshness {
@@ -21,12 +23,26 @@ impl RenderFreshness {
         Self {
             age,
             max_age: Some(max_age),
+            stale: false,
+        }
+    }
+
+    /// Create new freshness information for a response that is served after it expired while a fresh version is rendered
+    pub fn new_stale(age: u64, max_age: u64) -> Self {
+        Self {
+            age,
+            max_age: Some(max_age),
+            stale: true,
         }
     }
 
     /// Create new freshness information with only the age
     pub fn new_age(age: u64) -> Self {
-        Self { age, max_age: None }
+        Self {
+            age,
+            max_age: None,
+            stale: false,
+        }
     }
 
     /// Create new freshness information at the current time
@@ -34,6 +50,7 @@ impl RenderFreshness {
         Self {
             age: 0,
             max_age: max_age.map(|d| d.as_secs()),
+            stale: false,
         }
     }
 
@@ -47,11 +64,22 @@ impl RenderFreshness {
         self.max_age
     }
 
+    /// Check if the rendered response expired and is being revalidated
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
     /// Write the freshness to the response headers.
     pub fn write(&self, headers: &mut http::HeaderMap<http::HeaderValue>) {
         let age = self.age();
         headers.insert(http::header::AGE, age.into());
-        if let Some(max_age) = self.max_age() {
+        if self.is_stale() {
+            // Don't let other caches keep a response that is already being replaced
+            headers.insert(
+                http::header::CACHE_CONTROL,
+                http::HeaderValue::from_static("max-age=0"),
+            );
+        } else if let Some(max_age) = self.max_age() {
             headers.insert(
                 http::header::CACHE_CONTROL,
                 http::HeaderValue::from_str(&format!("max-age={}", max_age)).unwrap(),
@@ -102,12 +130,6 @@ impl ValidCachedPath {
             timestamp,
         })
     }
-
-    pub fn freshness(&self, max_age: Option<std::time::Duration>) -> Option<RenderFreshness> {
-        let age = self.timestamp.elapsed().ok()?.as_secs();
-        let max_age = max_age.map(|max_age| max_age.as_secs());
-        Some(RenderFreshness::new(age, max_age?))
-    }
 }
 
 fn decode_timestamp(timestamp: &str) -> Option<std::time::SystemTime> {
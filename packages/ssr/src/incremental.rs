@@ -2,32 +2,28 @@
 
 #![allow(non_snake_case)]
 
-use crate::fs_cache::ValidCachedPath;
 use dioxus_core::{Element, Scope, VirtualDom};
-use rustc_hash::FxHasher;
 use std::{
+    collections::HashSet,
     future::Future,
-    hash::BuildHasherDefault,
-    io::Write,
     ops::{Deref, DerefMut},
-    path::PathBuf,
     pin::Pin,
-    time::{Duration, SystemTime},
+    sync::{Arc, Mutex},
+    time::Duration,
 };
-use tokio::io::{AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 
 pub use crate::fs_cache::*;
 pub use crate::incremental_cfg::*;
+pub use crate::render_cache::*;
 
 /// An incremental renderer.
 pub struct IncrementalRenderer {
-    pub(crate) static_dir: PathBuf,
-    #[allow(clippy::type_complexity)]
-    pub(crate) memory_cache:
-        Option<lru::LruCache<String, (SystemTime, Vec<u8>), BuildHasherDefault<FxHasher>>>,
+    pub(crate) caches: Vec<Arc<dyn RenderCache>>,
     pub(crate) invalidate_after: Option<Duration>,
+    pub(crate) stale_while_revalidate: Option<Duration>,
+    pub(crate) revalidating: Arc<Mutex<HashSet<String>>>,
     pub(crate) ssr_renderer: crate::Renderer,
-    pub(crate) map_path: PathMapFn,
 }
 
 impl IncrementalRenderer {
@@ -46,27 +42,36 @@ impl IncrementalRenderer {
         IncrementalRendererConfig::new()
     }
 
+    /// Get the caches routes are stored in, from the fastest to the slowest.
+    pub fn caches(&self) -> &[Arc<dyn RenderCache>] {
+        &self.caches
+    }
+
     /// Remove a route from the cache.
     pub fn invalidate(&mut self, route: &str) {
-        if let Some(cache) = &mut self.memory_cache {
-            cache.pop(route);
+        for cache in &self.caches {
+            if let Err(err) = cache.invalidate(route) {
+                tracing::error!("Failed to invalidate route {:?}: {}", route, err);
+            }
         }
-        if let Some(path) = self.find_file(route) {
-            let _ = std::fs::remove_file(path.full_path);
+    }
+
+    /// Remove every route that was rendered with a tag from the cache. Routes are tagged with [`tag_render`].
+    pub fn invalidate_tag(&mut self, tag: &str) {
+        for cache in &self.caches {
+            if let Err(err) = cache.invalidate_tag(tag) {
+                tracing::error!("Failed to invalidate tag {:?}: {}", tag, err);
+            }
         }
     }
 
     /// Remove all routes from the cache.
     pub fn invalidate_all(&mut self) {
-        if let Some(cache) = &mut self.memory_cache {
-            cache.clear();
+        for cache in &self.caches {
+            if let Err(err) = cache.invalidate_all() {
+                tracing::error!("Failed to invalidate the cache: {}", err);
+            }
         }
-        // clear the static directory
-        let _ = std::fs::remove_dir_all(&self.static_dir);
-    }
-
-    fn track_timestamps(&self) -> bool {
-        self.invalidate_after.is_some()
     }
 
     async fn render_and_cache<'a, P: 'static, R: WrapBody + Send + Sync>(
@@ -79,9 +84,11 @@ impl IncrementalRenderer {
         renderer: &'a R,
     ) -> Result<RenderFreshness, IncrementalRendererError> {
         let mut html_buffer = WriteBuffer { buffer: Vec::new() };
+        let tags = RenderTags::default();
         {
             let mut vdom = VirtualDom::new_with_props(comp, props);
             crate::eval::init_eval(vdom.base_scope());
+            vdom.base_scope().provide_context(tags.clone());
             rebuild_with(&mut vdom).await;
 
             renderer.render_before_body(&mut *html_buffer)?;
@@ -92,36 +99,39 @@ impl IncrementalRenderer {
 
         output.write_all(&html_buffer).await?;
 
-        self.add_to_cache(route, html_buffer)
+        let tags = tags.0.take();
+        self.add_to_cache(route, CachedRender::new(html_buffer, tags))
     }
 
     fn add_to_cache(
         &mut self,
         route: String,
-        html: Vec<u8>,
+        render: CachedRender,
     ) -> Result<RenderFreshness, IncrementalRendererError> {
-        let file_path = self.route_as_path(&route);
-        if let Some(parent) = file_path.parent() {
-            if !parent.exists() {
-                std::fs::create_dir_all(parent)?;
-            }
+        for cache in &self.caches {
+            cache.put(&route, render.clone())?;
         }
-        let file = std::fs::File::create(file_path)?;
-        let mut file = std::io::BufWriter::new(file);
-        file.write_all(&html)?;
-        self.add_to_memory_cache(route, html);
         Ok(RenderFreshness::now(self.invalidate_after))
     }
 
-    fn add_to_memory_cache(&mut self, route: String, html: Vec<u8>) {
-        if let Some(cache) = self.memory_cache.as_mut() {
-            cache.put(route, (SystemTime::now(), html));
+    /// Get the freshness of a cached render, or `None` if it expired
+    fn freshness(&self, render: &CachedRender) -> Option<RenderFreshness> {
+        let age = render.age();
+        let Some(invalidate_after) = self.invalidate_after else {
+            return Some(RenderFreshness::new_age(age.as_secs()));
+        };
+        if age < invalidate_after {
+            return Some(RenderFreshness::new(
+                age.as_secs(),
+                invalidate_after.as_secs(),
+            ));
         }
-    }
-
-    fn promote_memory_cache<K: AsRef<str>>(&mut self, route: K) {
-        if let Some(cache) = self.memory_cache.as_mut() {
-            cache.promote(route.as_ref())
+        match self.stale_while_revalidate {
+            Some(stale) if age < invalidate_after + stale => Some(RenderFreshness::new_stale(
+                age.as_secs(),
+                invalidate_after.as_secs(),
+            )),
+            _ => None,
         }
     }
 
@@ -130,44 +140,38 @@ impl IncrementalRenderer {
         route: String,
         output: &mut (impl AsyncWrite + Unpin + std::marker::Send),
     ) -> Result<Option<RenderFreshness>, IncrementalRendererError> {
-        // check the memory cache
-        if let Some((timestamp, cache_hit)) = self
-            .memory_cache
-            .as_mut()
-            .and_then(|cache| cache.get(&route))
-        {
-            if let Ok(elapsed) = timestamp.elapsed() {
-                let age = elapsed.as_secs();
-                if let Some(invalidate_after) = self.invalidate_after {
-                    if elapsed < invalidate_after {
-                        tracing::trace!("memory cache hit {:?}", route);
-                        output.write_all(cache_hit).await?;
-                        let max_age = invalidate_after.as_secs();
-                        return Ok(Some(RenderFreshness::new(age, max_age)));
-                    }
-                } else {
-                    tracing::trace!("memory cache hit {:?}", route);
-                    output.write_all(cache_hit).await?;
-                    return Ok(Some(RenderFreshness::new_age(age)));
+        // Check the caches from the fastest to the slowest. A stale render is only used if no cache has a fresh render
+        let mut stale = None;
+        for cache in &self.caches {
+            let render = match cache.get(&route) {
+                Ok(Some(render)) => render,
+                Ok(None) => continue,
+                Err(err) => {
+                    tracing::error!("Failed to read route {:?} from the cache: {}", route, err);
+                    continue;
                 }
-            }
-        }
-        // check the file cache
-        if let Some(file_path) = self.find_file(&route) {
-            if let Some(freshness) = file_path.freshness(self.invalidate_after) {
-                if let Ok(file) = tokio::fs::File::open(file_path.full_path).await {
-                    let mut file = BufReader::new(file);
-                    tokio::io::copy_buf(&mut file, output).await?;
-                    tracing::trace!("file cache hit {:?}", route);
-                    self.promote_memory_cache(&route);
+            };
+            match self.freshness(&render) {
+                Some(freshness) if !freshness.is_stale() => {
+                    tracing::trace!("cache hit {:?}", route);
+                    output.write_all(&render.html).await?;
                     return Ok(Some(freshness));
                 }
+                Some(freshness) if stale.is_none() => stale = Some((freshness, render)),
+                _ => {}
             }
         }
+        if let Some((freshness, render)) = stale {
+            tracing::trace!("stale cache hit {:?}", route);
+            output.write_all(&render.html).await?;
+            return Ok(Some(freshness));
+        }
         Ok(None)
     }
 
     /// Render a route or get it from cache.
+    ///
+    /// If the cached route is stale, the stale route is written to the output and the returned freshness [is stale](RenderFreshness::is_stale). Call [`IncrementalRenderer::revalidate`] after responding to render a fresh version in the background.
     pub async fn render<P: 'static, R: WrapBody + Send + Sync>(
         &mut self,
         route: String,
@@ -190,52 +194,61 @@ impl IncrementalRenderer {
         }
     }
 
-    fn find_file(&self, route: &str) -> Option<ValidCachedPath> {
-        let mut file_path = (self.map_path)(route);
-        if let Some(deadline) = self.invalidate_after {
-            // find the first file that matches the route and is a html file
-            file_path.push("index");
-            if let Ok(dir) = std::fs::read_dir(file_path) {
-                let mut file = None;
-                for entry in dir.flatten() {
-                    if let Some(cached_path) = ValidCachedPath::try_from_path(entry.path()) {
-                        if let Ok(elapsed) = cached_path.timestamp.elapsed() {
-                            if elapsed < deadline {
-                                file = Some(cached_path);
-                                continue;
-                            }
-                        }
-                        // if the timestamp is invalid or passed, delete the file
-                        if let Err(err) = std::fs::remove_file(entry.path()) {
-                            tracing::error!("Failed to remove file: {}", err);
-                        }
-                    }
-                }
-                file
-            } else {
-                None
-            }
-        } else {
-            file_path.push("index.html");
-            file_path.exists().then_some({
-                ValidCachedPath {
-                    full_path: file_path,
-                    timestamp: SystemTime::now(),
-                }
-            })
-        }
+    /// Render a fresh version of a route and store it in the cache without writing it anywhere.
+    ///
+    /// This does nothing if another renderer built from the same config is already revalidating the route.
+    pub async fn revalidate<P: 'static, R: WrapBody + Send + Sync>(
+        &mut self,
+        route: String,
+        component: fn(Scope<P>) -> Element,
+        props: P,
+        rebuild_with: impl FnOnce(&mut VirtualDom) -> Pin<Box<dyn Future<Output = ()> + '_>>,
+        renderer: &R,
+    ) -> Result<(), IncrementalRendererError> {
+        let Some(_guard) = RevalidatingGuard::new(&self.revalidating, &route) else {
+            return Ok(());
+        };
+        tracing::trace!("revalidating {:?}", route);
+        self.render_and_cache(
+            route,
+            component,
+            props,
+            &mut tokio::io::sink(),
+            rebuild_with,
+            renderer,
+        )
+        .await
+        .map(|_| ())
     }
+}
 
-    fn route_as_path(&self, route: &str) -> PathBuf {
-        let mut file_path = (self.map_path)(route);
-        if self.track_timestamps() {
-            file_path.push("index");
-            file_path.push(timestamp());
-        } else {
-            file_path.push("index");
+/// Marks a route as revalidating until it is dropped, even if rendering the route panics
+struct RevalidatingGuard {
+    revalidating: Arc<Mutex<HashSet<String>>>,
+    route: String,
+}
+
+impl RevalidatingGuard {
+    /// Mark the route as revalidating. Returns `None` if it is already being revalidated
+    fn new(revalidating: &Arc<Mutex<HashSet<String>>>, route: &str) -> Option<Self> {
+        if !revalidating.lock().unwrap().insert(route.to_string()) {
+            return None;
         }
-        file_path.set_extension("html");
-        file_path
+        Some(Self {
+            revalidating: revalidating.clone(),
+            route: route.to_string(),
+        })
+    }
+}
+
+impl Drop for RevalidatingGuard {
+    fn drop(&mut self) {
+        // Don't panic again if the lock was poisoned by the panic that dropped the guard
+        let mut revalidating = match self.revalidating.lock() {
+            Ok(revalidating) => revalidating,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        revalidating.remove(&self.route);
     }
 }
 
@@ -2,12 +2,14 @@
 
 use crate::incremental::IncrementalRenderer;
 use crate::incremental::IncrementalRendererError;
+use crate::render_cache::{FileSystemRenderCache, MemoryRenderCache, RenderCache};
 
 use std::{
+    collections::HashSet,
     io::Write,
     num::NonZeroUsize,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, Mutex},
     time::Duration,
 };
 
@@ -66,8 +68,14 @@ pub struct IncrementalRendererConfig {
     static_dir: PathBuf,
     memory_cache_limit: usize,
     invalidate_after: Option<Duration>,
+    stale_while_revalidate: Option<Duration>,
     map_path: Option<PathMapFn>,
     clear_cache: bool,
+    caches: Vec<Arc<dyn RenderCache>>,
+    /// The caches of the renderers built from this config. They are created and cleared by the first build
+    #[allow(clippy::type_complexity)]
+    built_caches: Arc<Mutex<Option<Vec<Arc<dyn RenderCache>>>>>,
+    revalidating: Arc<Mutex<HashSet<String>>>,
 }
 
 impl Default for IncrementalRendererConfig {
@@ -83,8 +91,12 @@ impl IncrementalRendererConfig {
             static_dir: PathBuf::from("./static"),
             memory_cache_limit: 10000,
             invalidate_after: None,
+            stale_while_revalidate: None,
             map_path: None,
             clear_cache: true,
+            caches: Vec::new(),
+            built_caches: Default::default(),
+            revalidating: Default::default(),
         }
     }
 
@@ -119,30 +131,85 @@ impl IncrementalRendererConfig {
         self
     }
 
+    /// Keep serving routes for this long after they are invalidated while a fresh version is rendered in the background.
+    ///
+    /// This only has an effect if [`IncrementalRendererConfig::invalidate_after`] is set.
+    pub fn stale_while_revalidate(mut self, stale_while_revalidate: Duration) -> Self {
+        self.stale_while_revalidate = Some(stale_while_revalidate);
+        self
+    }
+
+    /// Add a cache to store routes in. Caches are checked in the order they are added, so faster caches should be added first.
+    ///
+    /// If no caches are added, routes are stored in memory and in the static directory. Keep a clone of an [`Arc`] cache to invalidate routes from outside of the renderer:
+    /// ```rust
+    /// use dioxus_ssr::incremental::{IncrementalRendererConfig, MemoryRenderCache, RenderCache};
+    /// use std::{num::NonZeroUsize, sync::Arc};
+    ///
+    /// let cache = Arc::new(MemoryRenderCache::new(NonZeroUsize::new(100).unwrap()));
+    /// let renderer = IncrementalRendererConfig::new().cache(cache.clone()).build();
+    /// cache.invalidate_tag("product:42").unwrap();
+    /// ```
+    pub fn cache(mut self, cache: impl RenderCache + 'static) -> Self {
+        self.caches.push(Arc::new(cache));
+        self
+    }
+
+    /// Get the duration after which cached routes are invalidated, if any.
+    pub fn invalidation_duration(&self) -> Option<Duration> {
+        self.invalidate_after
+    }
+
     /// Build the incremental renderer.
+    ///
+    /// Every renderer built from this config or a clone of it shares the same caches, so finish configuring the caches before the first build. The
+    /// caches are cleared when the first renderer is built if [`IncrementalRendererConfig::clear_cache`] is set.
     pub fn build(self) -> IncrementalRenderer {
-        let static_dir = self.static_dir.clone();
-        let mut renderer = IncrementalRenderer {
-            static_dir: self.static_dir.clone(),
-            memory_cache: NonZeroUsize::new(self.memory_cache_limit)
-                .map(|limit| lru::LruCache::with_hasher(limit, Default::default())),
+        let caches = self
+            .built_caches
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| self.create_caches())
+            .clone();
+
+        IncrementalRenderer {
+            caches,
             invalidate_after: self.invalidate_after,
+            stale_while_revalidate: self.stale_while_revalidate,
+            revalidating: self.revalidating.clone(),
             ssr_renderer: crate::Renderer::new(),
-            map_path: self.map_path.unwrap_or_else(move || {
-                Arc::new(move |route: &str| {
-                    let mut path = static_dir.clone();
-                    for segment in route.split('/') {
-                        path.push(segment);
-                    }
-                    path
-                })
-            }),
+        }
+    }
+
+    /// Create the caches renderers store routes in and clear them if needed
+    fn create_caches(&self) -> Vec<Arc<dyn RenderCache>> {
+        let caches = if self.caches.is_empty() {
+            let mut caches: Vec<Arc<dyn RenderCache>> = Vec::new();
+            if let Some(limit) = NonZeroUsize::new(self.memory_cache_limit) {
+                caches.push(Arc::new(MemoryRenderCache::new(limit)));
+            }
+            let max_age = self.invalidate_after.map(|invalidate_after| {
+                invalidate_after + self.stale_while_revalidate.unwrap_or_default()
+            });
+            let mut fs_cache =
+                FileSystemRenderCache::new(self.static_dir.clone()).with_max_age(max_age);
+            if let Some(map_path) = self.map_path.clone() {
+                fs_cache = fs_cache.with_map_path(map_path);
+            }
+            caches.push(Arc::new(fs_cache));
+            caches
+        } else {
+            self.caches.clone()
         };
 
         if self.clear_cache {
-            renderer.invalidate_all();
+            for cache in &caches {
+                if let Err(err) = cache.invalidate_all() {
+                    tracing::error!("Failed to clear the cache: {}", err);
+                }
+            }
         }
 
-        renderer
+        caches
     }
 }
@@ -9,6 +9,8 @@ mod fs_cache;
 pub mod incremental;
 #[cfg(feature = "incremental")]
 mod incremental_cfg;
+#[cfg(feature = "incremental")]
+mod render_cache;
 
 pub mod eval;
 pub mod renderer;
@@ -0,0 +1,547 @@
+//! Storage backends for the incremental renderer
+
+use crate::incremental::IncrementalRendererError;
+use crate::incremental_cfg::PathMapFn;
+use dioxus_core::ScopeState;
+use rustc_hash::FxHasher;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    convert::TryInto,
+    hash::BuildHasherDefault,
+    io::Write,
+    num::NonZeroUsize,
+    path::PathBuf,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// A rendered route stored in a [`RenderCache`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedRender {
+    /// The rendered HTML
+    pub html: Vec<u8>,
+    /// The time the route was rendered
+    pub created: SystemTime,
+    /// The tags the route was rendered with. See [`tag_render`]
+    pub tags: Vec<String>,
+}
+
+impl CachedRender {
+    /// Create a new cached render at the current time
+    pub fn new(html: Vec<u8>, tags: Vec<String>) -> Self {
+        Self {
+            html,
+            created: SystemTime::now(),
+            tags,
+        }
+    }
+
+    /// Encode the render into bytes that can be stored in a [`KeyValueStore`]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let created = self
+            .created
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let mut bytes = Vec::with_capacity(self.html.len() + 12);
+        bytes.extend_from_slice(&created.to_be_bytes());
+        bytes.extend_from_slice(&(self.tags.len() as u32).to_be_bytes());
+        for tag in &self.tags {
+            bytes.extend_from_slice(&(tag.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(tag.as_bytes());
+        }
+        bytes.extend_from_slice(&self.html);
+        bytes
+    }
+
+    /// Decode a render encoded with [`CachedRender::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+            if bytes.len() < len {
+                return None;
+            }
+            let (taken, rest) = bytes.split_at(len);
+            *bytes = rest;
+            Some(taken)
+        }
+        fn take_u32(bytes: &mut &[u8]) -> Option<usize> {
+            Some(u32::from_be_bytes(take(bytes, 4)?.try_into().ok()?) as usize)
+        }
+
+        let mut bytes = bytes;
+        let created = u64::from_be_bytes(take(&mut bytes, 8)?.try_into().ok()?);
+        let tag_count = take_u32(&mut bytes)?;
+        let mut tags = Vec::with_capacity(tag_count.min(64));
+        for _ in 0..tag_count {
+            let len = take_u32(&mut bytes)?;
+            tags.push(String::from_utf8(take(&mut bytes, len)?.to_vec()).ok()?);
+        }
+        Some(Self {
+            html: bytes.to_vec(),
+            created: UNIX_EPOCH + Duration::from_secs(created),
+            tags,
+        })
+    }
+
+    /// Get the time since the route was rendered
+    pub fn age(&self) -> Duration {
+        self.created.elapsed().unwrap_or_default()
+    }
+}
+
+/// A storage backend for rendered routes.
+///
+/// Backends are shared between every renderer built from the same [`crate::incremental::IncrementalRendererConfig`], including the default memory and file system caches. A backend that stores routes outside of the process (like [`KeyValueRenderCache`]) can be shared between multiple instances of a server.
+pub trait RenderCache: Send + Sync {
+    /// Get a rendered route from the cache
+    fn get(&self, route: &str) -> Result<Option<CachedRender>, IncrementalRendererError>;
+
+    /// Store a rendered route in the cache
+    fn put(&self, route: &str, render: CachedRender) -> Result<(), IncrementalRendererError>;
+
+    /// Remove a route from the cache
+    fn invalidate(&self, route: &str) -> Result<(), IncrementalRendererError>;
+
+    /// Remove every route that was rendered with a tag from the cache
+    fn invalidate_tag(&self, tag: &str) -> Result<(), IncrementalRendererError>;
+
+    /// Remove all routes from the cache
+    fn invalidate_all(&self) -> Result<(), IncrementalRendererError>;
+}
+
+impl<C: RenderCache + ?Sized> RenderCache for Arc<C> {
+    fn get(&self, route: &str) -> Result<Option<CachedRender>, IncrementalRendererError> {
+        (**self).get(route)
+    }
+
+    fn put(&self, route: &str, render: CachedRender) -> Result<(), IncrementalRendererError> {
+        (**self).put(route, render)
+    }
+
+    fn invalidate(&self, route: &str) -> Result<(), IncrementalRendererError> {
+        (**self).invalidate(route)
+    }
+
+    fn invalidate_tag(&self, tag: &str) -> Result<(), IncrementalRendererError> {
+        (**self).invalidate_tag(tag)
+    }
+
+    fn invalidate_all(&self) -> Result<(), IncrementalRendererError> {
+        (**self).invalidate_all()
+    }
+}
+
+/// The tags of the route that is currently being rendered
+#[derive(Clone, Default)]
+pub(crate) struct RenderTags(pub(crate) Rc<RefCell<Vec<String>>>);
+
+/// Tag the route that is currently being rendered. Every route with a tag can be removed from the cache at once with [`RenderCache::invalidate_tag`].
+///
+/// This does nothing if the component is not rendered by an incremental renderer.
+///
+/// # Example
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_ssr::incremental::tag_render;
+///
+/// #[component]
+/// fn Product(cx: Scope, id: u64) -> Element {
+///     tag_render(cx, format!("product:{id}"));
+///     render! { "Product {id}" }
+/// }
+/// ```
+pub fn tag_render(cx: &ScopeState, tag: impl Into<String>) {
+    if let Some(tags) = cx.consume_context::<RenderTags>() {
+        let tag = tag.into();
+        let mut tags = tags.0.borrow_mut();
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+}
+
+/// Routes that are tagged with each tag
+#[derive(Default)]
+struct TagIndex {
+    routes: HashMap<String, HashSet<String>>,
+}
+
+impl TagIndex {
+    fn insert(&mut self, route: &str, tags: &[String]) {
+        for tag in tags {
+            self.routes
+                .entry(tag.clone())
+                .or_default()
+                .insert(route.to_string());
+        }
+    }
+
+    /// Remove a route from the routes of its tags, and drop the tags that have no routes left
+    fn remove(&mut self, route: &str, tags: &[String]) {
+        for tag in tags {
+            if let Some(routes) = self.routes.get_mut(tag) {
+                routes.remove(route);
+                if routes.is_empty() {
+                    self.routes.remove(tag);
+                }
+            }
+        }
+    }
+
+    fn take(&mut self, tag: &str) -> HashSet<String> {
+        self.routes.remove(tag).unwrap_or_default()
+    }
+}
+
+/// A cache that keeps the most recently used routes in memory
+pub struct MemoryRenderCache {
+    #[allow(clippy::type_complexity)]
+    routes: Mutex<lru::LruCache<String, CachedRender, BuildHasherDefault<FxHasher>>>,
+    tags: Mutex<TagIndex>,
+}
+
+impl MemoryRenderCache {
+    /// Create a new memory cache that holds at most `limit` routes
+    pub fn new(limit: NonZeroUsize) -> Self {
+        Self {
+            routes: Mutex::new(lru::LruCache::with_hasher(limit, Default::default())),
+            tags: Default::default(),
+        }
+    }
+}
+
+impl RenderCache for MemoryRenderCache {
+    fn get(&self, route: &str) -> Result<Option<CachedRender>, IncrementalRendererError> {
+        Ok(self.routes.lock().unwrap().get(route).cloned())
+    }
+
+    fn put(&self, route: &str, render: CachedRender) -> Result<(), IncrementalRendererError> {
+        let mut routes = self.routes.lock().unwrap();
+        let mut tags = self.tags.lock().unwrap();
+        let new_tags = render.tags.clone();
+        // Forget the tags of the previous render of this route, or of the route that was evicted to make room
+        if let Some((evicted, old)) = routes.push(route.to_string(), render) {
+            tags.remove(&evicted, &old.tags);
+        }
+        tags.insert(route, &new_tags);
+        Ok(())
+    }
+
+    fn invalidate(&self, route: &str) -> Result<(), IncrementalRendererError> {
+        let mut routes = self.routes.lock().unwrap();
+        if let Some(render) = routes.pop(route) {
+            self.tags.lock().unwrap().remove(route, &render.tags);
+        }
+        Ok(())
+    }
+
+    fn invalidate_tag(&self, tag: &str) -> Result<(), IncrementalRendererError> {
+        let mut routes = self.routes.lock().unwrap();
+        let mut tags = self.tags.lock().unwrap();
+        for route in tags.take(tag) {
+            if let Some(render) = routes.pop(&route) {
+                tags.remove(&route, &render.tags);
+            }
+        }
+        Ok(())
+    }
+
+    fn invalidate_all(&self) -> Result<(), IncrementalRendererError> {
+        let mut routes = self.routes.lock().unwrap();
+        routes.clear();
+        *self.tags.lock().unwrap() = Default::default();
+        Ok(())
+    }
+}
+
+/// A cache that stores routes as HTML files on the local disk.
+///
+/// If the routes don't expire, each route is stored at `index.html` in the folder for the route, so the static directory can be served directly by a file server.
+pub struct FileSystemRenderCache {
+    static_dir: PathBuf,
+    map_path: PathMapFn,
+    max_age: Option<Duration>,
+}
+
+impl FileSystemRenderCache {
+    /// Create a new file system cache that stores routes in the static directory
+    pub fn new(static_dir: impl Into<PathBuf>) -> Self {
+        let static_dir = static_dir.into();
+        Self {
+            map_path: default_map_path(static_dir.clone()),
+            static_dir,
+            max_age: None,
+        }
+    }
+
+    /// Set a mapping from the route to the folder the route is stored in
+    pub fn map_path<F: Fn(&str) -> PathBuf + Send + Sync + 'static>(mut self, map_path: F) -> Self {
+        self.map_path = Arc::new(map_path);
+        self
+    }
+
+    /// Delete files that are older than the maximum age. Files are stored with their timestamp when this is set.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    pub(crate) fn with_map_path(mut self, map_path: PathMapFn) -> Self {
+        self.map_path = map_path;
+        self
+    }
+
+    pub(crate) fn with_max_age(mut self, max_age: Option<Duration>) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    fn find_file(&self, route: &str) -> Option<crate::fs_cache::ValidCachedPath> {
+        let mut file_path = (self.map_path)(route);
+        if let Some(deadline) = self.max_age {
+            // find the first file that matches the route and is a html file
+            file_path.push("index");
+            let dir = std::fs::read_dir(file_path).ok()?;
+            let mut file = None;
+            for entry in dir.flatten() {
+                if let Some(cached_path) =
+                    crate::fs_cache::ValidCachedPath::try_from_path(entry.path())
+                {
+                    if let Ok(elapsed) = cached_path.timestamp.elapsed() {
+                        if elapsed < deadline {
+                            file = Some(cached_path);
+                            continue;
+                        }
+                    }
+                    // if the timestamp is invalid or passed, delete the file
+                    if let Err(err) = std::fs::remove_file(entry.path()) {
+                        tracing::error!("Failed to remove file: {}", err);
+                    }
+                }
+            }
+            file
+        } else {
+            file_path.push("index.html");
+            let timestamp = std::fs::metadata(&file_path)
+                .and_then(|metadata| metadata.modified())
+                .ok()?;
+            Some(crate::fs_cache::ValidCachedPath {
+                full_path: file_path,
+                timestamp,
+            })
+        }
+    }
+
+    fn route_as_path(&self, route: &str) -> PathBuf {
+        let mut file_path = (self.map_path)(route);
+        file_path.push("index");
+        if self.max_age.is_some() {
+            file_path.push(crate::fs_cache::timestamp());
+        }
+        file_path.set_extension("html");
+        file_path
+    }
+
+    fn tag_path(&self, tag: &str) -> PathBuf {
+        let mut path = self.static_dir.join(".dioxus-tags");
+        path.push(
+            tag.bytes()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>(),
+        );
+        path
+    }
+}
+
+impl RenderCache for FileSystemRenderCache {
+    fn get(&self, route: &str) -> Result<Option<CachedRender>, IncrementalRendererError> {
+        let Some(file) = self.find_file(route) else {
+            return Ok(None);
+        };
+        match std::fs::read(&file.full_path) {
+            Ok(html) => Ok(Some(CachedRender {
+                html,
+                created: file.timestamp,
+                tags: Vec::new(),
+            })),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn put(&self, route: &str, render: CachedRender) -> Result<(), IncrementalRendererError> {
+        let file_path = self.route_as_path(route);
+        if let Some(parent) = file_path.parent() {
+            if !parent.exists() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let file = std::fs::File::create(file_path)?;
+        let mut file = std::io::BufWriter::new(file);
+        file.write_all(&render.html)?;
+
+        for tag in &render.tags {
+            let tag_path = self.tag_path(tag);
+            // Routes are rendered again every time they expire, so only add the route if the tag doesn't list it yet
+            match std::fs::read_to_string(&tag_path) {
+                Ok(routes) if routes.lines().any(|tagged| tagged == route) => continue,
+                Ok(_) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
+            }
+            if let Some(parent) = tag_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut tag_file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(tag_path)?;
+            writeln!(tag_file, "{}", route)?;
+        }
+        Ok(())
+    }
+
+    fn invalidate(&self, route: &str) -> Result<(), IncrementalRendererError> {
+        while let Some(file) = self.find_file(route) {
+            std::fs::remove_file(file.full_path)?;
+        }
+        Ok(())
+    }
+
+    fn invalidate_tag(&self, tag: &str) -> Result<(), IncrementalRendererError> {
+        let tag_path = self.tag_path(tag);
+        let routes = match std::fs::read_to_string(&tag_path) {
+            Ok(routes) => routes,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+        for route in routes.lines() {
+            self.invalidate(route)?;
+        }
+        std::fs::remove_file(tag_path)?;
+        Ok(())
+    }
+
+    fn invalidate_all(&self) -> Result<(), IncrementalRendererError> {
+        // clear the static directory
+        match std::fs::remove_dir_all(&self.static_dir) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub(crate) fn default_map_path(static_dir: PathBuf) -> PathMapFn {
+    Arc::new(move |route: &str| {
+        let mut path = static_dir.clone();
+        for segment in route.split('/') {
+            path.push(segment);
+        }
+        path
+    })
+}
+
+/// A generic key value store that a [`KeyValueRenderCache`] stores routes in.
+///
+/// This maps to a small subset of commands supported by most key value databases. For example, a Redis client could implement this with `GET`, `SET`, `DEL`, `SADD` and `SMEMBERS`.
+pub trait KeyValueStore: Send + Sync {
+    /// Get the value of a key
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, IncrementalRendererError>;
+
+    /// Set the value of a key
+    fn set(&self, key: &str, value: Vec<u8>) -> Result<(), IncrementalRendererError>;
+
+    /// Delete a key. This is used for both values and sets
+    fn delete(&self, key: &str) -> Result<(), IncrementalRendererError>;
+
+    /// Add a member to the set stored at a key
+    fn add_to_set(&self, key: &str, member: &str) -> Result<(), IncrementalRendererError>;
+
+    /// Get the members of the set stored at a key
+    fn set_members(&self, key: &str) -> Result<Vec<String>, IncrementalRendererError>;
+}
+
+/// A cache that stores routes in a [`KeyValueStore`]
+pub struct KeyValueRenderCache<S> {
+    store: S,
+    prefix: String,
+}
+
+impl<S: KeyValueStore> KeyValueRenderCache<S> {
+    /// Create a new cache that stores routes in a key value store
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            prefix: "dioxus-ssr".to_string(),
+        }
+    }
+
+    /// Set the prefix of every key the cache uses (default: `dioxus-ssr`)
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Get the inner key value store
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    fn route_key(&self, route: &str) -> String {
+        format!("{}:route:{}", self.prefix, route)
+    }
+
+    fn tag_key(&self, tag: &str) -> String {
+        format!("{}:tag:{}", self.prefix, tag)
+    }
+
+    fn routes_key(&self) -> String {
+        format!("{}:routes", self.prefix)
+    }
+}
+
+impl<S: KeyValueStore> RenderCache for KeyValueRenderCache<S> {
+    fn get(&self, route: &str) -> Result<Option<CachedRender>, IncrementalRendererError> {
+        let Some(bytes) = self.store.get(&self.route_key(route))? else {
+            return Ok(None);
+        };
+        match CachedRender::from_bytes(&bytes) {
+            Some(render) => Ok(Some(render)),
+            None => {
+                tracing::error!("Failed to decode the cached render for {:?}", route);
+                Ok(None)
+            }
+        }
+    }
+
+    fn put(&self, route: &str, render: CachedRender) -> Result<(), IncrementalRendererError> {
+        self.store.set(&self.route_key(route), render.to_bytes())?;
+        self.store.add_to_set(&self.routes_key(), route)?;
+        for tag in &render.tags {
+            self.store.add_to_set(&self.tag_key(tag), route)?;
+        }
+        Ok(())
+    }
+
+    fn invalidate(&self, route: &str) -> Result<(), IncrementalRendererError> {
+        self.store.delete(&self.route_key(route))
+    }
+
+    fn invalidate_tag(&self, tag: &str) -> Result<(), IncrementalRendererError> {
+        let tag_key = self.tag_key(tag);
+        for route in self.store.set_members(&tag_key)? {
+            self.invalidate(&route)?;
+        }
+        self.store.delete(&tag_key)
+    }
+
+    fn invalidate_all(&self) -> Result<(), IncrementalRendererError> {
+        let routes_key = self.routes_key();
+        for route in self.store.set_members(&routes_key)? {
+            self.invalidate(&route)?;
+        }
+        self.store.delete(&routes_key)
+    }
+}
@@ -0,0 +1,216 @@
+#![cfg(feature = "incremental")]
+
+use dioxus::prelude::*;
+use dioxus_ssr::incremental::{
+    CachedRender, DefaultRenderer, FileSystemRenderCache, IncrementalRendererConfig,
+    IncrementalRendererError, KeyValueRenderCache, KeyValueStore, MemoryRenderCache, RenderCache,
+};
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+
+#[derive(Default)]
+struct MockStore {
+    values: Mutex<HashMap<String, Vec<u8>>>,
+    sets: Mutex<HashMap<String, HashSet<String>>>,
+}
+
+impl KeyValueStore for MockStore {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, IncrementalRendererError> {
+        Ok(self.values.lock().unwrap().get(key).cloned())
+    }
+
+    fn set(&self, key: &str, value: Vec<u8>) -> Result<(), IncrementalRendererError> {
+        self.values.lock().unwrap().insert(key.to_string(), value);
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), IncrementalRendererError> {
+        self.values.lock().unwrap().remove(key);
+        self.sets.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn add_to_set(&self, key: &str, member: &str) -> Result<(), IncrementalRendererError> {
+        self.sets
+            .lock()
+            .unwrap()
+            .entry(key.to_string())
+            .or_default()
+            .insert(member.to_string());
+        Ok(())
+    }
+
+    fn set_members(&self, key: &str) -> Result<Vec<String>, IncrementalRendererError> {
+        Ok(self
+            .sets
+            .lock()
+            .unwrap()
+            .get(key)
+            .map(|set| set.iter().cloned().collect())
+            .unwrap_or_default())
+    }
+}
+
+fn render(html: &str, tags: &[&str]) -> CachedRender {
+    CachedRender::new(
+        html.as_bytes().to_vec(),
+        tags.iter().map(|tag| tag.to_string()).collect(),
+    )
+}
+
+fn invalidates_tagged_routes(cache: &dyn RenderCache) {
+    cache
+        .put("/product/42", render("42", &["product:42"]))
+        .unwrap();
+    cache
+        .put("/product/42/reviews", render("reviews", &["product:42"]))
+        .unwrap();
+    cache
+        .put("/product/7", render("7", &["product:7"]))
+        .unwrap();
+
+    cache.invalidate_tag("product:42").unwrap();
+
+    assert!(cache.get("/product/42").unwrap().is_none());
+    assert!(cache.get("/product/42/reviews").unwrap().is_none());
+    assert_eq!(cache.get("/product/7").unwrap().unwrap().html, b"7");
+
+    cache.invalidate_all().unwrap();
+    assert!(cache.get("/product/7").unwrap().is_none());
+}
+
+#[test]
+fn memory_cache_tags() {
+    invalidates_tagged_routes(&MemoryRenderCache::new(NonZeroUsize::new(10).unwrap()));
+}
+
+#[test]
+fn key_value_cache_tags() {
+    invalidates_tagged_routes(&KeyValueRenderCache::new(MockStore::default()));
+}
+
+#[test]
+fn file_system_cache_tags() {
+    let static_dir = std::env::temp_dir().join(format!("dioxus-ssr-tags-{}", std::process::id()));
+    let cache = FileSystemRenderCache::new(&static_dir);
+    invalidates_tagged_routes(&cache);
+
+    // Rendering a route again doesn't list it twice in the tag file
+    cache
+        .put("/product/42", render("42", &["product:42"]))
+        .unwrap();
+    cache
+        .put("/product/42", render("42", &["product:42"]))
+        .unwrap();
+    let tag_files: Vec<_> = std::fs::read_dir(static_dir.join(".dioxus-tags"))
+        .unwrap()
+        .map(|entry| std::fs::read_to_string(entry.unwrap().path()).unwrap())
+        .collect();
+    assert_eq!(tag_files, vec!["/product/42\n".to_string()]);
+
+    cache.invalidate_all().unwrap();
+}
+
+#[test]
+fn revalidating_routes_are_released_after_a_panic() {
+    fn app(cx: Scope) -> Element {
+        render! { "fresh" }
+    }
+
+    let cache = std::sync::Arc::new(MemoryRenderCache::new(NonZeroUsize::new(10).unwrap()));
+    let mut renderer = IncrementalRendererConfig::new()
+        .cache(cache.clone())
+        .build();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let mut revalidate = |panic: bool| {
+        runtime.block_on(renderer.revalidate(
+            "/".to_string(),
+            app,
+            (),
+            move |vdom| {
+                Box::pin(async move {
+                    if panic {
+                        panic!("failed to rebuild");
+                    }
+                    let _ = vdom.rebuild();
+                })
+            },
+            &DefaultRenderer::default(),
+        ))
+    };
+
+    let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| revalidate(true)));
+    assert!(panicked.is_err());
+    assert!(cache.get("/").unwrap().is_none());
+
+    // The route can be revalidated again
+    revalidate(false).unwrap();
+    let cached = cache.get("/").unwrap().unwrap();
+    assert!(String::from_utf8(cached.html).unwrap().contains("fresh"));
+}
+
+#[test]
+fn cached_render_round_trip() {
+    let cached = render("<div>hello</div>", &["a", "product:42"]);
+    let decoded = CachedRender::from_bytes(&cached.to_bytes()).unwrap();
+
+    assert_eq!(decoded.html, cached.html);
+    assert_eq!(decoded.tags, cached.tags);
+    let secs = |render: &CachedRender| {
+        render
+            .created
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    };
+    assert_eq!(secs(&decoded), secs(&cached));
+}
+
+#[test]
+fn memory_cache_forgets_the_tags_of_removed_routes() {
+    let cache = MemoryRenderCache::new(NonZeroUsize::new(1).unwrap());
+
+    // The route is evicted to make room for another route and rendered again without the tag
+    cache.put("/a", render("a", &["old"])).unwrap();
+    cache.put("/b", render("b", &[])).unwrap();
+    cache.put("/a", render("a", &[])).unwrap();
+    cache.invalidate_tag("old").unwrap();
+    assert!(cache.get("/a").unwrap().is_some());
+
+    // The route is invalidated and rendered again without the tag
+    cache.put("/a", render("a", &["old"])).unwrap();
+    cache.invalidate("/a").unwrap();
+    cache.put("/a", render("a", &[])).unwrap();
+    cache.invalidate_tag("old").unwrap();
+    assert!(cache.get("/a").unwrap().is_some());
+
+    // The route is rendered again with different tags
+    cache.put("/a", render("a", &["old"])).unwrap();
+    cache.put("/a", render("a", &["new"])).unwrap();
+    cache.invalidate_tag("old").unwrap();
+    assert!(cache.get("/a").unwrap().is_some());
+    cache.invalidate_tag("new").unwrap();
+    assert!(cache.get("/a").unwrap().is_none());
+}
+
+#[test]
+fn renderers_built_from_one_config_share_the_default_caches() {
+    let static_dir = std::env::temp_dir().join(format!("dioxus-ssr-shared-{}", std::process::id()));
+    let config = IncrementalRendererConfig::new().static_dir(&static_dir);
+
+    let first = config.clone().build();
+    first.caches()[0].put("/", render("cached", &[])).unwrap();
+
+    // Building another renderer doesn't clear the cache again
+    let second = config.build();
+    assert_eq!(second.caches().len(), first.caches().len());
+    assert_eq!(
+        second.caches()[0].get("/").unwrap().unwrap().html,
+        b"cached"
+    );
+
+    second.caches()[1].invalidate_all().unwrap();
+}
@@ -10,6 +10,8 @@
 pub struct Config {
     #[cfg(feature = "hydrate")]
     pub(crate) hydrate: bool,
+    #[cfg(feature = "hydrate")]
+    pub(crate) repair_hydration_mismatches: bool,
     pub(crate) rootname: String,
     pub(crate) cached_strings: Vec<String>,
     pub(crate) default_panic_hook: bool,
@@ -20,6 +22,8 @@ impl Default for Config {
         Self {
             #[cfg(feature = "hydrate")]
             hydrate: false,
+            #[cfg(feature = "hydrate")]
+            repair_hydration_mismatches: false,
             rootname: "main".to_string(),
             cached_strings: Vec::new(),
             default_panic_hook: true,
@@ -47,6 +51,20 @@ impl Config {
         self
     }
 
+    #[cfg(feature = "hydrate")]
+    /// Patch nodes that differ between the server HTML and the client render instead of keeping the server values.
+    ///
+    /// In debug builds, Dioxus compares every hydrated element, dynamic text node and dynamic attribute with the client render and logs
+    /// a report for each mismatch. If this is enabled, mismatched text and attributes are updated to match the client render, and only the
+    /// components with mismatches in the structure of the page are re-rendered on the client. If it is disabled, structural mismatches fall
+    /// back to rendering the whole app on the client.
+    ///
+    /// This has no effect in release builds.
+    pub fn repair_hydration_mismatches(mut self, f: bool) -> Self {
+        self.repair_hydration_mismatches = f;
+        self
+    }
+
     /// Set the name of the element that Dioxus will use as the root.
     ///
     /// This is akin to calling React.render() on the element with the specified name.
@@ -11,21 +11,33 @@ use dioxus_core::{
 };
 use dioxus_html::PlatformEventData;
 use dioxus_html::{event_bubbles, MountedData};
-use dioxus_interpreter_js::{get_node, minimal_bindings, save_template, Channel};
+use dioxus_interpreter_js::{get_node, minimal_bindings, save_template, set_active_dom, Channel};
 use futures_channel::mpsc;
 use rustc_hash::FxHashMap;
+use std::cell::RefCell;
 use wasm_bindgen::{closure::Closure, JsCast, JsValue};
 use web_sys::{Document, Element, Event};
 
 use crate::{load_document, virtual_event_from_websys_event, Config, WebEventConverter};
 
+thread_local! {
+    // Every VirtualDom on the page shares one channel so the strings it caches in javascript stay consistent
+    static INTERPRETER: RefCell<Channel> = RefCell::new(Channel::default());
+}
+
+/// Run a closure with the channel shared by all VirtualDoms on the page
+pub(crate) fn with_interpreter<O>(f: impl FnOnce(&mut Channel) -> O) -> O {
+    INTERPRETER.with(|interpreter| f(&mut interpreter.borrow_mut()))
+}
+
 pub struct WebsysDom {
-    document: Document,
+    pub(crate) document: Document,
     #[allow(dead_code)]
     pub(crate) root: Element,
-    templates: FxHashMap<String, u16>,
+    /// The id of the interpreter state this VirtualDom renders into
+    dom_id: u32,
+    pub(crate) templates: FxHashMap<String, u16>,
     max_template_id: u16,
-    pub(crate) interpreter: Channel,
     #[cfg(feature = "mounted")]
     event_channel: mpsc::UnboundedSender<UiEvent>,
 }
@@ -55,7 +67,11 @@ impl WebsysDom {
                 document.create_element("body").ok().unwrap()
             }
         };
-        let interpreter = Channel::default();
+        let dom_id = {
+            static NEXT_DOM_ID: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+            NEXT_DOM_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        };
+        set_active_dom(dom_id);
 
         let handler: Closure<dyn FnMut(&Event)> = Closure::wrap(Box::new({
             let event_channel = event_channel.clone();
@@ -107,7 +123,7 @@ impl WebsysDom {
         Self {
             document,
             root,
-            interpreter,
+            dom_id,
             templates: FxHashMap::default(),
             max_template_id: 0,
             #[cfg(feature = "mounted")]
@@ -115,11 +131,23 @@ impl WebsysDom {
         }
     }
 
+    /// Make the interpreter operate on the nodes of this VirtualDom. This must be called before sending any edits
+    pub(crate) fn activate(&self) {
+        set_active_dom(self.dom_id);
+    }
+
     pub fn mount(&mut self) {
-        self.interpreter.mount_to_root();
+        self.activate();
+        with_interpreter(|i| i.mount_to_root());
+    }
+
+    pub fn flush(&mut self) {
+        self.activate();
+        with_interpreter(|i| i.flush());
     }
 
     pub fn load_templates(&mut self, templates: &[Template]) {
+        self.activate();
         for template in templates {
             let mut roots = vec![];
 
@@ -180,83 +208,92 @@ impl WebsysDom {
 
     pub fn apply_edits(&mut self, mut edits: Vec<Mutation>) {
         use Mutation::*;
-        let i = &mut self.interpreter;
+        self.activate();
         #[cfg(feature = "mounted")]
         // we need to apply the mount events last, so we collect them here
         let mut to_mount = Vec::new();
-        for edit in &edits {
-            match edit {
-                AppendChildren { id, m } => i.append_children(id.0 as u32, *m as u16),
-                AssignId { path, id } => {
-                    i.assign_id(path.as_ptr() as u32, path.len() as u8, id.0 as u32)
-                }
-                CreatePlaceholder { id } => i.create_placeholder(id.0 as u32),
-                CreateTextNode { value, id } => i.create_text_node(value, id.0 as u32),
-                HydrateText { path, value, id } => {
-                    i.hydrate_text(path.as_ptr() as u32, path.len() as u8, value, id.0 as u32)
-                }
-                LoadTemplate { name, index, id } => {
-                    if let Some(tmpl_id) = self.templates.get(*name) {
-                        i.load_template(*tmpl_id, *index as u16, id.0 as u32)
+        let templates = &self.templates;
+        with_interpreter(|i| {
+            for edit in &edits {
+                match edit {
+                    AppendChildren { id, m } => i.append_children(id.0 as u32, *m as u16),
+                    AssignId { path, id } => {
+                        i.assign_id(path.as_ptr() as u32, path.len() as u8, id.0 as u32)
                     }
-                }
-                ReplaceWith { id, m } => i.replace_with(id.0 as u32, *m as u16),
-                ReplacePlaceholder { path, m } => {
-                    i.replace_placeholder(path.as_ptr() as u32, path.len() as u8, *m as u16)
-                }
-                InsertAfter { id, m } => i.insert_after(id.0 as u32, *m as u16),
-                InsertBefore { id, m } => i.insert_before(id.0 as u32, *m as u16),
-                SetAttribute {
-                    name,
-                    value,
-                    id,
-                    ns,
-                } => match value {
-                    BorrowedAttributeValue::Text(txt) => {
-                        i.set_attribute(id.0 as u32, name, txt, ns.unwrap_or_default())
+                    CreatePlaceholder { id } => i.create_placeholder(id.0 as u32),
+                    CreateTextNode { value, id } => i.create_text_node(value, id.0 as u32),
+                    HydrateText { path, value, id } => {
+                        i.hydrate_text(path.as_ptr() as u32, path.len() as u8, value, id.0 as u32)
                     }
-                    BorrowedAttributeValue::Float(f) => {
-                        i.set_attribute(id.0 as u32, name, &f.to_string(), ns.unwrap_or_default())
+                    LoadTemplate { name, index, id } => {
+                        if let Some(tmpl_id) = templates.get(*name) {
+                            i.load_template(*tmpl_id, *index as u16, id.0 as u32)
+                        }
                     }
-                    BorrowedAttributeValue::Int(n) => {
-                        i.set_attribute(id.0 as u32, name, &n.to_string(), ns.unwrap_or_default())
+                    ReplaceWith { id, m } => i.replace_with(id.0 as u32, *m as u16),
+                    ReplacePlaceholder { path, m } => {
+                        i.replace_placeholder(path.as_ptr() as u32, path.len() as u8, *m as u16)
                     }
-                    BorrowedAttributeValue::Bool(b) => i.set_attribute(
-                        id.0 as u32,
+                    InsertAfter { id, m } => i.insert_after(id.0 as u32, *m as u16),
+                    InsertBefore { id, m } => i.insert_before(id.0 as u32, *m as u16),
+                    SetAttribute {
                         name,
-                        if *b { "true" } else { "false" },
-                        ns.unwrap_or_default(),
-                    ),
-                    BorrowedAttributeValue::None => {
-                        i.remove_attribute(id.0 as u32, name, ns.unwrap_or_default())
-                    }
-                    _ => unreachable!(),
-                },
-                SetText { value, id } => i.set_text(id.0 as u32, value),
-                NewEventListener { name, id, .. } => {
-                    match *name {
-                        // mounted events are fired immediately after the element is mounted.
-                        "mounted" => {
-                            #[cfg(feature = "mounted")]
-                            to_mount.push(*id);
+                        value,
+                        id,
+                        ns,
+                    } => match value {
+                        BorrowedAttributeValue::Text(txt) => {
+                            i.set_attribute(id.0 as u32, name, txt, ns.unwrap_or_default())
                         }
-                        _ => {
-                            i.new_event_listener(name, id.0 as u32, event_bubbles(name) as u8);
+                        BorrowedAttributeValue::Float(f) => i.set_attribute(
+                            id.0 as u32,
+                            name,
+                            &f.to_string(),
+                            ns.unwrap_or_default(),
+                        ),
+                        BorrowedAttributeValue::Int(n) => i.set_attribute(
+                            id.0 as u32,
+                            name,
+                            &n.to_string(),
+                            ns.unwrap_or_default(),
+                        ),
+                        BorrowedAttributeValue::Bool(b) => i.set_attribute(
+                            id.0 as u32,
+                            name,
+                            if *b { "true" } else { "false" },
+                            ns.unwrap_or_default(),
+                        ),
+                        BorrowedAttributeValue::None => {
+                            i.remove_attribute(id.0 as u32, name, ns.unwrap_or_default())
+                        }
+                        _ => unreachable!(),
+                    },
+                    SetText { value, id } => i.set_text(id.0 as u32, value),
+                    NewEventListener { name, id, .. } => {
+                        match *name {
+                            // mounted events are fired immediately after the element is mounted.
+                            "mounted" => {
+                                #[cfg(feature = "mounted")]
+                                to_mount.push(*id);
+                            }
+                            _ => {
+                                i.new_event_listener(name, id.0 as u32, event_bubbles(name) as u8);
+                            }
                         }
                     }
+                    RemoveEventListener { name, id } => match *name {
+                        "mounted" => {}
+                        _ => {
+                            i.remove_event_listener(name, id.0 as u32, event_bubbles(name) as u8);
+                        }
+                    },
+                    Remove { id } => i.remove(id.0 as u32),
+                    PushRoot { id } => i.push_root(id.0 as u32),
                 }
-                RemoveEventListener { name, id } => match *name {
-                    "mounted" => {}
-                    _ => {
-                        i.remove_event_listener(name, id.0 as u32, event_bubbles(name) as u8);
-                    }
-                },
-                Remove { id } => i.remove(id.0 as u32),
-                PushRoot { id } => i.push_root(id.0 as u32),
             }
-        }
+            i.flush();
+        });
         edits.clear();
-        i.flush();
 
         #[cfg(feature = "mounted")]
         for id in to_mount {
@@ -265,6 +302,7 @@ impl WebsysDom {
     }
 
     pub(crate) fn send_mount_event(&self, id: ElementId) {
+        self.activate();
         let node = get_node(id.0 as u32);
         if let Some(element) = node.dyn_ref::<Element>() {
             let data: MountedData = element.into();
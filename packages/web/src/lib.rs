@@ -79,6 +79,8 @@ mod file_engine;
 mod hot_reload;
 #[cfg(feature = "hydrate")]
 mod rehydrate;
+#[cfg(feature = "hydrate")]
+pub use rehydrate::{HydrationMismatch, HydrationMismatchKind};
 
 // Currently disabled since it actually slows down immediate rendering
 // todo: only schedule non-immediate renders through ric/raf
@@ -207,6 +209,8 @@ pub async fn run_with_props<T: 'static>(root: fn(Scope<T>) -> Element, root_prop
 
     #[cfg(feature = "hydrate")]
     let should_hydrate = cfg.hydrate;
+    #[cfg(feature = "hydrate")]
+    let repair_hydration_mismatches = cfg.repair_hydration_mismatches;
     #[cfg(not(feature = "hydrate"))]
     let should_hydrate = false;
 
@@ -225,9 +229,9 @@ pub async fn run_with_props<T: 'static>(root: fn(Scope<T>) -> Element, root_prop
                 web_sys::console::log_1(&format!("mutations: {:#?}", mutations).into());
                 let templates = mutations.templates;
                 websys_dom.load_templates(&templates);
-                websys_dom.interpreter.flush();
+                websys_dom.flush();
             }
-            if let Err(err) = websys_dom.rehydrate(&dom) {
+            if let Err(err) = websys_dom.rehydrate(&dom, repair_hydration_mismatches) {
                 tracing::error!("Rehydration failed. {:?}", err);
                 tracing::error!("Rebuild DOM into element from scratch");
                 websys_dom.root.set_text_content(None);
@@ -1,28 +1,219 @@
 use crate::dom::WebsysDom;
 use dioxus_core::AttributeValue;
-use dioxus_core::{DynamicNode, ElementId, ScopeState, TemplateNode, VNode, VirtualDom};
+use dioxus_core::{DynamicNode, ElementId, ScopeId, ScopeState, TemplateNode, VNode, VirtualDom};
 
 #[derive(Debug)]
 pub enum RehydrationError {
     VNodeNotInitialized,
+    /// The server rendered HTML has a different structure than the client render
+    Mismatch(Vec<HydrationMismatch>),
 }
 
 use RehydrationError::*;
 
+/// A difference between the HTML rendered on the server and the first render on the client. Mismatches are only detected in debug builds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HydrationMismatch {
+    /// The names of the components from the root component to the component that rendered the mismatched node
+    pub path: Vec<String>,
+    /// The scope that rendered the mismatched node
+    pub scope: ScopeId,
+    /// How the node differs
+    pub kind: HydrationMismatchKind,
+}
+
+/// The ways a server rendered node can differ from the client render
+#[derive(Debug, Clone, PartialEq)]
+pub enum HydrationMismatchKind {
+    /// The client expected a node with this hydration id, but the server HTML doesn't contain it
+    MissingNode {
+        /// The hydration id of the node
+        hydration_id: usize,
+    },
+    /// The element has a different tag
+    Tag {
+        /// The tag the client rendered
+        expected: String,
+        /// The tag the server rendered
+        actual: String,
+    },
+    /// The dynamic text has a different value
+    Text {
+        /// The text the client rendered
+        expected: String,
+        /// The text the server rendered
+        actual: String,
+    },
+    /// A dynamic attribute has a different value
+    Attribute {
+        /// The name of the attribute
+        name: String,
+        /// The value the client rendered
+        expected: Option<String>,
+        /// The value the server rendered
+        actual: Option<String>,
+    },
+}
+
+impl HydrationMismatch {
+    /// Check if the mismatch can be fixed by patching the existing node instead of rebuilding the tree
+    pub fn is_repairable(&self) -> bool {
+        matches!(
+            self.kind,
+            HydrationMismatchKind::Text { .. } | HydrationMismatchKind::Attribute { .. }
+        )
+    }
+}
+
+impl std::fmt::Display for HydrationMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({:?}): ", self.path.join(" > "), self.scope)?;
+        match &self.kind {
+            HydrationMismatchKind::MissingNode { hydration_id } => {
+                write!(f, "node {hydration_id} is missing from the server HTML")
+            }
+            HydrationMismatchKind::Tag { expected, actual } => {
+                write!(f, "expected element <{expected}> but found <{actual}>")
+            }
+            HydrationMismatchKind::Text { expected, actual } => {
+                write!(f, "expected text {expected:?} but found {actual:?}")
+            }
+            HydrationMismatchKind::Attribute {
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "expected attribute {name}={expected:?} but found {name}={actual:?}"
+            ),
+        }
+    }
+}
+
+/// What the client render expects the node behind a hydration id to look like
+#[cfg(debug_assertions)]
+struct ExpectedNode {
+    path: Vec<String>,
+    scope: ScopeId,
+    kind: ExpectedNodeKind,
+}
+
+#[cfg(debug_assertions)]
+enum ExpectedNodeKind {
+    Element {
+        tag: String,
+        attributes: Vec<(String, Option<String>)>,
+    },
+    Text(String),
+    Placeholder,
+}
+
+/// The attribute that marks the wrapper element of a fullstack island. The hydration ids inside an island are numbered separately from the
+/// page around it
+const ISLAND_ATTRIBUTE: &str = "data-dioxus-island";
+
+/// The state collected while walking the VirtualDom
+#[derive(Default)]
+struct RehydrationState {
+    ids: Vec<u32>,
+    to_mount: Vec<ElementId>,
+    #[cfg(debug_assertions)]
+    path: Vec<(String, ScopeId)>,
+    #[cfg(debug_assertions)]
+    expected: Vec<ExpectedNode>,
+    /// The hydration ids of the islands rendered inside this VirtualDom
+    islands: Vec<IslandIds>,
+}
+
+/// The hydration ids of the nodes inside an island
+#[derive(Default)]
+struct IslandIds {
+    /// The id of the wrapper element of the island
+    key: String,
+    ids: Vec<u32>,
+    #[cfg(debug_assertions)]
+    expected: Vec<ExpectedNode>,
+}
+
+impl RehydrationState {
+    /// Start numbering the nodes inside an island from zero. Returns the ids of the nodes around the island
+    fn enter_island(&mut self) -> IslandIds {
+        IslandIds {
+            key: String::new(),
+            ids: std::mem::take(&mut self.ids),
+            #[cfg(debug_assertions)]
+            expected: std::mem::take(&mut self.expected),
+        }
+    }
+
+    /// Store the ids of the nodes inside the island and continue numbering the nodes around it
+    fn leave_island(&mut self, key: String, outer: IslandIds) {
+        self.islands.push(IslandIds {
+            key,
+            ids: std::mem::replace(&mut self.ids, outer.ids),
+            #[cfg(debug_assertions)]
+            expected: std::mem::replace(&mut self.expected, outer.expected),
+        });
+    }
+
+    #[cfg(debug_assertions)]
+    fn expect(&mut self, kind: ExpectedNodeKind) {
+        let (path, scope) = match self.path.last() {
+            Some((_, scope)) => (
+                self.path.iter().map(|(name, _)| name.clone()).collect(),
+                *scope,
+            ),
+            None => (Vec::new(), ScopeId::ROOT),
+        };
+        self.expected.push(ExpectedNode { path, scope, kind });
+    }
+}
+
 impl WebsysDom {
     // we're streaming in patches, but the nodes already exist
     // so we're just going to write the correct IDs to the node and load them in
-    pub fn rehydrate(&mut self, dom: &VirtualDom) -> Result<(), RehydrationError> {
+    pub fn rehydrate(
+        &mut self,
+        dom: &VirtualDom,
+        repair_mismatches: bool,
+    ) -> Result<(), RehydrationError> {
         let root_scope = dom.base_scope();
-        let mut ids = Vec::new();
-        let mut to_mount = Vec::new();
+        let mut state = RehydrationState::default();
 
         // Recursively rehydrate the dom from the VirtualDom
-        self.rehydrate_scope(root_scope, dom, &mut ids, &mut to_mount)?;
+        self.rehydrate_scope(root_scope, dom, &mut state)?;
 
-        dioxus_interpreter_js::hydrate(ids);
+        // Only look for hydration ids under the root so multiple VirtualDoms can hydrate on the same page
+        let mut namespaces = vec![(self.root.clone(), state.enter_island())];
+        for island in std::mem::take(&mut state.islands) {
+            match self.document.get_element_by_id(&island.key) {
+                Some(element) => namespaces.push((element, island)),
+                None => tracing::error!("Failed to find the island {}", island.key),
+            }
+        }
+
+        #[cfg(debug_assertions)]
+        let mut rerender = Vec::new();
+        #[cfg(debug_assertions)]
+        for (under, namespace) in &namespaces {
+            rerender.extend(self.check_hydration(dom, under, namespace, repair_mismatches)?);
+        }
+        #[cfg(not(debug_assertions))]
+        let _ = repair_mismatches;
+
+        self.activate();
+        for (under, namespace) in namespaces {
+            dioxus_interpreter_js::hydrate(namespace.ids, Some(under.into()));
+        }
 
-        for id in to_mount {
+        #[cfg(debug_assertions)]
+        for (scope, marker) in rerender {
+            // The re-rendered nodes already send their mount events when they are created
+            let mounted = self.rerender_scope(dom, scope, &marker);
+            state.to_mount.retain(|id| !mounted.contains(id));
+        }
+
+        for id in state.to_mount {
             self.send_mount_event(id);
         }
 
@@ -33,30 +224,34 @@ impl WebsysDom {
         &mut self,
         scope: &ScopeState,
         dom: &VirtualDom,
-        ids: &mut Vec<u32>,
-        to_mount: &mut Vec<ElementId>,
+        state: &mut RehydrationState,
     ) -> Result<(), RehydrationError> {
         let vnode = match scope.root_node() {
             dioxus_core::RenderReturn::Ready(ready) => ready,
             _ => return Err(VNodeNotInitialized),
         };
-        self.rehydrate_vnode(dom, vnode, ids, to_mount)
+        #[cfg(debug_assertions)]
+        state
+            .path
+            .push((scope.name().to_string(), scope.scope_id()));
+        self.rehydrate_vnode(dom, vnode, state)?;
+        #[cfg(debug_assertions)]
+        state.path.pop();
+        Ok(())
     }
 
     fn rehydrate_vnode(
         &mut self,
         dom: &VirtualDom,
         vnode: &VNode,
-        ids: &mut Vec<u32>,
-        to_mount: &mut Vec<ElementId>,
+        state: &mut RehydrationState,
     ) -> Result<(), RehydrationError> {
         for (i, root) in vnode.template.get().roots.iter().enumerate() {
             self.rehydrate_template_node(
                 dom,
                 vnode,
                 root,
-                ids,
-                to_mount,
+                state,
                 Some(*vnode.root_ids.borrow().get(i).ok_or(VNodeNotInitialized)?),
             )?;
         }
@@ -68,16 +263,22 @@ impl WebsysDom {
         dom: &VirtualDom,
         vnode: &VNode,
         node: &TemplateNode,
-        ids: &mut Vec<u32>,
-        to_mount: &mut Vec<ElementId>,
+        state: &mut RehydrationState,
         root_id: Option<ElementId>,
     ) -> Result<(), RehydrationError> {
         tracing::trace!("rehydrate template node: {:?}", node);
         match node {
             TemplateNode::Element {
-                children, attrs, ..
+                tag,
+                children,
+                attrs,
+                ..
             } => {
                 let mut mounted_id = root_id;
+                let mut island = false;
+                let mut element_id = None;
+                #[cfg(debug_assertions)]
+                let mut expected_attributes = Vec::new();
                 for attr in *attrs {
                     if let dioxus_core::TemplateAttribute::Dynamic { id } = attr {
                         let attribute = &vnode.dynamic_attrs[*id];
@@ -85,25 +286,51 @@ impl WebsysDom {
                         attribute.attribute_type().for_each(|attribute| {
                             let value = &attribute.value;
                             mounted_id = Some(id);
+                            match (attribute.name, value) {
+                                (ISLAND_ATTRIBUTE, _) => island = true,
+                                ("id", AttributeValue::Text(text)) => {
+                                    element_id = Some(text.to_string())
+                                }
+                                _ => {}
+                            }
                             if let AttributeValue::Listener(_) = value {
                                 if attribute.name == "onmounted" {
-                                    to_mount.push(id);
+                                    state.to_mount.push(id);
                                 }
                             }
+                            #[cfg(debug_assertions)]
+                            if let Some(expected) = comparable_attribute(attrs, attribute) {
+                                expected_attributes.push(expected);
+                            }
                         });
                     }
                 }
                 if let Some(id) = mounted_id {
-                    ids.push(id.0 as u32);
+                    state.ids.push(id.0 as u32);
+                    #[cfg(debug_assertions)]
+                    state.expect(ExpectedNodeKind::Element {
+                        tag: tag.to_string(),
+                        attributes: expected_attributes,
+                    });
                 }
+                #[cfg(not(debug_assertions))]
+                let _ = tag;
                 if !children.is_empty() {
+                    // The children of an island are numbered from zero under the id of the island
+                    let outer = match (island, element_id) {
+                        (true, Some(key)) => Some((key, state.enter_island())),
+                        _ => None,
+                    };
                     for child in *children {
-                        self.rehydrate_template_node(dom, vnode, child, ids, to_mount, None)?;
+                        self.rehydrate_template_node(dom, vnode, child, state, None)?;
+                    }
+                    if let Some((key, outer)) = outer {
+                        state.leave_island(key, outer);
                     }
                 }
             }
             TemplateNode::Dynamic { id } | TemplateNode::DynamicText { id } => {
-                self.rehydrate_dynamic_node(dom, &vnode.dynamic_nodes[*id], ids, to_mount)?;
+                self.rehydrate_dynamic_node(dom, &vnode.dynamic_nodes[*id], state)?;
             }
             _ => {}
         }
@@ -114,32 +341,649 @@ impl WebsysDom {
         &mut self,
         dom: &VirtualDom,
         dynamic: &DynamicNode,
-        ids: &mut Vec<u32>,
-        to_mount: &mut Vec<ElementId>,
+        state: &mut RehydrationState,
     ) -> Result<(), RehydrationError> {
         tracing::trace!("rehydrate dynamic node: {:?}", dynamic);
         match dynamic {
             dioxus_core::DynamicNode::Text(text) => {
-                ids.push(text.mounted_element().ok_or(VNodeNotInitialized)?.0 as u32);
+                state
+                    .ids
+                    .push(text.mounted_element().ok_or(VNodeNotInitialized)?.0 as u32);
+                #[cfg(debug_assertions)]
+                state.expect(ExpectedNodeKind::Text(text.value.to_string()));
             }
             dioxus_core::DynamicNode::Placeholder(placeholder) => {
-                ids.push(placeholder.mounted_element().ok_or(VNodeNotInitialized)?.0 as u32);
+                state
+                    .ids
+                    .push(placeholder.mounted_element().ok_or(VNodeNotInitialized)?.0 as u32);
+                #[cfg(debug_assertions)]
+                state.expect(ExpectedNodeKind::Placeholder);
             }
             dioxus_core::DynamicNode::Component(comp) => {
                 let scope = comp.mounted_scope().ok_or(VNodeNotInitialized)?;
-                self.rehydrate_scope(
-                    dom.get_scope(scope).ok_or(VNodeNotInitialized)?,
-                    dom,
-                    ids,
-                    to_mount,
-                )?;
+                self.rehydrate_scope(dom.get_scope(scope).ok_or(VNodeNotInitialized)?, dom, state)?;
             }
             dioxus_core::DynamicNode::Fragment(fragment) => {
                 for vnode in *fragment {
-                    self.rehydrate_vnode(dom, vnode, ids, to_mount)?;
+                    self.rehydrate_vnode(dom, vnode, state)?;
                 }
             }
         }
         Ok(())
     }
+
+    /// Compare the nodes the client render expects with the server rendered HTML under an element and report any differences. Only the nodes
+    /// in the same island as the element are compared.
+    ///
+    /// If `repair` is true, mismatched text and attributes are patched to match the client render, and the server nodes of every scope with a
+    /// structural mismatch are removed so the scope can be re-rendered on the client. Returns the scopes to re-render along with the marker
+    /// that was left where their nodes were. If the nodes of a scope can't be found, its parent scope is re-rendered instead.
+    ///
+    /// Structural mismatches fail hydration if `repair` is false or if the root scope would need to be re-rendered.
+    #[cfg(debug_assertions)]
+    fn check_hydration(
+        &self,
+        dom: &VirtualDom,
+        under: &web_sys::Element,
+        namespace: &IslandIds,
+        repair: bool,
+    ) -> Result<Vec<(ScopeId, web_sys::Node)>, RehydrationError> {
+        let server_nodes = server_hydration_nodes(under);
+        let mut mismatches = Vec::new();
+        let mut repairs: Vec<Box<dyn FnOnce()>> = Vec::new();
+
+        for (hydration_id, expected) in namespace.expected.iter().enumerate() {
+            let mut report = |kind| {
+                mismatches.push(HydrationMismatch {
+                    path: expected.path.clone(),
+                    scope: expected.scope,
+                    kind,
+                })
+            };
+            let Some(server_node) = server_nodes.get(&hydration_id) else {
+                report(HydrationMismatchKind::MissingNode { hydration_id });
+                continue;
+            };
+            match (&expected.kind, server_node) {
+                (ExpectedNodeKind::Element { tag, attributes }, ServerNode::Element(element)) => {
+                    let actual_tag = element.tag_name();
+                    if !actual_tag.eq_ignore_ascii_case(tag) {
+                        report(HydrationMismatchKind::Tag {
+                            expected: tag.clone(),
+                            actual: actual_tag.to_lowercase(),
+                        });
+                        continue;
+                    }
+                    for (name, expected_value) in attributes {
+                        let actual = element.get_attribute(name);
+                        if actual != *expected_value {
+                            report(HydrationMismatchKind::Attribute {
+                                name: name.clone(),
+                                expected: expected_value.clone(),
+                                actual,
+                            });
+                            let (element, name, value) =
+                                (element.clone(), name.clone(), expected_value.clone());
+                            repairs.push(Box::new(move || {
+                                let _ = match value {
+                                    Some(value) => element.set_attribute(&name, &value),
+                                    None => element.remove_attribute(&name),
+                                };
+                            }));
+                        }
+                    }
+                }
+                (ExpectedNodeKind::Placeholder, ServerNode::Element(element)) => {
+                    let actual_tag = element.tag_name();
+                    if !actual_tag.eq_ignore_ascii_case("pre") {
+                        report(HydrationMismatchKind::Tag {
+                            expected: "pre".to_string(),
+                            actual: actual_tag.to_lowercase(),
+                        });
+                    }
+                }
+                (ExpectedNodeKind::Text(expected_text), ServerNode::Text(marker)) => {
+                    // Empty text is rendered as two markers with nothing between them
+                    let text_node = marker
+                        .next_sibling()
+                        .filter(|node| node.node_type() == web_sys::Node::TEXT_NODE);
+                    let actual = text_node
+                        .as_ref()
+                        .and_then(|node| node.text_content())
+                        .unwrap_or_default();
+                    if actual != *expected_text {
+                        report(HydrationMismatchKind::Text {
+                            expected: expected_text.clone(),
+                            actual,
+                        });
+                        let (marker, text) = (marker.clone(), expected_text.clone());
+                        repairs.push(Box::new(move || match text_node {
+                            Some(node) => node.set_text_content(Some(&text)),
+                            None => {
+                                if let Some(parent) = marker.parent_node() {
+                                    let node = crate::load_document().create_text_node(&text);
+                                    let _ =
+                                        parent.insert_before(&node, marker.next_sibling().as_ref());
+                                }
+                            }
+                        }));
+                    }
+                }
+                (ExpectedNodeKind::Text(_), ServerNode::Element(element)) => {
+                    report(HydrationMismatchKind::Tag {
+                        expected: "#text".to_string(),
+                        actual: element.tag_name().to_lowercase(),
+                    });
+                }
+                (_, ServerNode::Text(_)) => {
+                    let expected = match &expected.kind {
+                        ExpectedNodeKind::Element { tag, .. } => tag.clone(),
+                        _ => "pre".to_string(),
+                    };
+                    report(HydrationMismatchKind::Tag {
+                        expected,
+                        actual: "#text".to_string(),
+                    });
+                }
+            }
+        }
+
+        if mismatches.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        for mismatch in &mismatches {
+            tracing::error!(
+                path = %mismatch.path.join(" > "),
+                scope = mismatch.scope.0,
+                "Hydration mismatch: {mismatch}"
+            );
+        }
+
+        let structural: Vec<ScopeId> = mismatches
+            .iter()
+            .filter(|mismatch| !mismatch.is_repairable())
+            .map(|mismatch| mismatch.scope)
+            .collect();
+
+        let rerender = match (repair, structural.is_empty()) {
+            // The structure matches, so hydration can continue with the server values
+            (false, true) => return Ok(Vec::new()),
+            (false, false) => return Err(Mismatch(mismatches)),
+            (true, _) => match self.detach_scopes(dom, &namespace.ids, &server_nodes, structural) {
+                Some(rerender) => rerender,
+                None => return Err(Mismatch(mismatches)),
+            },
+        };
+
+        tracing::warn!(
+            "Patching {} mismatched nodes and re-rendering {} scopes to match the client render",
+            repairs.len(),
+            rerender.len()
+        );
+        // Nodes in re-rendered scopes were already removed from the document, so patching them is harmless
+        for repair in repairs {
+            repair();
+        }
+
+        Ok(rerender)
+    }
+
+    /// Remove the server nodes of the scopes that need to be re-rendered, leaving a marker where they were.
+    ///
+    /// Returns `None` if the root scope would need to be re-rendered.
+    #[cfg(debug_assertions)]
+    fn detach_scopes(
+        &self,
+        dom: &VirtualDom,
+        ids: &[u32],
+        server_nodes: &std::collections::HashMap<usize, ServerNode>,
+        scopes: Vec<ScopeId>,
+    ) -> Option<Vec<(ScopeId, web_sys::Node)>> {
+        let mut ranges: Vec<(ScopeId, (web_sys::Node, web_sys::Node))> = Vec::new();
+        for mut scope in scopes {
+            let range = loop {
+                if scope == ScopeId::ROOT {
+                    return None;
+                }
+                if let Some(range) = server_range(dom, scope, ids, server_nodes) {
+                    break range;
+                }
+                scope = dom.get_scope(scope)?.parent()?;
+            };
+            if !ranges.iter().any(|(other, _)| *other == scope) {
+                ranges.push((scope, range));
+            }
+        }
+
+        // Re-rendering a scope also re-renders its children
+        let outermost: Vec<ScopeId> = ranges
+            .iter()
+            .map(|(scope, _)| *scope)
+            .filter(|scope| {
+                !ranges
+                    .iter()
+                    .any(|(other, _)| other != scope && is_ancestor(dom, *other, *scope))
+            })
+            .collect();
+        ranges.retain(|(scope, _)| outermost.contains(scope));
+
+        let rerender = ranges
+            .into_iter()
+            .filter_map(|(scope, (first, last))| {
+                let parent = first.parent_node()?;
+                let marker: web_sys::Node = self.document.create_comment("dioxus-rerender").into();
+                parent.insert_before(&marker, Some(&first)).ok()?;
+                let mut next = Some(first);
+                while let Some(node) = next {
+                    next = node.next_sibling();
+                    let _ = parent.remove_child(&node);
+                    if node.is_same_node(Some(&last)) {
+                        break;
+                    }
+                }
+                Some((scope, marker))
+            })
+            .collect();
+
+        Some(rerender)
+    }
+
+    /// Create the nodes of a scope on the client and insert them where the server nodes of the scope were. Returns the ids of the nodes that were mounted
+    #[cfg(debug_assertions)]
+    fn rerender_scope(
+        &mut self,
+        dom: &VirtualDom,
+        scope: ScopeId,
+        marker: &web_sys::Node,
+    ) -> Vec<ElementId> {
+        let mutations = dom.replay_scope(scope);
+
+        let templates: Vec<_> = mutations
+            .templates
+            .into_iter()
+            .filter(|template| !self.templates.contains_key(template.name))
+            .collect();
+        self.load_templates(&templates);
+
+        let mut mounted = Vec::new();
+        let mut created = 0;
+        for edit in &mutations.edits {
+            match edit {
+                dioxus_core::Mutation::NewEventListener {
+                    name: "mounted",
+                    id,
+                } => mounted.push(*id),
+                dioxus_core::Mutation::AppendChildren { m, .. } => created = *m,
+                _ => {}
+            }
+        }
+        self.apply_edits(mutations.edits);
+
+        // The new nodes were appended to the root, move them to where the scope was
+        let children = self.root.child_nodes();
+        let len = children.length() as usize;
+        let nodes: Vec<_> = (len.saturating_sub(created)..len)
+            .filter_map(|i| children.get(i as u32))
+            .collect();
+        if let Some(parent) = marker.parent_node() {
+            for node in nodes {
+                let _ = parent.insert_before(&node, Some(marker));
+            }
+            let _ = parent.remove_child(marker);
+        }
+
+        mounted
+    }
+}
+
+/// Find all of the nodes in the server rendered HTML under an element that have a hydration id. Nodes inside islands nested under the element
+/// have their own hydration ids and are skipped
+#[cfg(debug_assertions)]
+fn server_hydration_nodes(
+    under: &web_sys::Element,
+) -> std::collections::HashMap<usize, ServerNode> {
+    use wasm_bindgen::JsCast;
+
+    let island = closest_island(under);
+    let in_namespace = |node: &web_sys::Node| {
+        let node_island = node
+            .parent_element()
+            .and_then(|parent| closest_island(&parent));
+        match (&node_island, &island) {
+            (Some(node_island), Some(island)) => node_island.is_same_node(Some(island)),
+            (node_island, island) => node_island.is_none() && island.is_none(),
+        }
+    };
+    let mut nodes = std::collections::HashMap::new();
+
+    if let Ok(elements) = under.query_selector_all("[data-node-hydration]") {
+        for i in 0..elements.length() {
+            let Some(element) = elements
+                .get(i)
+                .and_then(|node| node.dyn_into::<web_sys::Element>().ok())
+            else {
+                continue;
+            };
+            if !in_namespace(&element) {
+                continue;
+            }
+            let id = element
+                .get_attribute("data-node-hydration")
+                .and_then(|hydration| hydration.split(',').next()?.parse().ok());
+            if let Some(id) = id {
+                nodes.insert(id, ServerNode::Element(element));
+            }
+        }
+    }
+
+    // Dynamic text nodes are marked with a <!--node-id{id}--> comment before the text
+    let document = crate::load_document();
+    // 0x80 is NodeFilter.SHOW_COMMENT
+    if let Ok(walker) = document.create_tree_walker_with_what_to_show(under, 0x80) {
+        while let Ok(Some(comment)) = walker.next_node() {
+            if !in_namespace(&comment) {
+                continue;
+            }
+            let id = comment
+                .text_content()
+                .and_then(|text| text.strip_prefix("node-id")?.parse().ok());
+            if let Some(id) = id {
+                nodes.insert(id, ServerNode::Text(comment));
+            }
+        }
+    }
+
+    nodes
+}
+
+/// Find the wrapper of the island an element is in, including the element itself
+#[cfg(debug_assertions)]
+fn closest_island(element: &web_sys::Element) -> Option<web_sys::Element> {
+    element
+        .closest(&format!("[{ISLAND_ATTRIBUTE}]"))
+        .ok()
+        .flatten()
+}
+
+/// A node in the server rendered HTML
+#[cfg(debug_assertions)]
+enum ServerNode {
+    Element(web_sys::Element),
+    /// The comment that marks the start of a dynamic text node
+    Text(web_sys::Node),
+}
+
+/// A node at the top level of a scope
+#[cfg(debug_assertions)]
+enum TopLevelNode {
+    /// An element or placeholder with a hydration id
+    Element(ElementId),
+    /// A dynamic text node, marked with comments before and after the text
+    Text(ElementId),
+    /// Static text that can't be found in the server HTML
+    Static,
+}
+
+/// Collect the nodes at the top level of a vnode, including the top level nodes of any components and fragments it renders at its root
+#[cfg(debug_assertions)]
+fn top_level_nodes(dom: &VirtualDom, vnode: &VNode, nodes: &mut Vec<TopLevelNode>) {
+    let root_ids = vnode.root_ids.borrow();
+    for (i, root) in vnode.template.get().roots.iter().enumerate() {
+        match root {
+            TemplateNode::Element { .. } => match root_ids.get(i) {
+                Some(id) => nodes.push(TopLevelNode::Element(*id)),
+                None => nodes.push(TopLevelNode::Static),
+            },
+            TemplateNode::Text { .. } => nodes.push(TopLevelNode::Static),
+            TemplateNode::Dynamic { id } | TemplateNode::DynamicText { id } => {
+                match &vnode.dynamic_nodes[*id] {
+                    DynamicNode::Text(text) => match text.mounted_element() {
+                        Some(id) => nodes.push(TopLevelNode::Text(id)),
+                        None => nodes.push(TopLevelNode::Static),
+                    },
+                    DynamicNode::Placeholder(placeholder) => match placeholder.mounted_element() {
+                        Some(id) => nodes.push(TopLevelNode::Element(id)),
+                        None => nodes.push(TopLevelNode::Static),
+                    },
+                    DynamicNode::Component(component) => {
+                        match component
+                            .mounted_scope()
+                            .and_then(|scope| dom.get_scope(scope))
+                            .map(|scope| scope.root_node())
+                        {
+                            Some(dioxus_core::RenderReturn::Ready(vnode)) => {
+                                top_level_nodes(dom, vnode, nodes)
+                            }
+                            _ => nodes.push(TopLevelNode::Static),
+                        }
+                    }
+                    DynamicNode::Fragment(fragment) => {
+                        for vnode in *fragment {
+                            top_level_nodes(dom, vnode, nodes);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Find the first and last node the server rendered for a scope. Returns `None` if the start or end of the scope can't be found
+#[cfg(debug_assertions)]
+fn server_range(
+    dom: &VirtualDom,
+    scope: ScopeId,
+    ids: &[u32],
+    server_nodes: &std::collections::HashMap<usize, ServerNode>,
+) -> Option<(web_sys::Node, web_sys::Node)> {
+    let vnode = match dom.get_scope(scope)?.root_node() {
+        dioxus_core::RenderReturn::Ready(vnode) => vnode,
+        _ => return None,
+    };
+    let mut nodes = Vec::new();
+    top_level_nodes(dom, vnode, &mut nodes);
+
+    let server_node = |node: &TopLevelNode| {
+        let id = match node {
+            TopLevelNode::Element(id) | TopLevelNode::Text(id) => id.0 as u32,
+            TopLevelNode::Static => return None,
+        };
+        let hydration_id = ids.iter().position(|other| *other == id)?;
+        match server_nodes.get(&hydration_id)? {
+            ServerNode::Element(element) => {
+                let node: web_sys::Node = element.clone().into();
+                Some((node.clone(), node))
+            }
+            ServerNode::Text(marker) => {
+                // The text is followed by a <!--#--> comment
+                let mut end = marker.next_sibling();
+                while let Some(node) = &end {
+                    if node.node_type() == web_sys::Node::COMMENT_NODE
+                        && node.text_content().as_deref() == Some("#")
+                    {
+                        break;
+                    }
+                    end = node.next_sibling();
+                }
+                Some((marker.clone(), end?))
+            }
+        }
+    };
+
+    let (first, _) = server_node(nodes.first()?)?;
+    let (_, last) = server_node(nodes.last()?)?;
+
+    // The nodes must be siblings with the first node before the last
+    let mut next = Some(first.clone());
+    while let Some(node) = next {
+        if node.is_same_node(Some(&last)) {
+            return Some((first, last));
+        }
+        next = node.next_sibling();
+    }
+    None
+}
+
+/// Check if a scope is an ancestor of another scope
+#[cfg(debug_assertions)]
+fn is_ancestor(dom: &VirtualDom, ancestor: ScopeId, scope: ScopeId) -> bool {
+    let mut current = dom.get_scope(scope).and_then(|scope| scope.parent());
+    while let Some(parent) = current {
+        if parent == ancestor {
+            return true;
+        }
+        current = dom.get_scope(parent).and_then(|scope| scope.parent());
+    }
+    false
+}
+
+/// Get the name and value of a dynamic attribute the server would have rendered into the HTML, if it can be compared to the DOM
+#[cfg(debug_assertions)]
+fn comparable_attribute(
+    attrs: &[dioxus_core::TemplateAttribute],
+    attribute: &dioxus_core::Attribute,
+) -> Option<(String, Option<String>)> {
+    if attribute.namespace.is_some() || attribute.name == "dangerous_inner_html" {
+        return None;
+    }
+    // The browser only keeps the first value of duplicate attributes
+    let has_static_value = attrs.iter().any(
+        |attr| matches!(attr, dioxus_core::TemplateAttribute::Static { name, .. } if *name == attribute.name),
+    );
+    if has_static_value {
+        return None;
+    }
+    let value = match &attribute.value {
+        AttributeValue::Text(text) => Some(text.to_string()),
+        AttributeValue::Float(float) => Some(float.to_string()),
+        AttributeValue::Int(int) => Some(int.to_string()),
+        AttributeValue::None => None,
+        // Boolean attributes and listeners are rendered differently on the server
+        AttributeValue::Bool(_) | AttributeValue::Listener(_) | AttributeValue::Any(_) => {
+            return None
+        }
+    };
+    Some((attribute.name.to_string(), value))
+}
+
+#[cfg(all(test, target_arch = "wasm32", debug_assertions))]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use dioxus::prelude::*;
+    use std::cell::Cell;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    thread_local! {
+        static RENDER_ON_SERVER: Cell<bool> = Cell::new(false);
+    }
+
+    fn app(cx: Scope) -> Element {
+        let server = RENDER_ON_SERVER.with(Cell::get);
+        let greeting = if server {
+            "hello from the server"
+        } else {
+            "hello"
+        };
+        cx.render(rsx! {
+            div { id: "outside", "{greeting}" }
+            Child {}
+        })
+    }
+
+    #[allow(non_snake_case)]
+    fn Child(cx: Scope) -> Element {
+        let class = "child";
+        if RENDER_ON_SERVER.with(Cell::get) {
+            cx.render(rsx! { span { class: "{class}", "server" } })
+        } else {
+            cx.render(rsx! { p { class: "{class}", "client" } })
+        }
+    }
+
+    /// Render the app on the server into a new root element and build the client VirtualDom for it
+    fn server_render(root_id: &str) -> (WebsysDom, VirtualDom) {
+        RENDER_ON_SERVER.with(|server| server.set(true));
+        let mut server = VirtualDom::new(app);
+        let _ = server.rebuild();
+        let html = dioxus_ssr::pre_render(&server);
+        RENDER_ON_SERVER.with(|server| server.set(false));
+
+        let document = crate::load_document();
+        let root = document.create_element("div").unwrap();
+        root.set_id(root_id);
+        root.set_inner_html(&html);
+        document.body().unwrap().append_child(&root).unwrap();
+
+        let (tx, _) = futures_channel::mpsc::unbounded();
+        let mut websys_dom = WebsysDom::new(Config::new().rootname(root_id), tx);
+        let mut dom = VirtualDom::new(app);
+        let templates = dom.rebuild().templates;
+        websys_dom.load_templates(&templates);
+        (websys_dom, dom)
+    }
+
+    #[wasm_bindgen_test]
+    fn reports_mismatches() {
+        let (mut websys_dom, dom) = server_render("reports-mismatches");
+
+        let mismatches = match websys_dom.rehydrate(&dom, false) {
+            Err(Mismatch(mismatches)) => mismatches,
+            other => panic!("expected a hydration mismatch, found {:?}", other),
+        };
+
+        assert!(mismatches.contains(&HydrationMismatch {
+            path: vec!["app".to_string()],
+            scope: ScopeId::ROOT,
+            kind: HydrationMismatchKind::Text {
+                expected: "hello".to_string(),
+                actual: "hello from the server".to_string(),
+            },
+        }));
+        let structural: Vec<_> = mismatches
+            .iter()
+            .filter(|mismatch| !mismatch.is_repairable())
+            .collect();
+        assert_eq!(structural.len(), 1);
+        assert_eq!(structural[0].path, vec!["app", "Child"]);
+        assert_ne!(structural[0].scope, ScopeId::ROOT);
+        assert_eq!(
+            structural[0].kind,
+            HydrationMismatchKind::Tag {
+                expected: "p".to_string(),
+                actual: "span".to_string(),
+            }
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn repairs_only_the_mismatched_scope() {
+        let (mut websys_dom, dom) = server_render("repairs-mismatches");
+        let root = websys_dom.root.clone();
+
+        // Mark the server node outside of the mismatched scope so we can check it was kept
+        let outside = root.query_selector("#outside").unwrap().unwrap();
+        outside.set_attribute("data-server", "true").unwrap();
+
+        websys_dom.rehydrate(&dom, true).unwrap();
+
+        let outside = root.query_selector("#outside").unwrap().unwrap();
+        assert_eq!(
+            outside.get_attribute("data-server").as_deref(),
+            Some("true")
+        );
+        assert_eq!(outside.text_content().as_deref(), Some("hello"));
+
+        assert!(root.query_selector("span").unwrap().is_none());
+        let child = root.query_selector("p.child").unwrap().unwrap();
+        assert_eq!(child.text_content().as_deref(), Some("client"));
+        // The re-rendered nodes take the place of the server nodes
+        assert!(outside
+            .next_element_sibling()
+            .map_or(false, |sibling| sibling.is_same_node(Some(&*child))));
+        assert_eq!(root.child_element_count(), 2);
+    }
 }